@@ -0,0 +1,215 @@
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{DocumentError, DocumentRepository, DocumentStatus};
+
+// ---------------- Attachments Core API ----------------
+
+/// File attached to a document: a signed scan, a GPZU, technical conditions, a drawing. The
+/// content is kept in the attachment store under its SHA-256 hash.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Attachment {
+    pub id: i64,
+    pub document_id: i64,
+    pub file_name: String,
+    pub mime_type: String,
+    pub size: i64,
+    /// Lowercase hex SHA-256 of the content.
+    pub sha256: String,
+    pub added_at: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AttachmentRepositoryError {
+    Storage(String),
+    /// The stored content no longer matches its hash.
+    Corrupted(i64),
+    /// The stored content is missing from the store.
+    Missing(i64),
+}
+
+impl AttachmentRepositoryError {
+    pub fn storage<S: Into<String>>(message: S) -> Self {
+        Self::Storage(message.into())
+    }
+}
+
+impl std::fmt::Display for AttachmentRepositoryError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Storage(message) => f.write_str(message),
+            Self::Corrupted(id) => write!(f, "Content of attachment {id} is corrupted"),
+            Self::Missing(id) => write!(f, "Content of attachment {id} is missing"),
+        }
+    }
+}
+
+impl std::error::Error for AttachmentRepositoryError {}
+
+pub trait AttachmentRepository {
+    /// Copies the file into the store and records it for the document. Identical content is
+    /// stored once.
+    fn add_attachment(
+        &self,
+        document_id: i64,
+        source: &Path,
+        file_name: &str,
+        mime_type: &str,
+    ) -> Result<Attachment, AttachmentRepositoryError>;
+    fn find_attachment(&self, id: i64) -> Result<Option<Attachment>, AttachmentRepositoryError>;
+    fn attachments(&self, document_id: i64) -> Result<Vec<Attachment>, AttachmentRepositoryError>;
+    /// Writes the content to `destination`, checking it against the recorded hash. The
+    /// destination is left untouched when the content does not match.
+    fn extract_attachment(
+        &self,
+        id: i64,
+        destination: &Path,
+    ) -> Result<(), AttachmentRepositoryError>;
+    /// Removes the record; the content is deleted once no attachment refers to it.
+    fn remove_attachment(&self, id: i64) -> Result<bool, AttachmentRepositoryError>;
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AttachmentError {
+    FileNotFound(String),
+    NotFound(i64),
+    Document(DocumentError),
+    Repository(AttachmentRepositoryError),
+}
+
+impl std::fmt::Display for AttachmentError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::FileNotFound(path) => write!(f, "File not found: {path}"),
+            Self::NotFound(id) => write!(f, "Attachment {id} not found"),
+            Self::Document(error) => write!(f, "{error}"),
+            Self::Repository(error) => write!(f, "Repository error: {error}"),
+        }
+    }
+}
+
+impl std::error::Error for AttachmentError {}
+
+impl From<DocumentError> for AttachmentError {
+    fn from(error: DocumentError) -> Self {
+        Self::Document(error)
+    }
+}
+
+/// MIME type of an attachment by the extension of its file name.
+pub fn mime_type_for(file_name: &str) -> &'static str {
+    let extension = Path::new(file_name)
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .map(str::to_ascii_lowercase)
+        .unwrap_or_default();
+
+    match extension.as_str() {
+        "pdf" => "application/pdf",
+        "dwg" => "image/vnd.dwg",
+        "dxf" => "image/vnd.dxf",
+        "jpg" | "jpeg" => "image/jpeg",
+        "png" => "image/png",
+        "tif" | "tiff" => "image/tiff",
+        "doc" => "application/msword",
+        "docx" => "application/vnd.openxmlformats-officedocument.wordprocessingml.document",
+        "xls" => "application/vnd.ms-excel",
+        "xlsx" => "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+        "odt" => "application/vnd.oasis.opendocument.text",
+        "xml" => "application/xml",
+        "txt" => "text/plain",
+        "zip" => "application/zip",
+        "sig" | "p7s" => "application/pkcs7-signature",
+        _ => "application/octet-stream",
+    }
+}
+
+fn find_attachment<D: AttachmentRepository>(
+    documents: &D,
+    id: i64,
+) -> Result<Attachment, AttachmentError> {
+    documents
+        .find_attachment(id)
+        .map_err(AttachmentError::Repository)?
+        .ok_or(AttachmentError::NotFound(id))
+}
+
+/// Attaches a file to a document. Unlike the content sections, attachments can be added at any
+/// stage: signed scans only exist once the document is issued.
+pub fn add_attachment<D>(
+    documents: &D,
+    document_id: i64,
+    path: &Path,
+) -> Result<Attachment, AttachmentError>
+where
+    D: DocumentRepository + AttachmentRepository,
+{
+    crate::find_document(documents, document_id)?;
+
+    let file_name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .filter(|_| path.is_file())
+        .ok_or_else(|| AttachmentError::FileNotFound(path.display().to_string()))?;
+
+    documents
+        .add_attachment(document_id, path, &file_name, mime_type_for(&file_name))
+        .map_err(AttachmentError::Repository)
+}
+
+pub fn list_attachments<D: AttachmentRepository>(
+    documents: &D,
+    document_id: i64,
+) -> Result<Vec<Attachment>, AttachmentRepositoryError> {
+    documents.attachments(document_id)
+}
+
+pub fn extract_attachment<D: AttachmentRepository>(
+    documents: &D,
+    id: i64,
+    destination: &Path,
+) -> Result<(), AttachmentError> {
+    find_attachment(documents, id)?;
+
+    documents
+        .extract_attachment(id, destination)
+        .map_err(AttachmentError::Repository)
+}
+
+/// Removes an attachment. Attachments of issued and superseded documents are part of the
+/// record and stay.
+pub fn remove_attachment<D>(documents: &D, id: i64) -> Result<(), AttachmentError>
+where
+    D: DocumentRepository + AttachmentRepository,
+{
+    let attachment = find_attachment(documents, id)?;
+    let document = crate::find_document(documents, attachment.document_id)?;
+    if matches!(
+        document.status,
+        DocumentStatus::Issued | DocumentStatus::Superseded
+    ) {
+        return Err(AttachmentError::Document(DocumentError::ReadOnly(
+            document.id,
+        )));
+    }
+
+    match documents.remove_attachment(id) {
+        Ok(true) => Ok(()),
+        Ok(false) => Err(AttachmentError::NotFound(id)),
+        Err(error) => Err(AttachmentError::Repository(error)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mime_types_follow_the_extension() {
+        assert_eq!(mime_type_for("ГПЗУ.PDF"), "application/pdf");
+        assert_eq!(mime_type_for("план.dwg"), "image/vnd.dwg");
+        assert_eq!(mime_type_for("scan.p7s"), "application/pkcs7-signature");
+        assert_eq!(mime_type_for("README"), "application/octet-stream");
+    }
+}
@@ -0,0 +1,358 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    DocumentError, DocumentRepository, EntityRef, ReferenceError, ReferenceRepository,
+    ensure_attachable,
+};
+
+// ---------------- Construction Object Core API ----------------
+
+/// Kind of work the ZNP is issued for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum WorkType {
+    Construction,
+    Reconstruction,
+    CapitalRepair,
+}
+
+impl WorkType {
+    pub const ALL: [WorkType; 3] = [
+        Self::Construction,
+        Self::Reconstruction,
+        Self::CapitalRepair,
+    ];
+
+    /// Value stored in the `construction_object.work_type` column.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Construction => "construction",
+            Self::Reconstruction => "reconstruction",
+            Self::CapitalRepair => "capital_repair",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|kind| kind.as_str() == value)
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Construction => "Строительство",
+            Self::Reconstruction => "Реконструкция",
+            Self::CapitalRepair => "Капитальный ремонт",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Default, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(default)]
+pub struct ConstructionObjectDto {
+    pub name: Option<String>,
+    /// Code of the functional purpose from the classifier of capital construction objects.
+    pub purpose_code: Option<String>,
+    /// Location of the object.
+    pub address_id: i64,
+    /// Cadastral numbers of the land plots the object is placed on.
+    pub cadastral_numbers: Vec<String>,
+    pub work_type: Option<WorkType>,
+}
+
+/// Capital construction object described by a document.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ConstructionObject {
+    pub id: i64,
+    pub document_id: i64,
+    pub name: String,
+    pub purpose_code: String,
+    pub address_id: i64,
+    pub cadastral_numbers: Vec<String>,
+    pub work_type: WorkType,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConstructionObjectRepositoryError {
+    Storage(String),
+}
+
+impl ConstructionObjectRepositoryError {
+    pub fn storage<S: Into<String>>(message: S) -> Self {
+        Self::Storage(message.into())
+    }
+}
+
+impl std::fmt::Display for ConstructionObjectRepositoryError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Storage(message) => f.write_str(message),
+        }
+    }
+}
+
+impl std::error::Error for ConstructionObjectRepositoryError {}
+
+pub trait ConstructionObjectRepository {
+    /// Creates or replaces the construction object of the document and returns its identifier,
+    /// or `None` when the document is not a draft.
+    fn save(
+        &self,
+        document_id: i64,
+        dto: &ConstructionObjectDto,
+    ) -> Result<Option<i64>, ConstructionObjectRepositoryError>;
+    fn find_by_document(
+        &self,
+        document_id: i64,
+    ) -> Result<Option<ConstructionObject>, ConstructionObjectRepositoryError>;
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConstructionObjectError {
+    MissingName,
+    MissingPurposeCode,
+    InvalidPurposeCode(String),
+    InvalidCadastralNumber(String),
+    MissingWorkType,
+    Document(DocumentError),
+    Reference(ReferenceError),
+    Repository(ConstructionObjectRepositoryError),
+}
+
+impl std::fmt::Display for ConstructionObjectError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::MissingName => f.write_str("Object name is required"),
+            Self::MissingPurposeCode => f.write_str("Functional purpose code is required"),
+            Self::InvalidPurposeCode(code) => write!(f, "Invalid functional purpose code: {code}"),
+            Self::InvalidCadastralNumber(number) => {
+                write!(f, "Invalid cadastral number: {number}")
+            }
+            Self::MissingWorkType => f.write_str("Work type is required"),
+            Self::Document(error) => write!(f, "{error}"),
+            Self::Reference(error) => write!(f, "{error}"),
+            Self::Repository(error) => write!(f, "Repository error: {error}"),
+        }
+    }
+}
+
+impl std::error::Error for ConstructionObjectError {}
+
+impl From<DocumentError> for ConstructionObjectError {
+    fn from(error: DocumentError) -> Self {
+        Self::Document(error)
+    }
+}
+
+impl From<ReferenceError> for ConstructionObjectError {
+    fn from(error: ReferenceError) -> Self {
+        Self::Reference(error)
+    }
+}
+
+/// Checks a code of the classifier of capital construction objects by purpose: digit groups
+/// separated by dots, starting with the two-digit section, e.g. `19.7.1.1`.
+fn is_purpose_code(code: &str) -> bool {
+    let groups: Vec<&str> = code.split('.').collect();
+
+    groups.len() >= 2
+        && groups.len() <= 6
+        && groups[0].len() == 2
+        && groups.iter().all(|group| {
+            !group.is_empty() && group.len() <= 3 && group.bytes().all(|b| b.is_ascii_digit())
+        })
+}
+
+/// Checks a cadastral number of a land plot: `AA:BB:CCCCCC[C]:N`, where `AA` is the region,
+/// `BB` the district, `CCCCCC[C]` the quarter and `N` the number of the plot in the quarter.
+fn is_cadastral_number(number: &str) -> bool {
+    let parts: Vec<&str> = number.split(':').collect();
+    let digits = |part: &str| !part.is_empty() && part.bytes().all(|b| b.is_ascii_digit());
+
+    parts.len() == 4
+        && parts.iter().all(|part| digits(part))
+        && parts[0].len() == 2
+        && parts[1].len() == 2
+        && (6..=7).contains(&parts[2].len())
+}
+
+fn sanitize_construction_object(
+    dto: &ConstructionObjectDto,
+) -> Result<ConstructionObjectDto, ConstructionObjectError> {
+    fn sanitize_field(value: &Option<String>) -> Option<String> {
+        value
+            .as_ref()
+            .map(|value| value.trim())
+            .filter(|value| !value.is_empty())
+            .map(|value| value.to_string())
+    }
+
+    let name = sanitize_field(&dto.name).ok_or(ConstructionObjectError::MissingName)?;
+    let purpose_code =
+        sanitize_field(&dto.purpose_code).ok_or(ConstructionObjectError::MissingPurposeCode)?;
+    if !is_purpose_code(&purpose_code) {
+        return Err(ConstructionObjectError::InvalidPurposeCode(purpose_code));
+    }
+
+    let work_type = dto
+        .work_type
+        .ok_or(ConstructionObjectError::MissingWorkType)?;
+
+    let mut cadastral_numbers: Vec<String> = Vec::new();
+    for number in &dto.cadastral_numbers {
+        let number = number.trim();
+        if number.is_empty() {
+            continue;
+        }
+        if !is_cadastral_number(number) {
+            return Err(ConstructionObjectError::InvalidCadastralNumber(
+                number.to_string(),
+            ));
+        }
+        if !cadastral_numbers.iter().any(|existing| existing == number) {
+            cadastral_numbers.push(number.to_string());
+        }
+    }
+
+    Ok(ConstructionObjectDto {
+        name: Some(name),
+        purpose_code: Some(purpose_code),
+        address_id: dto.address_id,
+        cadastral_numbers,
+        work_type: Some(work_type),
+    })
+}
+
+/// Fills in the construction object section of a draft document. The location address is
+/// checked only when it changes, like the references of the document itself.
+pub fn save_construction_object<D, R>(
+    documents: &D,
+    references: &R,
+    document_id: i64,
+    dto: &ConstructionObjectDto,
+) -> Result<i64, ConstructionObjectError>
+where
+    D: DocumentRepository + ConstructionObjectRepository,
+    R: ReferenceRepository,
+{
+    crate::find_editable_document(documents, document_id)?;
+
+    let sanitized = sanitize_construction_object(dto)?;
+
+    let current = documents
+        .find_by_document(document_id)
+        .map_err(ConstructionObjectError::Repository)?;
+    if current.map(|object| object.address_id) != Some(sanitized.address_id) {
+        ensure_attachable(references, EntityRef::Address(sanitized.address_id))?;
+    }
+
+    match documents.save(document_id, &sanitized) {
+        Ok(Some(id)) => Ok(id),
+        Ok(None) => Err(DocumentError::ReadOnly(document_id).into()),
+        Err(error) => Err(ConstructionObjectError::Repository(error)),
+    }
+}
+
+pub fn find_construction_object<D: ConstructionObjectRepository>(
+    documents: &D,
+    document_id: i64,
+) -> Result<Option<ConstructionObject>, ConstructionObjectRepositoryError> {
+    documents.find_by_document(document_id)
+}
+
+pub(crate) fn construction_object_dto(object: &ConstructionObject) -> ConstructionObjectDto {
+    ConstructionObjectDto {
+        name: Some(object.name.clone()),
+        purpose_code: Some(object.purpose_code.clone()),
+        address_id: object.address_id,
+        cadastral_numbers: object.cadastral_numbers.clone(),
+        work_type: Some(object.work_type),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::{self, MemoryDocuments};
+    use crate::{DocumentSection, submit_for_review};
+
+    fn dto() -> ConstructionObjectDto {
+        ConstructionObjectDto {
+            name: Some(" Школа на 550 мест ".into()),
+            purpose_code: Some("19.7.1.1".into()),
+            address_id: 9,
+            cadastral_numbers: vec![
+                "77:01:0001001:1234".into(),
+                " ".into(),
+                "77:01:0001001:1234".into(),
+            ],
+            work_type: Some(WorkType::Construction),
+        }
+    }
+
+    #[test]
+    fn construction_object_is_validated_and_fills_its_section() {
+        let documents = MemoryDocuments::default();
+        let references = memory::references();
+        documents.put(memory::document(1));
+
+        save_construction_object(&documents, &references, 1, &dto()).expect("object");
+
+        let object = find_construction_object(&documents, 1)
+            .expect("find")
+            .expect("object exists");
+        assert_eq!(object.name, "Школа на 550 мест");
+        assert_eq!(object.cadastral_numbers, vec!["77:01:0001001:1234"]);
+        assert_eq!(
+            documents.sections(1),
+            Ok(vec![DocumentSection::ConstructionObject])
+        );
+
+        let invalid = ConstructionObjectDto {
+            cadastral_numbers: vec!["77-01-0001001-1234".into()],
+            ..dto()
+        };
+        assert_eq!(
+            save_construction_object(&documents, &references, 1, &invalid),
+            Err(ConstructionObjectError::InvalidCadastralNumber(
+                "77-01-0001001-1234".into()
+            ))
+        );
+
+        let invalid = ConstructionObjectDto {
+            purpose_code: Some("1.7".into()),
+            ..dto()
+        };
+        assert_eq!(
+            save_construction_object(&documents, &references, 1, &invalid),
+            Err(ConstructionObjectError::InvalidPurposeCode("1.7".into()))
+        );
+    }
+
+    #[test]
+    fn construction_object_requires_active_address_and_draft_document() {
+        let documents = MemoryDocuments::default();
+        let references = memory::references();
+        documents.put(memory::document(1));
+
+        let missing = ConstructionObjectDto {
+            address_id: 404,
+            ..dto()
+        };
+        assert_eq!(
+            save_construction_object(&documents, &references, 1, &missing),
+            Err(ConstructionObjectError::Reference(
+                ReferenceError::NotFound(EntityRef::Address(404))
+            ))
+        );
+
+        submit_for_review(&documents, 1).expect("submit");
+        assert_eq!(
+            save_construction_object(&documents, &references, 1, &dto()),
+            Err(ConstructionObjectError::Document(DocumentError::ReadOnly(
+                1
+            )))
+        );
+    }
+}
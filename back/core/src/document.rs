@@ -0,0 +1,659 @@
+use serde::{Deserialize, Serialize};
+
+use crate::revision::parse_change_mark;
+use crate::{
+    Address, CompletenessIssue, DocumentNumbering, DocumentSection, DocumentSnapshot,
+    DocumentStatus, DocumentType, Entrepreneur, NumberingScheme, Organization, Person,
+    SourceDocument,
+};
+
+// ---------------- Reference checks ----------------
+
+/// Points to a row of one of the reference data tables.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", content = "id", rename_all = "snake_case")]
+pub enum EntityRef {
+    Address(i64),
+    Organization(i64),
+    Entrepreneur(i64),
+    Person(i64),
+    SourceDocument(i64),
+}
+
+impl std::fmt::Display for EntityRef {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Address(id) => write!(f, "address {id}"),
+            Self::Organization(id) => write!(f, "organization {id}"),
+            Self::Entrepreneur(id) => write!(f, "entrepreneur {id}"),
+            Self::Person(id) => write!(f, "person {id}"),
+            Self::SourceDocument(id) => write!(f, "source document {id}"),
+        }
+    }
+}
+
+/// A loaded row of one of the reference data tables.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ReferenceEntity {
+    Address(Address),
+    Organization(Organization),
+    Entrepreneur(Entrepreneur),
+    Person(Person),
+    SourceDocument(SourceDocument),
+}
+
+impl ReferenceEntity {
+    pub fn reference(&self) -> EntityRef {
+        match self {
+            Self::Address(address) => EntityRef::Address(address.id),
+            Self::Organization(organization) => EntityRef::Organization(organization.id),
+            Self::Entrepreneur(entrepreneur) => EntityRef::Entrepreneur(entrepreneur.id),
+            Self::Person(person) => EntityRef::Person(person.id),
+            Self::SourceDocument(source) => EntityRef::SourceDocument(source.id),
+        }
+    }
+
+    /// Entity this one points to: the address of a party or the issuer of a source document.
+    pub fn related(&self) -> Option<EntityRef> {
+        match self {
+            Self::Address(_) => None,
+            Self::Organization(organization) => Some(EntityRef::Address(organization.address_id)),
+            Self::Entrepreneur(entrepreneur) => Some(EntityRef::Address(entrepreneur.address_id)),
+            Self::Person(person) => Some(EntityRef::Address(person.address_id)),
+            Self::SourceDocument(source) => Some(EntityRef::Organization(source.issuer_id)),
+        }
+    }
+
+    /// Stable identifier of the row, see [`Address::uid`].
+    pub fn uid(&self) -> &str {
+        match self {
+            Self::Address(address) => &address.uid,
+            Self::Organization(organization) => &organization.uid,
+            Self::Entrepreneur(entrepreneur) => &entrepreneur.uid,
+            Self::Person(person) => &person.uid,
+            Self::SourceDocument(source) => &source.uid,
+        }
+    }
+
+    /// Name of an organization, entrepreneur or person as it is printed in documents.
+    pub fn party_name(&self) -> Option<String> {
+        match self {
+            Self::Organization(organization) => Some(organization.full_name.clone()),
+            Self::Entrepreneur(entrepreneur) => Some(format!("ИП {}", entrepreneur.full_name())),
+            Self::Person(person) => Some(person.full_name()),
+            Self::Address(_) | Self::SourceDocument(_) => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReferenceState {
+    Missing,
+    Active,
+    Archived,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReferenceRepositoryError {
+    Storage(String),
+}
+
+impl ReferenceRepositoryError {
+    pub fn storage<S: Into<String>>(message: S) -> Self {
+        Self::Storage(message.into())
+    }
+}
+
+impl std::fmt::Display for ReferenceRepositoryError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Storage(message) => f.write_str(message),
+        }
+    }
+}
+
+impl std::error::Error for ReferenceRepositoryError {}
+
+pub trait ReferenceRepository {
+    fn state(&self, reference: EntityRef) -> Result<ReferenceState, ReferenceRepositoryError>;
+    fn load(
+        &self,
+        reference: EntityRef,
+    ) -> Result<Option<ReferenceEntity>, ReferenceRepositoryError>;
+    /// Finds the row with the uid in any of the reference data tables.
+    fn find_by_uid(&self, uid: &str) -> Result<Option<EntityRef>, ReferenceRepositoryError>;
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReferenceError {
+    NotFound(EntityRef),
+    Archived(EntityRef),
+    Repository(ReferenceRepositoryError),
+}
+
+impl std::fmt::Display for ReferenceError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NotFound(reference) => write!(f, "Referenced {reference} not found"),
+            Self::Archived(reference) => write!(f, "Referenced {reference} is archived"),
+            Self::Repository(error) => write!(f, "Repository error: {error}"),
+        }
+    }
+}
+
+impl std::error::Error for ReferenceError {}
+
+/// Checks that the referenced entity exists and is not archived, i.e. that it may be attached
+/// to a new document.
+pub fn ensure_attachable<R: ReferenceRepository>(
+    repository: &R,
+    reference: EntityRef,
+) -> Result<(), ReferenceError> {
+    match repository
+        .state(reference)
+        .map_err(ReferenceError::Repository)?
+    {
+        ReferenceState::Active => Ok(()),
+        ReferenceState::Archived => Err(ReferenceError::Archived(reference)),
+        ReferenceState::Missing => Err(ReferenceError::NotFound(reference)),
+    }
+}
+
+/// Loads the reference data row with the uid, whichever table it is in.
+pub fn find_reference_by_uid<R: ReferenceRepository>(
+    repository: &R,
+    uid: &str,
+) -> Result<Option<ReferenceEntity>, ReferenceRepositoryError> {
+    let uid = uid.trim();
+    if uid.is_empty() {
+        return Ok(None);
+    }
+
+    match repository.find_by_uid(uid)? {
+        Some(reference) => repository.load(reference),
+        None => Ok(None),
+    }
+}
+
+// ---------------- Document Core API ----------------
+
+/// Author of a document. The variants mirror the rows of the `author_type` table.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum DocumentAuthor {
+    Organization { id: i64 },
+    Entrepreneur { id: i64 },
+    Person { id: i64 },
+    Informal { name: String },
+}
+
+impl DocumentAuthor {
+    /// Identifier of the matching `author_type` row.
+    pub fn type_id(&self) -> i64 {
+        match self {
+            Self::Organization { .. } => 1,
+            Self::Entrepreneur { .. } => 2,
+            Self::Person { .. } => 3,
+            Self::Informal { .. } => 4,
+        }
+    }
+
+    pub fn reference(&self) -> Option<EntityRef> {
+        match self {
+            Self::Organization { id } => Some(EntityRef::Organization(*id)),
+            Self::Entrepreneur { id } => Some(EntityRef::Entrepreneur(*id)),
+            Self::Person { id } => Some(EntityRef::Person(*id)),
+            Self::Informal { .. } => None,
+        }
+    }
+}
+
+/// Developer of a document (застройщик или технический заказчик).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum DocumentDeveloper {
+    Organization { id: i64 },
+    Person { id: i64 },
+}
+
+impl DocumentDeveloper {
+    pub fn reference(&self) -> EntityRef {
+        match self {
+            Self::Organization { id } => EntityRef::Organization(*id),
+            Self::Person { id } => EntityRef::Person(*id),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Default, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(default)]
+pub struct DocumentDto {
+    pub name: Option<String>,
+    pub number: Option<String>,
+    pub date: Option<String>,
+    pub change_mark: Option<String>,
+    pub uid: Option<String>,
+    pub document_type: Option<DocumentType>,
+    pub author: Option<DocumentAuthor>,
+    pub developer: Option<DocumentDeveloper>,
+    /// Set by [`crate::issue_revision`] only; [`create_document`] starts an unlinked draft.
+    pub previous_revision_id: Option<i64>,
+    /// Allocates the number from a numbering scheme when `number` is empty.
+    pub numbering: Option<DocumentNumbering>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct Document {
+    pub id: i64,
+    pub name: String,
+    pub number: Option<String>,
+    pub date: Option<String>,
+    pub change_mark: Option<String>,
+    pub uid: String,
+    pub document_type: Option<DocumentType>,
+    /// Free-text type of a legacy document that matched no [`DocumentType`] when the types were
+    /// introduced, kept for manual review.
+    pub type_note: Option<String>,
+    pub author: DocumentAuthor,
+    pub developer: Option<DocumentDeveloper>,
+    pub previous_revision_id: Option<i64>,
+    /// Marks the document as a starting point for new projects, see [`crate::clone_document`].
+    pub is_template: bool,
+    pub status: DocumentStatus,
+    pub submitted_at: Option<String>,
+    pub approved_at: Option<String>,
+    /// Set once the document is issued; the referenced entities are frozen at that moment.
+    pub issued_at: Option<String>,
+    pub superseded_at: Option<String>,
+    pub cancelled_at: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DocumentRepositoryError {
+    Storage(String),
+}
+
+impl DocumentRepositoryError {
+    pub fn storage<S: Into<String>>(message: S) -> Self {
+        Self::Storage(message.into())
+    }
+}
+
+impl std::fmt::Display for DocumentRepositoryError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Storage(message) => f.write_str(message),
+        }
+    }
+}
+
+impl std::error::Error for DocumentRepositoryError {}
+
+pub trait DocumentRepository {
+    /// Inserts the document. When `dto.numbering` is set, the next number of the stored scheme's
+    /// per-year counter is allocated in the same transaction.
+    fn create(&self, dto: &DocumentDto) -> Result<i64, DocumentRepositoryError>;
+    /// Updates the content of a draft document. Returns `false` when the document does not
+    /// exist or is no longer a draft; documents past the draft stage are read-only.
+    fn update(&self, id: i64, dto: &DocumentDto) -> Result<bool, DocumentRepositoryError>;
+    /// Deletes a draft document together with its sections. Returns `false` when the document
+    /// does not exist or is no longer a draft.
+    fn delete_draft(&self, id: i64) -> Result<bool, DocumentRepositoryError>;
+    fn find(&self, id: i64) -> Result<Option<Document>, DocumentRepositoryError>;
+    fn list(&self) -> Result<Vec<Document>, DocumentRepositoryError>;
+
+    /// Returns the identifier of the revision issued from the given document, if any.
+    fn next_revision(&self, id: i64) -> Result<Option<i64>, DocumentRepositoryError>;
+    /// Moves the document from `from` to `to` and stamps the matching timestamp. Returns `false`
+    /// when the document is not in the `from` status anymore.
+    fn set_status(
+        &self,
+        id: i64,
+        from: DocumentStatus,
+        to: DocumentStatus,
+    ) -> Result<bool, DocumentRepositoryError>;
+    /// Sets or clears the template flag. Returns `false` when the document does not exist.
+    fn set_template(&self, id: i64, template: bool) -> Result<bool, DocumentRepositoryError>;
+    /// Moves an approved document to [`DocumentStatus::Issued`] and stores the snapshot of its
+    /// referenced entities. When the document is a revision, its issued predecessor becomes
+    /// [`DocumentStatus::Superseded`] in the same transaction.
+    fn issue(&self, id: i64, snapshot: &DocumentSnapshot) -> Result<(), DocumentRepositoryError>;
+    fn snapshot(&self, id: i64) -> Result<Option<DocumentSnapshot>, DocumentRepositoryError>;
+    /// Returns the content sections the document has filled in.
+    fn sections(&self, id: i64) -> Result<Vec<DocumentSection>, DocumentRepositoryError>;
+
+    fn numbering_schemes(&self) -> Result<Vec<NumberingScheme>, DocumentRepositoryError>;
+    /// Stores a new numbering scheme. Returns `false` when a scheme with the prefix exists.
+    fn create_numbering_scheme(
+        &self,
+        scheme: &NumberingScheme,
+    ) -> Result<bool, DocumentRepositoryError>;
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DocumentError {
+    MissingName,
+    MissingAuthor,
+    MissingAuthorName,
+    InvalidChangeMark(String),
+    InvalidNumbering(String),
+    NotFound(i64),
+    AlreadyRevised {
+        id: i64,
+        revision_id: i64,
+    },
+    AlreadyIssued(i64),
+    NotIssued(i64),
+    ReadOnly(i64),
+    InvalidTransition {
+        id: i64,
+        from: DocumentStatus,
+        to: DocumentStatus,
+    },
+    Incomplete(Vec<CompletenessIssue>),
+    Reference(ReferenceError),
+    Repository(DocumentRepositoryError),
+}
+
+impl std::fmt::Display for DocumentError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::MissingName => f.write_str("Name is required"),
+            Self::MissingAuthor => f.write_str("Author is required"),
+            Self::MissingAuthorName => f.write_str("Author name is required"),
+            Self::InvalidChangeMark(value) => write!(f, "Invalid change mark: {value}"),
+            Self::InvalidNumbering(message) => write!(f, "Invalid numbering scheme: {message}"),
+            Self::NotFound(id) => write!(f, "Document {id} not found"),
+            Self::AlreadyRevised { id, revision_id } => {
+                write!(f, "Document {id} already has revision {revision_id}")
+            }
+            Self::AlreadyIssued(id) => write!(f, "Document {id} is already issued"),
+            Self::NotIssued(id) => write!(f, "Document {id} is not issued"),
+            Self::ReadOnly(id) => write!(f, "Document {id} is read-only"),
+            Self::InvalidTransition { id, from, to } => write!(
+                f,
+                "Document {id} cannot move from {} to {}",
+                from.as_str(),
+                to.as_str()
+            ),
+            Self::Incomplete(issues) => {
+                f.write_str("Document is incomplete: ")?;
+                let issues: Vec<&str> = issues.iter().map(CompletenessIssue::as_str).collect();
+                f.write_str(&issues.join(", "))
+            }
+            Self::Reference(error) => write!(f, "{error}"),
+            Self::Repository(error) => write!(f, "Repository error: {error}"),
+        }
+    }
+}
+
+impl std::error::Error for DocumentError {}
+
+impl From<ReferenceError> for DocumentError {
+    fn from(error: ReferenceError) -> Self {
+        Self::Reference(error)
+    }
+}
+
+/// Creates a draft document. A UUID is generated when `uid` is empty. The revision link is
+/// dropped: a document becomes a revision only through [`crate::issue_revision`].
+pub fn create_document<D: DocumentRepository, R: ReferenceRepository>(
+    documents: &D,
+    references: &R,
+    dto: &DocumentDto,
+) -> Result<i64, DocumentError> {
+    let sanitized = sanitize_document(&DocumentDto {
+        previous_revision_id: None,
+        ..dto.clone()
+    })?;
+
+    if let Some(numbering) = &sanitized.numbering {
+        crate::numbering::ensure_scheme_exists(documents, numbering)?;
+    }
+    for reference in dto_references(&sanitized) {
+        ensure_attachable(references, reference)?;
+    }
+
+    documents
+        .create(&sanitized)
+        .map_err(DocumentError::Repository)
+}
+
+/// Updates the content of a draft document. The uid, change mark and revision link are kept.
+/// Only references that change are checked, so a document keeps working with an entity that was
+/// archived after it was attached.
+pub fn update_document<D: DocumentRepository, R: ReferenceRepository>(
+    documents: &D,
+    references: &R,
+    id: i64,
+    dto: &DocumentDto,
+) -> Result<(), DocumentError> {
+    let document = find_document(documents, id)?;
+
+    if !document.status.is_editable() {
+        return Err(DocumentError::ReadOnly(id));
+    }
+
+    let sanitized = sanitize_document(&DocumentDto {
+        change_mark: document.change_mark.clone(),
+        uid: Some(document.uid.clone()),
+        previous_revision_id: document.previous_revision_id,
+        numbering: None,
+        ..dto.clone()
+    })?;
+
+    let current = crate::document_references(&document);
+    for reference in dto_references(&sanitized) {
+        if !current.contains(&reference) {
+            ensure_attachable(references, reference)?;
+        }
+    }
+
+    match documents.update(id, &sanitized) {
+        Ok(true) => Ok(()),
+        Ok(false) => Err(DocumentError::ReadOnly(id)),
+        Err(error) => Err(DocumentError::Repository(error)),
+    }
+}
+
+fn dto_references(dto: &DocumentDto) -> Vec<EntityRef> {
+    dto.author
+        .as_ref()
+        .and_then(DocumentAuthor::reference)
+        .into_iter()
+        .chain(dto.developer.as_ref().map(DocumentDeveloper::reference))
+        .collect()
+}
+
+/// Loads a document whose content may still be changed.
+pub(crate) fn find_editable_document<D: DocumentRepository>(
+    repository: &D,
+    id: i64,
+) -> Result<Document, DocumentError> {
+    let document = find_document(repository, id)?;
+    if document.status.is_editable() {
+        Ok(document)
+    } else {
+        Err(DocumentError::ReadOnly(id))
+    }
+}
+
+/// Reports a failure of one of the section repositories as a document storage error.
+pub(crate) fn section_error<E: std::fmt::Display>(error: E) -> DocumentError {
+    DocumentError::Repository(DocumentRepositoryError::storage(error.to_string()))
+}
+
+pub fn find_document<D: DocumentRepository>(
+    repository: &D,
+    id: i64,
+) -> Result<Document, DocumentError> {
+    repository
+        .find(id)
+        .map_err(DocumentError::Repository)?
+        .ok_or(DocumentError::NotFound(id))
+}
+
+pub fn list_documents<D: DocumentRepository>(
+    repository: &D,
+) -> Result<Vec<Document>, DocumentRepositoryError> {
+    repository.list()
+}
+
+fn sanitize_document(dto: &DocumentDto) -> Result<DocumentDto, DocumentError> {
+    fn sanitize_field(value: &Option<String>) -> Option<String> {
+        value
+            .as_ref()
+            .map(|v| v.trim())
+            .filter(|v| !v.is_empty())
+            .map(|v| v.to_string())
+    }
+
+    let name = sanitize_field(&dto.name).ok_or(DocumentError::MissingName)?;
+    let uid = sanitize_field(&dto.uid).unwrap_or_else(crate::generate_uid);
+    let number = sanitize_field(&dto.number);
+    let numbering = match (&number, &dto.numbering) {
+        (None, Some(numbering)) => Some(crate::numbering::sanitize_numbering(numbering)?),
+        _ => None,
+    };
+    let change_mark = sanitize_field(&dto.change_mark);
+
+    if let Some(change_mark) = &change_mark {
+        parse_change_mark(change_mark)?;
+    }
+
+    let author = match dto.author.as_ref().ok_or(DocumentError::MissingAuthor)? {
+        DocumentAuthor::Informal { name } => DocumentAuthor::Informal {
+            name: sanitize_field(&Some(name.clone())).ok_or(DocumentError::MissingAuthorName)?,
+        },
+        author => author.clone(),
+    };
+
+    Ok(DocumentDto {
+        name: Some(name),
+        number,
+        date: sanitize_field(&dto.date),
+        change_mark,
+        uid: Some(uid),
+        document_type: dto.document_type,
+        author: Some(author),
+        developer: dto.developer,
+        previous_revision_id: dto.previous_revision_id,
+        numbering,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::{self, MemoryDocuments};
+
+    #[test]
+    fn references_are_found_by_uid() {
+        let references = memory::references();
+
+        assert_eq!(
+            find_reference_by_uid(&references, " person-5 "),
+            Ok(Some(ReferenceEntity::Person(memory::person(5, 10))))
+        );
+        assert_eq!(find_reference_by_uid(&references, "person-6"), Ok(None));
+        assert_eq!(find_reference_by_uid(&references, ""), Ok(None));
+    }
+
+    #[test]
+    fn create_document_rejects_archived_author() {
+        let documents = MemoryDocuments::default();
+        let references = memory::references();
+        if let Some(ReferenceEntity::Organization(organization)) =
+            references.entities.borrow_mut().first_mut()
+        {
+            organization.archived_at = Some("2026-10-01 09:00:00".into());
+        }
+        let dto = DocumentDto {
+            name: Some("ЗНП".into()),
+            uid: Some("uid-1".into()),
+            author: Some(DocumentAuthor::Organization { id: 3 }),
+            ..Default::default()
+        };
+
+        let result = create_document(&documents, &references, &dto);
+
+        assert_eq!(
+            result,
+            Err(DocumentError::Reference(ReferenceError::Archived(
+                EntityRef::Organization(3)
+            )))
+        );
+        assert!(documents.documents.borrow().is_empty());
+    }
+
+    #[test]
+    fn create_document_rejects_missing_developer() {
+        let documents = MemoryDocuments::default();
+        let references = memory::references();
+        let dto = DocumentDto {
+            name: Some("ЗНП".into()),
+            uid: Some("uid-1".into()),
+            author: Some(DocumentAuthor::Organization { id: 3 }),
+            developer: Some(DocumentDeveloper::Organization { id: 8 }),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            create_document(&documents, &references, &dto),
+            Err(DocumentError::Reference(ReferenceError::NotFound(
+                EntityRef::Organization(8)
+            )))
+        );
+    }
+
+    #[test]
+    fn create_document_accepts_informal_author_without_lookup() {
+        let documents = MemoryDocuments::default();
+        let references = memory::MemoryReferences::default();
+        let dto = DocumentDto {
+            name: Some(" ЗНП ".into()),
+            uid: Some("uid-1".into()),
+            author: Some(DocumentAuthor::Informal {
+                name: " Иванов И. И. ".into(),
+            }),
+            ..Default::default()
+        };
+
+        let id =
+            create_document(&documents, &references, &dto).expect("document should be created");
+
+        let created = documents.get(id);
+        assert_eq!(created.name, "ЗНП");
+        assert_eq!(
+            created.author,
+            DocumentAuthor::Informal {
+                name: "Иванов И. И.".into()
+            }
+        );
+    }
+
+    #[test]
+    fn create_document_does_not_link_a_revision() {
+        let documents = MemoryDocuments::default();
+        let references = memory::MemoryReferences::default();
+        let dto = DocumentDto {
+            name: Some("ЗНП".into()),
+            author: Some(DocumentAuthor::Informal {
+                name: "Иванов И. И.".into(),
+            }),
+            previous_revision_id: Some(1),
+            ..Default::default()
+        };
+
+        let id =
+            create_document(&documents, &references, &dto).expect("document should be created");
+
+        assert_eq!(documents.get(id).previous_revision_id, None);
+    }
+}
@@ -0,0 +1,122 @@
+use serde::{Deserialize, Serialize};
+
+// ---------------- Document Types ----------------
+
+/// Kind of the ZNP. Identifiers match the `document_type` lookup table.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum DocumentType {
+    NewConstruction,
+    Reconstruction,
+    CapitalRepair,
+    LinearObject,
+}
+
+impl DocumentType {
+    pub const ALL: [DocumentType; 4] = [
+        Self::NewConstruction,
+        Self::Reconstruction,
+        Self::CapitalRepair,
+        Self::LinearObject,
+    ];
+
+    pub fn id(self) -> i64 {
+        match self {
+            Self::NewConstruction => 1,
+            Self::Reconstruction => 2,
+            Self::CapitalRepair => 3,
+            Self::LinearObject => 4,
+        }
+    }
+
+    pub fn from_id(id: i64) -> Option<Self> {
+        Self::ALL.into_iter().find(|kind| kind.id() == id)
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::NewConstruction => "ЗНП на строительство",
+            Self::Reconstruction => "ЗНП на реконструкцию",
+            Self::CapitalRepair => "ЗНП на капитальный ремонт",
+            Self::LinearObject => "ЗНП на линейный объект",
+        }
+    }
+
+    /// Sections that must be filled in before a document of this type leaves the draft stage.
+    /// Technical and economic indicators are not set for a capital repair.
+    pub fn required_sections(self) -> &'static [DocumentSection] {
+        match self {
+            Self::NewConstruction | Self::Reconstruction | Self::LinearObject => &[
+                DocumentSection::ConstructionObject,
+                DocumentSection::Indicators,
+                DocumentSection::Requirements,
+                DocumentSection::SourceDocuments,
+            ],
+            Self::CapitalRepair => &[
+                DocumentSection::ConstructionObject,
+                DocumentSection::Requirements,
+                DocumentSection::SourceDocuments,
+            ],
+        }
+    }
+}
+
+/// Content section of a ZNP.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DocumentSection {
+    ConstructionObject,
+    Indicators,
+    Requirements,
+    SourceDocuments,
+}
+
+impl DocumentSection {
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::ConstructionObject => "Сведения об объекте капитального строительства",
+            Self::Indicators => "Технико-экономические показатели",
+            Self::Requirements => "Требования к проектной документации",
+            Self::SourceDocuments => "Исходные документы",
+        }
+    }
+}
+
+/// Document type as listed for the UI.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DocumentTypeInfo {
+    pub id: i64,
+    pub code: DocumentType,
+    pub name: &'static str,
+    pub required_sections: Vec<DocumentSection>,
+}
+
+pub fn list_document_types() -> Vec<DocumentTypeInfo> {
+    DocumentType::ALL
+        .into_iter()
+        .map(|kind| DocumentTypeInfo {
+            id: kind.id(),
+            code: kind,
+            name: kind.name(),
+            required_sections: kind.required_sections().to_vec(),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn document_types_round_trip_through_lookup_identifiers() {
+        for kind in DocumentType::ALL {
+            assert_eq!(DocumentType::from_id(kind.id()), Some(kind));
+        }
+        assert_eq!(DocumentType::from_id(0), None);
+        assert_eq!(
+            serde_json::to_string(&DocumentType::CapitalRepair).expect("json"),
+            "\"capital_repair\""
+        );
+    }
+}
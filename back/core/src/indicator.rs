@@ -0,0 +1,403 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{DocumentError, DocumentRepository, OkeiUnit, find_okei_unit};
+
+// ---------------- Technical Indicators Core API ----------------
+
+#[derive(Debug, Clone, PartialEq, Eq, Default, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(default)]
+pub struct TechnicalIndicatorDto {
+    pub name: Option<String>,
+    pub value: Option<String>,
+    pub unit_okei: Option<String>,
+}
+
+/// Row of the technical and economic indicators (ТЭП) table of a document. The value is kept as
+/// a normalized decimal string so it is written out exactly as entered.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct TechnicalIndicator {
+    pub id: i64,
+    pub document_id: i64,
+    pub position: i64,
+    pub name: String,
+    pub value: String,
+    pub unit_okei: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TechnicalIndicatorRepositoryError {
+    Storage(String),
+}
+
+impl TechnicalIndicatorRepositoryError {
+    pub fn storage<S: Into<String>>(message: S) -> Self {
+        Self::Storage(message.into())
+    }
+}
+
+impl std::fmt::Display for TechnicalIndicatorRepositoryError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Storage(message) => f.write_str(message),
+        }
+    }
+}
+
+impl std::error::Error for TechnicalIndicatorRepositoryError {}
+
+pub trait TechnicalIndicatorRepository {
+    /// Appends the indicator to the end of the document's table. Returns `None` when the
+    /// document is not a draft.
+    fn add_indicator(
+        &self,
+        document_id: i64,
+        dto: &TechnicalIndicatorDto,
+    ) -> Result<Option<i64>, TechnicalIndicatorRepositoryError>;
+    /// Returns `false` when the indicator does not exist or its document is not a draft.
+    fn update_indicator(
+        &self,
+        id: i64,
+        dto: &TechnicalIndicatorDto,
+    ) -> Result<bool, TechnicalIndicatorRepositoryError>;
+    /// Returns `false` when the indicator does not exist or its document is not a draft.
+    fn delete_indicator(&self, id: i64) -> Result<bool, TechnicalIndicatorRepositoryError>;
+    fn find_indicator(
+        &self,
+        id: i64,
+    ) -> Result<Option<TechnicalIndicator>, TechnicalIndicatorRepositoryError>;
+    /// Indicators of the document ordered by position.
+    fn indicators(
+        &self,
+        document_id: i64,
+    ) -> Result<Vec<TechnicalIndicator>, TechnicalIndicatorRepositoryError>;
+    /// Renumbers the indicators of the document in the given order. Returns `false` when the
+    /// document is not a draft.
+    fn reorder_indicators(
+        &self,
+        document_id: i64,
+        ids: &[i64],
+    ) -> Result<bool, TechnicalIndicatorRepositoryError>;
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TechnicalIndicatorError {
+    MissingName,
+    MissingValue,
+    MissingUnit,
+    UnknownUnit(String),
+    InvalidValue(String),
+    /// The value does not fit the unit, e.g. a fractional number of places.
+    ValueNotAllowed {
+        value: String,
+        unit: &'static str,
+    },
+    NotFound(i64),
+    /// The new order does not list exactly the indicators of the document.
+    InvalidOrder,
+    Document(DocumentError),
+    Repository(TechnicalIndicatorRepositoryError),
+}
+
+impl std::fmt::Display for TechnicalIndicatorError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::MissingName => f.write_str("Indicator name is required"),
+            Self::MissingValue => f.write_str("Indicator value is required"),
+            Self::MissingUnit => f.write_str("Unit is required"),
+            Self::UnknownUnit(code) => write!(f, "Unknown OKEI unit: {code}"),
+            Self::InvalidValue(value) => write!(f, "Invalid numeric value: {value}"),
+            Self::ValueNotAllowed { value, unit } => {
+                write!(f, "Value {value} is not allowed for unit {unit}")
+            }
+            Self::NotFound(id) => write!(f, "Indicator {id} not found"),
+            Self::InvalidOrder => f.write_str("Order must list every indicator of the document"),
+            Self::Document(error) => write!(f, "{error}"),
+            Self::Repository(error) => write!(f, "Repository error: {error}"),
+        }
+    }
+}
+
+impl std::error::Error for TechnicalIndicatorError {}
+
+impl From<DocumentError> for TechnicalIndicatorError {
+    fn from(error: DocumentError) -> Self {
+        Self::Document(error)
+    }
+}
+
+/// Normalizes a decimal number: accepts a comma as the decimal separator and drops leading
+/// zeros of the integer part and trailing zeros of the fraction.
+fn normalize_value(value: &str) -> Option<String> {
+    let value = value.replace(',', ".");
+    let (integer, fraction) = value.split_once('.').unwrap_or((&value, ""));
+
+    let digits = |part: &str| part.bytes().all(|byte| byte.is_ascii_digit());
+    if integer.is_empty() || !digits(integer) || !digits(fraction) {
+        return None;
+    }
+    if value.contains('.') && fraction.is_empty() {
+        return None;
+    }
+
+    let integer = integer.trim_start_matches('0');
+    let integer = if integer.is_empty() { "0" } else { integer };
+    let fraction = fraction.trim_end_matches('0');
+
+    Some(if fraction.is_empty() {
+        integer.to_string()
+    } else {
+        format!("{integer}.{fraction}")
+    })
+}
+
+fn check_value(value: &str, unit: &OkeiUnit) -> Result<(), TechnicalIndicatorError> {
+    let not_allowed = || TechnicalIndicatorError::ValueNotAllowed {
+        value: value.to_string(),
+        unit: unit.symbol,
+    };
+
+    if unit.integer && value.contains('.') {
+        return Err(not_allowed());
+    }
+    if unit.code == "744" {
+        let percent: f64 = value.parse().map_err(|_| not_allowed())?;
+        if percent > 100.0 {
+            return Err(not_allowed());
+        }
+    }
+
+    Ok(())
+}
+
+fn sanitize_indicator(
+    dto: &TechnicalIndicatorDto,
+) -> Result<TechnicalIndicatorDto, TechnicalIndicatorError> {
+    fn sanitize_field(value: &Option<String>) -> Option<String> {
+        value
+            .as_ref()
+            .map(|value| value.trim())
+            .filter(|value| !value.is_empty())
+            .map(|value| value.to_string())
+    }
+
+    let name = sanitize_field(&dto.name).ok_or(TechnicalIndicatorError::MissingName)?;
+    let value = sanitize_field(&dto.value).ok_or(TechnicalIndicatorError::MissingValue)?;
+    let unit_okei = sanitize_field(&dto.unit_okei).ok_or(TechnicalIndicatorError::MissingUnit)?;
+
+    let unit = find_okei_unit(&unit_okei)
+        .ok_or_else(|| TechnicalIndicatorError::UnknownUnit(unit_okei.clone()))?;
+    let value = normalize_value(&value).ok_or(TechnicalIndicatorError::InvalidValue(value))?;
+    check_value(&value, unit)?;
+
+    Ok(TechnicalIndicatorDto {
+        name: Some(name),
+        value: Some(value),
+        unit_okei: Some(unit_okei),
+    })
+}
+
+fn find_indicator<D: TechnicalIndicatorRepository>(
+    documents: &D,
+    id: i64,
+) -> Result<TechnicalIndicator, TechnicalIndicatorError> {
+    documents
+        .find_indicator(id)
+        .map_err(TechnicalIndicatorError::Repository)?
+        .ok_or(TechnicalIndicatorError::NotFound(id))
+}
+
+pub fn add_indicator<D>(
+    documents: &D,
+    document_id: i64,
+    dto: &TechnicalIndicatorDto,
+) -> Result<i64, TechnicalIndicatorError>
+where
+    D: DocumentRepository + TechnicalIndicatorRepository,
+{
+    crate::find_editable_document(documents, document_id)?;
+    let sanitized = sanitize_indicator(dto)?;
+
+    match documents.add_indicator(document_id, &sanitized) {
+        Ok(Some(id)) => Ok(id),
+        Ok(None) => Err(DocumentError::ReadOnly(document_id).into()),
+        Err(error) => Err(TechnicalIndicatorError::Repository(error)),
+    }
+}
+
+pub fn update_indicator<D>(
+    documents: &D,
+    id: i64,
+    dto: &TechnicalIndicatorDto,
+) -> Result<(), TechnicalIndicatorError>
+where
+    D: DocumentRepository + TechnicalIndicatorRepository,
+{
+    let indicator = find_indicator(documents, id)?;
+    crate::find_editable_document(documents, indicator.document_id)?;
+    let sanitized = sanitize_indicator(dto)?;
+
+    match documents.update_indicator(id, &sanitized) {
+        Ok(true) => Ok(()),
+        Ok(false) => {
+            crate::find_editable_document(documents, indicator.document_id)?;
+            Err(TechnicalIndicatorError::NotFound(id))
+        }
+        Err(error) => Err(TechnicalIndicatorError::Repository(error)),
+    }
+}
+
+pub fn delete_indicator<D>(documents: &D, id: i64) -> Result<(), TechnicalIndicatorError>
+where
+    D: DocumentRepository + TechnicalIndicatorRepository,
+{
+    let indicator = find_indicator(documents, id)?;
+    crate::find_editable_document(documents, indicator.document_id)?;
+
+    match documents.delete_indicator(id) {
+        Ok(true) => Ok(()),
+        Ok(false) => {
+            crate::find_editable_document(documents, indicator.document_id)?;
+            Err(TechnicalIndicatorError::NotFound(id))
+        }
+        Err(error) => Err(TechnicalIndicatorError::Repository(error)),
+    }
+}
+
+pub fn list_indicators<D: TechnicalIndicatorRepository>(
+    documents: &D,
+    document_id: i64,
+) -> Result<Vec<TechnicalIndicator>, TechnicalIndicatorRepositoryError> {
+    documents.indicators(document_id)
+}
+
+/// Puts the indicators of the document in the given order. `ids` must list every indicator of
+/// the document exactly once.
+pub fn reorder_indicators<D>(
+    documents: &D,
+    document_id: i64,
+    ids: &[i64],
+) -> Result<(), TechnicalIndicatorError>
+where
+    D: DocumentRepository + TechnicalIndicatorRepository,
+{
+    crate::find_editable_document(documents, document_id)?;
+
+    let mut current: Vec<i64> = documents
+        .indicators(document_id)
+        .map_err(TechnicalIndicatorError::Repository)?
+        .iter()
+        .map(|indicator| indicator.id)
+        .collect();
+    let mut requested = ids.to_vec();
+    current.sort_unstable();
+    requested.sort_unstable();
+    if current != requested {
+        return Err(TechnicalIndicatorError::InvalidOrder);
+    }
+
+    match documents.reorder_indicators(document_id, ids) {
+        Ok(true) => Ok(()),
+        Ok(false) => Err(DocumentError::ReadOnly(document_id).into()),
+        Err(error) => Err(TechnicalIndicatorError::Repository(error)),
+    }
+}
+
+pub(crate) fn indicator_dto(indicator: &TechnicalIndicator) -> TechnicalIndicatorDto {
+    TechnicalIndicatorDto {
+        name: Some(indicator.name.clone()),
+        value: Some(indicator.value.clone()),
+        unit_okei: Some(indicator.unit_okei.clone()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::{self, MemoryDocuments};
+    use crate::{DocumentSection, submit_for_review};
+
+    fn dto(name: &str, value: &str, unit_okei: &str) -> TechnicalIndicatorDto {
+        TechnicalIndicatorDto {
+            name: Some(name.into()),
+            value: Some(value.into()),
+            unit_okei: Some(unit_okei.into()),
+        }
+    }
+
+    #[test]
+    fn values_are_normalized_and_checked_against_units() {
+        assert_eq!(normalize_value("0012,50"), Some("12.5".into()));
+        assert_eq!(normalize_value("1500"), Some("1500".into()));
+        assert_eq!(normalize_value("0.0"), Some("0".into()));
+        assert_eq!(normalize_value("-1"), None);
+        assert_eq!(normalize_value("1."), None);
+        assert_eq!(normalize_value("1e3"), None);
+
+        assert_eq!(
+            sanitize_indicator(&dto("Вместимость", "550,5", "698")),
+            Err(TechnicalIndicatorError::ValueNotAllowed {
+                value: "550.5".into(),
+                unit: "мест",
+            })
+        );
+        assert_eq!(
+            sanitize_indicator(&dto("Озеленение", "120", "744")),
+            Err(TechnicalIndicatorError::ValueNotAllowed {
+                value: "120".into(),
+                unit: "%",
+            })
+        );
+        assert_eq!(
+            sanitize_indicator(&dto("Площадь", "1", "999")),
+            Err(TechnicalIndicatorError::UnknownUnit("999".into()))
+        );
+    }
+
+    #[test]
+    fn indicators_are_kept_in_order_and_fill_their_section() {
+        let documents = MemoryDocuments::default();
+        documents.put(memory::document(1));
+
+        let area = add_indicator(&documents, 1, &dto("Площадь застройки", "1 500", "055"));
+        assert_eq!(
+            area,
+            Err(TechnicalIndicatorError::InvalidValue("1 500".into()))
+        );
+
+        let area =
+            add_indicator(&documents, 1, &dto("Площадь застройки", "1500", "055")).expect("area");
+        let floors = add_indicator(&documents, 1, &dto("Этажность", "4", "796")).expect("floors");
+        assert_eq!(documents.sections(1), Ok(vec![DocumentSection::Indicators]));
+
+        assert_eq!(
+            reorder_indicators(&documents, 1, &[floors]),
+            Err(TechnicalIndicatorError::InvalidOrder)
+        );
+        reorder_indicators(&documents, 1, &[floors, area]).expect("reorder");
+        update_indicator(
+            &documents,
+            area,
+            &dto("Площадь застройки", "1520,00", "055"),
+        )
+        .expect("update");
+
+        let indicators = list_indicators(&documents, 1).expect("list");
+        assert_eq!(
+            indicators
+                .iter()
+                .map(|indicator| (indicator.name.as_str(), indicator.value.as_str()))
+                .collect::<Vec<_>>(),
+            vec![("Этажность", "4"), ("Площадь застройки", "1520")]
+        );
+
+        submit_for_review(&documents, 1).expect("submit");
+        assert_eq!(
+            delete_indicator(&documents, floors),
+            Err(TechnicalIndicatorError::Document(DocumentError::ReadOnly(
+                1
+            )))
+        );
+    }
+}
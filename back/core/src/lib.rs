@@ -4,9 +4,52 @@ pub trait Migrations {
     fn run(&self) -> MigrationsResult<()>;
 }
 
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
+
+mod attachment;
+mod construction_object;
+mod document;
+mod document_type;
+mod indicator;
+mod lifecycle;
+#[cfg(test)]
+mod memory;
+mod numbering;
+mod okei;
+mod reference_import;
+mod requirement;
+mod revision;
+mod signatory;
+mod snapshot;
+mod source_document;
+mod template;
+
+pub use attachment::*;
+pub use construction_object::*;
+pub use document::*;
+pub use document_type::*;
+pub use indicator::*;
+pub use lifecycle::*;
+pub use numbering::*;
+pub use okei::*;
+pub use reference_import::*;
+pub use requirement::*;
+pub use revision::*;
+pub use signatory::*;
+pub use snapshot::*;
+pub use source_document::*;
+pub use template::*;
+
+/// Options shared by the `list` operations of the reference data repositories.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ListOptions {
+    /// Include rows that were archived (soft deleted). Archived rows are hidden by default.
+    pub include_archived: bool,
+}
 
-#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(default)]
 pub struct AddressDto {
     pub region_code: Option<String>,
     pub note: Option<String>,
@@ -17,6 +60,8 @@ pub struct AddressDto {
     pub street: Option<String>,
     pub building: Option<String>,
     pub room: Option<String>,
+    /// Stable identifier of a new row; a UUID is generated when it is empty.
+    pub uid: Option<String>,
 }
 
 pub fn format_address(dto: &AddressDto) -> String {
@@ -47,9 +92,13 @@ fn format_address_impl(dto: &AddressDto) -> String {
     parts.join(", ")
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct Address {
     pub id: i64,
+    /// Stable identifier shared by all workstations, a UUID generated when the row is created.
+    #[serde(default)]
+    pub uid: String,
     pub region_code: String,
     pub note: Option<String>,
     pub country: Option<String>,
@@ -59,6 +108,33 @@ pub struct Address {
     pub street: Option<String>,
     pub building: Option<String>,
     pub room: Option<String>,
+    pub archived_at: Option<String>,
+}
+
+impl Address {
+    /// Single-line form of the address, see [`format_address`].
+    pub fn formatted(&self) -> String {
+        format_address(&AddressDto {
+            region_code: Some(self.region_code.clone()),
+            note: self.note.clone(),
+            country: self.country.clone(),
+            district: self.district.clone(),
+            city: self.city.clone(),
+            settlement: self.settlement.clone(),
+            street: self.street.clone(),
+            building: self.building.clone(),
+            room: self.room.clone(),
+            uid: Some(self.uid.clone()),
+        })
+    }
+}
+
+fn full_name(surname: &str, name: &str, patronymic: Option<&str>) -> String {
+    [Some(surname), Some(name), patronymic]
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>()
+        .join(" ")
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -84,12 +160,16 @@ impl std::error::Error for AddressRepositoryError {}
 
 pub trait AddressRepository {
     fn create(&self, dto: &AddressDto) -> Result<i64, AddressRepositoryError>;
-    fn list(&self) -> Result<Vec<Address>, AddressRepositoryError>;
+    fn update(&self, id: i64, dto: &AddressDto) -> Result<bool, AddressRepositoryError>;
+    fn list(&self, options: &ListOptions) -> Result<Vec<Address>, AddressRepositoryError>;
+    fn archive(&self, id: i64) -> Result<bool, AddressRepositoryError>;
+    fn restore(&self, id: i64) -> Result<bool, AddressRepositoryError>;
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum AddressError {
     MissingRegionCode,
+    NotFound(i64),
     Repository(AddressRepositoryError),
 }
 
@@ -97,6 +177,7 @@ impl std::fmt::Display for AddressError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             Self::MissingRegionCode => f.write_str("Region code is required"),
+            Self::NotFound(id) => write!(f, "Address {id} not found"),
             Self::Repository(error) => write!(f, "Repository error: {error}"),
         }
     }
@@ -108,20 +189,54 @@ pub fn create_address<R: AddressRepository>(
     repository: &R,
     dto: &AddressDto,
 ) -> Result<i64, AddressError> {
-    let sanitized = sanitize_address(dto)?;
+    let mut sanitized = sanitize_address(dto)?;
+    sanitized.uid.get_or_insert_with(crate::generate_uid);
 
     repository
         .create(&sanitized)
         .map_err(AddressError::Repository)
 }
 
+pub fn update_address<R: AddressRepository>(
+    repository: &R,
+    id: i64,
+    dto: &AddressDto,
+) -> Result<(), AddressError> {
+    let sanitized = sanitize_address(dto)?;
+    match repository.update(id, &sanitized) {
+        Ok(true) => Ok(()),
+        Ok(false) => Err(AddressError::NotFound(id)),
+        Err(error) => Err(AddressError::Repository(error)),
+    }
+}
+
 pub fn list_addresses<R: AddressRepository>(
     repository: &R,
+    options: &ListOptions,
 ) -> Result<Vec<Address>, AddressRepositoryError> {
-    repository.list()
+    repository.list(options)
+}
+
+/// Archives the address so it is hidden from lists and can no longer be attached to new documents.
+/// Documents that already reference it keep working.
+pub fn archive_address<R: AddressRepository>(repository: &R, id: i64) -> Result<(), AddressError> {
+    match repository.archive(id) {
+        Ok(true) => Ok(()),
+        Ok(false) => Err(AddressError::NotFound(id)),
+        Err(error) => Err(AddressError::Repository(error)),
+    }
+}
+
+pub fn restore_address<R: AddressRepository>(repository: &R, id: i64) -> Result<(), AddressError> {
+    match repository.restore(id) {
+        Ok(true) => Ok(()),
+        Ok(false) => Err(AddressError::NotFound(id)),
+        Err(error) => Err(AddressError::Repository(error)),
+    }
 }
 
-fn sanitize_address(dto: &AddressDto) -> Result<AddressDto, AddressError> {
+/// Trims the fields and checks the required ones, as done before an address is stored.
+pub fn sanitize_address(dto: &AddressDto) -> Result<AddressDto, AddressError> {
     fn sanitize_field(value: &Option<String>) -> Option<String> {
         value
             .as_ref()
@@ -148,11 +263,14 @@ fn sanitize_address(dto: &AddressDto) -> Result<AddressDto, AddressError> {
         street: sanitize_field(&dto.street),
         building: sanitize_field(&dto.building),
         room: sanitize_field(&dto.room),
+        uid: sanitize_field(&dto.uid),
     })
 }
 
 // ---------------- Organization Core API ----------------
-#[derive(Debug, Clone, PartialEq, Eq, Default)]
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(default)]
 pub struct OrganizationDto {
     pub full_name: Option<String>,
     pub abbreviated_name: Option<String>,
@@ -162,6 +280,8 @@ pub struct OrganizationDto {
     pub kpp: Option<String>,
     pub address_id: i64,
     pub email: Option<String>,
+    /// Stable identifier of a new row; a UUID is generated when it is empty.
+    pub uid: Option<String>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -187,7 +307,11 @@ impl std::error::Error for OrganizationRepositoryError {}
 
 pub trait OrganizationRepository {
     fn create(&self, dto: &OrganizationDto) -> Result<i64, OrganizationRepositoryError>;
-    fn list(&self) -> Result<Vec<Organization>, OrganizationRepositoryError>;
+    fn update(&self, id: i64, dto: &OrganizationDto) -> Result<bool, OrganizationRepositoryError>;
+    fn list(&self, options: &ListOptions)
+    -> Result<Vec<Organization>, OrganizationRepositoryError>;
+    fn archive(&self, id: i64) -> Result<bool, OrganizationRepositoryError>;
+    fn restore(&self, id: i64) -> Result<bool, OrganizationRepositoryError>;
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -197,6 +321,7 @@ pub enum OrganizationError {
     MissingInn,
     MissingKpp,
     MissingEmail,
+    NotFound(i64),
     Repository(OrganizationRepositoryError),
 }
 
@@ -208,6 +333,7 @@ impl std::fmt::Display for OrganizationError {
             Self::MissingInn => f.write_str("INN is required"),
             Self::MissingKpp => f.write_str("KPP is required"),
             Self::MissingEmail => f.write_str("Email is required"),
+            Self::NotFound(id) => write!(f, "Organization {id} not found"),
             Self::Repository(error) => write!(f, "Repository error: {error}"),
         }
     }
@@ -219,15 +345,33 @@ pub fn create_organization<R: OrganizationRepository>(
     repository: &R,
     dto: &OrganizationDto,
 ) -> Result<i64, OrganizationError> {
-    let sanitized = sanitize_organization(dto)?;
+    let mut sanitized = sanitize_organization(dto)?;
+    sanitized.uid.get_or_insert_with(crate::generate_uid);
     repository
         .create(&sanitized)
         .map_err(OrganizationError::Repository)
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub fn update_organization<R: OrganizationRepository>(
+    repository: &R,
+    id: i64,
+    dto: &OrganizationDto,
+) -> Result<(), OrganizationError> {
+    let sanitized = sanitize_organization(dto)?;
+    match repository.update(id, &sanitized) {
+        Ok(true) => Ok(()),
+        Ok(false) => Err(OrganizationError::NotFound(id)),
+        Err(error) => Err(OrganizationError::Repository(error)),
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct Organization {
     pub id: i64,
+    /// Stable identifier shared by all workstations, a UUID generated when the row is created.
+    #[serde(default)]
+    pub uid: String,
     pub full_name: String,
     pub abbreviated_name: String,
     pub ogrn: Option<String>,
@@ -236,15 +380,42 @@ pub struct Organization {
     pub kpp: String,
     pub address_id: i64,
     pub email: String,
+    pub archived_at: Option<String>,
 }
 
 pub fn list_organizations<R: OrganizationRepository>(
     repository: &R,
+    options: &ListOptions,
 ) -> Result<Vec<Organization>, OrganizationRepositoryError> {
-    repository.list()
+    repository.list(options)
+}
+
+/// Archives the organization so it is hidden from lists and can no longer be attached to new documents.
+/// Documents that already reference it keep working.
+pub fn archive_organization<R: OrganizationRepository>(
+    repository: &R,
+    id: i64,
+) -> Result<(), OrganizationError> {
+    match repository.archive(id) {
+        Ok(true) => Ok(()),
+        Ok(false) => Err(OrganizationError::NotFound(id)),
+        Err(error) => Err(OrganizationError::Repository(error)),
+    }
 }
 
-fn sanitize_organization(dto: &OrganizationDto) -> Result<OrganizationDto, OrganizationError> {
+pub fn restore_organization<R: OrganizationRepository>(
+    repository: &R,
+    id: i64,
+) -> Result<(), OrganizationError> {
+    match repository.restore(id) {
+        Ok(true) => Ok(()),
+        Ok(false) => Err(OrganizationError::NotFound(id)),
+        Err(error) => Err(OrganizationError::Repository(error)),
+    }
+}
+
+/// Trims the fields and checks the required ones, as done before an organization is stored.
+pub fn sanitize_organization(dto: &OrganizationDto) -> Result<OrganizationDto, OrganizationError> {
     fn sanitize_field(value: &Option<String>) -> Option<String> {
         value
             .as_ref()
@@ -302,11 +473,14 @@ fn sanitize_organization(dto: &OrganizationDto) -> Result<OrganizationDto, Organ
         kpp: Some(kpp),
         address_id: dto.address_id,
         email: Some(email),
+        uid: sanitize_field(&dto.uid),
     })
 }
 
 // ---------------- Entrepreneur Core API ----------------
-#[derive(Debug, Clone, PartialEq, Eq, Default)]
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(default)]
 pub struct EntrepreneurDto {
     pub surname: Option<String>,
     pub name: Option<String>,
@@ -315,6 +489,8 @@ pub struct EntrepreneurDto {
     pub inn: Option<String>,
     pub address_id: i64,
     pub email: Option<String>,
+    /// Stable identifier of a new row; a UUID is generated when it is empty.
+    pub uid: Option<String>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -340,7 +516,11 @@ impl std::error::Error for EntrepreneurRepositoryError {}
 
 pub trait EntrepreneurRepository {
     fn create(&self, dto: &EntrepreneurDto) -> Result<i64, EntrepreneurRepositoryError>;
-    fn list(&self) -> Result<Vec<Entrepreneur>, EntrepreneurRepositoryError>;
+    fn update(&self, id: i64, dto: &EntrepreneurDto) -> Result<bool, EntrepreneurRepositoryError>;
+    fn list(&self, options: &ListOptions)
+    -> Result<Vec<Entrepreneur>, EntrepreneurRepositoryError>;
+    fn archive(&self, id: i64) -> Result<bool, EntrepreneurRepositoryError>;
+    fn restore(&self, id: i64) -> Result<bool, EntrepreneurRepositoryError>;
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -349,6 +529,7 @@ pub enum EntrepreneurError {
     MissingName,
     MissingOgrnip,
     MissingInn,
+    NotFound(i64),
     Repository(EntrepreneurRepositoryError),
 }
 
@@ -359,6 +540,7 @@ impl std::fmt::Display for EntrepreneurError {
             Self::MissingName => f.write_str("Name is required"),
             Self::MissingOgrnip => f.write_str("OGRNIP is required"),
             Self::MissingInn => f.write_str("INN is required"),
+            Self::NotFound(id) => write!(f, "Entrepreneur {id} not found"),
             Self::Repository(error) => write!(f, "Repository error: {error}"),
         }
     }
@@ -370,15 +552,33 @@ pub fn create_entrepreneur<R: EntrepreneurRepository>(
     repository: &R,
     dto: &EntrepreneurDto,
 ) -> Result<i64, EntrepreneurError> {
-    let sanitized = sanitize_entrepreneur(dto)?;
+    let mut sanitized = sanitize_entrepreneur(dto)?;
+    sanitized.uid.get_or_insert_with(crate::generate_uid);
     repository
         .create(&sanitized)
         .map_err(EntrepreneurError::Repository)
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub fn update_entrepreneur<R: EntrepreneurRepository>(
+    repository: &R,
+    id: i64,
+    dto: &EntrepreneurDto,
+) -> Result<(), EntrepreneurError> {
+    let sanitized = sanitize_entrepreneur(dto)?;
+    match repository.update(id, &sanitized) {
+        Ok(true) => Ok(()),
+        Ok(false) => Err(EntrepreneurError::NotFound(id)),
+        Err(error) => Err(EntrepreneurError::Repository(error)),
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct Entrepreneur {
     pub id: i64,
+    /// Stable identifier shared by all workstations, a UUID generated when the row is created.
+    #[serde(default)]
+    pub uid: String,
     pub surname: String,
     pub name: String,
     pub patronymic: Option<String>,
@@ -386,15 +586,49 @@ pub struct Entrepreneur {
     pub inn: String,
     pub address_id: i64,
     pub email: Option<String>,
+    pub archived_at: Option<String>,
+}
+
+impl Entrepreneur {
+    /// Surname, name and patronymic.
+    pub fn full_name(&self) -> String {
+        full_name(&self.surname, &self.name, self.patronymic.as_deref())
+    }
 }
 
 pub fn list_entrepreneurs<R: EntrepreneurRepository>(
     repository: &R,
+    options: &ListOptions,
 ) -> Result<Vec<Entrepreneur>, EntrepreneurRepositoryError> {
-    repository.list()
+    repository.list(options)
+}
+
+/// Archives the entrepreneur so it is hidden from lists and can no longer be attached to new documents.
+/// Documents that already reference it keep working.
+pub fn archive_entrepreneur<R: EntrepreneurRepository>(
+    repository: &R,
+    id: i64,
+) -> Result<(), EntrepreneurError> {
+    match repository.archive(id) {
+        Ok(true) => Ok(()),
+        Ok(false) => Err(EntrepreneurError::NotFound(id)),
+        Err(error) => Err(EntrepreneurError::Repository(error)),
+    }
 }
 
-fn sanitize_entrepreneur(dto: &EntrepreneurDto) -> Result<EntrepreneurDto, EntrepreneurError> {
+pub fn restore_entrepreneur<R: EntrepreneurRepository>(
+    repository: &R,
+    id: i64,
+) -> Result<(), EntrepreneurError> {
+    match repository.restore(id) {
+        Ok(true) => Ok(()),
+        Ok(false) => Err(EntrepreneurError::NotFound(id)),
+        Err(error) => Err(EntrepreneurError::Repository(error)),
+    }
+}
+
+/// Trims the fields and checks the required ones, as done before an entrepreneur is stored.
+pub fn sanitize_entrepreneur(dto: &EntrepreneurDto) -> Result<EntrepreneurDto, EntrepreneurError> {
     fn sanitize_field(value: &Option<String>) -> Option<String> {
         value
             .as_ref()
@@ -443,12 +677,15 @@ fn sanitize_entrepreneur(dto: &EntrepreneurDto) -> Result<EntrepreneurDto, Entre
         inn: Some(inn),
         address_id: dto.address_id,
         email: sanitize_field(&dto.email),
+        uid: sanitize_field(&dto.uid),
     })
 }
 
 // ---------------- Person Core API ----------------
 
-#[derive(Debug, Clone, PartialEq, Eq, Default)]
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(default)]
 pub struct PersonDto {
     pub name: Option<String>,
     pub patronymic: Option<String>,
@@ -456,6 +693,8 @@ pub struct PersonDto {
     pub snils: Option<String>,
     pub email: Option<String>,
     pub address_id: i64,
+    /// Stable identifier of a new row; a UUID is generated when it is empty.
+    pub uid: Option<String>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -481,7 +720,10 @@ impl std::error::Error for PersonRepositoryError {}
 
 pub trait PersonRepository {
     fn create(&self, dto: &PersonDto) -> Result<i64, PersonRepositoryError>;
-    fn list(&self) -> Result<Vec<Person>, PersonRepositoryError>;
+    fn update(&self, id: i64, dto: &PersonDto) -> Result<bool, PersonRepositoryError>;
+    fn list(&self, options: &ListOptions) -> Result<Vec<Person>, PersonRepositoryError>;
+    fn archive(&self, id: i64) -> Result<bool, PersonRepositoryError>;
+    fn restore(&self, id: i64) -> Result<bool, PersonRepositoryError>;
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -490,6 +732,7 @@ pub enum PersonError {
     MissingSurname,
     MissingSnils,
     MissingEmail,
+    NotFound(i64),
     Repository(PersonRepositoryError),
 }
 
@@ -500,6 +743,7 @@ impl std::fmt::Display for PersonError {
             Self::MissingSurname => f.write_str("Surname is required"),
             Self::MissingSnils => f.write_str("SNILS is required"),
             Self::MissingEmail => f.write_str("Email is required"),
+            Self::NotFound(id) => write!(f, "Person {id} not found"),
             Self::Repository(error) => write!(f, "Repository error: {error}"),
         }
     }
@@ -511,31 +755,77 @@ pub fn create_person<R: PersonRepository>(
     repository: &R,
     dto: &PersonDto,
 ) -> Result<i64, PersonError> {
-    let sanitized = sanitize_person(dto)?;
+    let mut sanitized = sanitize_person(dto)?;
+    sanitized.uid.get_or_insert_with(crate::generate_uid);
 
     repository
         .create(&sanitized)
         .map_err(PersonError::Repository)
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub fn update_person<R: PersonRepository>(
+    repository: &R,
+    id: i64,
+    dto: &PersonDto,
+) -> Result<(), PersonError> {
+    let sanitized = sanitize_person(dto)?;
+    match repository.update(id, &sanitized) {
+        Ok(true) => Ok(()),
+        Ok(false) => Err(PersonError::NotFound(id)),
+        Err(error) => Err(PersonError::Repository(error)),
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct Person {
     pub id: i64,
+    /// Stable identifier shared by all workstations, a UUID generated when the row is created.
+    #[serde(default)]
+    pub uid: String,
     pub name: String,
     pub patronymic: Option<String>,
     pub surname: String,
     pub snils: String,
     pub email: String,
     pub address_id: i64,
+    pub archived_at: Option<String>,
+}
+
+impl Person {
+    /// Surname, name and patronymic.
+    pub fn full_name(&self) -> String {
+        full_name(&self.surname, &self.name, self.patronymic.as_deref())
+    }
 }
 
 pub fn list_persons<R: PersonRepository>(
     repository: &R,
+    options: &ListOptions,
 ) -> Result<Vec<Person>, PersonRepositoryError> {
-    repository.list()
+    repository.list(options)
+}
+
+/// Archives the person so it is hidden from lists and can no longer be attached to new documents.
+/// Documents that already reference it keep working.
+pub fn archive_person<R: PersonRepository>(repository: &R, id: i64) -> Result<(), PersonError> {
+    match repository.archive(id) {
+        Ok(true) => Ok(()),
+        Ok(false) => Err(PersonError::NotFound(id)),
+        Err(error) => Err(PersonError::Repository(error)),
+    }
+}
+
+pub fn restore_person<R: PersonRepository>(repository: &R, id: i64) -> Result<(), PersonError> {
+    match repository.restore(id) {
+        Ok(true) => Ok(()),
+        Ok(false) => Err(PersonError::NotFound(id)),
+        Err(error) => Err(PersonError::Repository(error)),
+    }
 }
 
-fn sanitize_person(dto: &PersonDto) -> Result<PersonDto, PersonError> {
+/// Trims the fields and checks the required ones, as done before a person is stored.
+pub fn sanitize_person(dto: &PersonDto) -> Result<PersonDto, PersonError> {
     fn sanitize_field(value: &Option<String>) -> Option<String> {
         value
             .as_ref()
@@ -583,6 +873,7 @@ fn sanitize_person(dto: &PersonDto) -> Result<PersonDto, PersonError> {
         snils: Some(snils),
         email: Some(email),
         address_id: dto.address_id,
+        uid: sanitize_field(&dto.uid),
     })
 }
 
@@ -623,6 +914,7 @@ mod tests {
             street: Some("Тверская".into()),
             building: Some("1".into()),
             room: Some("101".into()),
+            uid: None,
         };
 
         let formatted = format_address(&dto);
@@ -665,6 +957,46 @@ mod tests {
         assert_eq!(captured.region_code.as_deref(), Some("77"));
         assert_eq!(captured.city.as_deref(), Some("Москва"));
         assert_eq!(captured.street, None);
+        assert!(captured.uid.is_some(), "a uid should be generated");
+    }
+
+    #[test]
+    fn update_address_sanitizes_fields_and_reports_missing_rows() {
+        let repository = RecordingRepository::default();
+        let dto = AddressDto {
+            region_code: Some(" 50 ".into()),
+            city: Some(" Химки ".into()),
+            ..Default::default()
+        };
+
+        assert_eq!(update_address(&repository, 42, &dto), Ok(()));
+        let captured = repository.last().expect("repository should capture dto");
+        assert_eq!(captured.region_code.as_deref(), Some("50"));
+        assert_eq!(captured.city.as_deref(), Some("Химки"));
+
+        assert_eq!(
+            update_address(&repository, 7, &dto),
+            Err(AddressError::NotFound(7))
+        );
+        assert_eq!(
+            update_address(&repository, 42, &AddressDto::default()),
+            Err(AddressError::MissingRegionCode)
+        );
+    }
+
+    #[test]
+    fn archive_address_reports_missing_rows() {
+        let repository = RecordingRepository::default();
+
+        assert_eq!(archive_address(&repository, 42), Ok(()));
+        assert_eq!(
+            archive_address(&repository, 7),
+            Err(AddressError::NotFound(7))
+        );
+        assert_eq!(
+            restore_address(&repository, 7),
+            Err(AddressError::NotFound(7))
+        );
     }
 
     #[derive(Default)]
@@ -684,8 +1016,21 @@ mod tests {
             Ok(42)
         }
 
-        fn list(&self) -> Result<Vec<Address>, AddressRepositoryError> {
+        fn update(&self, id: i64, dto: &AddressDto) -> Result<bool, AddressRepositoryError> {
+            *self.last.borrow_mut() = Some(dto.clone());
+            Ok(id == 42)
+        }
+
+        fn list(&self, _options: &ListOptions) -> Result<Vec<Address>, AddressRepositoryError> {
             Ok(Vec::new())
         }
+
+        fn archive(&self, id: i64) -> Result<bool, AddressRepositoryError> {
+            Ok(id == 42)
+        }
+
+        fn restore(&self, id: i64) -> Result<bool, AddressRepositoryError> {
+            Ok(id == 42)
+        }
     }
 }
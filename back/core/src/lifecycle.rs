@@ -0,0 +1,373 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    Document, DocumentError, DocumentRepository, DocumentSection, SignatoryRepository, SigningRole,
+};
+
+// ---------------- Document Lifecycle ----------------
+
+/// Status of a document. A document moves
+/// `draft → ready_for_review → approved → issued → superseded`, can be returned from review to
+/// the draft stage and can be cancelled at any point before it is superseded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum DocumentStatus {
+    #[default]
+    Draft,
+    ReadyForReview,
+    Approved,
+    Issued,
+    Superseded,
+    Cancelled,
+}
+
+impl DocumentStatus {
+    pub const ALL: [DocumentStatus; 6] = [
+        Self::Draft,
+        Self::ReadyForReview,
+        Self::Approved,
+        Self::Issued,
+        Self::Superseded,
+        Self::Cancelled,
+    ];
+
+    /// Value stored in the `document.status` column.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Draft => "draft",
+            Self::ReadyForReview => "ready_for_review",
+            Self::Approved => "approved",
+            Self::Issued => "issued",
+            Self::Superseded => "superseded",
+            Self::Cancelled => "cancelled",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|status| status.as_str() == value)
+    }
+
+    pub fn can_transition_to(self, next: Self) -> bool {
+        matches!(
+            (self, next),
+            (Self::Draft, Self::ReadyForReview)
+                | (Self::ReadyForReview, Self::Draft)
+                | (Self::ReadyForReview, Self::Approved)
+                | (Self::Approved, Self::Issued)
+                | (Self::Issued, Self::Superseded)
+                | (
+                    Self::Draft | Self::ReadyForReview | Self::Approved | Self::Issued,
+                    Self::Cancelled
+                )
+        )
+    }
+
+    /// Only drafts can be edited; every later stage is read-only.
+    pub fn is_editable(self) -> bool {
+        self == Self::Draft
+    }
+}
+
+/// Something that must be filled in before a document can move past the draft stage.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "issue", rename_all = "snake_case")]
+pub enum CompletenessIssue {
+    MissingNumber,
+    MissingDate,
+    MissingDeveloper,
+    MissingSection {
+        section: DocumentSection,
+    },
+    /// Checked on approval: the ZNP is approved by the developer and agreed by the designer.
+    MissingSignatory {
+        role: SigningRole,
+    },
+}
+
+impl CompletenessIssue {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::MissingNumber => "number is required",
+            Self::MissingDate => "date is required",
+            Self::MissingDeveloper => "developer is required",
+            Self::MissingSection { section } => match section {
+                DocumentSection::ConstructionObject => "construction object section is required",
+                DocumentSection::Indicators => "indicators section is required",
+                DocumentSection::Requirements => "requirements section is required",
+                DocumentSection::SourceDocuments => "source documents section is required",
+            },
+            Self::MissingSignatory { role } => match role {
+                SigningRole::Approval => "approving signatory is required",
+                SigningRole::Agreement => "agreeing signatory is required",
+            },
+        }
+    }
+}
+
+/// Lists what is missing for the document to be reviewed, approved and issued. `sections` are
+/// the sections the document has filled in; the required ones depend on the document type. The
+/// author is required when the document is created and is always present.
+pub fn check_completeness(
+    document: &Document,
+    sections: &[DocumentSection],
+) -> Vec<CompletenessIssue> {
+    let mut issues = Vec::new();
+
+    if document.number.is_none() {
+        issues.push(CompletenessIssue::MissingNumber);
+    }
+    if document.date.is_none() {
+        issues.push(CompletenessIssue::MissingDate);
+    }
+    if document.developer.is_none() {
+        issues.push(CompletenessIssue::MissingDeveloper);
+    }
+    if let Some(document_type) = document.document_type {
+        issues.extend(
+            document_type
+                .required_sections()
+                .iter()
+                .filter(|section| !sections.contains(section))
+                .map(|&section| CompletenessIssue::MissingSection { section }),
+        );
+    }
+
+    issues
+}
+
+pub fn document_completeness<D: DocumentRepository>(
+    documents: &D,
+    id: i64,
+) -> Result<Vec<CompletenessIssue>, DocumentError> {
+    let document = crate::find_document(documents, id)?;
+    let sections = documents.sections(id).map_err(DocumentError::Repository)?;
+
+    Ok(check_completeness(&document, &sections))
+}
+
+pub(crate) fn ensure_complete<D: DocumentRepository>(
+    documents: &D,
+    document: &Document,
+) -> Result<(), DocumentError> {
+    let sections = documents
+        .sections(document.id)
+        .map_err(DocumentError::Repository)?;
+    let issues = check_completeness(document, &sections);
+    if issues.is_empty() {
+        Ok(())
+    } else {
+        Err(DocumentError::Incomplete(issues))
+    }
+}
+
+pub(crate) fn ensure_transition(
+    document: &Document,
+    to: DocumentStatus,
+) -> Result<(), DocumentError> {
+    if document.status.can_transition_to(to) {
+        Ok(())
+    } else {
+        Err(DocumentError::InvalidTransition {
+            id: document.id,
+            from: document.status,
+            to,
+        })
+    }
+}
+
+fn transition<D: DocumentRepository>(
+    documents: &D,
+    document: &Document,
+    to: DocumentStatus,
+) -> Result<(), DocumentError> {
+    ensure_transition(document, to)?;
+
+    match documents.set_status(document.id, document.status, to) {
+        Ok(true) => Ok(()),
+        // Somebody moved the document in the meantime.
+        Ok(false) => Err(DocumentError::InvalidTransition {
+            id: document.id,
+            from: document.status,
+            to,
+        }),
+        Err(error) => Err(DocumentError::Repository(error)),
+    }
+}
+
+pub fn submit_for_review<D: DocumentRepository>(
+    documents: &D,
+    id: i64,
+) -> Result<(), DocumentError> {
+    let document = crate::find_document(documents, id)?;
+    ensure_transition(&document, DocumentStatus::ReadyForReview)?;
+    ensure_complete(documents, &document)?;
+    transition(documents, &document, DocumentStatus::ReadyForReview)
+}
+
+pub fn return_to_draft<D: DocumentRepository>(documents: &D, id: i64) -> Result<(), DocumentError> {
+    let document = crate::find_document(documents, id)?;
+    transition(documents, &document, DocumentStatus::Draft)
+}
+
+/// Approves a reviewed document. Besides being complete, the document must have a signatory
+/// in every [`SigningRole`].
+pub fn approve_document<D>(documents: &D, id: i64) -> Result<(), DocumentError>
+where
+    D: DocumentRepository + SignatoryRepository,
+{
+    let document = crate::find_document(documents, id)?;
+    ensure_transition(&document, DocumentStatus::Approved)?;
+    ensure_complete(documents, &document)?;
+
+    let signatories = documents.signatories(id).map_err(crate::section_error)?;
+    let missing: Vec<CompletenessIssue> = crate::missing_signing_roles(&signatories)
+        .into_iter()
+        .map(|role| CompletenessIssue::MissingSignatory { role })
+        .collect();
+    if !missing.is_empty() {
+        return Err(DocumentError::Incomplete(missing));
+    }
+
+    transition(documents, &document, DocumentStatus::Approved)
+}
+
+pub fn cancel_document<D: DocumentRepository>(documents: &D, id: i64) -> Result<(), DocumentError> {
+    let document = crate::find_document(documents, id)?;
+    transition(documents, &document, DocumentStatus::Cancelled)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::{self, MemoryDocuments};
+    use crate::{DocumentDto, issue_document, issue_revision, update_document};
+
+    #[test]
+    fn status_round_trips_through_storage_value() {
+        for status in DocumentStatus::ALL {
+            assert_eq!(DocumentStatus::parse(status.as_str()), Some(status));
+        }
+        assert_eq!(DocumentStatus::parse("archived"), None);
+    }
+
+    #[test]
+    fn document_moves_through_the_lifecycle() {
+        let documents = MemoryDocuments::default();
+        let references = memory::references();
+        documents.put(memory::document(1));
+        documents.sign(1);
+
+        assert_eq!(
+            approve_document(&documents, 1),
+            Err(DocumentError::InvalidTransition {
+                id: 1,
+                from: DocumentStatus::Draft,
+                to: DocumentStatus::Approved,
+            })
+        );
+
+        submit_for_review(&documents, 1).expect("submit");
+        approve_document(&documents, 1).expect("approve");
+        issue_document(&documents, &references, 1).expect("issue");
+
+        let issued = documents.get(1);
+        assert_eq!(issued.status, DocumentStatus::Issued);
+        assert!(issued.submitted_at.is_some());
+        assert!(issued.approved_at.is_some());
+        assert!(issued.issued_at.is_some());
+
+        let revision_id = issue_revision(&documents, 1, "uid-2").expect("revision");
+        submit_for_review(&documents, revision_id).expect("submit revision");
+        approve_document(&documents, revision_id).expect("approve revision");
+        issue_document(&documents, &references, revision_id).expect("issue revision");
+
+        assert_eq!(documents.get(1).status, DocumentStatus::Superseded);
+        assert_eq!(documents.get(revision_id).status, DocumentStatus::Issued);
+    }
+
+    #[test]
+    fn incomplete_documents_cannot_be_submitted() {
+        let documents = MemoryDocuments::default();
+        let mut document = memory::document(1);
+        document.number = None;
+        document.developer = None;
+        documents.put(document);
+
+        assert_eq!(
+            submit_for_review(&documents, 1),
+            Err(DocumentError::Incomplete(vec![
+                CompletenessIssue::MissingNumber,
+                CompletenessIssue::MissingDeveloper,
+            ]))
+        );
+        assert_eq!(documents.get(1).status, DocumentStatus::Draft);
+    }
+
+    #[test]
+    fn typed_documents_require_their_sections() {
+        let documents = MemoryDocuments::default();
+        let mut document = memory::document(1);
+        document.document_type = Some(crate::DocumentType::CapitalRepair);
+        documents.put(document);
+        documents.fill(1, DocumentSection::Requirements);
+
+        assert_eq!(
+            submit_for_review(&documents, 1),
+            Err(DocumentError::Incomplete(vec![
+                CompletenessIssue::MissingSection {
+                    section: DocumentSection::ConstructionObject
+                },
+                CompletenessIssue::MissingSection {
+                    section: DocumentSection::SourceDocuments
+                },
+            ]))
+        );
+
+        documents.fill(1, DocumentSection::ConstructionObject);
+        documents.fill(1, DocumentSection::SourceDocuments);
+        submit_for_review(&documents, 1).expect("all required sections are filled");
+    }
+
+    #[test]
+    fn documents_past_the_draft_stage_are_read_only() {
+        let documents = MemoryDocuments::default();
+        let references = memory::references();
+        documents.put(memory::document(1));
+        submit_for_review(&documents, 1).expect("submit");
+
+        let dto = DocumentDto {
+            name: Some("Другое название".into()),
+            author: Some(crate::DocumentAuthor::Organization { id: 3 }),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            update_document(&documents, &references, 1, &dto),
+            Err(DocumentError::ReadOnly(1))
+        );
+
+        return_to_draft(&documents, 1).expect("return to draft");
+        update_document(&documents, &references, 1, &dto).expect("drafts are editable");
+        assert_eq!(documents.get(1).name, "Другое название");
+    }
+
+    #[test]
+    fn cancelled_documents_stay_cancelled() {
+        let documents = MemoryDocuments::default();
+        documents.put(memory::document(1));
+
+        cancel_document(&documents, 1).expect("cancel");
+
+        assert_eq!(
+            submit_for_review(&documents, 1),
+            Err(DocumentError::InvalidTransition {
+                id: 1,
+                from: DocumentStatus::Cancelled,
+                to: DocumentStatus::ReadyForReview,
+            })
+        );
+    }
+}
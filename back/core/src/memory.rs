@@ -0,0 +1,888 @@
+//! In-memory repositories shared by the unit tests of the document modules.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+use crate::{
+    Address, ConstructionObject, ConstructionObjectDto, ConstructionObjectRepository,
+    ConstructionObjectRepositoryError, Document, DocumentAuthor, DocumentDto, DocumentRepository,
+    DocumentRepositoryError, DocumentSection, DocumentSnapshot, DocumentSourceRepository,
+    DocumentStatus, EntityRef, NumberingScheme, Organization, Person, ReferenceEntity,
+    ReferenceRepository, ReferenceRepositoryError, ReferenceState, RequirementCategory,
+    RequirementRepository, RequirementRepositoryError, RequirementSection, RequirementSectionDto,
+    RequirementTemplate, RequirementTemplateDto, RequirementTemplateRepository,
+    RequirementTemplateRepositoryError, Signatory, SignatoryDto, SignatoryRepository,
+    SignatoryRepositoryError, SigningRole, SourceDocument, SourceDocumentKind,
+    SourceDocumentRepositoryError, TechnicalIndicator, TechnicalIndicatorDto,
+    TechnicalIndicatorRepository, TechnicalIndicatorRepositoryError, WorkType,
+};
+
+const NOW: &str = "2026-10-01 09:00:00";
+
+#[derive(Default)]
+pub(crate) struct MemoryDocuments {
+    pub documents: RefCell<Vec<Document>>,
+    pub snapshots: RefCell<Vec<(i64, DocumentSnapshot)>>,
+    pub sequences: RefCell<HashMap<(String, i32), i64>>,
+    pub schemes: RefCell<Vec<NumberingScheme>>,
+    pub sections: RefCell<Vec<(i64, DocumentSection)>>,
+    pub objects: RefCell<Vec<ConstructionObject>>,
+    pub indicators: RefCell<Vec<TechnicalIndicator>>,
+    pub requirements: RefCell<Vec<RequirementSection>>,
+    pub sources: RefCell<Vec<(i64, i64)>>,
+    pub signatories: RefCell<Vec<Signatory>>,
+}
+
+impl MemoryDocuments {
+    pub fn get(&self, id: i64) -> Document {
+        self.find(id)
+            .expect("memory repository does not fail")
+            .expect("document should exist")
+    }
+
+    pub fn fill(&self, id: i64, section: DocumentSection) {
+        self.sections.borrow_mut().push((id, section));
+    }
+
+    /// Lets person 5 sign the document in every role.
+    pub fn sign(&self, id: i64) {
+        for role in SigningRole::ALL {
+            self.add_signatory(
+                id,
+                &SignatoryDto {
+                    person_id: 5,
+                    position: Some("Главный инженер".into()),
+                    role: Some(role),
+                    signed_on: None,
+                },
+            )
+            .expect("memory repository does not fail");
+        }
+    }
+
+    /// Section writes are refused once the document leaves the draft stage, like in SQLite.
+    fn is_draft(&self, document_id: i64) -> bool {
+        self.documents
+            .borrow()
+            .iter()
+            .any(|document| document.id == document_id && document.status.is_editable())
+    }
+
+    pub fn put(&self, document: Document) {
+        let mut documents = self.documents.borrow_mut();
+        documents.retain(|existing| existing.id != document.id);
+        documents.push(document);
+        documents.sort_by_key(|document| document.id);
+    }
+}
+
+impl DocumentRepository for MemoryDocuments {
+    fn create(&self, dto: &DocumentDto) -> Result<i64, DocumentRepositoryError> {
+        let id = self.documents.borrow().len() as i64 + 1;
+        let mut document = document(id);
+        document.uid = dto.uid.clone().unwrap_or_default();
+        document.change_mark = dto.change_mark.clone();
+        document.previous_revision_id = dto.previous_revision_id;
+        apply(&mut document, dto);
+        if let Some(numbering) = &dto.numbering {
+            let scheme = self
+                .numbering_schemes()?
+                .into_iter()
+                .find(|scheme| scheme.prefix == numbering.prefix)
+                .ok_or_else(|| DocumentRepositoryError::storage("unknown numbering scheme"))?;
+            let mut sequences = self.sequences.borrow_mut();
+            let counter = sequences
+                .entry((numbering.prefix.clone(), numbering.year))
+                .or_default();
+            *counter += 1;
+            document.number = Some(scheme.format(numbering.year, *counter));
+        }
+        self.documents.borrow_mut().push(document);
+        Ok(id)
+    }
+
+    fn update(&self, id: i64, dto: &DocumentDto) -> Result<bool, DocumentRepositoryError> {
+        let mut documents = self.documents.borrow_mut();
+        match documents.iter_mut().find(|document| document.id == id) {
+            Some(document) if document.status.is_editable() => {
+                apply(document, dto);
+                Ok(true)
+            }
+            _ => Ok(false),
+        }
+    }
+
+    fn delete_draft(&self, id: i64) -> Result<bool, DocumentRepositoryError> {
+        if !self.is_draft(id) {
+            return Ok(false);
+        }
+        self.documents
+            .borrow_mut()
+            .retain(|document| document.id != id);
+        self.sections
+            .borrow_mut()
+            .retain(|(document_id, _)| *document_id != id);
+        self.objects
+            .borrow_mut()
+            .retain(|object| object.document_id != id);
+        self.indicators
+            .borrow_mut()
+            .retain(|indicator| indicator.document_id != id);
+        self.requirements
+            .borrow_mut()
+            .retain(|requirement| requirement.document_id != id);
+        self.sources
+            .borrow_mut()
+            .retain(|(document_id, _)| *document_id != id);
+        self.signatories
+            .borrow_mut()
+            .retain(|signatory| signatory.document_id != id);
+        Ok(true)
+    }
+
+    fn find(&self, id: i64) -> Result<Option<Document>, DocumentRepositoryError> {
+        Ok(self
+            .documents
+            .borrow()
+            .iter()
+            .find(|document| document.id == id)
+            .cloned())
+    }
+
+    fn list(&self) -> Result<Vec<Document>, DocumentRepositoryError> {
+        Ok(self.documents.borrow().clone())
+    }
+
+    fn next_revision(&self, id: i64) -> Result<Option<i64>, DocumentRepositoryError> {
+        Ok(self
+            .documents
+            .borrow()
+            .iter()
+            .find(|document| document.previous_revision_id == Some(id))
+            .map(|document| document.id))
+    }
+
+    fn set_status(
+        &self,
+        id: i64,
+        from: DocumentStatus,
+        to: DocumentStatus,
+    ) -> Result<bool, DocumentRepositoryError> {
+        let mut documents = self.documents.borrow_mut();
+        match documents.iter_mut().find(|document| document.id == id) {
+            Some(document) if document.status == from => {
+                document.status = to;
+                let stamp = Some(NOW.to_string());
+                match to {
+                    DocumentStatus::Draft => {
+                        document.submitted_at = None;
+                        document.approved_at = None;
+                    }
+                    DocumentStatus::ReadyForReview => document.submitted_at = stamp,
+                    DocumentStatus::Approved => document.approved_at = stamp,
+                    DocumentStatus::Issued => document.issued_at = stamp,
+                    DocumentStatus::Superseded => document.superseded_at = stamp,
+                    DocumentStatus::Cancelled => document.cancelled_at = stamp,
+                }
+                Ok(true)
+            }
+            _ => Ok(false),
+        }
+    }
+
+    fn set_template(&self, id: i64, template: bool) -> Result<bool, DocumentRepositoryError> {
+        let mut documents = self.documents.borrow_mut();
+        match documents.iter_mut().find(|document| document.id == id) {
+            Some(document) => {
+                document.is_template = template;
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    fn issue(&self, id: i64, snapshot: &DocumentSnapshot) -> Result<(), DocumentRepositoryError> {
+        if !self.set_status(id, DocumentStatus::Approved, DocumentStatus::Issued)? {
+            return Err(DocumentRepositoryError::storage("Document is not approved"));
+        }
+        self.snapshots.borrow_mut().push((id, snapshot.clone()));
+        if let Some(previous_id) = self.get(id).previous_revision_id {
+            self.set_status(
+                previous_id,
+                DocumentStatus::Issued,
+                DocumentStatus::Superseded,
+            )?;
+        }
+        Ok(())
+    }
+
+    fn snapshot(&self, id: i64) -> Result<Option<DocumentSnapshot>, DocumentRepositoryError> {
+        Ok(self
+            .snapshots
+            .borrow()
+            .iter()
+            .find(|(document_id, _)| *document_id == id)
+            .map(|(_, snapshot)| snapshot.clone()))
+    }
+
+    fn sections(&self, id: i64) -> Result<Vec<DocumentSection>, DocumentRepositoryError> {
+        let mut sections: Vec<DocumentSection> = self
+            .sections
+            .borrow()
+            .iter()
+            .filter(|(document_id, _)| *document_id == id)
+            .map(|(_, section)| *section)
+            .collect();
+        if self.find_by_document(id).ok().flatten().is_some() {
+            sections.push(DocumentSection::ConstructionObject);
+        }
+        if self
+            .indicators
+            .borrow()
+            .iter()
+            .any(|indicator| indicator.document_id == id)
+        {
+            sections.push(DocumentSection::Indicators);
+        }
+        if self
+            .requirements
+            .borrow()
+            .iter()
+            .any(|requirement| requirement.document_id == id)
+        {
+            sections.push(DocumentSection::Requirements);
+        }
+        if self
+            .sources
+            .borrow()
+            .iter()
+            .any(|(document_id, _)| *document_id == id)
+        {
+            sections.push(DocumentSection::SourceDocuments);
+        }
+        Ok(sections)
+    }
+
+    /// The default scheme is seeded by the migration in SQLite and always present here.
+    fn numbering_schemes(&self) -> Result<Vec<NumberingScheme>, DocumentRepositoryError> {
+        let mut schemes = vec![NumberingScheme::default()];
+        schemes.extend(self.schemes.borrow().iter().cloned());
+        Ok(schemes)
+    }
+
+    fn create_numbering_scheme(
+        &self,
+        scheme: &NumberingScheme,
+    ) -> Result<bool, DocumentRepositoryError> {
+        if self
+            .numbering_schemes()?
+            .iter()
+            .any(|existing| existing.prefix == scheme.prefix)
+        {
+            return Ok(false);
+        }
+        self.schemes.borrow_mut().push(scheme.clone());
+        Ok(true)
+    }
+}
+
+impl ConstructionObjectRepository for MemoryDocuments {
+    fn save(
+        &self,
+        document_id: i64,
+        dto: &ConstructionObjectDto,
+    ) -> Result<Option<i64>, ConstructionObjectRepositoryError> {
+        if !self.is_draft(document_id) {
+            return Ok(None);
+        }
+        let mut objects = self.objects.borrow_mut();
+        objects.retain(|object| object.document_id != document_id);
+        let id = document_id * 100;
+        objects.push(ConstructionObject {
+            id,
+            document_id,
+            name: dto.name.clone().unwrap_or_default(),
+            purpose_code: dto.purpose_code.clone().unwrap_or_default(),
+            address_id: dto.address_id,
+            cadastral_numbers: dto.cadastral_numbers.clone(),
+            work_type: dto.work_type.unwrap_or(WorkType::Construction),
+        });
+        Ok(Some(id))
+    }
+
+    fn find_by_document(
+        &self,
+        document_id: i64,
+    ) -> Result<Option<ConstructionObject>, ConstructionObjectRepositoryError> {
+        Ok(self
+            .objects
+            .borrow()
+            .iter()
+            .find(|object| object.document_id == document_id)
+            .cloned())
+    }
+}
+
+impl TechnicalIndicatorRepository for MemoryDocuments {
+    fn add_indicator(
+        &self,
+        document_id: i64,
+        dto: &TechnicalIndicatorDto,
+    ) -> Result<Option<i64>, TechnicalIndicatorRepositoryError> {
+        if !self.is_draft(document_id) {
+            return Ok(None);
+        }
+        let mut indicators = self.indicators.borrow_mut();
+        let id = indicators
+            .iter()
+            .map(|indicator| indicator.id)
+            .max()
+            .unwrap_or(0)
+            + 1;
+        let position = indicators
+            .iter()
+            .filter(|indicator| indicator.document_id == document_id)
+            .count() as i64;
+        indicators.push(TechnicalIndicator {
+            id,
+            document_id,
+            position,
+            name: dto.name.clone().unwrap_or_default(),
+            value: dto.value.clone().unwrap_or_default(),
+            unit_okei: dto.unit_okei.clone().unwrap_or_default(),
+        });
+        Ok(Some(id))
+    }
+
+    fn update_indicator(
+        &self,
+        id: i64,
+        dto: &TechnicalIndicatorDto,
+    ) -> Result<bool, TechnicalIndicatorRepositoryError> {
+        let mut indicators = self.indicators.borrow_mut();
+        let Some(indicator) = indicators
+            .iter_mut()
+            .find(|indicator| indicator.id == id && self.is_draft(indicator.document_id))
+        else {
+            return Ok(false);
+        };
+        indicator.name = dto.name.clone().unwrap_or_default();
+        indicator.value = dto.value.clone().unwrap_or_default();
+        indicator.unit_okei = dto.unit_okei.clone().unwrap_or_default();
+        Ok(true)
+    }
+
+    fn delete_indicator(&self, id: i64) -> Result<bool, TechnicalIndicatorRepositoryError> {
+        let mut indicators = self.indicators.borrow_mut();
+        let count = indicators.len();
+        indicators.retain(|indicator| indicator.id != id || !self.is_draft(indicator.document_id));
+        Ok(indicators.len() < count)
+    }
+
+    fn find_indicator(
+        &self,
+        id: i64,
+    ) -> Result<Option<TechnicalIndicator>, TechnicalIndicatorRepositoryError> {
+        Ok(self
+            .indicators
+            .borrow()
+            .iter()
+            .find(|indicator| indicator.id == id)
+            .cloned())
+    }
+
+    fn indicators(
+        &self,
+        document_id: i64,
+    ) -> Result<Vec<TechnicalIndicator>, TechnicalIndicatorRepositoryError> {
+        let mut indicators: Vec<TechnicalIndicator> = self
+            .indicators
+            .borrow()
+            .iter()
+            .filter(|indicator| indicator.document_id == document_id)
+            .cloned()
+            .collect();
+        indicators.sort_by_key(|indicator| indicator.position);
+        Ok(indicators)
+    }
+
+    fn reorder_indicators(
+        &self,
+        document_id: i64,
+        ids: &[i64],
+    ) -> Result<bool, TechnicalIndicatorRepositoryError> {
+        if !self.is_draft(document_id) {
+            return Ok(false);
+        }
+        for indicator in self.indicators.borrow_mut().iter_mut() {
+            if let Some(position) = ids.iter().position(|id| *id == indicator.id) {
+                indicator.position = position as i64;
+            }
+        }
+        Ok(true)
+    }
+}
+
+impl RequirementRepository for MemoryDocuments {
+    fn add_requirement(
+        &self,
+        document_id: i64,
+        dto: &RequirementSectionDto,
+    ) -> Result<Option<i64>, RequirementRepositoryError> {
+        if !self.is_draft(document_id) {
+            return Ok(None);
+        }
+        let mut requirements = self.requirements.borrow_mut();
+        let id = requirements
+            .iter()
+            .map(|requirement| requirement.id)
+            .max()
+            .unwrap_or(0)
+            + 1;
+        let position = requirements
+            .iter()
+            .filter(|requirement| requirement.document_id == document_id)
+            .count() as i64;
+        requirements.push(RequirementSection {
+            id,
+            document_id,
+            position,
+            category: dto.category.unwrap_or(RequirementCategory::Architecture),
+            title: dto.title.clone().unwrap_or_default(),
+            text: dto.text.clone().unwrap_or_default(),
+            template_id: dto.template_id,
+        });
+        Ok(Some(id))
+    }
+
+    fn update_requirement(
+        &self,
+        id: i64,
+        dto: &RequirementSectionDto,
+    ) -> Result<bool, RequirementRepositoryError> {
+        let mut requirements = self.requirements.borrow_mut();
+        let Some(requirement) = requirements
+            .iter_mut()
+            .find(|requirement| requirement.id == id && self.is_draft(requirement.document_id))
+        else {
+            return Ok(false);
+        };
+        requirement.category = dto.category.unwrap_or(requirement.category);
+        requirement.title = dto.title.clone().unwrap_or_default();
+        requirement.text = dto.text.clone().unwrap_or_default();
+        Ok(true)
+    }
+
+    fn delete_requirement(&self, id: i64) -> Result<bool, RequirementRepositoryError> {
+        let mut requirements = self.requirements.borrow_mut();
+        let count = requirements.len();
+        requirements
+            .retain(|requirement| requirement.id != id || !self.is_draft(requirement.document_id));
+        Ok(requirements.len() < count)
+    }
+
+    fn find_requirement(
+        &self,
+        id: i64,
+    ) -> Result<Option<RequirementSection>, RequirementRepositoryError> {
+        Ok(self
+            .requirements
+            .borrow()
+            .iter()
+            .find(|requirement| requirement.id == id)
+            .cloned())
+    }
+
+    fn requirements(
+        &self,
+        document_id: i64,
+    ) -> Result<Vec<RequirementSection>, RequirementRepositoryError> {
+        let mut requirements: Vec<RequirementSection> = self
+            .requirements
+            .borrow()
+            .iter()
+            .filter(|requirement| requirement.document_id == document_id)
+            .cloned()
+            .collect();
+        requirements.sort_by_key(|requirement| requirement.position);
+        Ok(requirements)
+    }
+
+    fn reorder_requirements(
+        &self,
+        document_id: i64,
+        ids: &[i64],
+    ) -> Result<bool, RequirementRepositoryError> {
+        if !self.is_draft(document_id) {
+            return Ok(false);
+        }
+        for requirement in self.requirements.borrow_mut().iter_mut() {
+            if let Some(position) = ids.iter().position(|id| *id == requirement.id) {
+                requirement.position = position as i64;
+            }
+        }
+        Ok(true)
+    }
+}
+
+impl DocumentSourceRepository for MemoryDocuments {
+    fn attach_source(
+        &self,
+        document_id: i64,
+        source_id: i64,
+    ) -> Result<bool, SourceDocumentRepositoryError> {
+        let mut sources = self.sources.borrow_mut();
+        if !self.is_draft(document_id) || sources.contains(&(document_id, source_id)) {
+            return Ok(false);
+        }
+        sources.push((document_id, source_id));
+        Ok(true)
+    }
+
+    fn detach_source(
+        &self,
+        document_id: i64,
+        source_id: i64,
+    ) -> Result<bool, SourceDocumentRepositoryError> {
+        if !self.is_draft(document_id) {
+            return Ok(false);
+        }
+        let mut sources = self.sources.borrow_mut();
+        let count = sources.len();
+        sources.retain(|link| *link != (document_id, source_id));
+        Ok(sources.len() < count)
+    }
+
+    fn source_ids(&self, document_id: i64) -> Result<Vec<i64>, SourceDocumentRepositoryError> {
+        Ok(self
+            .sources
+            .borrow()
+            .iter()
+            .filter(|(id, _)| *id == document_id)
+            .map(|(_, source_id)| *source_id)
+            .collect())
+    }
+
+    fn documents_using_source(
+        &self,
+        source_id: i64,
+    ) -> Result<Vec<i64>, SourceDocumentRepositoryError> {
+        Ok(self
+            .sources
+            .borrow()
+            .iter()
+            .filter(|(_, id)| *id == source_id)
+            .map(|(document_id, _)| *document_id)
+            .collect())
+    }
+}
+
+impl SignatoryRepository for MemoryDocuments {
+    fn add_signatory(
+        &self,
+        document_id: i64,
+        dto: &SignatoryDto,
+    ) -> Result<Option<i64>, SignatoryRepositoryError> {
+        if !self.is_draft(document_id) {
+            return Ok(None);
+        }
+        let mut signatories = self.signatories.borrow_mut();
+        let id = signatories
+            .iter()
+            .map(|signatory| signatory.id)
+            .max()
+            .unwrap_or(0)
+            + 1;
+        signatories.push(Signatory {
+            id,
+            document_id,
+            person_id: dto.person_id,
+            position: dto.position.clone().unwrap_or_default(),
+            role: dto.role.unwrap_or(SigningRole::Approval),
+            signed_on: dto.signed_on.clone(),
+        });
+        Ok(Some(id))
+    }
+
+    fn update_signatory(
+        &self,
+        id: i64,
+        dto: &SignatoryDto,
+    ) -> Result<bool, SignatoryRepositoryError> {
+        let mut signatories = self.signatories.borrow_mut();
+        let Some(signatory) = signatories
+            .iter_mut()
+            .find(|signatory| signatory.id == id && self.is_draft(signatory.document_id))
+        else {
+            return Ok(false);
+        };
+        signatory.person_id = dto.person_id;
+        signatory.position = dto.position.clone().unwrap_or_default();
+        signatory.role = dto.role.unwrap_or(signatory.role);
+        signatory.signed_on = dto.signed_on.clone();
+        Ok(true)
+    }
+
+    fn delete_signatory(&self, id: i64) -> Result<bool, SignatoryRepositoryError> {
+        let mut signatories = self.signatories.borrow_mut();
+        let count = signatories.len();
+        signatories.retain(|signatory| signatory.id != id || !self.is_draft(signatory.document_id));
+        Ok(signatories.len() < count)
+    }
+
+    fn find_signatory(&self, id: i64) -> Result<Option<Signatory>, SignatoryRepositoryError> {
+        Ok(self
+            .signatories
+            .borrow()
+            .iter()
+            .find(|signatory| signatory.id == id)
+            .cloned())
+    }
+
+    fn signatories(&self, document_id: i64) -> Result<Vec<Signatory>, SignatoryRepositoryError> {
+        let mut signatories: Vec<Signatory> = self
+            .signatories
+            .borrow()
+            .iter()
+            .filter(|signatory| signatory.document_id == document_id)
+            .cloned()
+            .collect();
+        signatories
+            .sort_by_key(|signatory| (signatory.role != SigningRole::Approval, signatory.id));
+        Ok(signatories)
+    }
+}
+
+#[derive(Default)]
+pub(crate) struct MemoryTemplates {
+    pub templates: RefCell<Vec<RequirementTemplate>>,
+}
+
+impl RequirementTemplateRepository for MemoryTemplates {
+    fn create(
+        &self,
+        dto: &RequirementTemplateDto,
+    ) -> Result<i64, RequirementTemplateRepositoryError> {
+        let mut templates = self.templates.borrow_mut();
+        let id = templates.len() as i64 + 1;
+        templates.push(RequirementTemplate {
+            id,
+            category: dto.category.unwrap_or(RequirementCategory::Architecture),
+            title: dto.title.clone().unwrap_or_default(),
+            text: dto.text.clone().unwrap_or_default(),
+        });
+        Ok(id)
+    }
+
+    fn update(
+        &self,
+        id: i64,
+        dto: &RequirementTemplateDto,
+    ) -> Result<bool, RequirementTemplateRepositoryError> {
+        let mut templates = self.templates.borrow_mut();
+        let Some(template) = templates.iter_mut().find(|template| template.id == id) else {
+            return Ok(false);
+        };
+        template.category = dto.category.unwrap_or(template.category);
+        template.title = dto.title.clone().unwrap_or_default();
+        template.text = dto.text.clone().unwrap_or_default();
+        Ok(true)
+    }
+
+    fn delete(&self, id: i64) -> Result<bool, RequirementTemplateRepositoryError> {
+        let mut templates = self.templates.borrow_mut();
+        let count = templates.len();
+        templates.retain(|template| template.id != id);
+        Ok(templates.len() < count)
+    }
+
+    fn find(
+        &self,
+        id: i64,
+    ) -> Result<Option<RequirementTemplate>, RequirementTemplateRepositoryError> {
+        Ok(self
+            .templates
+            .borrow()
+            .iter()
+            .find(|template| template.id == id)
+            .cloned())
+    }
+
+    fn list(
+        &self,
+        category: Option<RequirementCategory>,
+    ) -> Result<Vec<RequirementTemplate>, RequirementTemplateRepositoryError> {
+        Ok(self
+            .templates
+            .borrow()
+            .iter()
+            .filter(|template| category.is_none_or(|category| template.category == category))
+            .cloned()
+            .collect())
+    }
+}
+
+fn apply(document: &mut Document, dto: &DocumentDto) {
+    document.name = dto.name.clone().unwrap_or_default();
+    document.number = dto.number.clone();
+    document.date = dto.date.clone();
+    document.document_type = dto.document_type;
+    if let Some(author) = &dto.author {
+        document.author = author.clone();
+    }
+    document.developer = dto.developer;
+}
+
+#[derive(Default)]
+pub(crate) struct MemoryReferences {
+    pub entities: RefCell<Vec<ReferenceEntity>>,
+}
+
+impl MemoryReferences {
+    pub fn with(entities: Vec<ReferenceEntity>) -> Self {
+        Self {
+            entities: RefCell::new(entities),
+        }
+    }
+}
+
+impl ReferenceRepository for MemoryReferences {
+    fn state(&self, reference: EntityRef) -> Result<ReferenceState, ReferenceRepositoryError> {
+        let entities = self.entities.borrow();
+        let Some(entity) = entities
+            .iter()
+            .find(|entity| entity.reference() == reference)
+        else {
+            return Ok(ReferenceState::Missing);
+        };
+
+        let archived = match entity {
+            ReferenceEntity::Address(address) => address.archived_at.is_some(),
+            ReferenceEntity::Organization(organization) => organization.archived_at.is_some(),
+            ReferenceEntity::Entrepreneur(entrepreneur) => entrepreneur.archived_at.is_some(),
+            ReferenceEntity::Person(person) => person.archived_at.is_some(),
+            ReferenceEntity::SourceDocument(source) => source.archived_at.is_some(),
+        };
+
+        Ok(if archived {
+            ReferenceState::Archived
+        } else {
+            ReferenceState::Active
+        })
+    }
+
+    fn load(
+        &self,
+        reference: EntityRef,
+    ) -> Result<Option<ReferenceEntity>, ReferenceRepositoryError> {
+        Ok(self
+            .entities
+            .borrow()
+            .iter()
+            .find(|entity| entity.reference() == reference)
+            .cloned())
+    }
+
+    fn find_by_uid(&self, uid: &str) -> Result<Option<EntityRef>, ReferenceRepositoryError> {
+        Ok(self
+            .entities
+            .borrow()
+            .iter()
+            .find(|entity| entity.uid() == uid)
+            .map(ReferenceEntity::reference))
+    }
+}
+
+/// A complete draft authored by organization 3 and developed by person 5.
+pub(crate) fn document(id: i64) -> Document {
+    Document {
+        id,
+        name: "ЗНП".into(),
+        number: Some("42".into()),
+        date: Some("2026-10-01".into()),
+        change_mark: None,
+        uid: format!("uid-{id}"),
+        document_type: None,
+        type_note: None,
+        author: DocumentAuthor::Organization { id: 3 },
+        developer: Some(crate::DocumentDeveloper::Person { id: 5 }),
+        previous_revision_id: None,
+        is_template: false,
+        status: DocumentStatus::Draft,
+        submitted_at: None,
+        approved_at: None,
+        issued_at: None,
+        superseded_at: None,
+        cancelled_at: None,
+    }
+}
+
+pub(crate) fn address(id: i64) -> Address {
+    Address {
+        id,
+        uid: format!("address-{id}"),
+        region_code: "77".into(),
+        note: None,
+        country: None,
+        district: None,
+        city: Some("Москва".into()),
+        settlement: None,
+        street: Some("Тверская".into()),
+        building: Some("1".into()),
+        room: None,
+        archived_at: None,
+    }
+}
+
+pub(crate) fn organization(id: i64, address_id: i64) -> Organization {
+    Organization {
+        id,
+        uid: format!("organization-{id}"),
+        full_name: "ООО \"Проект\"".into(),
+        abbreviated_name: "Проект".into(),
+        ogrn: Some("1027700000000".into()),
+        rafp: None,
+        inn: "7700000000".into(),
+        kpp: "770001001".into(),
+        address_id,
+        email: "info@example.com".into(),
+        archived_at: None,
+    }
+}
+
+pub(crate) fn person(id: i64, address_id: i64) -> Person {
+    Person {
+        id,
+        uid: format!("person-{id}"),
+        name: "Иван".into(),
+        patronymic: Some("Иванович".into()),
+        surname: "Иванов".into(),
+        snils: "11223344595".into(),
+        email: "ivanov@example.com".into(),
+        address_id,
+        archived_at: None,
+    }
+}
+
+pub(crate) fn source_document(id: i64, issuer_id: i64) -> SourceDocument {
+    SourceDocument {
+        id,
+        uid: format!("source-document-{id}"),
+        kind: SourceDocumentKind::Gpzu,
+        number: "РФ-77-4-53-3-12-2026-1234".into(),
+        date: "2026-09-15".into(),
+        issuer_id,
+        title: None,
+        archived_at: None,
+    }
+}
+
+/// References used by [`document`]: organization 3 and person 5 with their addresses.
+pub(crate) fn references() -> MemoryReferences {
+    MemoryReferences::with(vec![
+        ReferenceEntity::Organization(organization(3, 9)),
+        ReferenceEntity::Address(address(9)),
+        ReferenceEntity::Person(person(5, 10)),
+        ReferenceEntity::Address(address(10)),
+    ])
+}
@@ -0,0 +1,310 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{DocumentError, DocumentRepository, DocumentRepositoryError};
+
+// ---------------- Document Numbering ----------------
+
+/// Generates a random (version 4) RFC 4122 UUID for a new document or reference data row.
+pub fn generate_uid() -> String {
+    uuid::Uuid::new_v4().to_string()
+}
+
+/// Format of generated document numbers: `{prefix}-{year}-{counter}` with the counter padded to
+/// `width` digits, e.g. `ЗНП-2026-0042`. Schemes are stored in the database, one per prefix, and
+/// counters are kept per prefix and year.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct NumberingScheme {
+    pub prefix: String,
+    pub width: usize,
+}
+
+impl Default for NumberingScheme {
+    fn default() -> Self {
+        Self {
+            prefix: "ЗНП".to_string(),
+            width: 4,
+        }
+    }
+}
+
+impl NumberingScheme {
+    pub fn format(&self, year: i32, counter: i64) -> String {
+        format!(
+            "{}-{year:04}-{counter:0width$}",
+            self.prefix,
+            width = self.width
+        )
+    }
+}
+
+/// Requests a number for a new document from the per-year counter of the stored scheme with
+/// the given prefix. The number is allocated by the repository inside the transaction that
+/// creates the document, so numbers are gap-free and always padded the same way.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct DocumentNumbering {
+    pub prefix: String,
+    pub year: i32,
+}
+
+impl DocumentNumbering {
+    /// Numbers the document within the year of its date (`YYYY-MM-DD`).
+    pub fn for_date(prefix: &str, date: &str) -> Option<Self> {
+        let year = date.get(..4)?;
+        if !year.bytes().all(|byte| byte.is_ascii_digit()) {
+            return None;
+        }
+
+        Some(Self {
+            prefix: prefix.to_string(),
+            year: year.parse().ok()?,
+        })
+    }
+}
+
+fn sanitize_prefix(prefix: &str) -> Result<String, DocumentError> {
+    let prefix = prefix.trim();
+
+    if prefix.is_empty() || prefix.chars().any(char::is_whitespace) {
+        return Err(DocumentError::InvalidNumbering(format!(
+            "invalid prefix \"{prefix}\""
+        )));
+    }
+
+    Ok(prefix.to_string())
+}
+
+pub(crate) fn sanitize_numbering(
+    numbering: &DocumentNumbering,
+) -> Result<DocumentNumbering, DocumentError> {
+    let prefix = sanitize_prefix(&numbering.prefix)?;
+
+    if !(1..=9999).contains(&numbering.year) {
+        return Err(DocumentError::InvalidNumbering(format!(
+            "invalid year {}",
+            numbering.year
+        )));
+    }
+
+    Ok(DocumentNumbering {
+        prefix,
+        year: numbering.year,
+    })
+}
+
+/// Checks that the numbering of a new document refers to a stored scheme.
+pub(crate) fn ensure_scheme_exists<D: DocumentRepository>(
+    documents: &D,
+    numbering: &DocumentNumbering,
+) -> Result<(), DocumentError> {
+    let schemes = documents
+        .numbering_schemes()
+        .map_err(DocumentError::Repository)?;
+
+    if schemes
+        .iter()
+        .any(|scheme| scheme.prefix == numbering.prefix)
+    {
+        Ok(())
+    } else {
+        Err(DocumentError::InvalidNumbering(format!(
+            "unknown numbering scheme \"{}\"",
+            numbering.prefix
+        )))
+    }
+}
+
+pub fn list_numbering_schemes<D: DocumentRepository>(
+    documents: &D,
+) -> Result<Vec<NumberingScheme>, DocumentRepositoryError> {
+    documents.numbering_schemes()
+}
+
+/// Registers a numbering scheme. The width of a prefix cannot change once it is stored, so every
+/// workstation and caller pads the numbers of a counter the same way.
+pub fn create_numbering_scheme<D: DocumentRepository>(
+    documents: &D,
+    scheme: &NumberingScheme,
+) -> Result<(), DocumentError> {
+    let prefix = sanitize_prefix(&scheme.prefix)?;
+
+    if scheme.width == 0 || scheme.width > 10 {
+        return Err(DocumentError::InvalidNumbering(format!(
+            "counter width must be between 1 and 10, got {}",
+            scheme.width
+        )));
+    }
+
+    let scheme = NumberingScheme {
+        prefix,
+        width: scheme.width,
+    };
+    match documents.create_numbering_scheme(&scheme) {
+        Ok(true) => Ok(()),
+        Ok(false) => Err(DocumentError::InvalidNumbering(format!(
+            "numbering scheme \"{}\" already exists",
+            scheme.prefix
+        ))),
+        Err(error) => Err(DocumentError::Repository(error)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::{self, MemoryDocuments, MemoryReferences};
+    use crate::{DocumentAuthor, DocumentDto, create_document};
+
+    #[test]
+    fn scheme_formats_prefix_year_and_padded_counter() {
+        let scheme = NumberingScheme::default();
+        assert_eq!(scheme.format(2026, 42), "ЗНП-2026-0042");
+        assert_eq!(scheme.format(2026, 12345), "ЗНП-2026-12345");
+
+        let numbering = DocumentNumbering::for_date(&scheme.prefix, "2026-10-01");
+        assert_eq!(numbering.map(|numbering| numbering.year), Some(2026));
+        assert_eq!(
+            DocumentNumbering::for_date(&scheme.prefix, "01.10.2026"),
+            None
+        );
+    }
+
+    #[test]
+    fn created_documents_get_uuid_and_sequential_numbers_per_year() {
+        let documents = MemoryDocuments::default();
+        let references = MemoryReferences::default();
+        let dto = |year| DocumentDto {
+            name: Some("ЗНП".into()),
+            author: Some(DocumentAuthor::Informal {
+                name: "Иванов И. И.".into(),
+            }),
+            numbering: Some(DocumentNumbering {
+                prefix: "ЗНП".into(),
+                year,
+            }),
+            ..Default::default()
+        };
+
+        let first = create_document(&documents, &references, &dto(2026)).expect("first");
+        let second = create_document(&documents, &references, &dto(2026)).expect("second");
+        let next_year = create_document(&documents, &references, &dto(2027)).expect("third");
+
+        let numbers: Vec<_> = [first, second, next_year]
+            .into_iter()
+            .map(|id| documents.get(id).number)
+            .collect();
+        assert_eq!(
+            numbers,
+            vec![
+                Some("ЗНП-2026-0001".to_string()),
+                Some("ЗНП-2026-0002".to_string()),
+                Some("ЗНП-2027-0001".to_string()),
+            ]
+        );
+
+        let uid = documents.get(first).uid;
+        assert_eq!(
+            uuid::Uuid::parse_str(&uid).map(|uid| uid.get_version_num()),
+            Ok(4)
+        );
+        assert_ne!(uid, documents.get(second).uid);
+        assert_ne!(uid, memory::document(first).uid);
+    }
+
+    #[test]
+    fn explicit_number_is_kept_and_invalid_schemes_are_rejected() {
+        let documents = MemoryDocuments::default();
+        let references = MemoryReferences::default();
+        let mut dto = DocumentDto {
+            name: Some("ЗНП".into()),
+            number: Some("17".into()),
+            author: Some(DocumentAuthor::Informal {
+                name: "Иванов И. И.".into(),
+            }),
+            numbering: Some(DocumentNumbering {
+                prefix: "ЗНП".into(),
+                year: 2026,
+            }),
+            ..Default::default()
+        };
+
+        let id = create_document(&documents, &references, &dto).expect("document");
+        assert_eq!(documents.get(id).number.as_deref(), Some("17"));
+
+        dto.number = None;
+        dto.numbering = Some(DocumentNumbering {
+            prefix: "З Н П".into(),
+            year: 2026,
+        });
+        assert_eq!(
+            create_document(&documents, &references, &dto),
+            Err(DocumentError::InvalidNumbering(
+                "invalid prefix \"З Н П\"".into()
+            ))
+        );
+
+        dto.numbering = Some(DocumentNumbering {
+            prefix: "ТЗ".into(),
+            year: 2026,
+        });
+        assert_eq!(
+            create_document(&documents, &references, &dto),
+            Err(DocumentError::InvalidNumbering(
+                "unknown numbering scheme \"ТЗ\"".into()
+            ))
+        );
+    }
+
+    #[test]
+    fn stored_scheme_sets_the_width_of_its_numbers() {
+        let documents = MemoryDocuments::default();
+        let references = MemoryReferences::default();
+        let scheme = NumberingScheme {
+            prefix: " ТЗ ".into(),
+            width: 2,
+        };
+
+        create_numbering_scheme(&documents, &scheme).expect("scheme");
+        assert_eq!(
+            create_numbering_scheme(&documents, &NumberingScheme { width: 6, ..scheme }),
+            Err(DocumentError::InvalidNumbering(
+                "numbering scheme \"ТЗ\" already exists".into()
+            ))
+        );
+        assert_eq!(
+            create_numbering_scheme(
+                &documents,
+                &NumberingScheme {
+                    prefix: "ПЗ".into(),
+                    width: 0,
+                }
+            ),
+            Err(DocumentError::InvalidNumbering(
+                "counter width must be between 1 and 10, got 0".into()
+            ))
+        );
+        assert_eq!(
+            list_numbering_schemes(&documents).map(|schemes| schemes.len()),
+            Ok(2)
+        );
+
+        let id = create_document(
+            &documents,
+            &references,
+            &DocumentDto {
+                name: Some("ТЗ".into()),
+                author: Some(DocumentAuthor::Informal {
+                    name: "Иванов И. И.".into(),
+                }),
+                numbering: Some(DocumentNumbering {
+                    prefix: "ТЗ".into(),
+                    year: 2026,
+                }),
+                ..Default::default()
+            },
+        )
+        .expect("document");
+        assert_eq!(documents.get(id).number.as_deref(), Some("ТЗ-2026-01"));
+    }
+}
@@ -0,0 +1,61 @@
+use serde::Serialize;
+
+// ---------------- OKEI Units ----------------
+
+/// Unit of measurement from the All-Russian classifier of units of measurement (OKEI).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct OkeiUnit {
+    pub code: &'static str,
+    pub name: &'static str,
+    pub symbol: &'static str,
+    /// Counted units take whole values only.
+    pub integer: bool,
+}
+
+const fn unit(code: &'static str, name: &'static str, symbol: &'static str) -> OkeiUnit {
+    OkeiUnit {
+        code,
+        name,
+        symbol,
+        integer: false,
+    }
+}
+
+const fn counted(code: &'static str, name: &'static str, symbol: &'static str) -> OkeiUnit {
+    OkeiUnit {
+        code,
+        name,
+        symbol,
+        integer: true,
+    }
+}
+
+/// Units used in the technical and economic indicators of design assignments.
+pub const OKEI_UNITS: &[OkeiUnit] = &[
+    unit("006", "Метр", "м"),
+    unit("008", "Километр", "км"),
+    unit("018", "Погонный метр", "пог. м"),
+    unit("055", "Квадратный метр", "м2"),
+    unit("058", "Тысяча квадратных метров", "тыс. м2"),
+    unit("059", "Гектар", "га"),
+    unit("112", "Литр", "л"),
+    unit("113", "Кубический метр", "м3"),
+    unit("166", "Килограмм", "кг"),
+    unit("168", "Тонна", "т"),
+    unit("214", "Киловатт", "кВт"),
+    unit("233", "Гигакалория", "Гкал"),
+    unit("245", "Киловатт-час", "кВт.ч"),
+    unit("356", "Час", "ч"),
+    unit("359", "Сутки", "сут"),
+    unit("366", "Год", "лет"),
+    counted("642", "Единица", "ед"),
+    counted("698", "Место", "мест"),
+    unit("744", "Процент", "%"),
+    counted("792", "Человек", "чел"),
+    counted("796", "Штука", "шт"),
+    counted("839", "Комплект", "компл"),
+];
+
+pub fn find_okei_unit(code: &str) -> Option<&'static OkeiUnit> {
+    OKEI_UNITS.iter().find(|unit| unit.code == code)
+}
@@ -0,0 +1,250 @@
+use serde::Serialize;
+
+use crate::{
+    AddressDto, AddressError, EntityRef, EntrepreneurDto, EntrepreneurError, OrganizationDto,
+    OrganizationError, PersonDto, PersonError, sanitize_address, sanitize_entrepreneur,
+    sanitize_organization, sanitize_person,
+};
+
+// ---------------- Reference Data Import ----------------
+
+/// Reference data record imported in bulk. Every entity comes with its own address, which is
+/// created first; the `address_id` of the entity DTO is ignored.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ReferenceRecord {
+    Address {
+        address: AddressDto,
+    },
+    Organization {
+        organization: OrganizationDto,
+        address: AddressDto,
+    },
+    Entrepreneur {
+        entrepreneur: EntrepreneurDto,
+        address: AddressDto,
+    },
+    Person {
+        person: PersonDto,
+        address: AddressDto,
+    },
+}
+
+impl ReferenceRecord {
+    pub fn address(&self) -> &AddressDto {
+        match self {
+            Self::Address { address }
+            | Self::Organization { address, .. }
+            | Self::Entrepreneur { address, .. }
+            | Self::Person { address, .. } => address,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReferenceRecordError {
+    Address(AddressError),
+    Organization(OrganizationError),
+    Entrepreneur(EntrepreneurError),
+    Person(PersonError),
+}
+
+impl std::fmt::Display for ReferenceRecordError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Address(error) => write!(f, "Address error: {error}"),
+            Self::Organization(error) => write!(f, "Organization error: {error}"),
+            Self::Entrepreneur(error) => write!(f, "Entrepreneur error: {error}"),
+            Self::Person(error) => write!(f, "Person error: {error}"),
+        }
+    }
+}
+
+impl std::error::Error for ReferenceRecordError {}
+
+/// Sanitizes the record with the same rules as the single-record `create_*` operations.
+pub fn sanitize_reference_record(
+    record: &ReferenceRecord,
+) -> Result<ReferenceRecord, ReferenceRecordError> {
+    let address = sanitize_address(record.address()).map_err(ReferenceRecordError::Address)?;
+
+    Ok(match record {
+        ReferenceRecord::Address { .. } => ReferenceRecord::Address { address },
+        ReferenceRecord::Organization { organization, .. } => ReferenceRecord::Organization {
+            organization: sanitize_organization(organization)
+                .map_err(ReferenceRecordError::Organization)?,
+            address,
+        },
+        ReferenceRecord::Entrepreneur { entrepreneur, .. } => ReferenceRecord::Entrepreneur {
+            entrepreneur: sanitize_entrepreneur(entrepreneur)
+                .map_err(ReferenceRecordError::Entrepreneur)?,
+            address,
+        },
+        ReferenceRecord::Person { person, .. } => ReferenceRecord::Person {
+            person: sanitize_person(person).map_err(ReferenceRecordError::Person)?,
+            address,
+        },
+    })
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReferenceImportRepositoryError {
+    Storage(String),
+}
+
+impl ReferenceImportRepositoryError {
+    pub fn storage<S: Into<String>>(message: S) -> Self {
+        Self::Storage(message.into())
+    }
+}
+
+impl std::fmt::Display for ReferenceImportRepositoryError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Storage(message) => f.write_str(message),
+        }
+    }
+}
+
+impl std::error::Error for ReferenceImportRepositoryError {}
+
+pub trait ReferenceImportRepository {
+    /// Stores all records in a single transaction and returns the created entities in order.
+    /// Nothing is stored when any of the records fails.
+    fn import(
+        &self,
+        records: &[ReferenceRecord],
+    ) -> Result<Vec<EntityRef>, ReferenceImportRepositoryError>;
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReferenceImportError {
+    /// The record at the given position of the batch is invalid.
+    Record {
+        index: usize,
+        error: ReferenceRecordError,
+    },
+    Repository(ReferenceImportRepositoryError),
+}
+
+impl std::fmt::Display for ReferenceImportError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Record { index, error } => write!(f, "Record {}: {error}", index + 1),
+            Self::Repository(error) => write!(f, "Repository error: {error}"),
+        }
+    }
+}
+
+impl std::error::Error for ReferenceImportError {}
+
+/// Sanitizes all records and stores them together; nothing is stored if any record is invalid.
+pub fn import_reference_records<R: ReferenceImportRepository>(
+    repository: &R,
+    records: &[ReferenceRecord],
+) -> Result<Vec<EntityRef>, ReferenceImportError> {
+    let sanitized = records
+        .iter()
+        .enumerate()
+        .map(|(index, record)| {
+            let mut record = sanitize_reference_record(record)
+                .map_err(|error| ReferenceImportError::Record { index, error })?;
+            generate_uids(&mut record);
+            Ok(record)
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    repository
+        .import(&sanitized)
+        .map_err(ReferenceImportError::Repository)
+}
+
+/// Gives the rows created for the record their uids, as the `create_*` operations do.
+fn generate_uids(record: &mut ReferenceRecord) {
+    let (address, uid) = match record {
+        ReferenceRecord::Address { address } => (address, None),
+        ReferenceRecord::Organization {
+            organization,
+            address,
+        } => (address, Some(&mut organization.uid)),
+        ReferenceRecord::Entrepreneur {
+            entrepreneur,
+            address,
+        } => (address, Some(&mut entrepreneur.uid)),
+        ReferenceRecord::Person { person, address } => (address, Some(&mut person.uid)),
+    };
+
+    address.uid.get_or_insert_with(crate::generate_uid);
+    if let Some(uid) = uid {
+        uid.get_or_insert_with(crate::generate_uid);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+
+    use super::*;
+
+    #[derive(Default)]
+    struct RecordingImport {
+        imported: RefCell<Vec<ReferenceRecord>>,
+    }
+
+    impl ReferenceImportRepository for RecordingImport {
+        fn import(
+            &self,
+            records: &[ReferenceRecord],
+        ) -> Result<Vec<EntityRef>, ReferenceImportRepositoryError> {
+            self.imported.borrow_mut().extend_from_slice(records);
+            Ok((1..=records.len() as i64).map(EntityRef::Person).collect())
+        }
+    }
+
+    fn person(surname: &str) -> ReferenceRecord {
+        ReferenceRecord::Person {
+            person: PersonDto {
+                name: Some(" Иван ".into()),
+                surname: Some(surname.into()),
+                snils: Some("11223344595".into()),
+                email: Some("ivanov@example.com".into()),
+                ..PersonDto::default()
+            },
+            address: AddressDto {
+                region_code: Some("77".into()),
+                ..AddressDto::default()
+            },
+        }
+    }
+
+    #[test]
+    fn records_are_sanitized_and_stored_together() {
+        let repository = RecordingImport::default();
+
+        let imported =
+            import_reference_records(&repository, &[person("Иванов"), person("Петров")]).unwrap();
+
+        assert_eq!(imported, vec![EntityRef::Person(1), EntityRef::Person(2)]);
+        let ReferenceRecord::Person { person, .. } = &repository.imported.borrow()[0] else {
+            panic!("person expected");
+        };
+        assert_eq!(person.name.as_deref(), Some("Иван"));
+        assert!(person.uid.is_some());
+    }
+
+    #[test]
+    fn nothing_is_stored_when_a_record_is_invalid() {
+        let repository = RecordingImport::default();
+
+        let result = import_reference_records(&repository, &[person("Иванов"), person(" ")]);
+
+        assert_eq!(
+            result,
+            Err(ReferenceImportError::Record {
+                index: 1,
+                error: ReferenceRecordError::Person(PersonError::MissingSurname),
+            })
+        );
+        assert!(repository.imported.borrow().is_empty());
+    }
+}
@@ -0,0 +1,722 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    ConstructionObject, Document, DocumentError, DocumentRepository, EntityRef, ReferenceEntity,
+};
+
+// ---------------- Requirements Core API ----------------
+
+/// Subject area of a requirement to the design documentation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum RequirementCategory {
+    Architecture,
+    Structures,
+    EngineeringNetworks,
+    FireSafety,
+    Accessibility,
+    EnergyEfficiency,
+}
+
+impl RequirementCategory {
+    pub const ALL: [RequirementCategory; 6] = [
+        Self::Architecture,
+        Self::Structures,
+        Self::EngineeringNetworks,
+        Self::FireSafety,
+        Self::Accessibility,
+        Self::EnergyEfficiency,
+    ];
+
+    /// Value stored in the `category` columns.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Architecture => "architecture",
+            Self::Structures => "structures",
+            Self::EngineeringNetworks => "engineering_networks",
+            Self::FireSafety => "fire_safety",
+            Self::Accessibility => "accessibility",
+            Self::EnergyEfficiency => "energy_efficiency",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|category| category.as_str() == value)
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Architecture => "Архитектурные решения",
+            Self::Structures => "Конструктивные решения",
+            Self::EngineeringNetworks => "Инженерные сети и системы",
+            Self::FireSafety => "Пожарная безопасность",
+            Self::Accessibility => "Доступность для маломобильных групп населения",
+            Self::EnergyEfficiency => "Энергетическая эффективность",
+        }
+    }
+}
+
+/// Requirement category as listed for the UI.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RequirementCategoryInfo {
+    pub code: RequirementCategory,
+    pub name: &'static str,
+}
+
+pub fn list_requirement_categories() -> Vec<RequirementCategoryInfo> {
+    RequirementCategory::ALL
+        .into_iter()
+        .map(|category| RequirementCategoryInfo {
+            code: category,
+            name: category.name(),
+        })
+        .collect()
+}
+
+/// Value that can be inserted into a requirement text as `{{key}}`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct Placeholder {
+    pub key: &'static str,
+    pub name: &'static str,
+}
+
+pub const PLACEHOLDERS: [Placeholder; 3] = [
+    Placeholder {
+        key: "object_name",
+        name: "Наименование объекта",
+    },
+    Placeholder {
+        key: "object_address",
+        name: "Адрес объекта",
+    },
+    Placeholder {
+        key: "developer",
+        name: "Застройщик",
+    },
+];
+
+#[derive(Debug, Clone, PartialEq, Eq, Default, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(default)]
+pub struct RequirementSectionDto {
+    pub category: Option<RequirementCategory>,
+    pub title: Option<String>,
+    /// Text with `{{key}}` placeholders, see [`PLACEHOLDERS`].
+    pub text: Option<String>,
+    /// Template the section was inserted from.
+    pub template_id: Option<i64>,
+}
+
+/// Requirements of one subject area in a document. The text keeps its placeholders; they are
+/// substituted when the document is exported.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct RequirementSection {
+    pub id: i64,
+    pub document_id: i64,
+    pub position: i64,
+    pub category: RequirementCategory,
+    pub title: String,
+    pub text: String,
+    pub template_id: Option<i64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RequirementRepositoryError {
+    Storage(String),
+}
+
+impl RequirementRepositoryError {
+    pub fn storage<S: Into<String>>(message: S) -> Self {
+        Self::Storage(message.into())
+    }
+}
+
+impl std::fmt::Display for RequirementRepositoryError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Storage(message) => f.write_str(message),
+        }
+    }
+}
+
+impl std::error::Error for RequirementRepositoryError {}
+
+pub trait RequirementRepository {
+    /// Appends the section to the end of the document's requirements. Returns `None` when the
+    /// document is not a draft.
+    fn add_requirement(
+        &self,
+        document_id: i64,
+        dto: &RequirementSectionDto,
+    ) -> Result<Option<i64>, RequirementRepositoryError>;
+    /// Updates the category, title and text. The source template is kept. Returns `false` when
+    /// the section does not exist or its document is not a draft.
+    fn update_requirement(
+        &self,
+        id: i64,
+        dto: &RequirementSectionDto,
+    ) -> Result<bool, RequirementRepositoryError>;
+    /// Returns `false` when the section does not exist or its document is not a draft.
+    fn delete_requirement(&self, id: i64) -> Result<bool, RequirementRepositoryError>;
+    fn find_requirement(
+        &self,
+        id: i64,
+    ) -> Result<Option<RequirementSection>, RequirementRepositoryError>;
+    /// Requirement sections of the document ordered by position.
+    fn requirements(
+        &self,
+        document_id: i64,
+    ) -> Result<Vec<RequirementSection>, RequirementRepositoryError>;
+    /// Renumbers the requirement sections of the document in the given order. Returns `false`
+    /// when the document is not a draft.
+    fn reorder_requirements(
+        &self,
+        document_id: i64,
+        ids: &[i64],
+    ) -> Result<bool, RequirementRepositoryError>;
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Default, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(default)]
+pub struct RequirementTemplateDto {
+    pub category: Option<RequirementCategory>,
+    pub title: Option<String>,
+    pub text: Option<String>,
+}
+
+/// Standard requirement text from the template library.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct RequirementTemplate {
+    pub id: i64,
+    pub category: RequirementCategory,
+    pub title: String,
+    pub text: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RequirementTemplateRepositoryError {
+    Storage(String),
+}
+
+impl RequirementTemplateRepositoryError {
+    pub fn storage<S: Into<String>>(message: S) -> Self {
+        Self::Storage(message.into())
+    }
+}
+
+impl std::fmt::Display for RequirementTemplateRepositoryError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Storage(message) => f.write_str(message),
+        }
+    }
+}
+
+impl std::error::Error for RequirementTemplateRepositoryError {}
+
+pub trait RequirementTemplateRepository {
+    fn create(
+        &self,
+        dto: &RequirementTemplateDto,
+    ) -> Result<i64, RequirementTemplateRepositoryError>;
+    fn update(
+        &self,
+        id: i64,
+        dto: &RequirementTemplateDto,
+    ) -> Result<bool, RequirementTemplateRepositoryError>;
+    fn delete(&self, id: i64) -> Result<bool, RequirementTemplateRepositoryError>;
+    fn find(
+        &self,
+        id: i64,
+    ) -> Result<Option<RequirementTemplate>, RequirementTemplateRepositoryError>;
+    /// Templates ordered by category and title, optionally of one category only.
+    fn list(
+        &self,
+        category: Option<RequirementCategory>,
+    ) -> Result<Vec<RequirementTemplate>, RequirementTemplateRepositoryError>;
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RequirementError {
+    MissingCategory,
+    MissingTitle,
+    MissingText,
+    UnknownPlaceholder(String),
+    /// A `{{` without the matching `}}`.
+    UnclosedPlaceholder,
+    NotFound(i64),
+    TemplateNotFound(i64),
+    /// The new order does not list exactly the requirement sections of the document.
+    InvalidOrder,
+    Document(DocumentError),
+    Repository(RequirementRepositoryError),
+    TemplateRepository(RequirementTemplateRepositoryError),
+}
+
+impl std::fmt::Display for RequirementError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::MissingCategory => f.write_str("Requirement category is required"),
+            Self::MissingTitle => f.write_str("Requirement title is required"),
+            Self::MissingText => f.write_str("Requirement text is required"),
+            Self::UnknownPlaceholder(key) => write!(f, "Unknown placeholder: {{{{{key}}}}}"),
+            Self::UnclosedPlaceholder => f.write_str("Placeholder is not closed with }}"),
+            Self::NotFound(id) => write!(f, "Requirement section {id} not found"),
+            Self::TemplateNotFound(id) => write!(f, "Requirement template {id} not found"),
+            Self::InvalidOrder => {
+                f.write_str("Order must list every requirement section of the document")
+            }
+            Self::Document(error) => write!(f, "{error}"),
+            Self::Repository(error) => write!(f, "Repository error: {error}"),
+            Self::TemplateRepository(error) => write!(f, "Repository error: {error}"),
+        }
+    }
+}
+
+impl std::error::Error for RequirementError {}
+
+impl From<DocumentError> for RequirementError {
+    fn from(error: DocumentError) -> Self {
+        Self::Document(error)
+    }
+}
+
+/// Splits a text into literal parts and placeholder keys.
+enum Segment<'a> {
+    Text(&'a str),
+    Placeholder(&'a str),
+}
+
+fn segments(text: &str) -> Result<Vec<Segment<'_>>, RequirementError> {
+    let mut segments = Vec::new();
+    let mut rest = text;
+
+    while let Some(start) = rest.find("{{") {
+        segments.push(Segment::Text(&rest[..start]));
+        let after = &rest[start + 2..];
+        let end = after
+            .find("}}")
+            .ok_or(RequirementError::UnclosedPlaceholder)?;
+        let key = after[..end].trim();
+        if !PLACEHOLDERS
+            .iter()
+            .any(|placeholder| placeholder.key == key)
+        {
+            return Err(RequirementError::UnknownPlaceholder(key.to_string()));
+        }
+        segments.push(Segment::Placeholder(key));
+        rest = &after[end + 2..];
+    }
+    segments.push(Segment::Text(rest));
+
+    Ok(segments)
+}
+
+/// Values of the placeholders taken from the document.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct RequirementValues {
+    pub object_name: Option<String>,
+    pub object_address: Option<String>,
+    pub developer: Option<String>,
+}
+
+impl RequirementValues {
+    fn get(&self, key: &str) -> Option<&str> {
+        match key {
+            "object_name" => self.object_name.as_deref(),
+            "object_address" => self.object_address.as_deref(),
+            "developer" => self.developer.as_deref(),
+            _ => None,
+        }
+    }
+}
+
+/// Collects the placeholder values from the document, its construction object and the loaded
+/// reference entities. Values whose entity is not among `entities` stay empty.
+pub fn requirement_values(
+    document: &Document,
+    construction_object: Option<&ConstructionObject>,
+    entities: &[ReferenceEntity],
+) -> RequirementValues {
+    let entity = |reference: EntityRef| {
+        entities
+            .iter()
+            .find(|entity| entity.reference() == reference)
+    };
+
+    let object_address = construction_object
+        .and_then(|object| entity(EntityRef::Address(object.address_id)))
+        .and_then(|entity| match entity {
+            ReferenceEntity::Address(address) => Some(address.formatted()),
+            _ => None,
+        });
+
+    let developer = document
+        .developer
+        .and_then(|developer| entity(developer.reference()))
+        .and_then(ReferenceEntity::party_name);
+
+    RequirementValues {
+        object_name: construction_object.map(|object| object.name.clone()),
+        object_address,
+        developer,
+    }
+}
+
+/// Substitutes the placeholders of a requirement text. Placeholders without a value are left in
+/// place so the gap is visible in the output.
+pub fn render_requirement_text(text: &str, values: &RequirementValues) -> String {
+    let Ok(segments) = segments(text) else {
+        return text.to_string();
+    };
+
+    segments
+        .into_iter()
+        .map(|segment| match segment {
+            Segment::Text(text) => text.to_string(),
+            Segment::Placeholder(key) => values
+                .get(key)
+                .map(str::to_string)
+                .unwrap_or_else(|| format!("{{{{{key}}}}}")),
+        })
+        .collect()
+}
+
+fn sanitize_field(value: &Option<String>) -> Option<String> {
+    value
+        .as_ref()
+        .map(|value| value.trim())
+        .filter(|value| !value.is_empty())
+        .map(|value| value.to_string())
+}
+
+fn sanitize_text(
+    category: Option<RequirementCategory>,
+    title: &Option<String>,
+    text: &Option<String>,
+) -> Result<(RequirementCategory, String, String), RequirementError> {
+    let category = category.ok_or(RequirementError::MissingCategory)?;
+    let title = sanitize_field(title).ok_or(RequirementError::MissingTitle)?;
+    let text = sanitize_field(text).ok_or(RequirementError::MissingText)?;
+    segments(&text)?;
+
+    Ok((category, title, text))
+}
+
+fn sanitize_requirement(
+    dto: &RequirementSectionDto,
+) -> Result<RequirementSectionDto, RequirementError> {
+    let (category, title, text) = sanitize_text(dto.category, &dto.title, &dto.text)?;
+
+    Ok(RequirementSectionDto {
+        category: Some(category),
+        title: Some(title),
+        text: Some(text),
+        template_id: dto.template_id,
+    })
+}
+
+fn sanitize_template(
+    dto: &RequirementTemplateDto,
+) -> Result<RequirementTemplateDto, RequirementError> {
+    let (category, title, text) = sanitize_text(dto.category, &dto.title, &dto.text)?;
+
+    Ok(RequirementTemplateDto {
+        category: Some(category),
+        title: Some(title),
+        text: Some(text),
+    })
+}
+
+fn find_requirement<D: RequirementRepository>(
+    documents: &D,
+    id: i64,
+) -> Result<RequirementSection, RequirementError> {
+    documents
+        .find_requirement(id)
+        .map_err(RequirementError::Repository)?
+        .ok_or(RequirementError::NotFound(id))
+}
+
+pub fn add_requirement<D>(
+    documents: &D,
+    document_id: i64,
+    dto: &RequirementSectionDto,
+) -> Result<i64, RequirementError>
+where
+    D: DocumentRepository + RequirementRepository,
+{
+    crate::find_editable_document(documents, document_id)?;
+    let sanitized = sanitize_requirement(dto)?;
+
+    match documents.add_requirement(document_id, &sanitized) {
+        Ok(Some(id)) => Ok(id),
+        Ok(None) => Err(DocumentError::ReadOnly(document_id).into()),
+        Err(error) => Err(RequirementError::Repository(error)),
+    }
+}
+
+/// Appends a copy of a library template to a draft document. The copy can be edited without
+/// touching the template.
+pub fn insert_requirement_template<D, T>(
+    documents: &D,
+    templates: &T,
+    document_id: i64,
+    template_id: i64,
+) -> Result<i64, RequirementError>
+where
+    D: DocumentRepository + RequirementRepository,
+    T: RequirementTemplateRepository,
+{
+    let template = templates
+        .find(template_id)
+        .map_err(RequirementError::TemplateRepository)?
+        .ok_or(RequirementError::TemplateNotFound(template_id))?;
+
+    add_requirement(
+        documents,
+        document_id,
+        &RequirementSectionDto {
+            category: Some(template.category),
+            title: Some(template.title),
+            text: Some(template.text),
+            template_id: Some(template.id),
+        },
+    )
+}
+
+pub fn update_requirement<D>(
+    documents: &D,
+    id: i64,
+    dto: &RequirementSectionDto,
+) -> Result<(), RequirementError>
+where
+    D: DocumentRepository + RequirementRepository,
+{
+    let requirement = find_requirement(documents, id)?;
+    crate::find_editable_document(documents, requirement.document_id)?;
+    let sanitized = sanitize_requirement(dto)?;
+
+    match documents.update_requirement(id, &sanitized) {
+        Ok(true) => Ok(()),
+        Ok(false) => {
+            crate::find_editable_document(documents, requirement.document_id)?;
+            Err(RequirementError::NotFound(id))
+        }
+        Err(error) => Err(RequirementError::Repository(error)),
+    }
+}
+
+pub fn delete_requirement<D>(documents: &D, id: i64) -> Result<(), RequirementError>
+where
+    D: DocumentRepository + RequirementRepository,
+{
+    let requirement = find_requirement(documents, id)?;
+    crate::find_editable_document(documents, requirement.document_id)?;
+
+    match documents.delete_requirement(id) {
+        Ok(true) => Ok(()),
+        Ok(false) => {
+            crate::find_editable_document(documents, requirement.document_id)?;
+            Err(RequirementError::NotFound(id))
+        }
+        Err(error) => Err(RequirementError::Repository(error)),
+    }
+}
+
+pub fn list_requirements<D: RequirementRepository>(
+    documents: &D,
+    document_id: i64,
+) -> Result<Vec<RequirementSection>, RequirementRepositoryError> {
+    documents.requirements(document_id)
+}
+
+/// Puts the requirement sections of the document in the given order. `ids` must list every
+/// section of the document exactly once.
+pub fn reorder_requirements<D>(
+    documents: &D,
+    document_id: i64,
+    ids: &[i64],
+) -> Result<(), RequirementError>
+where
+    D: DocumentRepository + RequirementRepository,
+{
+    crate::find_editable_document(documents, document_id)?;
+
+    let mut current: Vec<i64> = documents
+        .requirements(document_id)
+        .map_err(RequirementError::Repository)?
+        .iter()
+        .map(|requirement| requirement.id)
+        .collect();
+    let mut requested = ids.to_vec();
+    current.sort_unstable();
+    requested.sort_unstable();
+    if current != requested {
+        return Err(RequirementError::InvalidOrder);
+    }
+
+    match documents.reorder_requirements(document_id, ids) {
+        Ok(true) => Ok(()),
+        Ok(false) => Err(DocumentError::ReadOnly(document_id).into()),
+        Err(error) => Err(RequirementError::Repository(error)),
+    }
+}
+
+pub(crate) fn requirement_dto(requirement: &RequirementSection) -> RequirementSectionDto {
+    RequirementSectionDto {
+        category: Some(requirement.category),
+        title: Some(requirement.title.clone()),
+        text: Some(requirement.text.clone()),
+        template_id: requirement.template_id,
+    }
+}
+
+pub fn create_requirement_template<T: RequirementTemplateRepository>(
+    templates: &T,
+    dto: &RequirementTemplateDto,
+) -> Result<i64, RequirementError> {
+    let sanitized = sanitize_template(dto)?;
+
+    templates
+        .create(&sanitized)
+        .map_err(RequirementError::TemplateRepository)
+}
+
+pub fn update_requirement_template<T: RequirementTemplateRepository>(
+    templates: &T,
+    id: i64,
+    dto: &RequirementTemplateDto,
+) -> Result<(), RequirementError> {
+    let sanitized = sanitize_template(dto)?;
+
+    match templates.update(id, &sanitized) {
+        Ok(true) => Ok(()),
+        Ok(false) => Err(RequirementError::TemplateNotFound(id)),
+        Err(error) => Err(RequirementError::TemplateRepository(error)),
+    }
+}
+
+/// Removes a template from the library. Sections inserted from it keep their text.
+pub fn delete_requirement_template<T: RequirementTemplateRepository>(
+    templates: &T,
+    id: i64,
+) -> Result<(), RequirementError> {
+    match templates.delete(id) {
+        Ok(true) => Ok(()),
+        Ok(false) => Err(RequirementError::TemplateNotFound(id)),
+        Err(error) => Err(RequirementError::TemplateRepository(error)),
+    }
+}
+
+pub fn list_requirement_templates<T: RequirementTemplateRepository>(
+    templates: &T,
+    category: Option<RequirementCategory>,
+) -> Result<Vec<RequirementTemplate>, RequirementTemplateRepositoryError> {
+    templates.list(category)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::{self, MemoryDocuments, MemoryTemplates};
+    use crate::{DocumentSection, WorkType, submit_for_review};
+
+    fn object() -> ConstructionObject {
+        ConstructionObject {
+            id: 1,
+            document_id: 1,
+            name: "Школа на 550 мест".into(),
+            purpose_code: "19.7.1.1".into(),
+            address_id: 9,
+            cadastral_numbers: Vec::new(),
+            work_type: WorkType::Construction,
+        }
+    }
+
+    #[test]
+    fn placeholders_are_checked_and_rendered_from_document_data() {
+        let references = memory::references();
+        let entities = references.entities.borrow();
+        let values = requirement_values(&memory::document(1), Some(&object()), &entities);
+
+        assert_eq!(
+            render_requirement_text(
+                "Объект «{{object_name}}» по адресу {{ object_address }}. Застройщик: {{developer}}.",
+                &values
+            ),
+            "Объект «Школа на 550 мест» по адресу 77, Москва, Тверская, 1. \
+             Застройщик: Иванов Иван Иванович."
+        );
+        assert_eq!(
+            render_requirement_text("Объект: {{object_name}}", &RequirementValues::default()),
+            "Объект: {{object_name}}"
+        );
+
+        let dto = |text: &str| RequirementSectionDto {
+            category: Some(RequirementCategory::FireSafety),
+            title: Some("Пожарная безопасность".into()),
+            text: Some(text.into()),
+            template_id: None,
+        };
+        assert_eq!(
+            sanitize_requirement(&dto("Заказчик: {{customer}}")),
+            Err(RequirementError::UnknownPlaceholder("customer".into()))
+        );
+        assert_eq!(
+            sanitize_requirement(&dto("Объект: {{object_name")),
+            Err(RequirementError::UnclosedPlaceholder)
+        );
+    }
+
+    #[test]
+    fn templates_are_inserted_as_editable_copies() {
+        let documents = MemoryDocuments::default();
+        let templates = MemoryTemplates::default();
+        documents.put(memory::document(1));
+
+        let template_id = create_requirement_template(
+            &templates,
+            &RequirementTemplateDto {
+                category: Some(RequirementCategory::Accessibility),
+                title: Some(" Доступность ".into()),
+                text: Some("Обеспечить доступ МГН в {{object_name}}.".into()),
+            },
+        )
+        .expect("template");
+
+        let id =
+            insert_requirement_template(&documents, &templates, 1, template_id).expect("inserted");
+        assert_eq!(
+            documents.sections(1),
+            Ok(vec![DocumentSection::Requirements])
+        );
+
+        let mut dto = requirement_dto(&find_requirement(&documents, id).expect("requirement"));
+        assert_eq!(dto.title.as_deref(), Some("Доступность"));
+        dto.text = Some("Обеспечить доступ МГН.".into());
+        update_requirement(&documents, id, &dto).expect("update");
+
+        let template = templates
+            .find(template_id)
+            .expect("find")
+            .expect("template exists");
+        assert_eq!(template.text, "Обеспечить доступ МГН в {{object_name}}.");
+        assert_eq!(
+            insert_requirement_template(&documents, &templates, 1, 404),
+            Err(RequirementError::TemplateNotFound(404))
+        );
+
+        submit_for_review(&documents, 1).expect("submit");
+        assert_eq!(
+            delete_requirement(&documents, id),
+            Err(RequirementError::Document(DocumentError::ReadOnly(1)))
+        );
+    }
+}
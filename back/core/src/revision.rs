@@ -0,0 +1,578 @@
+use serde::Serialize;
+
+use crate::{
+    ConstructionObject, ConstructionObjectRepository, Document, DocumentAuthor, DocumentDeveloper,
+    DocumentDto, DocumentError, DocumentRepository, DocumentRepositoryError,
+    DocumentSourceRepository, DocumentStatus, EntityRef, RequirementRepository,
+    SignatoryRepository, TechnicalIndicatorRepository,
+};
+
+// ---------------- Document Revisions ----------------
+
+/// Parses a change mark. The schema treats the change mark as the sequence number of a change
+/// to the document: an original document has no change mark and every revision increments it,
+/// starting with `1`.
+pub(crate) fn parse_change_mark(value: &str) -> Result<u32, DocumentError> {
+    if value.is_empty() || !value.bytes().all(|byte| byte.is_ascii_digit()) {
+        return Err(DocumentError::InvalidChangeMark(value.to_string()));
+    }
+
+    value
+        .parse::<u32>()
+        .map_err(|_| DocumentError::InvalidChangeMark(value.to_string()))
+}
+
+/// Returns the change mark of the revision that follows a document with the given change mark.
+pub fn next_change_mark(current: Option<&str>) -> Result<String, DocumentError> {
+    let current = match current.map(str::trim).filter(|value| !value.is_empty()) {
+        Some(value) => parse_change_mark(value)?,
+        None => 0,
+    };
+
+    current
+        .checked_add(1)
+        .map(|next| next.to_string())
+        .ok_or_else(|| DocumentError::InvalidChangeMark(current.to_string()))
+}
+
+/// Issues a new revision of the document: copies its content into a new document with the
+/// given `uid` (a new UUID when it is empty), increments the change mark and links the revision
+/// to its predecessor. The content sections are copied as well.
+///
+/// Only the latest issued revision can be revised; the new revision starts as a draft and
+/// supersedes its predecessor once it is issued. Referenced entities are copied as is even when
+/// they were archived after the original was issued, so revisions stay reproducible. When a
+/// section cannot be copied the partial revision is deleted again, so the document can still be
+/// revised later.
+pub fn issue_revision<D>(repository: &D, id: i64, uid: &str) -> Result<i64, DocumentError>
+where
+    D: DocumentRepository
+        + ConstructionObjectRepository
+        + TechnicalIndicatorRepository
+        + RequirementRepository
+        + DocumentSourceRepository
+        + SignatoryRepository,
+{
+    let document = crate::find_document(repository, id)?;
+
+    if document.status != DocumentStatus::Issued {
+        return Err(DocumentError::NotIssued(id));
+    }
+
+    if let Some(revision_id) = repository
+        .next_revision(id)
+        .map_err(DocumentError::Repository)?
+    {
+        return Err(DocumentError::AlreadyRevised { id, revision_id });
+    }
+
+    let uid = match uid.trim() {
+        "" => crate::generate_uid(),
+        uid => uid.to_string(),
+    };
+
+    let dto = DocumentDto {
+        name: Some(document.name),
+        number: document.number,
+        date: document.date,
+        change_mark: Some(next_change_mark(document.change_mark.as_deref())?),
+        uid: Some(uid),
+        document_type: document.document_type,
+        author: Some(document.author),
+        developer: document.developer,
+        previous_revision_id: Some(document.id),
+        numbering: None,
+    };
+
+    let revision_id = repository.create(&dto).map_err(DocumentError::Repository)?;
+
+    match copy_sections(repository, id, revision_id) {
+        Ok(()) => Ok(revision_id),
+        Err(error) => match repository.delete_draft(revision_id) {
+            Ok(_) => Err(error),
+            Err(cleanup) => Err(DocumentError::Repository(DocumentRepositoryError::storage(
+                format!(
+                    "{error}; the partial revision {revision_id} could not be deleted: {cleanup}"
+                ),
+            ))),
+        },
+    }
+}
+
+/// Copies the content sections of one document into another, which must be a draft.
+/// Signatories are copied without their signing dates: the copy has to be signed anew.
+pub(crate) fn copy_sections<D>(repository: &D, from: i64, to: i64) -> Result<(), DocumentError>
+where
+    D: ConstructionObjectRepository
+        + TechnicalIndicatorRepository
+        + RequirementRepository
+        + DocumentSourceRepository
+        + SignatoryRepository,
+{
+    let construction_object = repository
+        .find_by_document(from)
+        .map_err(crate::section_error)?;
+    if let Some(object) = construction_object {
+        repository
+            .save(
+                to,
+                &crate::construction_object::construction_object_dto(&object),
+            )
+            .map_err(crate::section_error)?
+            .ok_or(DocumentError::ReadOnly(to))?;
+    }
+
+    for indicator in repository.indicators(from).map_err(crate::section_error)? {
+        repository
+            .add_indicator(to, &crate::indicator::indicator_dto(&indicator))
+            .map_err(crate::section_error)?
+            .ok_or(DocumentError::ReadOnly(to))?;
+    }
+
+    for requirement in repository
+        .requirements(from)
+        .map_err(crate::section_error)?
+    {
+        repository
+            .add_requirement(to, &crate::requirement::requirement_dto(&requirement))
+            .map_err(crate::section_error)?
+            .ok_or(DocumentError::ReadOnly(to))?;
+    }
+
+    for source_id in repository.source_ids(from).map_err(crate::section_error)? {
+        repository
+            .attach_source(to, source_id)
+            .map_err(crate::section_error)?;
+    }
+
+    for signatory in repository.signatories(from).map_err(crate::section_error)? {
+        let dto = crate::SignatoryDto {
+            signed_on: None,
+            ..crate::signatory::signatory_dto(&signatory)
+        };
+        repository
+            .add_signatory(to, &dto)
+            .map_err(crate::section_error)?
+            .ok_or(DocumentError::ReadOnly(to))?;
+    }
+
+    Ok(())
+}
+
+/// Returns the revision chain that ends with the given document, oldest first.
+pub fn document_revisions<D: DocumentRepository>(
+    repository: &D,
+    id: i64,
+) -> Result<Vec<Document>, DocumentError> {
+    let mut revisions = Vec::new();
+    let mut next = Some(id);
+
+    while let Some(id) = next {
+        let document = crate::find_document(repository, id)?;
+        next = document.previous_revision_id;
+        revisions.push(document);
+    }
+
+    revisions.reverse();
+    Ok(revisions)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FieldChange {
+    pub field: String,
+    pub before: Option<String>,
+    pub after: Option<String>,
+}
+
+fn describe_author(author: &DocumentAuthor) -> String {
+    match author {
+        DocumentAuthor::Informal { name } => name.clone(),
+        author => match author.reference() {
+            Some(reference) => reference.to_string(),
+            None => String::new(),
+        },
+    }
+}
+
+fn describe_developer(developer: DocumentDeveloper) -> String {
+    developer.reference().to_string()
+}
+
+/// Compares the header fields of two documents. Identifiers, revision links, statuses and
+/// timestamps are not content and are not compared; the sections are compared by
+/// [`diff_revisions`].
+pub fn diff_documents(before: &Document, after: &Document) -> Vec<FieldChange> {
+    let fields: [(&'static str, Option<String>, Option<String>); 7] = [
+        ("name", Some(before.name.clone()), Some(after.name.clone())),
+        ("number", before.number.clone(), after.number.clone()),
+        ("date", before.date.clone(), after.date.clone()),
+        (
+            "change_mark",
+            before.change_mark.clone(),
+            after.change_mark.clone(),
+        ),
+        (
+            "document_type",
+            before.document_type.map(|kind| kind.name().to_string()),
+            after.document_type.map(|kind| kind.name().to_string()),
+        ),
+        (
+            "author",
+            Some(describe_author(&before.author)),
+            Some(describe_author(&after.author)),
+        ),
+        (
+            "developer",
+            before.developer.map(describe_developer),
+            after.developer.map(describe_developer),
+        ),
+    ];
+
+    changed_fields(
+        fields
+            .into_iter()
+            .map(|(field, before, after)| (field.to_string(), before, after)),
+    )
+}
+
+fn changed_fields(
+    fields: impl IntoIterator<Item = (String, Option<String>, Option<String>)>,
+) -> Vec<FieldChange> {
+    fields
+        .into_iter()
+        .filter(|(_, before, after)| before != after)
+        .map(|(field, before, after)| FieldChange {
+            field,
+            before,
+            after,
+        })
+        .collect()
+}
+
+/// Entries of a list section, keyed so that an entry is matched with its counterpart in the
+/// other document wherever it sits in the list. Repeated keys get a counter.
+fn keyed_entries(entries: impl IntoIterator<Item = (String, String)>) -> Vec<(String, String)> {
+    let mut seen: Vec<String> = Vec::new();
+    entries
+        .into_iter()
+        .map(|(key, value)| {
+            let repeats = seen.iter().filter(|existing| **existing == key).count();
+            seen.push(key.clone());
+            match repeats {
+                0 => (key, value),
+                repeats => (format!("{key} #{}", repeats + 1), value),
+            }
+        })
+        .collect()
+}
+
+/// Compares two list sections entry by entry: `section[key]` is reported when the entry was
+/// added, removed or changed.
+fn diff_entries(
+    section: &str,
+    before: Vec<(String, String)>,
+    after: Vec<(String, String)>,
+) -> Vec<FieldChange> {
+    let find = |entries: &[(String, String)], key: &str| {
+        entries
+            .iter()
+            .find(|(existing, _)| existing == key)
+            .map(|(_, value)| value.clone())
+    };
+
+    let mut keys: Vec<&String> = before.iter().map(|(key, _)| key).collect();
+    for (key, _) in &after {
+        if !keys.contains(&key) {
+            keys.push(key);
+        }
+    }
+
+    changed_fields(keys.into_iter().map(|key| {
+        (
+            format!("{section}[{key}]"),
+            find(&before, key),
+            find(&after, key),
+        )
+    }))
+}
+
+fn diff_construction_objects(
+    before: Option<&ConstructionObject>,
+    after: Option<&ConstructionObject>,
+) -> Vec<FieldChange> {
+    type Describe = fn(&ConstructionObject) -> String;
+    let fields: [(&str, Describe); 5] = [
+        ("name", |object| object.name.clone()),
+        ("purpose_code", |object| object.purpose_code.clone()),
+        ("address", |object| {
+            EntityRef::Address(object.address_id).to_string()
+        }),
+        ("cadastral_numbers", |object| {
+            object.cadastral_numbers.join(", ")
+        }),
+        ("work_type", |object| object.work_type.name().to_string()),
+    ];
+
+    changed_fields(fields.into_iter().map(|(field, describe)| {
+        (
+            format!("construction_object.{field}"),
+            before.map(describe),
+            after.map(describe),
+        )
+    }))
+}
+
+/// Content sections of a document in a form that can be compared.
+struct Sections {
+    construction_object: Option<ConstructionObject>,
+    indicators: Vec<(String, String)>,
+    requirements: Vec<(String, String)>,
+    source_documents: Vec<(String, String)>,
+    signatories: Vec<(String, String)>,
+}
+
+fn load_sections<D>(repository: &D, id: i64) -> Result<Sections, DocumentError>
+where
+    D: ConstructionObjectRepository
+        + TechnicalIndicatorRepository
+        + RequirementRepository
+        + DocumentSourceRepository
+        + SignatoryRepository,
+{
+    let indicators = repository.indicators(id).map_err(crate::section_error)?;
+    let requirements = repository.requirements(id).map_err(crate::section_error)?;
+    let source_ids = repository.source_ids(id).map_err(crate::section_error)?;
+    let signatories = repository.signatories(id).map_err(crate::section_error)?;
+
+    Ok(Sections {
+        construction_object: repository
+            .find_by_document(id)
+            .map_err(crate::section_error)?,
+        indicators: keyed_entries(indicators.into_iter().map(|indicator| {
+            (
+                indicator.name,
+                format!("{} (ОКЕИ {})", indicator.value, indicator.unit_okei),
+            )
+        })),
+        requirements: keyed_entries(requirements.into_iter().map(|requirement| {
+            (
+                format!("{}: {}", requirement.category.name(), requirement.title),
+                requirement.text,
+            )
+        })),
+        source_documents: keyed_entries(source_ids.into_iter().map(|source_id| {
+            (
+                EntityRef::SourceDocument(source_id).to_string(),
+                "linked".to_string(),
+            )
+        })),
+        signatories: keyed_entries(signatories.into_iter().map(|signatory| {
+            (
+                format!(
+                    "{}: {}",
+                    signatory.role.name(),
+                    EntityRef::Person(signatory.person_id)
+                ),
+                signatory.position,
+            )
+        })),
+    })
+}
+
+/// Compares two documents, usually consecutive revisions: the header fields first, then the
+/// construction object, the technical and economic indicators, the requirements, the linked
+/// source documents and the signatories. Entries of the list sections are matched by name,
+/// title, source document and signatory rather than by position; signing dates are not
+/// compared because a revision has to be signed anew.
+pub fn diff_revisions<D>(
+    repository: &D,
+    before_id: i64,
+    after_id: i64,
+) -> Result<Vec<FieldChange>, DocumentError>
+where
+    D: DocumentRepository
+        + ConstructionObjectRepository
+        + TechnicalIndicatorRepository
+        + RequirementRepository
+        + DocumentSourceRepository
+        + SignatoryRepository,
+{
+    let before = crate::find_document(repository, before_id)?;
+    let after = crate::find_document(repository, after_id)?;
+    let before_sections = load_sections(repository, before_id)?;
+    let after_sections = load_sections(repository, after_id)?;
+
+    let mut changes = diff_documents(&before, &after);
+    changes.extend(diff_construction_objects(
+        before_sections.construction_object.as_ref(),
+        after_sections.construction_object.as_ref(),
+    ));
+    changes.extend(diff_entries(
+        "indicators",
+        before_sections.indicators,
+        after_sections.indicators,
+    ));
+    changes.extend(diff_entries(
+        "requirements",
+        before_sections.requirements,
+        after_sections.requirements,
+    ));
+    changes.extend(diff_entries(
+        "source_documents",
+        before_sections.source_documents,
+        after_sections.source_documents,
+    ));
+    changes.extend(diff_entries(
+        "signatories",
+        before_sections.signatories,
+        after_sections.signatories,
+    ));
+
+    Ok(changes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::{self, MemoryDocuments};
+
+    #[test]
+    fn next_change_mark_starts_at_one_and_increments() {
+        assert_eq!(next_change_mark(None), Ok("1".to_string()));
+        assert_eq!(next_change_mark(Some(" ")), Ok("1".to_string()));
+        assert_eq!(next_change_mark(Some("4")), Ok("5".to_string()));
+        assert_eq!(
+            next_change_mark(Some("A")),
+            Err(DocumentError::InvalidChangeMark("A".into()))
+        );
+    }
+
+    #[test]
+    fn issue_revision_copies_content_and_links_previous() {
+        let repository = MemoryDocuments::default();
+        let mut issued = memory::document(1);
+        issued.status = DocumentStatus::Issued;
+        repository.put(issued);
+
+        let id = issue_revision(&repository, 1, "uid-2").expect("revision should be issued");
+
+        let revision = repository.get(id);
+        assert_eq!(revision.name, "ЗНП");
+        assert_eq!(revision.uid, "uid-2");
+        assert_eq!(revision.change_mark.as_deref(), Some("1"));
+        assert_eq!(revision.previous_revision_id, Some(1));
+        assert_eq!(revision.developer, memory::document(1).developer);
+        assert_eq!(revision.status, DocumentStatus::Draft);
+
+        assert_eq!(
+            issue_revision(&repository, 1, "uid-3"),
+            Err(DocumentError::AlreadyRevised {
+                id: 1,
+                revision_id: id
+            })
+        );
+        assert_eq!(
+            issue_revision(&repository, id, "uid-3"),
+            Err(DocumentError::NotIssued(id))
+        );
+
+        let chain = document_revisions(&repository, id).expect("chain");
+        assert_eq!(
+            chain.iter().map(|document| document.id).collect::<Vec<_>>(),
+            vec![1, id]
+        );
+    }
+
+    #[test]
+    fn diff_documents_reports_changed_fields_only() {
+        let before = memory::document(1);
+        let mut after = memory::document(2);
+        after.change_mark = Some("1".into());
+        after.previous_revision_id = Some(1);
+        after.number = Some("7".into());
+
+        let changes = diff_documents(&before, &after);
+
+        assert_eq!(
+            changes,
+            vec![
+                FieldChange {
+                    field: "number".into(),
+                    before: Some("42".into()),
+                    after: Some("7".into()),
+                },
+                FieldChange {
+                    field: "change_mark".into(),
+                    before: None,
+                    after: Some("1".into()),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn diff_revisions_compares_the_sections() {
+        let repository = MemoryDocuments::default();
+        repository.put(memory::document(1));
+        let indicator = |name: &str, value: &str| crate::TechnicalIndicatorDto {
+            name: Some(name.into()),
+            value: Some(value.into()),
+            unit_okei: Some("055".into()),
+        };
+        repository
+            .add_indicator(1, &indicator("Общая площадь", "5400"))
+            .expect("indicator");
+        repository
+            .add_requirement(
+                1,
+                &crate::RequirementSectionDto {
+                    category: Some(crate::RequirementCategory::Architecture),
+                    title: Some("Фасады".into()),
+                    text: Some("Облицовка кирпичом".into()),
+                    template_id: None,
+                },
+            )
+            .expect("requirement");
+        let mut issued = repository.get(1);
+        issued.status = DocumentStatus::Issued;
+        repository.put(issued);
+
+        let id = issue_revision(&repository, 1, "").expect("revision");
+        assert_eq!(
+            diff_revisions(&repository, 1, id).map(|changes| changes.len()),
+            Ok(1),
+            "only the change mark differs in a fresh revision"
+        );
+
+        let copied = repository.indicators(id).expect("indicators")[0].id;
+        repository
+            .update_indicator(copied, &indicator("Общая площадь", "5600"))
+            .expect("update");
+        repository
+            .add_indicator(id, &indicator("Этажность", "3"))
+            .expect("indicator");
+        repository.attach_source(id, 20).expect("source");
+
+        let changes = diff_revisions(&repository, 1, id).expect("diff");
+        assert_eq!(
+            changes[1..],
+            [
+                FieldChange {
+                    field: "indicators[Общая площадь]".into(),
+                    before: Some("5400 (ОКЕИ 055)".into()),
+                    after: Some("5600 (ОКЕИ 055)".into()),
+                },
+                FieldChange {
+                    field: "indicators[Этажность]".into(),
+                    before: None,
+                    after: Some("3 (ОКЕИ 055)".into()),
+                },
+                FieldChange {
+                    field: "source_documents[source document 20]".into(),
+                    before: None,
+                    after: Some("linked".into()),
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,436 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    DocumentError, DocumentRepository, EntityRef, ReferenceError, ReferenceRepository,
+    ensure_attachable,
+};
+
+// ---------------- Document Signatories ----------------
+
+/// Capacity in which a person signs a ZNP: the developer approves it («Утверждаю») and the
+/// designer agrees it («Согласовано»).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum SigningRole {
+    Approval,
+    Agreement,
+}
+
+impl SigningRole {
+    pub const ALL: [SigningRole; 2] = [Self::Approval, Self::Agreement];
+
+    /// Value stored in the `signatory.role` column.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Approval => "approval",
+            Self::Agreement => "agreement",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|role| role.as_str() == value)
+    }
+
+    /// Caption of the signature block.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Approval => "Утверждаю",
+            Self::Agreement => "Согласовано",
+        }
+    }
+}
+
+/// Signing role as listed for the UI.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SigningRoleInfo {
+    pub code: SigningRole,
+    pub name: &'static str,
+}
+
+pub fn list_signing_roles() -> Vec<SigningRoleInfo> {
+    SigningRole::ALL
+        .into_iter()
+        .map(|role| SigningRoleInfo {
+            code: role,
+            name: role.name(),
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Default, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(default)]
+pub struct SignatoryDto {
+    pub person_id: i64,
+    /// Position of the person, e.g. «Генеральный директор ООО "Проект"».
+    pub position: Option<String>,
+    pub role: Option<SigningRole>,
+    /// Date of signing, `YYYY-MM-DD`; filled in once the document is signed.
+    pub signed_on: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct Signatory {
+    pub id: i64,
+    pub document_id: i64,
+    pub person_id: i64,
+    pub position: String,
+    pub role: SigningRole,
+    pub signed_on: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SignatoryRepositoryError {
+    Storage(String),
+}
+
+impl SignatoryRepositoryError {
+    pub fn storage<S: Into<String>>(message: S) -> Self {
+        Self::Storage(message.into())
+    }
+}
+
+impl std::fmt::Display for SignatoryRepositoryError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Storage(message) => f.write_str(message),
+        }
+    }
+}
+
+impl std::error::Error for SignatoryRepositoryError {}
+
+pub trait SignatoryRepository {
+    /// Returns `None` when the document is not a draft.
+    fn add_signatory(
+        &self,
+        document_id: i64,
+        dto: &SignatoryDto,
+    ) -> Result<Option<i64>, SignatoryRepositoryError>;
+    /// Returns `false` when the signatory does not exist or its document is not a draft.
+    fn update_signatory(
+        &self,
+        id: i64,
+        dto: &SignatoryDto,
+    ) -> Result<bool, SignatoryRepositoryError>;
+    /// Returns `false` when the signatory does not exist or its document is not a draft.
+    fn delete_signatory(&self, id: i64) -> Result<bool, SignatoryRepositoryError>;
+    fn find_signatory(&self, id: i64) -> Result<Option<Signatory>, SignatoryRepositoryError>;
+    /// Signatories of the document, the approving one first.
+    fn signatories(&self, document_id: i64) -> Result<Vec<Signatory>, SignatoryRepositoryError>;
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SignatoryError {
+    MissingPosition,
+    MissingRole,
+    InvalidDate(String),
+    /// The document already has a signatory in this role.
+    DuplicateRole(SigningRole),
+    NotFound(i64),
+    Document(DocumentError),
+    Reference(ReferenceError),
+    Repository(SignatoryRepositoryError),
+}
+
+impl std::fmt::Display for SignatoryError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::MissingPosition => f.write_str("Signatory position is required"),
+            Self::MissingRole => f.write_str("Signing role is required"),
+            Self::InvalidDate(date) => write!(f, "Invalid date: {date}"),
+            Self::DuplicateRole(role) => {
+                write!(f, "Document already has a signatory for {}", role.as_str())
+            }
+            Self::NotFound(id) => write!(f, "Signatory {id} not found"),
+            Self::Document(error) => write!(f, "{error}"),
+            Self::Reference(error) => write!(f, "{error}"),
+            Self::Repository(error) => write!(f, "Repository error: {error}"),
+        }
+    }
+}
+
+impl std::error::Error for SignatoryError {}
+
+impl From<DocumentError> for SignatoryError {
+    fn from(error: DocumentError) -> Self {
+        Self::Document(error)
+    }
+}
+
+impl From<ReferenceError> for SignatoryError {
+    fn from(error: ReferenceError) -> Self {
+        Self::Reference(error)
+    }
+}
+
+fn sanitize_signatory(dto: &SignatoryDto) -> Result<SignatoryDto, SignatoryError> {
+    fn sanitize_field(value: &Option<String>) -> Option<String> {
+        value
+            .as_ref()
+            .map(|value| value.trim())
+            .filter(|value| !value.is_empty())
+            .map(|value| value.to_string())
+    }
+
+    let position = sanitize_field(&dto.position).ok_or(SignatoryError::MissingPosition)?;
+    let role = dto.role.ok_or(SignatoryError::MissingRole)?;
+
+    let signed_on = sanitize_field(&dto.signed_on);
+    if let Some(date) = &signed_on
+        && !crate::source_document::is_iso_date(date)
+    {
+        return Err(SignatoryError::InvalidDate(date.clone()));
+    }
+
+    Ok(SignatoryDto {
+        person_id: dto.person_id,
+        position: Some(position),
+        role: Some(role),
+        signed_on,
+    })
+}
+
+fn find_signatory<D: SignatoryRepository>(
+    documents: &D,
+    id: i64,
+) -> Result<Signatory, SignatoryError> {
+    documents
+        .find_signatory(id)
+        .map_err(SignatoryError::Repository)?
+        .ok_or(SignatoryError::NotFound(id))
+}
+
+/// Fails when another signatory of the document already signs in `role`.
+fn ensure_role_free<D: SignatoryRepository>(
+    documents: &D,
+    document_id: i64,
+    role: SigningRole,
+    except: Option<i64>,
+) -> Result<(), SignatoryError> {
+    let taken = documents
+        .signatories(document_id)
+        .map_err(SignatoryError::Repository)?
+        .iter()
+        .any(|signatory| signatory.role == role && Some(signatory.id) != except);
+
+    if taken {
+        Err(SignatoryError::DuplicateRole(role))
+    } else {
+        Ok(())
+    }
+}
+
+/// Adds a signatory to a draft document. The person must be active and each role can be taken
+/// by one signatory only.
+pub fn add_signatory<D, R>(
+    documents: &D,
+    references: &R,
+    document_id: i64,
+    dto: &SignatoryDto,
+) -> Result<i64, SignatoryError>
+where
+    D: DocumentRepository + SignatoryRepository,
+    R: ReferenceRepository,
+{
+    crate::find_editable_document(documents, document_id)?;
+    let sanitized = sanitize_signatory(dto)?;
+    ensure_attachable(references, EntityRef::Person(sanitized.person_id))?;
+    if let Some(role) = sanitized.role {
+        ensure_role_free(documents, document_id, role, None)?;
+    }
+
+    match documents.add_signatory(document_id, &sanitized) {
+        Ok(Some(id)) => Ok(id),
+        Ok(None) => Err(DocumentError::ReadOnly(document_id).into()),
+        Err(error) => Err(SignatoryError::Repository(error)),
+    }
+}
+
+pub fn update_signatory<D, R>(
+    documents: &D,
+    references: &R,
+    id: i64,
+    dto: &SignatoryDto,
+) -> Result<(), SignatoryError>
+where
+    D: DocumentRepository + SignatoryRepository,
+    R: ReferenceRepository,
+{
+    let signatory = find_signatory(documents, id)?;
+    crate::find_editable_document(documents, signatory.document_id)?;
+    let sanitized = sanitize_signatory(dto)?;
+    if sanitized.person_id != signatory.person_id {
+        ensure_attachable(references, EntityRef::Person(sanitized.person_id))?;
+    }
+    if let Some(role) = sanitized.role {
+        ensure_role_free(documents, signatory.document_id, role, Some(id))?;
+    }
+
+    match documents.update_signatory(id, &sanitized) {
+        Ok(true) => Ok(()),
+        Ok(false) => {
+            crate::find_editable_document(documents, signatory.document_id)?;
+            Err(SignatoryError::NotFound(id))
+        }
+        Err(error) => Err(SignatoryError::Repository(error)),
+    }
+}
+
+pub fn delete_signatory<D>(documents: &D, id: i64) -> Result<(), SignatoryError>
+where
+    D: DocumentRepository + SignatoryRepository,
+{
+    let signatory = find_signatory(documents, id)?;
+    crate::find_editable_document(documents, signatory.document_id)?;
+
+    match documents.delete_signatory(id) {
+        Ok(true) => Ok(()),
+        Ok(false) => {
+            crate::find_editable_document(documents, signatory.document_id)?;
+            Err(SignatoryError::NotFound(id))
+        }
+        Err(error) => Err(SignatoryError::Repository(error)),
+    }
+}
+
+pub fn list_signatories<D: SignatoryRepository>(
+    documents: &D,
+    document_id: i64,
+) -> Result<Vec<Signatory>, SignatoryRepositoryError> {
+    documents.signatories(document_id)
+}
+
+/// Roles nobody signs in yet. A document can only be approved once every role is taken.
+pub fn missing_signing_roles(signatories: &[Signatory]) -> Vec<SigningRole> {
+    SigningRole::ALL
+        .into_iter()
+        .filter(|role| !signatories.iter().any(|signatory| signatory.role == *role))
+        .collect()
+}
+
+pub(crate) fn signatory_dto(signatory: &Signatory) -> SignatoryDto {
+    SignatoryDto {
+        person_id: signatory.person_id,
+        position: Some(signatory.position.clone()),
+        role: Some(signatory.role),
+        signed_on: signatory.signed_on.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::{self, MemoryDocuments};
+    use crate::{CompletenessIssue, approve_document, submit_for_review};
+
+    fn dto(person_id: i64, role: SigningRole) -> SignatoryDto {
+        SignatoryDto {
+            person_id,
+            position: Some(" Генеральный директор ".into()),
+            role: Some(role),
+            signed_on: None,
+        }
+    }
+
+    #[test]
+    fn approval_requires_every_signatory() {
+        let documents = MemoryDocuments::default();
+        let references = memory::references();
+        documents.put(memory::document(1));
+
+        let id = add_signatory(&documents, &references, 1, &dto(5, SigningRole::Approval))
+            .expect("approval signatory");
+        assert_eq!(
+            add_signatory(&documents, &references, 1, &dto(5, SigningRole::Approval)),
+            Err(SignatoryError::DuplicateRole(SigningRole::Approval))
+        );
+        assert_eq!(
+            documents
+                .find_signatory(id)
+                .expect("find")
+                .map(|s| s.position),
+            Some("Генеральный директор".into())
+        );
+
+        submit_for_review(&documents, 1).expect("submit");
+        assert_eq!(
+            approve_document(&documents, 1),
+            Err(DocumentError::Incomplete(vec![
+                CompletenessIssue::MissingSignatory {
+                    role: SigningRole::Agreement
+                }
+            ]))
+        );
+        assert_eq!(
+            add_signatory(&documents, &references, 1, &dto(5, SigningRole::Agreement)),
+            Err(SignatoryError::Document(DocumentError::ReadOnly(1)))
+        );
+
+        crate::return_to_draft(&documents, 1).expect("return to draft");
+        add_signatory(&documents, &references, 1, &dto(5, SigningRole::Agreement))
+            .expect("agreement signatory");
+        submit_for_review(&documents, 1).expect("submit");
+        approve_document(&documents, 1).expect("approve");
+    }
+
+    #[test]
+    fn signatories_are_validated() {
+        let documents = MemoryDocuments::default();
+        let references = memory::references();
+        documents.put(memory::document(1));
+
+        assert_eq!(
+            add_signatory(&documents, &references, 1, &dto(404, SigningRole::Approval)),
+            Err(SignatoryError::Reference(ReferenceError::NotFound(
+                EntityRef::Person(404)
+            )))
+        );
+        assert_eq!(
+            add_signatory(
+                &documents,
+                &references,
+                1,
+                &SignatoryDto {
+                    position: Some("  ".into()),
+                    ..dto(5, SigningRole::Approval)
+                }
+            ),
+            Err(SignatoryError::MissingPosition)
+        );
+        assert_eq!(
+            add_signatory(
+                &documents,
+                &references,
+                1,
+                &SignatoryDto {
+                    signed_on: Some("2026-02-30".into()),
+                    ..dto(5, SigningRole::Approval)
+                }
+            ),
+            Err(SignatoryError::InvalidDate("2026-02-30".into()))
+        );
+
+        let approval = add_signatory(&documents, &references, 1, &dto(5, SigningRole::Approval))
+            .expect("approval");
+        add_signatory(&documents, &references, 1, &dto(5, SigningRole::Agreement))
+            .expect("agreement");
+        assert_eq!(
+            update_signatory(
+                &documents,
+                &references,
+                approval,
+                &dto(5, SigningRole::Agreement)
+            ),
+            Err(SignatoryError::DuplicateRole(SigningRole::Agreement))
+        );
+
+        delete_signatory(&documents, approval).expect("delete");
+        assert_eq!(
+            missing_signing_roles(&documents.signatories(1).expect("signatories")),
+            vec![SigningRole::Approval]
+        );
+    }
+}
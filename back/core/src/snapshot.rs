@@ -0,0 +1,412 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{
+    ConstructionObject, ConstructionObjectRepository, Document, DocumentDeveloper, DocumentError,
+    DocumentRepository, DocumentSourceRepository, DocumentStatus, EntityRef, FieldChange,
+    ReferenceEntity, ReferenceError, ReferenceRepository, RequirementRepository,
+    RequirementSection, Signatory, SignatoryRepository, TechnicalIndicator,
+    TechnicalIndicatorRepository,
+};
+
+// ---------------- Issued Document Snapshots ----------------
+
+/// Denormalized copy of every entity an issued document refers to, including their addresses
+/// and the issuers of its source documents.
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct DocumentSnapshot {
+    pub entities: Vec<ReferenceEntity>,
+}
+
+impl DocumentSnapshot {
+    pub fn get(&self, reference: EntityRef) -> Option<&ReferenceEntity> {
+        self.entities
+            .iter()
+            .find(|entity| entity.reference() == reference)
+    }
+}
+
+/// Entities referenced directly by the document. Addresses of these entities are resolved when
+/// the snapshot is taken.
+pub fn document_references(document: &Document) -> Vec<EntityRef> {
+    document
+        .author
+        .reference()
+        .into_iter()
+        .chain(
+            document
+                .developer
+                .as_ref()
+                .map(DocumentDeveloper::reference),
+        )
+        .collect()
+}
+
+/// References of the document together with the location of its construction object, its
+/// source documents and its signatories.
+fn content_references(
+    document: &Document,
+    construction_object: Option<&ConstructionObject>,
+    source_ids: &[i64],
+    signatories: &[Signatory],
+) -> Vec<EntityRef> {
+    document_references(document)
+        .into_iter()
+        .chain(construction_object.map(|object| EntityRef::Address(object.address_id)))
+        .chain(source_ids.iter().map(|&id| EntityRef::SourceDocument(id)))
+        .chain(
+            signatories
+                .iter()
+                .map(|signatory| EntityRef::Person(signatory.person_id)),
+        )
+        .collect()
+}
+
+fn load_references<R: ReferenceRepository>(
+    references: &R,
+    direct: Vec<EntityRef>,
+) -> Result<Vec<ReferenceEntity>, ReferenceError> {
+    fn push<R: ReferenceRepository>(
+        references: &R,
+        entities: &mut Vec<ReferenceEntity>,
+        reference: EntityRef,
+    ) -> Result<(), ReferenceError> {
+        if entities
+            .iter()
+            .any(|entity| entity.reference() == reference)
+        {
+            return Ok(());
+        }
+
+        let entity = references
+            .load(reference)
+            .map_err(ReferenceError::Repository)?
+            .ok_or(ReferenceError::NotFound(reference))?;
+        let related = entity.related();
+        entities.push(entity);
+
+        if let Some(related) = related {
+            push(references, entities, related)?;
+        }
+
+        Ok(())
+    }
+
+    let mut entities = Vec::new();
+    for reference in direct {
+        push(references, &mut entities, reference)?;
+    }
+
+    Ok(entities)
+}
+
+pub fn take_snapshot<R: ReferenceRepository>(
+    references: &R,
+    document: &Document,
+    construction_object: Option<&ConstructionObject>,
+    source_ids: &[i64],
+    signatories: &[Signatory],
+) -> Result<DocumentSnapshot, ReferenceError> {
+    Ok(DocumentSnapshot {
+        entities: load_references(
+            references,
+            content_references(document, construction_object, source_ids, signatories),
+        )?,
+    })
+}
+
+fn load_construction_object<D: ConstructionObjectRepository>(
+    documents: &D,
+    id: i64,
+) -> Result<Option<ConstructionObject>, DocumentError> {
+    documents.find_by_document(id).map_err(crate::section_error)
+}
+
+/// Issues an approved document, freezing the current state of the entities it refers to.
+/// Exports of the document use the snapshot from then on. When the document is a revision, the
+/// revision it replaces becomes superseded.
+pub fn issue_document<D, R>(documents: &D, references: &R, id: i64) -> Result<(), DocumentError>
+where
+    D: DocumentRepository
+        + ConstructionObjectRepository
+        + DocumentSourceRepository
+        + SignatoryRepository,
+    R: ReferenceRepository,
+{
+    let document = crate::find_document(documents, id)?;
+
+    if document.issued_at.is_some() {
+        return Err(DocumentError::AlreadyIssued(id));
+    }
+
+    crate::lifecycle::ensure_transition(&document, DocumentStatus::Issued)?;
+    crate::lifecycle::ensure_complete(documents, &document)?;
+
+    let construction_object = load_construction_object(documents, id)?;
+    let source_ids = documents.source_ids(id).map_err(crate::section_error)?;
+    let signatories = documents.signatories(id).map_err(crate::section_error)?;
+    let snapshot = take_snapshot(
+        references,
+        &document,
+        construction_object.as_ref(),
+        &source_ids,
+        &signatories,
+    )?;
+
+    documents
+        .issue(id, &snapshot)
+        .map_err(DocumentError::Repository)
+}
+
+/// Document together with the entities it refers to, ready to be exported.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct DocumentExport {
+    pub document: Document,
+    pub construction_object: Option<ConstructionObject>,
+    pub indicators: Vec<TechnicalIndicator>,
+    /// Requirement sections with their placeholders substituted.
+    pub requirements: Vec<RequirementSection>,
+    pub signatories: Vec<Signatory>,
+    /// Referenced entities, including the source documents and the signatories of the document.
+    pub entities: Vec<ReferenceEntity>,
+    /// `true` when the entities come from the snapshot frozen at issue.
+    pub from_snapshot: bool,
+}
+
+pub fn export_document<D, R>(
+    documents: &D,
+    references: &R,
+    id: i64,
+) -> Result<DocumentExport, DocumentError>
+where
+    D: DocumentRepository
+        + ConstructionObjectRepository
+        + TechnicalIndicatorRepository
+        + RequirementRepository
+        + DocumentSourceRepository
+        + SignatoryRepository,
+    R: ReferenceRepository,
+{
+    let document = crate::find_document(documents, id)?;
+    let construction_object = load_construction_object(documents, id)?;
+    let indicators = documents.indicators(id).map_err(crate::section_error)?;
+    let requirements = documents.requirements(id).map_err(crate::section_error)?;
+    let source_ids = documents.source_ids(id).map_err(crate::section_error)?;
+    let signatories = documents.signatories(id).map_err(crate::section_error)?;
+
+    let snapshot = if document.issued_at.is_some() {
+        documents.snapshot(id).map_err(DocumentError::Repository)?
+    } else {
+        None
+    };
+    let from_snapshot = snapshot.is_some();
+    let entities = match snapshot {
+        Some(snapshot) => snapshot.entities,
+        None => load_references(
+            references,
+            content_references(
+                &document,
+                construction_object.as_ref(),
+                &source_ids,
+                &signatories,
+            ),
+        )?,
+    };
+
+    let values = crate::requirement_values(&document, construction_object.as_ref(), &entities);
+    let requirements = requirements
+        .into_iter()
+        .map(|requirement| RequirementSection {
+            text: crate::render_requirement_text(&requirement.text, &values),
+            ..requirement
+        })
+        .collect();
+
+    Ok(DocumentExport {
+        document,
+        construction_object,
+        indicators,
+        requirements,
+        signatories,
+        entities,
+        from_snapshot,
+    })
+}
+
+/// Differences between a snapshotted entity and its live row.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ReferenceChange {
+    pub reference: EntityRef,
+    /// The live row no longer exists.
+    pub removed: bool,
+    pub changes: Vec<FieldChange>,
+}
+
+fn diff_entities(before: &ReferenceEntity, after: &ReferenceEntity) -> Vec<FieldChange> {
+    fn text(value: Option<&Value>) -> Option<String> {
+        match value {
+            None | Some(Value::Null) => None,
+            Some(Value::String(value)) => Some(value.clone()),
+            Some(value) => Some(value.to_string()),
+        }
+    }
+
+    let before = serde_json::to_value(before).unwrap_or(Value::Null);
+    let after = serde_json::to_value(after).unwrap_or(Value::Null);
+
+    let (Some(before), Some(after)) = (before.as_object(), after.as_object()) else {
+        return Vec::new();
+    };
+
+    before
+        .keys()
+        .chain(after.keys().filter(|key| !before.contains_key(*key)))
+        .filter(|key| key.as_str() != "kind")
+        .filter_map(|key| {
+            let old = text(before.get(key));
+            let new = text(after.get(key));
+            (old != new).then(|| FieldChange {
+                field: key.clone(),
+                before: old,
+                after: new,
+            })
+        })
+        .collect()
+}
+
+/// Reports what changed in the live reference data since the document was issued.
+pub fn snapshot_changes<D: DocumentRepository, R: ReferenceRepository>(
+    documents: &D,
+    references: &R,
+    id: i64,
+) -> Result<Vec<ReferenceChange>, DocumentError> {
+    let snapshot = documents
+        .snapshot(id)
+        .map_err(DocumentError::Repository)?
+        .ok_or(DocumentError::NotIssued(id))?;
+
+    let mut result = Vec::new();
+    for frozen in &snapshot.entities {
+        let reference = frozen.reference();
+        let live = references
+            .load(reference)
+            .map_err(|error| DocumentError::Reference(ReferenceError::Repository(error)))?;
+
+        let change = match live {
+            None => ReferenceChange {
+                reference,
+                removed: true,
+                changes: Vec::new(),
+            },
+            Some(live) => ReferenceChange {
+                reference,
+                removed: false,
+                changes: diff_entities(frozen, &live),
+            },
+        };
+
+        if change.removed || !change.changes.is_empty() {
+            result.push(change);
+        }
+    }
+
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::{self, MemoryDocuments};
+    use crate::{approve_document, submit_for_review};
+
+    fn approved(documents: &MemoryDocuments) {
+        documents.put(memory::document(1));
+        documents.sign(1);
+        submit_for_review(documents, 1).expect("submit");
+        approve_document(documents, 1).expect("approve");
+    }
+
+    #[test]
+    fn issued_documents_export_the_frozen_snapshot() {
+        let documents = MemoryDocuments::default();
+        let references = memory::references();
+        approved(&documents);
+
+        issue_document(&documents, &references, 1).expect("document should be issued");
+        assert_eq!(
+            issue_document(&documents, &references, 1),
+            Err(DocumentError::AlreadyIssued(1))
+        );
+
+        {
+            let mut entities = references.entities.borrow_mut();
+            for entity in entities.iter_mut() {
+                match entity {
+                    ReferenceEntity::Organization(organization) => {
+                        organization.email = "new@example.com".into()
+                    }
+                    ReferenceEntity::Address(address) if address.id == 9 => {
+                        address.street = Some("Новая".into())
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        let export = export_document(&documents, &references, 1).expect("export");
+        assert!(export.from_snapshot);
+        assert_eq!(export.entities.len(), 4);
+        match export.entities.first() {
+            Some(ReferenceEntity::Organization(organization)) => {
+                assert_eq!(organization.email, "info@example.com")
+            }
+            other => panic!("unexpected entity: {other:?}"),
+        }
+
+        let changes = snapshot_changes(&documents, &references, 1).expect("changes");
+        assert_eq!(
+            changes,
+            vec![
+                ReferenceChange {
+                    reference: EntityRef::Organization(3),
+                    removed: false,
+                    changes: vec![FieldChange {
+                        field: "email".into(),
+                        before: Some("info@example.com".into()),
+                        after: Some("new@example.com".into()),
+                    }],
+                },
+                ReferenceChange {
+                    reference: EntityRef::Address(9),
+                    removed: false,
+                    changes: vec![FieldChange {
+                        field: "street".into(),
+                        before: Some("Тверская".into()),
+                        after: Some("Новая".into()),
+                    }],
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn only_approved_documents_can_be_issued() {
+        let documents = MemoryDocuments::default();
+        let references = memory::references();
+        documents.put(memory::document(1));
+
+        assert_eq!(
+            issue_document(&documents, &references, 1),
+            Err(DocumentError::InvalidTransition {
+                id: 1,
+                from: DocumentStatus::Draft,
+                to: DocumentStatus::Issued,
+            })
+        );
+        assert_eq!(
+            snapshot_changes(&documents, &references, 1),
+            Err(DocumentError::NotIssued(1))
+        );
+    }
+}
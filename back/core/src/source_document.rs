@@ -0,0 +1,563 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    Document, DocumentError, DocumentRepository, EntityRef, ListOptions, ReferenceEntity,
+    ReferenceError, ReferenceRepository, ensure_attachable,
+};
+
+// ---------------- Source Documents Core API ----------------
+
+/// Kind of a source document (исходно-разрешительная документация) a ZNP relies on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum SourceDocumentKind {
+    /// Градостроительный план земельного участка.
+    Gpzu,
+    TechnicalConditions,
+    SurveyReport,
+    Other,
+}
+
+impl SourceDocumentKind {
+    pub const ALL: [SourceDocumentKind; 4] = [
+        Self::Gpzu,
+        Self::TechnicalConditions,
+        Self::SurveyReport,
+        Self::Other,
+    ];
+
+    /// Value stored in the `source_document.kind` column.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Gpzu => "gpzu",
+            Self::TechnicalConditions => "technical_conditions",
+            Self::SurveyReport => "survey_report",
+            Self::Other => "other",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|kind| kind.as_str() == value)
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Gpzu => "Градостроительный план земельного участка",
+            Self::TechnicalConditions => "Технические условия",
+            Self::SurveyReport => "Отчёт об инженерных изысканиях",
+            Self::Other => "Иной документ",
+        }
+    }
+}
+
+/// Source document kind as listed for the UI.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SourceDocumentKindInfo {
+    pub code: SourceDocumentKind,
+    pub name: &'static str,
+}
+
+pub fn list_source_document_kinds() -> Vec<SourceDocumentKindInfo> {
+    SourceDocumentKind::ALL
+        .into_iter()
+        .map(|kind| SourceDocumentKindInfo {
+            code: kind,
+            name: kind.name(),
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Default, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(default)]
+pub struct SourceDocumentDto {
+    pub kind: Option<SourceDocumentKind>,
+    pub number: Option<String>,
+    /// Date of issue, `YYYY-MM-DD`.
+    pub date: Option<String>,
+    /// Organization that issued the document.
+    pub issuer_id: i64,
+    pub title: Option<String>,
+    /// Stable identifier of a new row; a UUID is generated when it is empty.
+    pub uid: Option<String>,
+}
+
+/// Entry of the source documents register.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct SourceDocument {
+    pub id: i64,
+    /// Stable identifier shared by all workstations, a UUID generated when the row is created.
+    #[serde(default)]
+    pub uid: String,
+    pub kind: SourceDocumentKind,
+    pub number: String,
+    pub date: String,
+    pub issuer_id: i64,
+    pub title: Option<String>,
+    pub archived_at: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SourceDocumentRepositoryError {
+    Storage(String),
+}
+
+impl SourceDocumentRepositoryError {
+    pub fn storage<S: Into<String>>(message: S) -> Self {
+        Self::Storage(message.into())
+    }
+}
+
+impl std::fmt::Display for SourceDocumentRepositoryError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Storage(message) => f.write_str(message),
+        }
+    }
+}
+
+impl std::error::Error for SourceDocumentRepositoryError {}
+
+pub trait SourceDocumentRepository {
+    fn create(&self, dto: &SourceDocumentDto) -> Result<i64, SourceDocumentRepositoryError>;
+    fn update(
+        &self,
+        id: i64,
+        dto: &SourceDocumentDto,
+    ) -> Result<bool, SourceDocumentRepositoryError>;
+    fn list(
+        &self,
+        options: &ListOptions,
+    ) -> Result<Vec<SourceDocument>, SourceDocumentRepositoryError>;
+    fn archive(&self, id: i64) -> Result<bool, SourceDocumentRepositoryError>;
+    fn restore(&self, id: i64) -> Result<bool, SourceDocumentRepositoryError>;
+}
+
+/// Links between documents and the source documents they rely on.
+pub trait DocumentSourceRepository {
+    /// Links the source document to the document. Returns `false` when it is already linked or
+    /// the document is not a draft.
+    fn attach_source(
+        &self,
+        document_id: i64,
+        source_id: i64,
+    ) -> Result<bool, SourceDocumentRepositoryError>;
+    /// Returns `false` when the source document is not linked or the document is not a draft.
+    fn detach_source(
+        &self,
+        document_id: i64,
+        source_id: i64,
+    ) -> Result<bool, SourceDocumentRepositoryError>;
+    /// Source documents of the document in the order they were linked.
+    fn source_ids(&self, document_id: i64) -> Result<Vec<i64>, SourceDocumentRepositoryError>;
+    /// Documents that rely on the source document.
+    fn documents_using_source(
+        &self,
+        source_id: i64,
+    ) -> Result<Vec<i64>, SourceDocumentRepositoryError>;
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SourceDocumentError {
+    MissingKind,
+    MissingNumber,
+    InvalidNumber(String),
+    MissingDate,
+    InvalidDate(String),
+    NotFound(i64),
+    NotAttached { document_id: i64, source_id: i64 },
+    Document(DocumentError),
+    Reference(ReferenceError),
+    Repository(SourceDocumentRepositoryError),
+}
+
+impl std::fmt::Display for SourceDocumentError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::MissingKind => f.write_str("Source document kind is required"),
+            Self::MissingNumber => f.write_str("Source document number is required"),
+            Self::InvalidNumber(number) => write!(f, "Invalid source document number: {number}"),
+            Self::MissingDate => f.write_str("Source document date is required"),
+            Self::InvalidDate(date) => write!(f, "Invalid date: {date}"),
+            Self::NotFound(id) => write!(f, "Source document {id} not found"),
+            Self::NotAttached {
+                document_id,
+                source_id,
+            } => write!(
+                f,
+                "Source document {source_id} is not linked to document {document_id}"
+            ),
+            Self::Document(error) => write!(f, "{error}"),
+            Self::Reference(error) => write!(f, "{error}"),
+            Self::Repository(error) => write!(f, "Repository error: {error}"),
+        }
+    }
+}
+
+impl std::error::Error for SourceDocumentError {}
+
+impl From<DocumentError> for SourceDocumentError {
+    fn from(error: DocumentError) -> Self {
+        Self::Document(error)
+    }
+}
+
+impl From<ReferenceError> for SourceDocumentError {
+    fn from(error: ReferenceError) -> Self {
+        Self::Reference(error)
+    }
+}
+
+/// Checks a calendar date written as `YYYY-MM-DD`.
+pub(crate) fn is_iso_date(value: &str) -> bool {
+    let parts: Vec<&str> = value.split('-').collect();
+    let [year, month, day] = parts.as_slice() else {
+        return false;
+    };
+    if year.len() != 4 || month.len() != 2 || day.len() != 2 {
+        return false;
+    }
+    let (Ok(year), Ok(month), Ok(day)) = (
+        year.parse::<u32>(),
+        month.parse::<u32>(),
+        day.parse::<u32>(),
+    ) else {
+        return false;
+    };
+
+    let leap = year % 4 == 0 && (year % 100 != 0 || year % 400 == 0);
+    let days = match month {
+        1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+        4 | 6 | 9 | 11 => 30,
+        2 if leap => 29,
+        2 => 28,
+        _ => return false,
+    };
+
+    year > 0 && (1..=days).contains(&day)
+}
+
+/// Checks a GPZU number: `РФ-` followed by dash-separated groups of digits, e.g.
+/// `РФ-77-4-53-3-12-2026-1234`.
+fn is_gpzu_number(number: &str) -> bool {
+    let Some(rest) = number.strip_prefix("РФ-") else {
+        return false;
+    };
+    let groups: Vec<&str> = rest.split('-').collect();
+
+    groups.len() >= 4
+        && groups
+            .iter()
+            .all(|group| !group.is_empty() && group.bytes().all(|byte| byte.is_ascii_digit()))
+}
+
+fn sanitize_source_document(
+    dto: &SourceDocumentDto,
+) -> Result<SourceDocumentDto, SourceDocumentError> {
+    fn sanitize_field(value: &Option<String>) -> Option<String> {
+        value
+            .as_ref()
+            .map(|value| value.trim())
+            .filter(|value| !value.is_empty())
+            .map(|value| value.to_string())
+    }
+
+    let kind = dto.kind.ok_or(SourceDocumentError::MissingKind)?;
+
+    let number = sanitize_field(&dto.number).ok_or(SourceDocumentError::MissingNumber)?;
+    let valid_number = match kind {
+        SourceDocumentKind::Gpzu => is_gpzu_number(&number),
+        _ => !number.chars().any(char::is_control),
+    };
+    if !valid_number {
+        return Err(SourceDocumentError::InvalidNumber(number));
+    }
+
+    let date = sanitize_field(&dto.date).ok_or(SourceDocumentError::MissingDate)?;
+    if !is_iso_date(&date) {
+        return Err(SourceDocumentError::InvalidDate(date));
+    }
+
+    Ok(SourceDocumentDto {
+        kind: Some(kind),
+        number: Some(number),
+        date: Some(date),
+        issuer_id: dto.issuer_id,
+        title: sanitize_field(&dto.title),
+        uid: sanitize_field(&dto.uid),
+    })
+}
+
+/// Adds a source document to the register. The issuer must be an active organization.
+pub fn create_source_document<S, R>(
+    repository: &S,
+    references: &R,
+    dto: &SourceDocumentDto,
+) -> Result<i64, SourceDocumentError>
+where
+    S: SourceDocumentRepository,
+    R: ReferenceRepository,
+{
+    let mut sanitized = sanitize_source_document(dto)?;
+    sanitized.uid.get_or_insert_with(crate::generate_uid);
+    ensure_attachable(references, EntityRef::Organization(sanitized.issuer_id))?;
+
+    repository
+        .create(&sanitized)
+        .map_err(SourceDocumentError::Repository)
+}
+
+/// Corrects a source document. A new issuer must be an active organization; the current one is
+/// kept even if it has been archived since.
+pub fn update_source_document<S, R>(
+    repository: &S,
+    references: &R,
+    id: i64,
+    dto: &SourceDocumentDto,
+) -> Result<(), SourceDocumentError>
+where
+    S: SourceDocumentRepository,
+    R: ReferenceRepository,
+{
+    let sanitized = sanitize_source_document(dto)?;
+    let current = match references
+        .load(EntityRef::SourceDocument(id))
+        .map_err(|error| SourceDocumentError::Reference(ReferenceError::Repository(error)))?
+    {
+        Some(ReferenceEntity::SourceDocument(source)) => source,
+        _ => return Err(SourceDocumentError::NotFound(id)),
+    };
+    if current.issuer_id != sanitized.issuer_id {
+        ensure_attachable(references, EntityRef::Organization(sanitized.issuer_id))?;
+    }
+
+    match repository.update(id, &sanitized) {
+        Ok(true) => Ok(()),
+        Ok(false) => Err(SourceDocumentError::NotFound(id)),
+        Err(error) => Err(SourceDocumentError::Repository(error)),
+    }
+}
+
+pub fn list_source_documents<S: SourceDocumentRepository>(
+    repository: &S,
+    options: &ListOptions,
+) -> Result<Vec<SourceDocument>, SourceDocumentRepositoryError> {
+    repository.list(options)
+}
+
+/// Archives the source document so it can no longer be linked to documents. Documents that
+/// already rely on it keep the link.
+pub fn archive_source_document<S: SourceDocumentRepository>(
+    repository: &S,
+    id: i64,
+) -> Result<(), SourceDocumentError> {
+    match repository.archive(id) {
+        Ok(true) => Ok(()),
+        Ok(false) => Err(SourceDocumentError::NotFound(id)),
+        Err(error) => Err(SourceDocumentError::Repository(error)),
+    }
+}
+
+pub fn restore_source_document<S: SourceDocumentRepository>(
+    repository: &S,
+    id: i64,
+) -> Result<(), SourceDocumentError> {
+    match repository.restore(id) {
+        Ok(true) => Ok(()),
+        Ok(false) => Err(SourceDocumentError::NotFound(id)),
+        Err(error) => Err(SourceDocumentError::Repository(error)),
+    }
+}
+
+/// Links an active source document to a draft document. Linking it twice is a no-op.
+pub fn attach_source_document<D, R>(
+    documents: &D,
+    references: &R,
+    document_id: i64,
+    source_id: i64,
+) -> Result<(), SourceDocumentError>
+where
+    D: DocumentRepository + DocumentSourceRepository,
+    R: ReferenceRepository,
+{
+    crate::find_editable_document(documents, document_id)?;
+    ensure_attachable(references, EntityRef::SourceDocument(source_id))?;
+
+    match documents.attach_source(document_id, source_id) {
+        Ok(true) => Ok(()),
+        Ok(false) => {
+            crate::find_editable_document(documents, document_id)?;
+            Ok(())
+        }
+        Err(error) => Err(SourceDocumentError::Repository(error)),
+    }
+}
+
+pub fn detach_source_document<D>(
+    documents: &D,
+    document_id: i64,
+    source_id: i64,
+) -> Result<(), SourceDocumentError>
+where
+    D: DocumentRepository + DocumentSourceRepository,
+{
+    crate::find_editable_document(documents, document_id)?;
+
+    match documents.detach_source(document_id, source_id) {
+        Ok(true) => Ok(()),
+        Ok(false) => {
+            crate::find_editable_document(documents, document_id)?;
+            Err(SourceDocumentError::NotAttached {
+                document_id,
+                source_id,
+            })
+        }
+        Err(error) => Err(SourceDocumentError::Repository(error)),
+    }
+}
+
+/// Source documents the document relies on, loaded from the register.
+pub fn document_source_documents<D, R>(
+    documents: &D,
+    references: &R,
+    document_id: i64,
+) -> Result<Vec<SourceDocument>, SourceDocumentError>
+where
+    D: DocumentSourceRepository,
+    R: ReferenceRepository,
+{
+    let mut sources = Vec::new();
+    for source_id in documents
+        .source_ids(document_id)
+        .map_err(SourceDocumentError::Repository)?
+    {
+        match references
+            .load(EntityRef::SourceDocument(source_id))
+            .map_err(|error| SourceDocumentError::Reference(ReferenceError::Repository(error)))?
+        {
+            Some(ReferenceEntity::SourceDocument(source)) => sources.push(source),
+            _ => return Err(SourceDocumentError::NotFound(source_id)),
+        }
+    }
+
+    Ok(sources)
+}
+
+/// Documents that rely on the source document, e.g. every ZNP issued on the basis of a GPZU.
+pub fn documents_using_source_document<D>(
+    documents: &D,
+    source_id: i64,
+) -> Result<Vec<Document>, SourceDocumentError>
+where
+    D: DocumentRepository + DocumentSourceRepository,
+{
+    documents
+        .documents_using_source(source_id)
+        .map_err(SourceDocumentError::Repository)?
+        .into_iter()
+        .map(|id| crate::find_document(documents, id).map_err(SourceDocumentError::Document))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::{self, MemoryDocuments};
+    use crate::{DocumentSection, submit_for_review};
+
+    fn dto(kind: SourceDocumentKind, number: &str, date: &str) -> SourceDocumentDto {
+        SourceDocumentDto {
+            kind: Some(kind),
+            number: Some(number.into()),
+            date: Some(date.into()),
+            issuer_id: 3,
+            title: None,
+            uid: None,
+        }
+    }
+
+    #[test]
+    fn numbers_and_dates_are_validated() {
+        assert!(is_iso_date("2024-02-29"));
+        assert!(!is_iso_date("2026-02-29"));
+        assert!(!is_iso_date("2026-13-01"));
+        assert!(!is_iso_date("01.10.2026"));
+
+        assert_eq!(
+            sanitize_source_document(&dto(
+                SourceDocumentKind::Gpzu,
+                " РФ-77-4-53-3-12-2026-1234 ",
+                "2026-09-15"
+            ))
+            .map(|dto| dto.number),
+            Ok(Some("РФ-77-4-53-3-12-2026-1234".into()))
+        );
+        assert_eq!(
+            sanitize_source_document(&dto(SourceDocumentKind::Gpzu, "12345", "2026-09-15")),
+            Err(SourceDocumentError::InvalidNumber("12345".into()))
+        );
+        assert_eq!(
+            sanitize_source_document(&dto(
+                SourceDocumentKind::TechnicalConditions,
+                "ТУ-15/2026",
+                "2026-09-31"
+            )),
+            Err(SourceDocumentError::InvalidDate("2026-09-31".into()))
+        );
+    }
+
+    #[test]
+    fn source_documents_are_linked_to_draft_documents() {
+        let documents = MemoryDocuments::default();
+        let references = memory::references();
+        references
+            .entities
+            .borrow_mut()
+            .push(ReferenceEntity::SourceDocument(memory::source_document(
+                7, 3,
+            )));
+        documents.put(memory::document(1));
+        documents.put(memory::document(2));
+
+        attach_source_document(&documents, &references, 1, 7).expect("attach");
+        attach_source_document(&documents, &references, 1, 7).expect("attach twice");
+        attach_source_document(&documents, &references, 2, 7).expect("attach to another");
+        assert_eq!(
+            attach_source_document(&documents, &references, 1, 8),
+            Err(SourceDocumentError::Reference(ReferenceError::NotFound(
+                EntityRef::SourceDocument(8)
+            )))
+        );
+
+        assert_eq!(
+            documents.sections(1),
+            Ok(vec![DocumentSection::SourceDocuments])
+        );
+        assert_eq!(
+            document_source_documents(&documents, &references, 1)
+                .map(|sources| sources.into_iter().map(|source| source.id).collect()),
+            Ok(vec![7])
+        );
+        assert_eq!(
+            documents_using_source_document(&documents, 7)
+                .map(|using| using.into_iter().map(|document| document.id).collect()),
+            Ok(vec![1, 2])
+        );
+
+        submit_for_review(&documents, 2).expect("submit");
+        assert_eq!(
+            detach_source_document(&documents, 2, 7),
+            Err(SourceDocumentError::Document(DocumentError::ReadOnly(2)))
+        );
+        detach_source_document(&documents, 1, 7).expect("detach");
+        assert_eq!(
+            detach_source_document(&documents, 1, 7),
+            Err(SourceDocumentError::NotAttached {
+                document_id: 1,
+                source_id: 7
+            })
+        );
+    }
+}
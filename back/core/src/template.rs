@@ -0,0 +1,293 @@
+use crate::{
+    ConstructionObjectRepository, Document, DocumentDto, DocumentError, DocumentNumbering,
+    DocumentRepository, DocumentRepositoryError, DocumentSourceRepository, EntityRef,
+    ReferenceRepository, RequirementRepository, SignatoryRepository, TechnicalIndicatorRepository,
+    ensure_attachable,
+};
+
+// ---------------- Document Templates ----------------
+
+/// Fields of a cloned document that differ from the original. Empty fields keep the value of
+/// the original, except the number and date which belong to the original and start empty.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct CloneOverrides {
+    pub name: Option<String>,
+    pub number: Option<String>,
+    pub date: Option<String>,
+    /// Allocates the number of the clone from a numbering scheme when `number` is empty.
+    pub numbering: Option<DocumentNumbering>,
+    /// Name of the construction object; applied when the original has the object filled in.
+    pub object_name: Option<String>,
+    /// Location of the construction object; applied when the original has the object filled in.
+    pub object_address_id: Option<i64>,
+}
+
+/// Deep-copies a document into a new draft for another project: the author and developer
+/// links, the construction object, technical indicators, requirement texts, source documents and
+/// signatories are copied, the clone gets a fresh uid and starts without a change mark or
+/// revision link.
+///
+/// Any document can be cloned, not only templates. Attachments are not copied. When a section
+/// cannot be copied the partial clone is deleted again, so a failed clone leaves no draft
+/// behind; a number allocated for it is not handed out again.
+pub fn clone_document<D, R>(
+    documents: &D,
+    references: &R,
+    id: i64,
+    overrides: &CloneOverrides,
+) -> Result<i64, DocumentError>
+where
+    D: DocumentRepository
+        + ConstructionObjectRepository
+        + TechnicalIndicatorRepository
+        + RequirementRepository
+        + DocumentSourceRepository
+        + SignatoryRepository,
+    R: ReferenceRepository,
+{
+    let document = crate::find_document(documents, id)?;
+
+    if let Some(address_id) = overrides.object_address_id {
+        ensure_attachable(references, EntityRef::Address(address_id))?;
+    }
+
+    let name = overrides
+        .name
+        .as_deref()
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .map_or(document.name, str::to_string);
+
+    let clone_id = crate::create_document(
+        documents,
+        references,
+        &DocumentDto {
+            name: Some(name),
+            number: overrides.number.clone(),
+            date: overrides.date.clone(),
+            change_mark: None,
+            uid: None,
+            document_type: document.document_type,
+            author: Some(document.author),
+            developer: document.developer,
+            previous_revision_id: None,
+            numbering: overrides.numbering.clone(),
+        },
+    )?;
+
+    match copy_into_clone(documents, id, clone_id, overrides) {
+        Ok(()) => Ok(clone_id),
+        Err(error) => match documents.delete_draft(clone_id) {
+            Ok(_) => Err(error),
+            Err(cleanup) => Err(DocumentError::Repository(DocumentRepositoryError::storage(
+                format!("{error}; the partial clone {clone_id} could not be deleted: {cleanup}"),
+            ))),
+        },
+    }
+}
+
+fn copy_into_clone<D>(
+    documents: &D,
+    id: i64,
+    clone_id: i64,
+    overrides: &CloneOverrides,
+) -> Result<(), DocumentError>
+where
+    D: ConstructionObjectRepository
+        + TechnicalIndicatorRepository
+        + RequirementRepository
+        + DocumentSourceRepository
+        + SignatoryRepository,
+{
+    crate::copy_sections(documents, id, clone_id)?;
+
+    let object_name = overrides
+        .object_name
+        .as_deref()
+        .map(str::trim)
+        .filter(|name| !name.is_empty());
+    if object_name.is_some() || overrides.object_address_id.is_some() {
+        let object = documents
+            .find_by_document(clone_id)
+            .map_err(crate::section_error)?;
+        if let Some(object) = object {
+            let mut dto = crate::construction_object::construction_object_dto(&object);
+            if let Some(name) = object_name {
+                dto.name = Some(name.to_string());
+            }
+            if let Some(address_id) = overrides.object_address_id {
+                dto.address_id = address_id;
+            }
+            documents
+                .save(clone_id, &dto)
+                .map_err(crate::section_error)?
+                .ok_or(DocumentError::ReadOnly(clone_id))?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Marks a document as a template or clears the mark. The flag is not content, so it may be
+/// changed in any status: an issued ZNP is a fine starting point for the next one.
+pub fn set_document_template<D: DocumentRepository>(
+    documents: &D,
+    id: i64,
+    template: bool,
+) -> Result<(), DocumentError> {
+    match documents.set_template(id, template) {
+        Ok(true) => Ok(()),
+        Ok(false) => Err(DocumentError::NotFound(id)),
+        Err(error) => Err(DocumentError::Repository(error)),
+    }
+}
+
+pub fn list_document_templates<D: DocumentRepository>(
+    documents: &D,
+) -> Result<Vec<Document>, DocumentRepositoryError> {
+    Ok(documents
+        .list()?
+        .into_iter()
+        .filter(|document| document.is_template)
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::{self, MemoryDocuments};
+    use crate::{
+        ConstructionObjectDto, DocumentStatus, ReferenceEntity, ReferenceError,
+        RequirementCategory, RequirementSectionDto, TechnicalIndicatorDto, WorkType, add_indicator,
+        add_requirement, attach_source_document, issue_revision, save_construction_object,
+    };
+
+    fn issued_template(documents: &MemoryDocuments) {
+        let references = memory::references();
+        references
+            .entities
+            .borrow_mut()
+            .push(ReferenceEntity::SourceDocument(memory::source_document(
+                20, 3,
+            )));
+        let mut document = memory::document(1);
+        document.change_mark = Some("2".into());
+        documents.put(document);
+
+        save_construction_object(
+            documents,
+            &references,
+            1,
+            &ConstructionObjectDto {
+                name: Some("Школа на 550 мест".into()),
+                purpose_code: Some("19.7.1.1".into()),
+                address_id: 9,
+                cadastral_numbers: vec!["77:01:0001001:1234".into()],
+                work_type: Some(WorkType::Construction),
+            },
+        )
+        .expect("object");
+        add_indicator(
+            documents,
+            1,
+            &TechnicalIndicatorDto {
+                name: Some("Общая площадь".into()),
+                value: Some("5400".into()),
+                unit_okei: Some("055".into()),
+            },
+        )
+        .expect("indicator");
+        add_requirement(
+            documents,
+            1,
+            &RequirementSectionDto {
+                category: Some(RequirementCategory::Architecture),
+                title: Some("Архитектурные решения".into()),
+                text: Some("Фасады объекта {{object_name}}".into()),
+                template_id: None,
+            },
+        )
+        .expect("requirement");
+        attach_source_document(documents, &references, 1, 20).expect("source");
+        set_document_template(documents, 1, true).expect("template");
+
+        let mut document = documents.get(1);
+        document.status = DocumentStatus::Issued;
+        documents.put(document);
+    }
+
+    #[test]
+    fn clone_copies_content_into_a_fresh_draft() {
+        let documents = MemoryDocuments::default();
+        let references = memory::references();
+        issued_template(&documents);
+
+        let overrides = CloneOverrides {
+            name: Some(" ЗНП на детский сад ".into()),
+            object_name: Some("Детский сад на 220 мест".into()),
+            object_address_id: Some(10),
+            ..CloneOverrides::default()
+        };
+        let id = clone_document(&documents, &references, 1, &overrides).expect("clone");
+
+        let clone = documents.get(id);
+        assert_eq!(clone.name, "ЗНП на детский сад");
+        assert_eq!(clone.status, DocumentStatus::Draft);
+        assert_eq!(clone.change_mark, None);
+        assert_eq!(clone.previous_revision_id, None);
+        assert_eq!(clone.number, None);
+        assert!(!clone.is_template);
+        assert_ne!(clone.uid, documents.get(1).uid);
+        assert_eq!(clone.author, documents.get(1).author);
+        assert_eq!(clone.developer, documents.get(1).developer);
+
+        let object = documents
+            .find_by_document(id)
+            .expect("find")
+            .expect("object copied");
+        assert_eq!(object.name, "Детский сад на 220 мест");
+        assert_eq!(object.address_id, 10);
+        assert_eq!(object.cadastral_numbers, vec!["77:01:0001001:1234"]);
+        assert_eq!(documents.indicators(id).expect("indicators").len(), 1);
+        assert_eq!(
+            documents.requirements(id).expect("requirements")[0].text,
+            "Фасады объекта {{object_name}}"
+        );
+        assert_eq!(documents.source_ids(id), Ok(vec![20]));
+
+        assert_eq!(
+            list_document_templates(&documents)
+                .expect("templates")
+                .iter()
+                .map(|document| document.id)
+                .collect::<Vec<_>>(),
+            vec![1]
+        );
+
+        // The clone is an independent document, not a revision of the original.
+        assert_eq!(issue_revision(&documents, 1, "").map(|_| ()), Ok(()));
+    }
+
+    #[test]
+    fn clone_checks_the_new_object_address() {
+        let documents = MemoryDocuments::default();
+        let references = memory::references();
+        issued_template(&documents);
+
+        let overrides = CloneOverrides {
+            object_address_id: Some(404),
+            ..CloneOverrides::default()
+        };
+        assert_eq!(
+            clone_document(&documents, &references, 1, &overrides),
+            Err(DocumentError::Reference(ReferenceError::NotFound(
+                EntityRef::Address(404)
+            )))
+        );
+        assert_eq!(documents.list().expect("list").len(), 1);
+        assert_eq!(
+            set_document_template(&documents, 404, true),
+            Err(DocumentError::NotFound(404))
+        );
+    }
+}
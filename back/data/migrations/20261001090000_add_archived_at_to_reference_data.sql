@@ -0,0 +1,6 @@
+-- Мягкое удаление справочных данных: архивные записи скрываются из списков,
+-- но остаются доступными для уже созданных документов
+ALTER TABLE address ADD COLUMN archived_at TEXT;
+ALTER TABLE organization ADD COLUMN archived_at TEXT;
+ALTER TABLE entrepreneur ADD COLUMN archived_at TEXT;
+ALTER TABLE person ADD COLUMN archived_at TEXT;
@@ -0,0 +1,6 @@
+-- Ссылка на предыдущую редакцию документа. У каждой редакции может быть не более одной
+-- следующей редакции
+ALTER TABLE document
+ADD COLUMN previous_revision_id INTEGER REFERENCES document(id) ON DELETE RESTRICT;
+
+CREATE UNIQUE INDEX document_previous_revision_id_unique ON document(previous_revision_id);
@@ -0,0 +1,7 @@
+-- Выпуск документа: дата выпуска и неизменяемый снимок связанных сущностей и их адресов
+ALTER TABLE document ADD COLUMN issued_at TEXT;
+
+CREATE TABLE document_snapshot (
+    document_id INTEGER PRIMARY KEY REFERENCES document(id) ON DELETE CASCADE,
+    content TEXT NOT NULL -- JSON со снимком организаций, предпринимателей, физических лиц и адресов
+);
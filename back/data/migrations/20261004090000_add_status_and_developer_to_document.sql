@@ -0,0 +1,20 @@
+-- Жизненный цикл документа: статус, даты переходов и разработчик
+ALTER TABLE document
+ADD COLUMN status TEXT NOT NULL DEFAULT 'draft' CHECK (
+    status IN ('draft', 'ready_for_review', 'approved', 'issued', 'superseded', 'cancelled')
+);
+ALTER TABLE document ADD COLUMN submitted_at TEXT;
+ALTER TABLE document ADD COLUMN approved_at TEXT;
+ALTER TABLE document ADD COLUMN superseded_at TEXT;
+ALTER TABLE document ADD COLUMN cancelled_at TEXT;
+ALTER TABLE document
+ADD COLUMN developer_id INTEGER REFERENCES developer(id) ON DELETE RESTRICT;
+
+-- Документы, выпущенные до появления статусов
+UPDATE document SET status = 'issued' WHERE issued_at IS NOT NULL;
+UPDATE document
+SET status = 'superseded', superseded_at = next.issued_at
+FROM document AS next
+WHERE next.previous_revision_id = document.id
+  AND next.issued_at IS NOT NULL
+  AND document.status = 'issued';
@@ -0,0 +1,7 @@
+-- Счётчики номеров документов по префиксу схемы нумерации и году
+CREATE TABLE document_number_sequence (
+    prefix TEXT NOT NULL,
+    year INTEGER NOT NULL,
+    last_value INTEGER NOT NULL,
+    PRIMARY KEY (prefix, year)
+);
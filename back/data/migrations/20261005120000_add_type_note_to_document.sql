@@ -0,0 +1,14 @@
+-- Текст вида документа, который не совпадает ни с кодом, ни с названием вида из справочника
+-- document_type, сохраняется как примечание до удаления поля type, чтобы его можно было
+-- разобрать вручную. Коды и названия повторяют справочник из следующей миграции.
+ALTER TABLE document ADD COLUMN type_note TEXT;
+
+UPDATE document
+SET type_note = type
+WHERE trim(type) <> ''
+  AND trim(type) NOT IN (
+      'new_construction', 'ЗНП на строительство',
+      'reconstruction', 'ЗНП на реконструкцию',
+      'capital_repair', 'ЗНП на капитальный ремонт',
+      'linear_object', 'ЗНП на линейный объект'
+  );
@@ -0,0 +1,25 @@
+-- Таблица видов документов
+CREATE TABLE document_type (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    code TEXT NOT NULL UNIQUE,
+    name TEXT NOT NULL
+);
+
+INSERT INTO document_type (id, code, name) VALUES
+    (1, 'new_construction', 'ЗНП на строительство'),
+    (2, 'reconstruction', 'ЗНП на реконструкцию'),
+    (3, 'capital_repair', 'ЗНП на капитальный ремонт'),
+    (4, 'linear_object', 'ЗНП на линейный объект');
+
+-- Вид документа вместо произвольного текста в поле type
+ALTER TABLE document
+ADD COLUMN type_id INTEGER REFERENCES document_type(id) ON DELETE RESTRICT;
+
+UPDATE document
+SET type_id = (
+    SELECT dt.id FROM document_type dt
+    WHERE dt.code = trim(document.type) OR dt.name = trim(document.type)
+)
+WHERE type IS NOT NULL;
+
+ALTER TABLE document DROP COLUMN type;
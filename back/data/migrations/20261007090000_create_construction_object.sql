@@ -0,0 +1,17 @@
+-- Сведения об объекте капитального строительства, описываемом документом
+CREATE TABLE construction_object (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    document_id INTEGER NOT NULL UNIQUE REFERENCES document(id) ON DELETE CASCADE,
+    name TEXT NOT NULL,
+    purpose_code TEXT NOT NULL, -- код функционального назначения по классификатору
+    address_id INTEGER NOT NULL REFERENCES address(id) ON DELETE RESTRICT,
+    work_type TEXT NOT NULL CHECK (work_type IN ('construction', 'reconstruction', 'capital_repair'))
+);
+
+-- Кадастровые номера земельных участков объекта
+CREATE TABLE construction_object_land_plot (
+    construction_object_id INTEGER NOT NULL REFERENCES construction_object(id) ON DELETE CASCADE,
+    cadastral_number TEXT NOT NULL,
+    position INTEGER NOT NULL,
+    PRIMARY KEY (construction_object_id, cadastral_number)
+);
@@ -0,0 +1,11 @@
+-- Технико-экономические показатели документа
+CREATE TABLE technical_indicator (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    document_id INTEGER NOT NULL REFERENCES document(id) ON DELETE CASCADE,
+    position INTEGER NOT NULL,
+    name TEXT NOT NULL,
+    value TEXT NOT NULL, -- десятичное число с точкой в качестве разделителя
+    unit_okei TEXT NOT NULL -- код единицы измерения по ОКЕИ
+);
+
+CREATE INDEX technical_indicator_document_idx ON technical_indicator (document_id, position);
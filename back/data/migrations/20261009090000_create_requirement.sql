@@ -0,0 +1,40 @@
+-- Библиотека типовых требований к проектной документации
+CREATE TABLE requirement_template (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    category TEXT NOT NULL CHECK (category IN (
+        'architecture', 'structures', 'engineering_networks',
+        'fire_safety', 'accessibility', 'energy_efficiency'
+    )),
+    title TEXT NOT NULL,
+    text TEXT NOT NULL -- текст с подстановками вида {{object_name}}
+);
+
+INSERT INTO requirement_template (category, title, text) VALUES
+    ('architecture', 'Архитектурные решения',
+     'Архитектурные решения объекта «{{object_name}}» разработать с учётом сложившейся застройки по адресу: {{object_address}}. Цветовое решение фасадов согласовать с застройщиком ({{developer}}).'),
+    ('structures', 'Конструктивные решения',
+     'Конструктивные решения принять на основании результатов инженерных изысканий. Уровень ответственности объекта — нормальный.'),
+    ('engineering_networks', 'Инженерные сети и системы',
+     'Подключение объекта «{{object_name}}» к сетям инженерно-технического обеспечения выполнить в соответствии с техническими условиями, полученными застройщиком ({{developer}}).'),
+    ('fire_safety', 'Пожарная безопасность',
+     'Разработать раздел «Мероприятия по обеспечению пожарной безопасности» в соответствии с Федеральным законом от 22.07.2008 № 123-ФЗ.'),
+    ('accessibility', 'Доступность для маломобильных групп населения',
+     'Предусмотреть мероприятия по обеспечению доступа инвалидов и маломобильных групп населения к объекту «{{object_name}}» в соответствии с СП 59.13330.'),
+    ('energy_efficiency', 'Энергетическая эффективность',
+     'Разработать мероприятия по обеспечению соблюдения требований энергетической эффективности и оснащённости объекта приборами учёта используемых энергетических ресурсов.');
+
+-- Разделы требований документа
+CREATE TABLE requirement_section (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    document_id INTEGER NOT NULL REFERENCES document(id) ON DELETE CASCADE,
+    position INTEGER NOT NULL,
+    category TEXT NOT NULL CHECK (category IN (
+        'architecture', 'structures', 'engineering_networks',
+        'fire_safety', 'accessibility', 'energy_efficiency'
+    )),
+    title TEXT NOT NULL,
+    text TEXT NOT NULL,
+    template_id INTEGER REFERENCES requirement_template(id) ON DELETE SET NULL
+);
+
+CREATE INDEX requirement_section_document_idx ON requirement_section (document_id, position);
@@ -0,0 +1,20 @@
+-- Реестр исходно-разрешительной документации: ГПЗУ, технические условия, отчёты об изысканиях
+CREATE TABLE source_document (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    kind TEXT NOT NULL CHECK (kind IN ('gpzu', 'technical_conditions', 'survey_report', 'other')),
+    number TEXT NOT NULL,
+    date TEXT NOT NULL, -- дата выдачи в формате YYYY-MM-DD
+    issuer_id INTEGER NOT NULL REFERENCES organization(id) ON DELETE RESTRICT,
+    title TEXT,
+    archived_at TEXT
+);
+
+-- Исходные документы, на которые опирается документ
+CREATE TABLE document_source_document (
+    document_id INTEGER NOT NULL REFERENCES document(id) ON DELETE CASCADE,
+    source_document_id INTEGER NOT NULL REFERENCES source_document(id) ON DELETE RESTRICT,
+    position INTEGER NOT NULL,
+    PRIMARY KEY (document_id, source_document_id)
+);
+
+CREATE INDEX document_source_document_source_idx ON document_source_document (source_document_id);
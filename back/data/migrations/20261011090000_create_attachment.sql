@@ -0,0 +1,14 @@
+-- Файлы, приложенные к документу. Содержимое хранится в каталоге attachments рядом с базой
+-- под именем, равным SHA-256 содержимого; одинаковые файлы хранятся один раз
+CREATE TABLE attachment (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    document_id INTEGER NOT NULL REFERENCES document(id) ON DELETE CASCADE,
+    file_name TEXT NOT NULL,
+    mime_type TEXT NOT NULL,
+    size INTEGER NOT NULL,
+    sha256 TEXT NOT NULL,
+    added_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+CREATE INDEX attachment_document_idx ON attachment (document_id);
+CREATE INDEX attachment_sha256_idx ON attachment (sha256);
@@ -0,0 +1,3 @@
+-- Признак шаблона: документ служит отправной точкой для новых проектов и копируется
+-- вместе с разделами
+ALTER TABLE document ADD COLUMN is_template INTEGER NOT NULL DEFAULT 0;
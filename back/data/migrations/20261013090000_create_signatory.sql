@@ -0,0 +1,10 @@
+-- Подписанты документа: застройщик утверждает задание, проектировщик согласовывает
+CREATE TABLE signatory (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    document_id INTEGER NOT NULL REFERENCES document(id) ON DELETE CASCADE,
+    person_id INTEGER NOT NULL REFERENCES person(id) ON DELETE RESTRICT,
+    position TEXT NOT NULL, -- должность подписанта
+    role TEXT NOT NULL CHECK (role IN ('approval', 'agreement')),
+    signed_on TEXT, -- дата подписания в формате YYYY-MM-DD
+    UNIQUE (document_id, role)
+);
@@ -0,0 +1,44 @@
+-- Постоянные идентификаторы справочных данных: в отличие от id совпадают на всех рабочих
+-- местах, поэтому по ним записи сопоставляются при переносе и объединении баз.
+-- Существующим записям присваиваются случайные UUID версии 4
+ALTER TABLE address ADD COLUMN uid TEXT;
+ALTER TABLE organization ADD COLUMN uid TEXT;
+ALTER TABLE entrepreneur ADD COLUMN uid TEXT;
+ALTER TABLE person ADD COLUMN uid TEXT;
+ALTER TABLE source_document ADD COLUMN uid TEXT;
+
+UPDATE address SET uid = lower(
+    hex(randomblob(4)) || '-' || hex(randomblob(2)) || '-4' || substr(hex(randomblob(2)), 2) || '-'
+    || substr('89ab', 1 + (abs(random()) % 4), 1) || substr(hex(randomblob(2)), 2) || '-'
+    || hex(randomblob(6))
+);
+
+UPDATE organization SET uid = lower(
+    hex(randomblob(4)) || '-' || hex(randomblob(2)) || '-4' || substr(hex(randomblob(2)), 2) || '-'
+    || substr('89ab', 1 + (abs(random()) % 4), 1) || substr(hex(randomblob(2)), 2) || '-'
+    || hex(randomblob(6))
+);
+
+UPDATE entrepreneur SET uid = lower(
+    hex(randomblob(4)) || '-' || hex(randomblob(2)) || '-4' || substr(hex(randomblob(2)), 2) || '-'
+    || substr('89ab', 1 + (abs(random()) % 4), 1) || substr(hex(randomblob(2)), 2) || '-'
+    || hex(randomblob(6))
+);
+
+UPDATE person SET uid = lower(
+    hex(randomblob(4)) || '-' || hex(randomblob(2)) || '-4' || substr(hex(randomblob(2)), 2) || '-'
+    || substr('89ab', 1 + (abs(random()) % 4), 1) || substr(hex(randomblob(2)), 2) || '-'
+    || hex(randomblob(6))
+);
+
+UPDATE source_document SET uid = lower(
+    hex(randomblob(4)) || '-' || hex(randomblob(2)) || '-4' || substr(hex(randomblob(2)), 2) || '-'
+    || substr('89ab', 1 + (abs(random()) % 4), 1) || substr(hex(randomblob(2)), 2) || '-'
+    || hex(randomblob(6))
+);
+
+CREATE UNIQUE INDEX address_uid_unique ON address(uid);
+CREATE UNIQUE INDEX organization_uid_unique ON organization(uid);
+CREATE UNIQUE INDEX entrepreneur_uid_unique ON entrepreneur(uid);
+CREATE UNIQUE INDEX person_uid_unique ON person(uid);
+CREATE UNIQUE INDEX source_document_uid_unique ON source_document(uid);
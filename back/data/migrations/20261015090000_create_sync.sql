@@ -0,0 +1,45 @@
+-- Синхронизация рабочих мест через общую папку: каждое рабочее место выгружает наборы изменений
+-- справочных данных в свою подпапку и применяет наборы остальных мест
+
+-- Идентификатор этого рабочего места и номер последнего выгруженного набора изменений
+CREATE TABLE sync_workstation (
+    id INTEGER PRIMARY KEY CHECK (id = 1),
+    uid TEXT NOT NULL,
+    last_sequence INTEGER NOT NULL DEFAULT 0
+);
+
+-- Последняя известная версия каждого поля: значение в JSON, рабочее место и набор изменений,
+-- в котором оно записано, и время изменения для правила «побеждает последняя запись»
+CREATE TABLE sync_field_version (
+    entity TEXT NOT NULL,
+    uid TEXT NOT NULL,
+    field TEXT NOT NULL,
+    value TEXT NOT NULL,
+    workstation TEXT NOT NULL,
+    sequence INTEGER NOT NULL,
+    changed_at TEXT NOT NULL,
+    PRIMARY KEY (entity, uid, field)
+);
+
+-- Номер последнего применённого набора изменений каждого другого рабочего места
+CREATE TABLE sync_applied (
+    workstation TEXT PRIMARY KEY,
+    last_sequence INTEGER NOT NULL
+);
+
+-- Одновременные изменения одного поля на разных рабочих местах: победившее значение уже
+-- записано, конфликт ждёт ручного разрешения
+CREATE TABLE sync_conflict (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    entity TEXT NOT NULL,
+    uid TEXT NOT NULL,
+    field TEXT NOT NULL,
+    local_value TEXT NOT NULL,
+    remote_value TEXT NOT NULL,
+    remote_workstation TEXT NOT NULL,
+    winner TEXT NOT NULL CHECK (winner IN ('local', 'remote')),
+    detected_at TEXT NOT NULL,
+    resolved_at TEXT
+);
+
+CREATE INDEX sync_conflict_unresolved ON sync_conflict (resolved_at);
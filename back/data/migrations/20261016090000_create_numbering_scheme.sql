@@ -0,0 +1,13 @@
+-- Схемы нумерации документов: ширина счётчика хранится вместе с префиксом,
+-- чтобы номера одного счётчика всегда дополнялись нулями одинаково
+CREATE TABLE numbering_scheme (
+    prefix TEXT PRIMARY KEY,
+    width INTEGER NOT NULL CHECK (width BETWEEN 1 AND 10)
+);
+
+-- Схема по умолчанию
+INSERT INTO numbering_scheme (prefix, width) VALUES ('ЗНП', 4);
+
+-- Префиксы, по которым уже выдавались номера, получают прежнюю ширину по умолчанию
+INSERT OR IGNORE INTO numbering_scheme (prefix, width)
+SELECT DISTINCT prefix, 4 FROM document_number_sequence;
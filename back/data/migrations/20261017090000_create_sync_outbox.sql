@@ -0,0 +1,8 @@
+-- Наборы изменений, номер которых уже выделен, но которые ещё не выгружены в общую папку.
+-- Набор выгружается только после фиксации транзакции, в которой выделен его номер, и
+-- удаляется из очереди после выгрузки; невыгруженный набор выгружается при следующей
+-- синхронизации, поэтому номера не пропускаются и не используются повторно
+CREATE TABLE sync_outbox (
+    sequence INTEGER PRIMARY KEY,
+    change_set TEXT NOT NULL
+);
@@ -0,0 +1,99 @@
+-- Постоянный идентификатор обязателен для справочных данных, как document.uid: без него запись
+-- не попадает в архив, синхронизацию и поиск по uid. Столбцы uid добавлены через ALTER TABLE
+-- и допускают NULL, поэтому запрет пустых значений проверяется триггерами.
+-- Записям, добавленным в обход сервисов без uid, присваиваются случайные UUID версии 4
+
+UPDATE address SET uid = lower(
+    hex(randomblob(4)) || '-' || hex(randomblob(2)) || '-4' || substr(hex(randomblob(2)), 2) || '-'
+    || substr('89ab', 1 + (abs(random()) % 4), 1) || substr(hex(randomblob(2)), 2) || '-'
+    || hex(randomblob(6))
+)
+WHERE uid IS NULL OR trim(uid) = '';
+
+UPDATE organization SET uid = lower(
+    hex(randomblob(4)) || '-' || hex(randomblob(2)) || '-4' || substr(hex(randomblob(2)), 2) || '-'
+    || substr('89ab', 1 + (abs(random()) % 4), 1) || substr(hex(randomblob(2)), 2) || '-'
+    || hex(randomblob(6))
+)
+WHERE uid IS NULL OR trim(uid) = '';
+
+UPDATE entrepreneur SET uid = lower(
+    hex(randomblob(4)) || '-' || hex(randomblob(2)) || '-4' || substr(hex(randomblob(2)), 2) || '-'
+    || substr('89ab', 1 + (abs(random()) % 4), 1) || substr(hex(randomblob(2)), 2) || '-'
+    || hex(randomblob(6))
+)
+WHERE uid IS NULL OR trim(uid) = '';
+
+UPDATE person SET uid = lower(
+    hex(randomblob(4)) || '-' || hex(randomblob(2)) || '-4' || substr(hex(randomblob(2)), 2) || '-'
+    || substr('89ab', 1 + (abs(random()) % 4), 1) || substr(hex(randomblob(2)), 2) || '-'
+    || hex(randomblob(6))
+)
+WHERE uid IS NULL OR trim(uid) = '';
+
+UPDATE source_document SET uid = lower(
+    hex(randomblob(4)) || '-' || hex(randomblob(2)) || '-4' || substr(hex(randomblob(2)), 2) || '-'
+    || substr('89ab', 1 + (abs(random()) % 4), 1) || substr(hex(randomblob(2)), 2) || '-'
+    || hex(randomblob(6))
+)
+WHERE uid IS NULL OR trim(uid) = '';
+
+CREATE TRIGGER address_uid_required_insert BEFORE INSERT ON address
+WHEN NEW.uid IS NULL OR trim(NEW.uid) = ''
+BEGIN
+    SELECT RAISE(ABORT, 'address.uid is required');
+END;
+
+CREATE TRIGGER address_uid_required_update BEFORE UPDATE OF uid ON address
+WHEN NEW.uid IS NULL OR trim(NEW.uid) = ''
+BEGIN
+    SELECT RAISE(ABORT, 'address.uid is required');
+END;
+
+CREATE TRIGGER organization_uid_required_insert BEFORE INSERT ON organization
+WHEN NEW.uid IS NULL OR trim(NEW.uid) = ''
+BEGIN
+    SELECT RAISE(ABORT, 'organization.uid is required');
+END;
+
+CREATE TRIGGER organization_uid_required_update BEFORE UPDATE OF uid ON organization
+WHEN NEW.uid IS NULL OR trim(NEW.uid) = ''
+BEGIN
+    SELECT RAISE(ABORT, 'organization.uid is required');
+END;
+
+CREATE TRIGGER entrepreneur_uid_required_insert BEFORE INSERT ON entrepreneur
+WHEN NEW.uid IS NULL OR trim(NEW.uid) = ''
+BEGIN
+    SELECT RAISE(ABORT, 'entrepreneur.uid is required');
+END;
+
+CREATE TRIGGER entrepreneur_uid_required_update BEFORE UPDATE OF uid ON entrepreneur
+WHEN NEW.uid IS NULL OR trim(NEW.uid) = ''
+BEGIN
+    SELECT RAISE(ABORT, 'entrepreneur.uid is required');
+END;
+
+CREATE TRIGGER person_uid_required_insert BEFORE INSERT ON person
+WHEN NEW.uid IS NULL OR trim(NEW.uid) = ''
+BEGIN
+    SELECT RAISE(ABORT, 'person.uid is required');
+END;
+
+CREATE TRIGGER person_uid_required_update BEFORE UPDATE OF uid ON person
+WHEN NEW.uid IS NULL OR trim(NEW.uid) = ''
+BEGIN
+    SELECT RAISE(ABORT, 'person.uid is required');
+END;
+
+CREATE TRIGGER source_document_uid_required_insert BEFORE INSERT ON source_document
+WHEN NEW.uid IS NULL OR trim(NEW.uid) = ''
+BEGIN
+    SELECT RAISE(ABORT, 'source_document.uid is required');
+END;
+
+CREATE TRIGGER source_document_uid_required_update BEFORE UPDATE OF uid ON source_document
+WHEN NEW.uid IS NULL OR trim(NEW.uid) = ''
+BEGIN
+    SELECT RAISE(ABORT, 'source_document.uid is required');
+END;
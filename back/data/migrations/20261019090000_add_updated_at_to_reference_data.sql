@@ -0,0 +1,80 @@
+-- Время последнего изменения записи справочных данных. По нему датируются изменения полей при
+-- синхронизации рабочих мест, чтобы правило «побеждает последняя запись» сравнивало время правки,
+-- а не время синхронизации, которая её заметила. Время записывается триггерами при любой вставке
+-- и изменении, если запрос не задал его сам. Для существующих записей время правки неизвестно,
+-- им присваивается время миграции
+
+ALTER TABLE address ADD COLUMN updated_at TEXT;
+UPDATE address SET updated_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now');
+
+ALTER TABLE organization ADD COLUMN updated_at TEXT;
+UPDATE organization SET updated_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now');
+
+ALTER TABLE entrepreneur ADD COLUMN updated_at TEXT;
+UPDATE entrepreneur SET updated_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now');
+
+ALTER TABLE person ADD COLUMN updated_at TEXT;
+UPDATE person SET updated_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now');
+
+ALTER TABLE source_document ADD COLUMN updated_at TEXT;
+UPDATE source_document SET updated_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now');
+
+CREATE TRIGGER address_updated_at_insert AFTER INSERT ON address
+WHEN NEW.updated_at IS NULL
+BEGIN
+    UPDATE address SET updated_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now') WHERE id = NEW.id;
+END;
+
+CREATE TRIGGER address_updated_at_update AFTER UPDATE ON address
+WHEN NEW.updated_at IS OLD.updated_at
+BEGIN
+    UPDATE address SET updated_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now') WHERE id = NEW.id;
+END;
+
+CREATE TRIGGER organization_updated_at_insert AFTER INSERT ON organization
+WHEN NEW.updated_at IS NULL
+BEGIN
+    UPDATE organization SET updated_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now') WHERE id = NEW.id;
+END;
+
+CREATE TRIGGER organization_updated_at_update AFTER UPDATE ON organization
+WHEN NEW.updated_at IS OLD.updated_at
+BEGIN
+    UPDATE organization SET updated_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now') WHERE id = NEW.id;
+END;
+
+CREATE TRIGGER entrepreneur_updated_at_insert AFTER INSERT ON entrepreneur
+WHEN NEW.updated_at IS NULL
+BEGIN
+    UPDATE entrepreneur SET updated_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now') WHERE id = NEW.id;
+END;
+
+CREATE TRIGGER entrepreneur_updated_at_update AFTER UPDATE ON entrepreneur
+WHEN NEW.updated_at IS OLD.updated_at
+BEGIN
+    UPDATE entrepreneur SET updated_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now') WHERE id = NEW.id;
+END;
+
+CREATE TRIGGER person_updated_at_insert AFTER INSERT ON person
+WHEN NEW.updated_at IS NULL
+BEGIN
+    UPDATE person SET updated_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now') WHERE id = NEW.id;
+END;
+
+CREATE TRIGGER person_updated_at_update AFTER UPDATE ON person
+WHEN NEW.updated_at IS OLD.updated_at
+BEGIN
+    UPDATE person SET updated_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now') WHERE id = NEW.id;
+END;
+
+CREATE TRIGGER source_document_updated_at_insert AFTER INSERT ON source_document
+WHEN NEW.updated_at IS NULL
+BEGIN
+    UPDATE source_document SET updated_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now') WHERE id = NEW.id;
+END;
+
+CREATE TRIGGER source_document_updated_at_update AFTER UPDATE ON source_document
+WHEN NEW.updated_at IS OLD.updated_at
+BEGIN
+    UPDATE source_document SET updated_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now') WHERE id = NEW.id;
+END;
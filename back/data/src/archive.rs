@@ -0,0 +1,2018 @@
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use sqlx::{Connection, Row, SqliteConnection, sqlite::SqliteConnectOptions};
+use tokio::runtime::Runtime;
+use zdnp_core::{
+    DocumentAuthor, DocumentDeveloper, DocumentSnapshot, DocumentStatus, DocumentType, EntityRef,
+    ReferenceEntity, RequirementCategory, SigningRole, SourceDocumentKind, WorkType,
+};
+use zip::{ZipArchive, ZipWriter, write::SimpleFileOptions};
+
+use crate::attachment::{
+    attachment_from_row, content_path, is_sha256, store_content, store_directory,
+};
+use crate::document::{DOCUMENT_SELECT, document_from_row, insert_author, insert_developer};
+use crate::source_document::source_document_from_row;
+use crate::{
+    DEFAULT_DATABASE_FILE_NAME, address_from_row, entrepreneur_from_row, organization_from_row,
+    person_from_row,
+};
+
+// ---------------- Portable Archive ----------------
+
+/// Value of [`Archive::format`].
+pub const ARCHIVE_FORMAT: &str = "zdnp-archive";
+/// Current version of the archive format. Archives of older versions can still be imported.
+pub const ARCHIVE_VERSION: u32 = 1;
+
+/// Zip entry with the JSON data; attachment content is stored under `attachments/<sha256>`.
+const ARCHIVE_ENTRY: &str = "archive.json";
+const ATTACHMENTS_PREFIX: &str = "attachments/";
+
+/// Whole dataset in a form independent of the database schema. Entities refer to each other by
+/// the uid every document and reference data row gets when it is created; requirement
+/// templates, which have none, are identified by their category and title.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Archive {
+    pub format: String,
+    pub version: u32,
+    pub exported_at: String,
+    pub addresses: Vec<ArchivedAddress>,
+    pub organizations: Vec<ArchivedOrganization>,
+    pub entrepreneurs: Vec<ArchivedEntrepreneur>,
+    pub persons: Vec<ArchivedPerson>,
+    pub source_documents: Vec<ArchivedSourceDocument>,
+    pub requirement_templates: Vec<ArchivedRequirementTemplate>,
+    /// Absent from archives exported before the schemes were stored.
+    #[serde(default)]
+    pub numbering_schemes: Vec<ArchivedNumberingScheme>,
+    pub number_sequences: Vec<ArchivedNumberSequence>,
+    pub documents: Vec<ArchivedDocument>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ArchivedAddress {
+    pub uid: String,
+    pub region_code: String,
+    pub note: Option<String>,
+    pub country: Option<String>,
+    pub district: Option<String>,
+    pub city: Option<String>,
+    pub settlement: Option<String>,
+    pub street: Option<String>,
+    pub building: Option<String>,
+    pub room: Option<String>,
+    pub archived_at: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ArchivedOrganization {
+    pub uid: String,
+    pub full_name: String,
+    pub abbreviated_name: String,
+    pub ogrn: Option<String>,
+    pub rafp: Option<String>,
+    pub inn: String,
+    pub kpp: String,
+    pub email: String,
+    pub address: String,
+    pub archived_at: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ArchivedEntrepreneur {
+    pub uid: String,
+    pub surname: String,
+    pub name: String,
+    pub patronymic: Option<String>,
+    pub ogrnip: String,
+    pub inn: String,
+    pub email: Option<String>,
+    pub address: String,
+    pub archived_at: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ArchivedPerson {
+    pub uid: String,
+    pub surname: String,
+    pub name: String,
+    pub patronymic: Option<String>,
+    pub snils: String,
+    pub email: String,
+    pub address: String,
+    pub archived_at: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ArchivedSourceDocument {
+    pub uid: String,
+    pub kind: SourceDocumentKind,
+    pub number: String,
+    pub date: String,
+    pub issuer: String,
+    pub title: Option<String>,
+    pub archived_at: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ArchivedRequirementTemplate {
+    pub uid: String,
+    pub category: RequirementCategory,
+    pub title: String,
+    pub text: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ArchivedNumberingScheme {
+    pub prefix: String,
+    pub width: i64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ArchivedNumberSequence {
+    pub prefix: String,
+    pub year: i64,
+    pub last_value: i64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ArchivedAuthor {
+    Organization { uid: String },
+    Entrepreneur { uid: String },
+    Person { uid: String },
+    Informal { name: String },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ArchivedDeveloper {
+    Organization { uid: String },
+    Person { uid: String },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ArchivedDocument {
+    pub uid: String,
+    pub name: String,
+    pub number: Option<String>,
+    pub date: Option<String>,
+    pub change_mark: Option<String>,
+    pub document_type: Option<DocumentType>,
+    /// Legacy free-text type that matched no document type, see
+    /// [`zdnp_core::Document::type_note`].
+    #[serde(default)]
+    pub type_note: Option<String>,
+    pub author: ArchivedAuthor,
+    pub developer: Option<ArchivedDeveloper>,
+    pub previous_revision: Option<String>,
+    pub is_template: bool,
+    pub status: DocumentStatus,
+    pub submitted_at: Option<String>,
+    pub approved_at: Option<String>,
+    pub issued_at: Option<String>,
+    pub superseded_at: Option<String>,
+    pub cancelled_at: Option<String>,
+    /// Snapshot of an issued document as stored, with the entity ids of the exporting database.
+    pub snapshot: Option<DocumentSnapshot>,
+    pub construction_object: Option<ArchivedConstructionObject>,
+    pub indicators: Vec<ArchivedIndicator>,
+    pub requirements: Vec<ArchivedRequirement>,
+    pub source_documents: Vec<String>,
+    pub attachments: Vec<ArchivedAttachment>,
+    pub signatories: Vec<ArchivedSignatory>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ArchivedConstructionObject {
+    pub name: String,
+    pub purpose_code: String,
+    pub address: String,
+    pub work_type: WorkType,
+    pub cadastral_numbers: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ArchivedIndicator {
+    pub name: String,
+    pub value: String,
+    pub unit_okei: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ArchivedRequirement {
+    pub category: RequirementCategory,
+    pub title: String,
+    pub text: String,
+    pub template: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ArchivedAttachment {
+    pub file_name: String,
+    pub mime_type: String,
+    pub size: i64,
+    pub sha256: String,
+    pub added_at: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ArchivedSignatory {
+    pub person: String,
+    pub position: String,
+    pub role: SigningRole,
+    pub signed_on: Option<String>,
+}
+
+fn hashed_uid(kind: &str, parts: &[&str]) -> String {
+    let mut hasher = Sha256::new();
+    for part in parts {
+        hasher.update(part.as_bytes());
+        hasher.update([0x1f]);
+    }
+    let hash = hex::encode(hasher.finalize());
+    format!("{kind}:{}", &hash[..32])
+}
+
+fn requirement_template_uid(category: RequirementCategory, title: &str) -> String {
+    hashed_uid("requirement_template", &[category.as_str(), title])
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ArchiveEntityKind {
+    Address,
+    Organization,
+    Entrepreneur,
+    Person,
+    SourceDocument,
+    RequirementTemplate,
+    Document,
+}
+
+impl ArchiveEntityKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Address => "address",
+            Self::Organization => "organization",
+            Self::Entrepreneur => "entrepreneur",
+            Self::Person => "person",
+            Self::SourceDocument => "source_document",
+            Self::RequirementTemplate => "requirement_template",
+            Self::Document => "document",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "address" => Some(Self::Address),
+            "organization" => Some(Self::Organization),
+            "entrepreneur" => Some(Self::Entrepreneur),
+            "person" => Some(Self::Person),
+            "source_document" => Some(Self::SourceDocument),
+            "requirement_template" => Some(Self::RequirementTemplate),
+            "document" => Some(Self::Document),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ArchiveEntry {
+    pub kind: ArchiveEntityKind,
+    pub uid: String,
+    pub id: i64,
+}
+
+/// Archived data that could not be merged as is. The existing data is always kept.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ArchiveConflict {
+    pub kind: ArchiveEntityKind,
+    pub uid: String,
+    /// The matching row of this database, if any.
+    pub id: Option<i64>,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize)]
+pub struct ArchiveImportReport {
+    pub created: Vec<ArchiveEntry>,
+    /// Entities that already exist unchanged.
+    pub matched: Vec<ArchiveEntry>,
+    pub conflicts: Vec<ArchiveConflict>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ArchiveError {
+    Io(String),
+    Storage(String),
+    /// The file is not an archive or refers to entities it does not contain.
+    Malformed(String),
+    UnsupportedVersion(u32),
+}
+
+impl std::fmt::Display for ArchiveError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(message) => write!(f, "I/O error: {message}"),
+            Self::Storage(message) => f.write_str(message),
+            Self::Malformed(message) => write!(f, "Malformed archive: {message}"),
+            Self::UnsupportedVersion(version) => {
+                write!(f, "Archive version {version} is not supported")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ArchiveError {}
+
+impl From<sqlx::Error> for ArchiveError {
+    fn from(error: sqlx::Error) -> Self {
+        Self::Storage(error.to_string())
+    }
+}
+
+impl From<std::io::Error> for ArchiveError {
+    fn from(error: std::io::Error) -> Self {
+        Self::Io(error.to_string())
+    }
+}
+
+impl From<zip::result::ZipError> for ArchiveError {
+    fn from(error: zip::result::ZipError) -> Self {
+        Self::Malformed(error.to_string())
+    }
+}
+
+impl From<serde_json::Error> for ArchiveError {
+    fn from(error: serde_json::Error) -> Self {
+        Self::Malformed(error.to_string())
+    }
+}
+
+/// Ids of the rows of this database by archive uid.
+pub(crate) type Ids = HashMap<(ArchiveEntityKind, String), i64>;
+
+/// Export and merging import of the whole dataset as a zip archive, see [`Archive`].
+pub struct SqliteArchive {
+    database_file_name: String,
+}
+
+impl Default for SqliteArchive {
+    fn default() -> Self {
+        Self {
+            database_file_name: DEFAULT_DATABASE_FILE_NAME.to_string(),
+        }
+    }
+}
+
+impl SqliteArchive {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_file_name<S: Into<String>>(file_name: S) -> Self {
+        Self {
+            database_file_name: file_name.into(),
+        }
+    }
+
+    fn database_path(&self) -> Result<PathBuf, ArchiveError> {
+        let executable = std::env::current_exe()?;
+        let directory = executable
+            .parent()
+            .ok_or_else(|| ArchiveError::Io("Failed to determine application directory".into()))?;
+
+        Ok(directory.join(&self.database_file_name))
+    }
+
+    /// Writes all data with the content of the attachments to the zip file at `path`.
+    pub fn export(&self, path: &Path) -> Result<Archive, ArchiveError> {
+        let database_path = self.database_path()?;
+        let store = store_directory(&database_path);
+        let runtime = Runtime::new()?;
+
+        let archive = runtime.block_on(async {
+            let mut connection = connect(&database_path).await?;
+            let (archive, _) = read_archive(&mut connection).await?;
+            connection.close().await?;
+            Ok::<Archive, ArchiveError>(archive)
+        })?;
+
+        let options = SimpleFileOptions::default();
+        let mut zip = ZipWriter::new(File::create(path)?);
+        zip.start_file(ARCHIVE_ENTRY, options)?;
+        zip.write_all(&serde_json::to_vec_pretty(&archive)?)?;
+
+        let mut written = Vec::new();
+        for attachment in archive
+            .documents
+            .iter()
+            .flat_map(|document| &document.attachments)
+        {
+            let content = content_path(&store, &attachment.sha256);
+            if written.contains(&attachment.sha256) || !content.is_file() {
+                continue;
+            }
+            zip.start_file(
+                format!("{ATTACHMENTS_PREFIX}{}", attachment.sha256),
+                options,
+            )?;
+            std::io::copy(&mut File::open(content)?, &mut zip)?;
+            written.push(attachment.sha256.clone());
+        }
+        zip.finish()?;
+
+        Ok(archive)
+    }
+
+    /// Reads the archive without importing it.
+    pub fn read(path: &Path) -> Result<Archive, ArchiveError> {
+        let mut zip = ZipArchive::new(File::open(path)?)?;
+        let mut json = String::new();
+        zip.by_name(ARCHIVE_ENTRY)?.read_to_string(&mut json)?;
+
+        let archive: Archive = serde_json::from_str(&json)?;
+        if archive.format != ARCHIVE_FORMAT {
+            return Err(ArchiveError::Malformed(format!(
+                "Unknown format: {}",
+                archive.format
+            )));
+        }
+        if archive.version > ARCHIVE_VERSION {
+            return Err(ArchiveError::UnsupportedVersion(archive.version));
+        }
+        let attachments = archive
+            .documents
+            .iter()
+            .flat_map(|document| &document.attachments);
+        for attachment in attachments {
+            if !is_sha256(&attachment.sha256) {
+                return Err(ArchiveError::Malformed(format!(
+                    "Invalid hash of attachment {}: {}",
+                    attachment.file_name, attachment.sha256
+                )));
+            }
+        }
+
+        Ok(archive)
+    }
+
+    /// Merges the archive into this database in one transaction. Entities are matched by uid;
+    /// new ones are created with fresh ids, existing ones are kept and reported as conflicts
+    /// when the archive holds different data.
+    pub fn import(&self, path: &Path) -> Result<ArchiveImportReport, ArchiveError> {
+        let archive = Self::read(path)?;
+        let database_path = self.database_path()?;
+        let store = store_directory(&database_path);
+
+        // Content is stored under its hash, so storing it before the transaction is harmless.
+        // Entry names come from the archive: only a hash that matches the content is stored.
+        let mut zip = ZipArchive::new(File::open(path)?)?;
+        for index in 0..zip.len() {
+            let mut entry = zip.by_index(index)?;
+            let Some(sha256) = entry.name().strip_prefix(ATTACHMENTS_PREFIX) else {
+                continue;
+            };
+            if !is_sha256(sha256) {
+                return Err(ArchiveError::Malformed(format!(
+                    "Invalid attachment entry: {}",
+                    entry.name()
+                )));
+            }
+            let sha256 = sha256.to_string();
+            if content_path(&store, &sha256).is_file() {
+                continue;
+            }
+            std::fs::create_dir_all(&store)?;
+            let incoming = store.join(format!(".archive-{sha256}"));
+            let stored = copy_hashing(&mut entry, &incoming)
+                .map_err(ArchiveError::from)
+                .and_then(|actual| {
+                    if actual != sha256 {
+                        return Err(ArchiveError::Malformed(format!(
+                            "Attachment {sha256} does not match its content"
+                        )));
+                    }
+                    store_content(&store, &incoming)?;
+                    Ok(())
+                });
+            std::fs::remove_file(&incoming)?;
+            stored?;
+        }
+
+        let runtime = Runtime::new()?;
+        runtime.block_on(async {
+            let mut connection = connect(&database_path).await?;
+            let mut transaction = connection.begin().await?;
+            let report = merge(&mut transaction, &archive, &store).await?;
+            transaction.commit().await?;
+            connection.close().await?;
+            Ok(report)
+        })
+    }
+}
+
+/// Writes the zip entry to `destination` and returns the hex SHA-256 of its content.
+fn copy_hashing(entry: &mut impl Read, destination: &Path) -> std::io::Result<String> {
+    let mut output = File::create(destination)?;
+    let mut hasher = Sha256::new();
+    let mut buffer = vec![0u8; 64 * 1024];
+
+    loop {
+        let read = entry.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+        output.write_all(&buffer[..read])?;
+    }
+
+    Ok(hex::encode(hasher.finalize()))
+}
+
+pub(crate) async fn connect(path: &Path) -> Result<SqliteConnection, ArchiveError> {
+    let options = SqliteConnectOptions::new()
+        .filename(path)
+        .create_if_missing(true);
+    Ok(SqliteConnection::connect_with(&options).await?)
+}
+
+fn uid_of(
+    uids: &HashMap<i64, String>,
+    kind: ArchiveEntityKind,
+    id: i64,
+) -> Result<String, ArchiveError> {
+    uids.get(&id)
+        .cloned()
+        .ok_or_else(|| ArchiveError::Storage(format!("Unknown {} {id}", kind.as_str())))
+}
+
+/// Reads the whole database into an archive, with the ids of the archived entities. Rows with
+/// the same uid are archived once.
+pub(crate) async fn read_archive(
+    connection: &mut SqliteConnection,
+) -> Result<(Archive, Ids), ArchiveError> {
+    let mut ids = Ids::new();
+    let mut first = |kind: ArchiveEntityKind, uid: &str, id: i64| {
+        let vacant = !ids.contains_key(&(kind, uid.to_string()));
+        if vacant {
+            ids.insert((kind, uid.to_string()), id);
+        }
+        vacant
+    };
+
+    let exported_at: String = sqlx::query("SELECT strftime('%Y-%m-%dT%H:%M:%SZ', 'now') AS now")
+        .fetch_one(&mut *connection)
+        .await?
+        .get("now");
+
+    let mut address_uids = HashMap::new();
+    let mut addresses = Vec::new();
+    for row in sqlx::query("SELECT * FROM address ORDER BY id")
+        .fetch_all(&mut *connection)
+        .await?
+    {
+        let address = address_from_row(&row);
+        let uid = address.uid.clone();
+        address_uids.insert(address.id, uid.clone());
+        if first(ArchiveEntityKind::Address, &uid, address.id) {
+            addresses.push(ArchivedAddress {
+                uid,
+                region_code: address.region_code,
+                note: address.note,
+                country: address.country,
+                district: address.district,
+                city: address.city,
+                settlement: address.settlement,
+                street: address.street,
+                building: address.building,
+                room: address.room,
+                archived_at: address.archived_at,
+            });
+        }
+    }
+
+    let mut organization_uids = HashMap::new();
+    let mut organizations = Vec::new();
+    for row in sqlx::query("SELECT * FROM organization ORDER BY id")
+        .fetch_all(&mut *connection)
+        .await?
+    {
+        let organization = organization_from_row(&row);
+        let uid = organization.uid.clone();
+        organization_uids.insert(organization.id, uid.clone());
+        if first(ArchiveEntityKind::Organization, &uid, organization.id) {
+            organizations.push(ArchivedOrganization {
+                uid,
+                address: uid_of(
+                    &address_uids,
+                    ArchiveEntityKind::Address,
+                    organization.address_id,
+                )?,
+                full_name: organization.full_name,
+                abbreviated_name: organization.abbreviated_name,
+                ogrn: organization.ogrn,
+                rafp: organization.rafp,
+                inn: organization.inn,
+                kpp: organization.kpp,
+                email: organization.email,
+                archived_at: organization.archived_at,
+            });
+        }
+    }
+
+    let mut entrepreneur_uids = HashMap::new();
+    let mut entrepreneurs = Vec::new();
+    for row in sqlx::query("SELECT * FROM entrepreneur ORDER BY id")
+        .fetch_all(&mut *connection)
+        .await?
+    {
+        let entrepreneur = entrepreneur_from_row(&row);
+        let uid = entrepreneur.uid.clone();
+        entrepreneur_uids.insert(entrepreneur.id, uid.clone());
+        if first(ArchiveEntityKind::Entrepreneur, &uid, entrepreneur.id) {
+            entrepreneurs.push(ArchivedEntrepreneur {
+                uid,
+                address: uid_of(
+                    &address_uids,
+                    ArchiveEntityKind::Address,
+                    entrepreneur.address_id,
+                )?,
+                surname: entrepreneur.surname,
+                name: entrepreneur.name,
+                patronymic: entrepreneur.patronymic,
+                ogrnip: entrepreneur.ogrnip,
+                inn: entrepreneur.inn,
+                email: entrepreneur.email,
+                archived_at: entrepreneur.archived_at,
+            });
+        }
+    }
+
+    let mut person_uids = HashMap::new();
+    let mut persons = Vec::new();
+    for row in sqlx::query("SELECT * FROM person ORDER BY id")
+        .fetch_all(&mut *connection)
+        .await?
+    {
+        let person = person_from_row(&row);
+        let uid = person.uid.clone();
+        person_uids.insert(person.id, uid.clone());
+        if first(ArchiveEntityKind::Person, &uid, person.id) {
+            persons.push(ArchivedPerson {
+                uid,
+                address: uid_of(&address_uids, ArchiveEntityKind::Address, person.address_id)?,
+                surname: person.surname,
+                name: person.name,
+                patronymic: person.patronymic,
+                snils: person.snils,
+                email: person.email,
+                archived_at: person.archived_at,
+            });
+        }
+    }
+
+    let mut source_uids = HashMap::new();
+    let mut source_documents = Vec::new();
+    for row in sqlx::query("SELECT * FROM source_document ORDER BY id")
+        .fetch_all(&mut *connection)
+        .await?
+    {
+        let source = source_document_from_row(&row).map_err(ArchiveError::Storage)?;
+        let issuer = uid_of(
+            &organization_uids,
+            ArchiveEntityKind::Organization,
+            source.issuer_id,
+        )?;
+        let uid = source.uid.clone();
+        source_uids.insert(source.id, uid.clone());
+        if first(ArchiveEntityKind::SourceDocument, &uid, source.id) {
+            source_documents.push(ArchivedSourceDocument {
+                uid,
+                kind: source.kind,
+                number: source.number,
+                date: source.date,
+                issuer,
+                title: source.title,
+                archived_at: source.archived_at,
+            });
+        }
+    }
+
+    let mut template_uids = HashMap::new();
+    let mut requirement_templates = Vec::new();
+    for row in sqlx::query("SELECT * FROM requirement_template ORDER BY id")
+        .fetch_all(&mut *connection)
+        .await?
+    {
+        let id: i64 = row.get("id");
+        let category = requirement_category(row.get("category"))?;
+        let title: String = row.get("title");
+        let uid = requirement_template_uid(category, &title);
+        template_uids.insert(id, uid.clone());
+        if first(ArchiveEntityKind::RequirementTemplate, &uid, id) {
+            requirement_templates.push(ArchivedRequirementTemplate {
+                uid,
+                category,
+                title,
+                text: row.get("text"),
+            });
+        }
+    }
+
+    let numbering_schemes =
+        sqlx::query("SELECT prefix, width FROM numbering_scheme ORDER BY prefix")
+            .fetch_all(&mut *connection)
+            .await?
+            .iter()
+            .map(|row| ArchivedNumberingScheme {
+                prefix: row.get("prefix"),
+                width: row.get("width"),
+            })
+            .collect();
+
+    let number_sequences = sqlx::query(
+        "SELECT prefix, year, last_value FROM document_number_sequence ORDER BY prefix, year",
+    )
+    .fetch_all(&mut *connection)
+    .await?
+    .iter()
+    .map(|row| ArchivedNumberSequence {
+        prefix: row.get("prefix"),
+        year: row.get("year"),
+        last_value: row.get("last_value"),
+    })
+    .collect();
+
+    let document_uids: HashMap<i64, String> = sqlx::query("SELECT id, uid FROM document")
+        .fetch_all(&mut *connection)
+        .await?
+        .iter()
+        .map(|row| (row.get("id"), row.get("uid")))
+        .collect();
+
+    let mut documents = Vec::new();
+    for row in sqlx::query(&format!("{DOCUMENT_SELECT} ORDER BY d.id"))
+        .fetch_all(&mut *connection)
+        .await?
+    {
+        let document =
+            document_from_row(&row).map_err(|error| ArchiveError::Storage(error.to_string()))?;
+        first(ArchiveEntityKind::Document, &document.uid, document.id);
+
+        let author = match document.author {
+            DocumentAuthor::Organization { id } => ArchivedAuthor::Organization {
+                uid: uid_of(&organization_uids, ArchiveEntityKind::Organization, id)?,
+            },
+            DocumentAuthor::Entrepreneur { id } => ArchivedAuthor::Entrepreneur {
+                uid: uid_of(&entrepreneur_uids, ArchiveEntityKind::Entrepreneur, id)?,
+            },
+            DocumentAuthor::Person { id } => ArchivedAuthor::Person {
+                uid: uid_of(&person_uids, ArchiveEntityKind::Person, id)?,
+            },
+            DocumentAuthor::Informal { name } => ArchivedAuthor::Informal { name },
+        };
+        let developer = match document.developer {
+            Some(DocumentDeveloper::Organization { id }) => Some(ArchivedDeveloper::Organization {
+                uid: uid_of(&organization_uids, ArchiveEntityKind::Organization, id)?,
+            }),
+            Some(DocumentDeveloper::Person { id }) => Some(ArchivedDeveloper::Person {
+                uid: uid_of(&person_uids, ArchiveEntityKind::Person, id)?,
+            }),
+            None => None,
+        };
+        let previous_revision = match document.previous_revision_id {
+            Some(id) => Some(uid_of(&document_uids, ArchiveEntityKind::Document, id)?),
+            None => None,
+        };
+
+        let snapshot =
+            match sqlx::query("SELECT content FROM document_snapshot WHERE document_id = ?1")
+                .bind(document.id)
+                .fetch_optional(&mut *connection)
+                .await?
+            {
+                Some(row) => Some(
+                    serde_json::from_str(row.get("content"))
+                        .map_err(|error| ArchiveError::Storage(error.to_string()))?,
+                ),
+                None => None,
+            };
+
+        let construction_object =
+            match sqlx::query("SELECT * FROM construction_object WHERE document_id = ?1")
+                .bind(document.id)
+                .fetch_optional(&mut *connection)
+                .await?
+            {
+                Some(row) => {
+                    let work_type: String = row.get("work_type");
+                    let cadastral_numbers = sqlx::query(
+                        r#"SELECT cadastral_number FROM construction_object_land_plot
+                       WHERE construction_object_id = ?1 ORDER BY position"#,
+                    )
+                    .bind(row.get::<i64, _>("id"))
+                    .fetch_all(&mut *connection)
+                    .await?
+                    .iter()
+                    .map(|row| row.get("cadastral_number"))
+                    .collect();
+
+                    Some(ArchivedConstructionObject {
+                        name: row.get("name"),
+                        purpose_code: row.get("purpose_code"),
+                        address: uid_of(
+                            &address_uids,
+                            ArchiveEntityKind::Address,
+                            row.get("address_id"),
+                        )?,
+                        work_type: WorkType::parse(&work_type).ok_or_else(|| {
+                            ArchiveError::Storage(format!("Unknown work type: {work_type}"))
+                        })?,
+                        cadastral_numbers,
+                    })
+                }
+                None => None,
+            };
+
+        let indicators = sqlx::query(
+            "SELECT name, value, unit_okei FROM technical_indicator WHERE document_id = ?1 ORDER BY position",
+        )
+        .bind(document.id)
+        .fetch_all(&mut *connection)
+        .await?
+        .iter()
+        .map(|row| ArchivedIndicator {
+            name: row.get("name"),
+            value: row.get("value"),
+            unit_okei: row.get("unit_okei"),
+        })
+        .collect();
+
+        let mut requirements = Vec::new();
+        for row in sqlx::query(
+            "SELECT * FROM requirement_section WHERE document_id = ?1 ORDER BY position",
+        )
+        .bind(document.id)
+        .fetch_all(&mut *connection)
+        .await?
+        {
+            requirements.push(ArchivedRequirement {
+                category: requirement_category(row.get("category"))?,
+                title: row.get("title"),
+                text: row.get("text"),
+                template: row
+                    .get::<Option<i64>, _>("template_id")
+                    .and_then(|id| template_uids.get(&id).cloned()),
+            });
+        }
+
+        let mut source_documents = Vec::new();
+        for row in sqlx::query(
+            r#"SELECT source_document_id FROM document_source_document
+               WHERE document_id = ?1 ORDER BY position"#,
+        )
+        .bind(document.id)
+        .fetch_all(&mut *connection)
+        .await?
+        {
+            source_documents.push(uid_of(
+                &source_uids,
+                ArchiveEntityKind::SourceDocument,
+                row.get("source_document_id"),
+            )?);
+        }
+
+        let attachments =
+            sqlx::query("SELECT * FROM attachment WHERE document_id = ?1 ORDER BY id")
+                .bind(document.id)
+                .fetch_all(&mut *connection)
+                .await?
+                .iter()
+                .map(attachment_from_row)
+                .map(|attachment| ArchivedAttachment {
+                    file_name: attachment.file_name,
+                    mime_type: attachment.mime_type,
+                    size: attachment.size,
+                    sha256: attachment.sha256,
+                    added_at: attachment.added_at,
+                })
+                .collect();
+
+        let mut signatories = Vec::new();
+        for row in sqlx::query("SELECT * FROM signatory WHERE document_id = ?1 ORDER BY id")
+            .bind(document.id)
+            .fetch_all(&mut *connection)
+            .await?
+        {
+            let role: String = row.get("role");
+            signatories.push(ArchivedSignatory {
+                person: uid_of(
+                    &person_uids,
+                    ArchiveEntityKind::Person,
+                    row.get("person_id"),
+                )?,
+                position: row.get("position"),
+                role: SigningRole::parse(&role).ok_or_else(|| {
+                    ArchiveError::Storage(format!("Unknown signing role: {role}"))
+                })?,
+                signed_on: row.get("signed_on"),
+            });
+        }
+
+        documents.push(ArchivedDocument {
+            uid: document.uid,
+            name: document.name,
+            number: document.number,
+            date: document.date,
+            change_mark: document.change_mark,
+            document_type: document.document_type,
+            type_note: document.type_note,
+            author,
+            developer,
+            previous_revision,
+            is_template: document.is_template,
+            status: document.status,
+            submitted_at: document.submitted_at,
+            approved_at: document.approved_at,
+            issued_at: document.issued_at,
+            superseded_at: document.superseded_at,
+            cancelled_at: document.cancelled_at,
+            snapshot,
+            construction_object,
+            indicators,
+            requirements,
+            source_documents,
+            attachments,
+            signatories,
+        });
+    }
+
+    let archive = Archive {
+        format: ARCHIVE_FORMAT.to_string(),
+        version: ARCHIVE_VERSION,
+        exported_at,
+        addresses,
+        organizations,
+        entrepreneurs,
+        persons,
+        source_documents,
+        requirement_templates,
+        numbering_schemes,
+        number_sequences,
+        documents,
+    };
+
+    Ok((archive, ids))
+}
+
+fn requirement_category(value: String) -> Result<RequirementCategory, ArchiveError> {
+    RequirementCategory::parse(&value)
+        .ok_or_else(|| ArchiveError::Storage(format!("Unknown requirement category: {value}")))
+}
+
+/// State of an import: the ids of this database by uid and the report so far.
+struct Merge {
+    ids: Ids,
+    /// Entities of this database before the import, as archived.
+    current: HashMap<(ArchiveEntityKind, String), Value>,
+    report: ArchiveImportReport,
+}
+
+impl Merge {
+    /// Id of the existing entity with the uid. A match is reported, as a conflict when the
+    /// archived data differs from the stored one.
+    fn existing<T: Serialize>(
+        &mut self,
+        kind: ArchiveEntityKind,
+        uid: &str,
+        archived: &T,
+    ) -> Result<Option<i64>, ArchiveError> {
+        let Some(&id) = self.ids.get(&(kind, uid.to_string())) else {
+            return Ok(None);
+        };
+
+        let archived = serde_json::to_value(archived)?;
+        let fields: Vec<&str> = match (self.current.get(&(kind, uid.to_string())), &archived) {
+            (Some(Value::Object(current)), Value::Object(archived)) => archived
+                .iter()
+                .filter(|(field, value)| current.get(field.as_str()) != Some(value))
+                .map(|(field, _)| field.as_str())
+                .collect(),
+            _ => Vec::new(),
+        };
+
+        if fields.is_empty() {
+            self.report.matched.push(ArchiveEntry {
+                kind,
+                uid: uid.to_string(),
+                id,
+            });
+        } else {
+            self.conflict(
+                kind,
+                uid,
+                Some(id),
+                format!(
+                    "Differs in {}; the existing data is kept",
+                    fields.join(", ")
+                ),
+            );
+        }
+
+        Ok(Some(id))
+    }
+
+    fn created(&mut self, kind: ArchiveEntityKind, uid: &str, id: i64) {
+        self.ids.insert((kind, uid.to_string()), id);
+        self.report.created.push(ArchiveEntry {
+            kind,
+            uid: uid.to_string(),
+            id,
+        });
+    }
+
+    fn conflict(&mut self, kind: ArchiveEntityKind, uid: &str, id: Option<i64>, message: String) {
+        self.report.conflicts.push(ArchiveConflict {
+            kind,
+            uid: uid.to_string(),
+            id,
+            message,
+        });
+    }
+
+    fn id(&self, kind: ArchiveEntityKind, uid: &str) -> Result<i64, ArchiveError> {
+        referenced_id(&self.ids, kind, uid)
+    }
+}
+
+/// Id of an entity the archive refers to; it is either stored or imported before.
+fn referenced_id(ids: &Ids, kind: ArchiveEntityKind, uid: &str) -> Result<i64, ArchiveError> {
+    ids.get(&(kind, uid.to_string()))
+        .copied()
+        .ok_or_else(|| ArchiveError::Malformed(format!("Unknown {} {uid}", kind.as_str())))
+}
+
+fn current_values<T: Serialize>(
+    current: &mut HashMap<(ArchiveEntityKind, String), Value>,
+    kind: ArchiveEntityKind,
+    entities: &[T],
+) -> Result<(), ArchiveError> {
+    for entity in entities {
+        let value = serde_json::to_value(entity)?;
+        let uid = value["uid"].as_str().unwrap_or_default().to_string();
+        current.insert((kind, uid), value);
+    }
+    Ok(())
+}
+
+fn parse_number(field: &str, value: &str) -> Result<i64, ArchiveError> {
+    value
+        .trim()
+        .parse()
+        .map_err(|_| ArchiveError::Malformed(format!("Invalid {field}: {value}")))
+}
+
+async fn merge(
+    connection: &mut SqliteConnection,
+    archive: &Archive,
+    store: &Path,
+) -> Result<ArchiveImportReport, ArchiveError> {
+    let (current, ids) = read_archive(connection).await?;
+    let mut values = HashMap::new();
+    current_values(&mut values, ArchiveEntityKind::Address, &current.addresses)?;
+    current_values(
+        &mut values,
+        ArchiveEntityKind::Organization,
+        &current.organizations,
+    )?;
+    current_values(
+        &mut values,
+        ArchiveEntityKind::Entrepreneur,
+        &current.entrepreneurs,
+    )?;
+    current_values(&mut values, ArchiveEntityKind::Person, &current.persons)?;
+    current_values(
+        &mut values,
+        ArchiveEntityKind::SourceDocument,
+        &current.source_documents,
+    )?;
+    current_values(
+        &mut values,
+        ArchiveEntityKind::RequirementTemplate,
+        &current.requirement_templates,
+    )?;
+    current_values(&mut values, ArchiveEntityKind::Document, &current.documents)?;
+    let mut merge = Merge {
+        ids,
+        current: values,
+        report: ArchiveImportReport::default(),
+    };
+
+    for address in &archive.addresses {
+        let kind = ArchiveEntityKind::Address;
+        if merge.existing(kind, &address.uid, address)?.is_some() {
+            continue;
+        }
+        let id = store_address(connection, None, address).await?;
+        merge.created(kind, &address.uid, id);
+    }
+
+    for organization in &archive.organizations {
+        let kind = ArchiveEntityKind::Organization;
+        if merge
+            .existing(kind, &organization.uid, organization)?
+            .is_some()
+        {
+            continue;
+        }
+        let id = store_organization(connection, None, organization, &merge.ids).await?;
+        merge.created(kind, &organization.uid, id);
+    }
+
+    for entrepreneur in &archive.entrepreneurs {
+        let kind = ArchiveEntityKind::Entrepreneur;
+        if merge
+            .existing(kind, &entrepreneur.uid, entrepreneur)?
+            .is_some()
+        {
+            continue;
+        }
+        let id = store_entrepreneur(connection, None, entrepreneur, &merge.ids).await?;
+        merge.created(kind, &entrepreneur.uid, id);
+    }
+
+    for person in &archive.persons {
+        let kind = ArchiveEntityKind::Person;
+        if merge.existing(kind, &person.uid, person)?.is_some() {
+            continue;
+        }
+        let id = store_person(connection, None, person, &merge.ids).await?;
+        merge.created(kind, &person.uid, id);
+    }
+
+    for source in &archive.source_documents {
+        let kind = ArchiveEntityKind::SourceDocument;
+        if merge.existing(kind, &source.uid, source)?.is_some() {
+            continue;
+        }
+        let id = store_source_document(connection, None, source, &merge.ids).await?;
+        merge.created(kind, &source.uid, id);
+    }
+
+    for template in &archive.requirement_templates {
+        let kind = ArchiveEntityKind::RequirementTemplate;
+        if merge.existing(kind, &template.uid, template)?.is_some() {
+            continue;
+        }
+        let id = sqlx::query(
+            "INSERT INTO requirement_template (category, title, text) VALUES (?1, ?2, ?3)",
+        )
+        .bind(template.category.as_str())
+        .bind(&template.title)
+        .bind(&template.text)
+        .execute(&mut *connection)
+        .await?
+        .last_insert_rowid();
+        merge.created(kind, &template.uid, id);
+    }
+
+    // The local width of a prefix wins, so numbers already issued here keep their format.
+    for scheme in &archive.numbering_schemes {
+        sqlx::query(
+            r#"INSERT INTO numbering_scheme (prefix, width) VALUES (?1, ?2)
+               ON CONFLICT (prefix) DO NOTHING"#,
+        )
+        .bind(&scheme.prefix)
+        .bind(scheme.width)
+        .execute(&mut *connection)
+        .await?;
+    }
+
+    // Numbers already handed out in either database must not be allocated again.
+    for sequence in &archive.number_sequences {
+        sqlx::query(
+            r#"INSERT INTO document_number_sequence (prefix, year, last_value) VALUES (?1, ?2, ?3)
+               ON CONFLICT (prefix, year) DO UPDATE
+               SET last_value = max(last_value, excluded.last_value)"#,
+        )
+        .bind(&sequence.prefix)
+        .bind(sequence.year)
+        .bind(sequence.last_value)
+        .execute(&mut *connection)
+        .await?;
+    }
+
+    for document in &archive.documents {
+        let kind = ArchiveEntityKind::Document;
+        if merge.existing(kind, &document.uid, document)?.is_some() {
+            continue;
+        }
+        let id = insert_document(connection, &mut merge, document, store).await?;
+        merge.created(kind, &document.uid, id);
+    }
+
+    Ok(merge.report)
+}
+
+// Writers of archived reference data: a new row is inserted unless the id of an existing one
+// is given, which is then overwritten.
+
+pub(crate) async fn store_address(
+    connection: &mut SqliteConnection,
+    id: Option<i64>,
+    address: &ArchivedAddress,
+) -> Result<i64, ArchiveError> {
+    let sql = match id {
+        None => {
+            r#"INSERT INTO address (
+                region_code, note, country, district, city, settlement, street, building, room,
+                archived_at, uid
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)"#
+        }
+        Some(_) => {
+            r#"UPDATE address SET
+                region_code = ?1, note = ?2, country = ?3, district = ?4, city = ?5,
+                settlement = ?6, street = ?7, building = ?8, room = ?9, archived_at = ?10,
+                uid = ?11
+            WHERE id = ?12"#
+        }
+    };
+    let query = sqlx::query(sql)
+        .bind(&address.region_code)
+        .bind(&address.note)
+        .bind(&address.country)
+        .bind(&address.district)
+        .bind(&address.city)
+        .bind(&address.settlement)
+        .bind(&address.street)
+        .bind(&address.building)
+        .bind(&address.room)
+        .bind(&address.archived_at)
+        .bind(&address.uid);
+    stored(connection, query, id).await
+}
+
+pub(crate) async fn store_organization(
+    connection: &mut SqliteConnection,
+    id: Option<i64>,
+    organization: &ArchivedOrganization,
+    ids: &Ids,
+) -> Result<i64, ArchiveError> {
+    let optional = |field: &str, value: &Option<String>| {
+        value
+            .as_deref()
+            .map(|value| parse_number(field, value))
+            .transpose()
+    };
+    let sql = match id {
+        None => {
+            r#"INSERT INTO organization (
+                full_name, abbreviated_name, ogrn, rafp, inn, kpp, address_id, email, archived_at,
+                uid
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)"#
+        }
+        Some(_) => {
+            r#"UPDATE organization SET
+                full_name = ?1, abbreviated_name = ?2, ogrn = ?3, rafp = ?4, inn = ?5, kpp = ?6,
+                address_id = ?7, email = ?8, archived_at = ?9, uid = ?10
+            WHERE id = ?11"#
+        }
+    };
+    let query = sqlx::query(sql)
+        .bind(&organization.full_name)
+        .bind(&organization.abbreviated_name)
+        .bind(optional("OGRN", &organization.ogrn)?)
+        .bind(optional("RAFP", &organization.rafp)?)
+        .bind(parse_number("INN", &organization.inn)?)
+        .bind(parse_number("KPP", &organization.kpp)?)
+        .bind(referenced_id(
+            ids,
+            ArchiveEntityKind::Address,
+            &organization.address,
+        )?)
+        .bind(&organization.email)
+        .bind(&organization.archived_at)
+        .bind(&organization.uid);
+    stored(connection, query, id).await
+}
+
+pub(crate) async fn store_entrepreneur(
+    connection: &mut SqliteConnection,
+    id: Option<i64>,
+    entrepreneur: &ArchivedEntrepreneur,
+    ids: &Ids,
+) -> Result<i64, ArchiveError> {
+    let sql = match id {
+        None => {
+            r#"INSERT INTO entrepreneur (
+                surname, name, patronymic, ogrnip, inn, address_id, email, archived_at, uid
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)"#
+        }
+        Some(_) => {
+            r#"UPDATE entrepreneur SET
+                surname = ?1, name = ?2, patronymic = ?3, ogrnip = ?4, inn = ?5, address_id = ?6,
+                email = ?7, archived_at = ?8, uid = ?9
+            WHERE id = ?10"#
+        }
+    };
+    let query = sqlx::query(sql)
+        .bind(&entrepreneur.surname)
+        .bind(&entrepreneur.name)
+        .bind(&entrepreneur.patronymic)
+        .bind(parse_number("OGRNIP", &entrepreneur.ogrnip)?)
+        .bind(parse_number("INN", &entrepreneur.inn)?)
+        .bind(referenced_id(
+            ids,
+            ArchiveEntityKind::Address,
+            &entrepreneur.address,
+        )?)
+        .bind(&entrepreneur.email)
+        .bind(&entrepreneur.archived_at)
+        .bind(&entrepreneur.uid);
+    stored(connection, query, id).await
+}
+
+pub(crate) async fn store_person(
+    connection: &mut SqliteConnection,
+    id: Option<i64>,
+    person: &ArchivedPerson,
+    ids: &Ids,
+) -> Result<i64, ArchiveError> {
+    let sql = match id {
+        None => {
+            r#"INSERT INTO person (
+                name, patronymic, surname, snils, email, address_id, archived_at, uid
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)"#
+        }
+        Some(_) => {
+            r#"UPDATE person SET
+                name = ?1, patronymic = ?2, surname = ?3, snils = ?4, email = ?5, address_id = ?6,
+                archived_at = ?7, uid = ?8
+            WHERE id = ?9"#
+        }
+    };
+    let query = sqlx::query(sql)
+        .bind(&person.name)
+        .bind(&person.patronymic)
+        .bind(&person.surname)
+        .bind(parse_number("SNILS", &person.snils)?)
+        .bind(&person.email)
+        .bind(referenced_id(
+            ids,
+            ArchiveEntityKind::Address,
+            &person.address,
+        )?)
+        .bind(&person.archived_at)
+        .bind(&person.uid);
+    stored(connection, query, id).await
+}
+
+pub(crate) async fn store_source_document(
+    connection: &mut SqliteConnection,
+    id: Option<i64>,
+    source: &ArchivedSourceDocument,
+    ids: &Ids,
+) -> Result<i64, ArchiveError> {
+    let sql = match id {
+        None => {
+            r#"INSERT INTO source_document (kind, number, date, issuer_id, title, archived_at, uid)
+               VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)"#
+        }
+        Some(_) => {
+            r#"UPDATE source_document SET
+                kind = ?1, number = ?2, date = ?3, issuer_id = ?4, title = ?5, archived_at = ?6,
+                uid = ?7
+            WHERE id = ?8"#
+        }
+    };
+    let query = sqlx::query(sql)
+        .bind(source.kind.as_str())
+        .bind(&source.number)
+        .bind(&source.date)
+        .bind(referenced_id(
+            ids,
+            ArchiveEntityKind::Organization,
+            &source.issuer,
+        )?)
+        .bind(&source.title)
+        .bind(&source.archived_at)
+        .bind(&source.uid);
+    stored(connection, query, id).await
+}
+
+async fn stored<'q>(
+    connection: &mut SqliteConnection,
+    query: sqlx::query::Query<'q, sqlx::Sqlite, sqlx::sqlite::SqliteArguments<'q>>,
+    id: Option<i64>,
+) -> Result<i64, ArchiveError> {
+    match id {
+        None => Ok(query.execute(&mut *connection).await?.last_insert_rowid()),
+        Some(id) => {
+            query.bind(id).execute(&mut *connection).await?;
+            Ok(id)
+        }
+    }
+}
+
+async fn insert_document(
+    connection: &mut SqliteConnection,
+    merge: &mut Merge,
+    document: &ArchivedDocument,
+    store: &Path,
+) -> Result<i64, ArchiveError> {
+    let storage =
+        |error: zdnp_core::DocumentRepositoryError| ArchiveError::Storage(error.to_string());
+    let author = match &document.author {
+        ArchivedAuthor::Organization { uid } => DocumentAuthor::Organization {
+            id: merge.id(ArchiveEntityKind::Organization, uid)?,
+        },
+        ArchivedAuthor::Entrepreneur { uid } => DocumentAuthor::Entrepreneur {
+            id: merge.id(ArchiveEntityKind::Entrepreneur, uid)?,
+        },
+        ArchivedAuthor::Person { uid } => DocumentAuthor::Person {
+            id: merge.id(ArchiveEntityKind::Person, uid)?,
+        },
+        ArchivedAuthor::Informal { name } => DocumentAuthor::Informal { name: name.clone() },
+    };
+    let developer = match &document.developer {
+        Some(ArchivedDeveloper::Organization { uid }) => Some(DocumentDeveloper::Organization {
+            id: merge.id(ArchiveEntityKind::Organization, uid)?,
+        }),
+        Some(ArchivedDeveloper::Person { uid }) => Some(DocumentDeveloper::Person {
+            id: merge.id(ArchiveEntityKind::Person, uid)?,
+        }),
+        None => None,
+    };
+
+    // A revision chain is linear: the link is dropped when this database already continues
+    // the previous revision differently.
+    let mut previous_revision_id = None;
+    if let Some(previous) = &document.previous_revision {
+        match merge
+            .ids
+            .get(&(ArchiveEntityKind::Document, previous.clone()))
+        {
+            None => merge.conflict(
+                ArchiveEntityKind::Document,
+                &document.uid,
+                None,
+                format!("Previous revision {previous} is missing"),
+            ),
+            Some(&id) => {
+                let taken = sqlx::query("SELECT id FROM document WHERE previous_revision_id = ?1")
+                    .bind(id)
+                    .fetch_optional(&mut *connection)
+                    .await?
+                    .is_some();
+                if taken {
+                    merge.conflict(
+                        ArchiveEntityKind::Document,
+                        &document.uid,
+                        None,
+                        format!("Previous revision {previous} already has a next revision"),
+                    );
+                } else {
+                    previous_revision_id = Some(id);
+                }
+            }
+        }
+    }
+
+    let author_id = insert_author(connection, &author).await.map_err(storage)?;
+    let developer_id = insert_developer(connection, developer)
+        .await
+        .map_err(storage)?;
+
+    let id = sqlx::query(
+        r#"INSERT INTO document (
+            name, number, date, change_mark, uid, author_id, type_id, previous_revision_id,
+            is_template, status, submitted_at, approved_at, issued_at, superseded_at,
+            cancelled_at, developer_id, type_note
+        ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17)"#,
+    )
+    .bind(&document.name)
+    .bind(&document.number)
+    .bind(&document.date)
+    .bind(&document.change_mark)
+    .bind(&document.uid)
+    .bind(author_id)
+    .bind(document.document_type.map(DocumentType::id))
+    .bind(previous_revision_id)
+    .bind(document.is_template)
+    .bind(document.status.as_str())
+    .bind(&document.submitted_at)
+    .bind(&document.approved_at)
+    .bind(&document.issued_at)
+    .bind(&document.superseded_at)
+    .bind(&document.cancelled_at)
+    .bind(developer_id)
+    .bind(&document.type_note)
+    .execute(&mut *connection)
+    .await?
+    .last_insert_rowid();
+
+    if let Some(snapshot) = &document.snapshot {
+        let (snapshot, unresolved) = remap_snapshot(snapshot, &merge.ids);
+        for reference in unresolved {
+            merge.conflict(
+                ArchiveEntityKind::Document,
+                &document.uid,
+                Some(id),
+                format!("Snapshot entity {reference} cannot be resolved by uid and is left out"),
+            );
+        }
+        sqlx::query("INSERT INTO document_snapshot (document_id, content) VALUES (?1, ?2)")
+            .bind(id)
+            .bind(serde_json::to_string(&snapshot)?)
+            .execute(&mut *connection)
+            .await?;
+    }
+
+    if let Some(object) = &document.construction_object {
+        let object_id = sqlx::query(
+            r#"INSERT INTO construction_object (
+                document_id, name, purpose_code, address_id, work_type
+            ) VALUES (?1, ?2, ?3, ?4, ?5)"#,
+        )
+        .bind(id)
+        .bind(&object.name)
+        .bind(&object.purpose_code)
+        .bind(merge.id(ArchiveEntityKind::Address, &object.address)?)
+        .bind(object.work_type.as_str())
+        .execute(&mut *connection)
+        .await?
+        .last_insert_rowid();
+
+        for (position, cadastral_number) in object.cadastral_numbers.iter().enumerate() {
+            sqlx::query(
+                r#"INSERT INTO construction_object_land_plot (
+                    construction_object_id, cadastral_number, position
+                ) VALUES (?1, ?2, ?3)"#,
+            )
+            .bind(object_id)
+            .bind(cadastral_number)
+            .bind(position as i64)
+            .execute(&mut *connection)
+            .await?;
+        }
+    }
+
+    for (position, indicator) in document.indicators.iter().enumerate() {
+        sqlx::query(
+            r#"INSERT INTO technical_indicator (document_id, position, name, value, unit_okei)
+               VALUES (?1, ?2, ?3, ?4, ?5)"#,
+        )
+        .bind(id)
+        .bind(position as i64)
+        .bind(&indicator.name)
+        .bind(&indicator.value)
+        .bind(&indicator.unit_okei)
+        .execute(&mut *connection)
+        .await?;
+    }
+
+    for (position, requirement) in document.requirements.iter().enumerate() {
+        let template_id = requirement.template.as_ref().and_then(|uid| {
+            merge
+                .ids
+                .get(&(ArchiveEntityKind::RequirementTemplate, uid.clone()))
+                .copied()
+        });
+        sqlx::query(
+            r#"INSERT INTO requirement_section (
+                document_id, position, category, title, text, template_id
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6)"#,
+        )
+        .bind(id)
+        .bind(position as i64)
+        .bind(requirement.category.as_str())
+        .bind(&requirement.title)
+        .bind(&requirement.text)
+        .bind(template_id)
+        .execute(&mut *connection)
+        .await?;
+    }
+
+    for (position, source) in document.source_documents.iter().enumerate() {
+        sqlx::query(
+            r#"INSERT INTO document_source_document (document_id, source_document_id, position)
+               VALUES (?1, ?2, ?3)"#,
+        )
+        .bind(id)
+        .bind(merge.id(ArchiveEntityKind::SourceDocument, source)?)
+        .bind(position as i64)
+        .execute(&mut *connection)
+        .await?;
+    }
+
+    for attachment in &document.attachments {
+        if !content_path(store, &attachment.sha256).is_file() {
+            merge.conflict(
+                ArchiveEntityKind::Document,
+                &document.uid,
+                None,
+                format!("Content of attachment {} is missing", attachment.file_name),
+            );
+            continue;
+        }
+        sqlx::query(
+            r#"INSERT INTO attachment (document_id, file_name, mime_type, size, sha256, added_at)
+               VALUES (?1, ?2, ?3, ?4, ?5, ?6)"#,
+        )
+        .bind(id)
+        .bind(&attachment.file_name)
+        .bind(&attachment.mime_type)
+        .bind(attachment.size)
+        .bind(&attachment.sha256)
+        .bind(&attachment.added_at)
+        .execute(&mut *connection)
+        .await?;
+    }
+
+    for signatory in &document.signatories {
+        sqlx::query(
+            r#"INSERT INTO signatory (document_id, person_id, position, role, signed_on)
+               VALUES (?1, ?2, ?3, ?4, ?5)"#,
+        )
+        .bind(id)
+        .bind(merge.id(ArchiveEntityKind::Person, &signatory.person)?)
+        .bind(&signatory.position)
+        .bind(signatory.role.as_str())
+        .bind(&signatory.signed_on)
+        .execute(&mut *connection)
+        .await?;
+    }
+
+    Ok(id)
+}
+
+/// Points the snapshot entities to the ids they have in this database. The entities are
+/// identified by their uids. Ids of the exporting database never stay in the snapshot: an
+/// entity that cannot be resolved, e.g. from a snapshot taken before reference data had uids,
+/// is left out, and a reference to it from another entity is set to `0`, which matches no row.
+/// The unresolved references are returned so the import can report them.
+fn remap_snapshot(snapshot: &DocumentSnapshot, ids: &Ids) -> (DocumentSnapshot, Vec<EntityRef>) {
+    let resolved: Vec<(EntityRef, i64)> = snapshot
+        .entities
+        .iter()
+        .filter(|entity| !entity.uid().is_empty())
+        .filter_map(|entity| {
+            let kind = match entity {
+                ReferenceEntity::Address(_) => ArchiveEntityKind::Address,
+                ReferenceEntity::Organization(_) => ArchiveEntityKind::Organization,
+                ReferenceEntity::Entrepreneur(_) => ArchiveEntityKind::Entrepreneur,
+                ReferenceEntity::Person(_) => ArchiveEntityKind::Person,
+                ReferenceEntity::SourceDocument(_) => ArchiveEntityKind::SourceDocument,
+            };
+            ids.get(&(kind, entity.uid().to_string()))
+                .map(|&id| (entity.reference(), id))
+        })
+        .collect();
+
+    let mut unresolved = Vec::new();
+    let mut id = |reference: EntityRef| {
+        let id = resolved
+            .iter()
+            .find(|(candidate, _)| *candidate == reference)
+            .map(|&(_, id)| id);
+        if id.is_none() && !unresolved.contains(&reference) {
+            unresolved.push(reference);
+        }
+        id
+    };
+
+    let mut entities = Vec::new();
+    for entity in &snapshot.entities {
+        let Some(entity_id) = id(entity.reference()) else {
+            continue;
+        };
+        entities.push(match entity.clone() {
+            ReferenceEntity::Address(mut address) => {
+                address.id = entity_id;
+                ReferenceEntity::Address(address)
+            }
+            ReferenceEntity::Organization(mut organization) => {
+                organization.id = entity_id;
+                organization.address_id =
+                    id(EntityRef::Address(organization.address_id)).unwrap_or(0);
+                ReferenceEntity::Organization(organization)
+            }
+            ReferenceEntity::Entrepreneur(mut entrepreneur) => {
+                entrepreneur.id = entity_id;
+                entrepreneur.address_id =
+                    id(EntityRef::Address(entrepreneur.address_id)).unwrap_or(0);
+                ReferenceEntity::Entrepreneur(entrepreneur)
+            }
+            ReferenceEntity::Person(mut person) => {
+                person.id = entity_id;
+                person.address_id = id(EntityRef::Address(person.address_id)).unwrap_or(0);
+                ReferenceEntity::Person(person)
+            }
+            ReferenceEntity::SourceDocument(mut source) => {
+                source.id = entity_id;
+                source.issuer_id = id(EntityRef::Organization(source.issuer_id)).unwrap_or(0);
+                ReferenceEntity::SourceDocument(source)
+            }
+        });
+    }
+
+    (DocumentSnapshot { entities }, unresolved)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{TestDatabase, create_organization};
+    use crate::{
+        SqliteDocumentRepository, SqliteReferenceRepository, SqliteSourceDocumentRepository,
+    };
+    use sqlx::SqlitePool;
+    use std::fs;
+    use zdnp_core::{Address, DocumentDto, DocumentRepository, Organization, SourceDocumentDto};
+
+    #[test]
+    fn snapshot_entities_without_a_local_uid_are_left_out() {
+        let address = Address {
+            id: 7,
+            uid: String::new(),
+            region_code: "77".into(),
+            note: None,
+            country: None,
+            district: None,
+            city: Some("Москва".into()),
+            settlement: None,
+            street: None,
+            building: None,
+            room: None,
+            archived_at: None,
+        };
+        let organization = Organization {
+            id: 5,
+            uid: "organization-uid".into(),
+            full_name: "ООО \"Проект\"".into(),
+            abbreviated_name: "Проект".into(),
+            ogrn: None,
+            rafp: None,
+            inn: "7700000000".into(),
+            kpp: "770001001".into(),
+            address_id: 7,
+            email: "info@example.com".into(),
+            archived_at: None,
+        };
+        let snapshot = DocumentSnapshot {
+            entities: vec![
+                ReferenceEntity::Organization(organization.clone()),
+                ReferenceEntity::Address(address),
+            ],
+        };
+        let ids = Ids::from([(
+            (
+                ArchiveEntityKind::Organization,
+                "organization-uid".to_string(),
+            ),
+            1,
+        )]);
+
+        let (remapped, unresolved) = remap_snapshot(&snapshot, &ids);
+
+        assert_eq!(
+            remapped.entities,
+            vec![ReferenceEntity::Organization(Organization {
+                id: 1,
+                address_id: 0,
+                ..organization
+            })]
+        );
+        assert_eq!(unresolved, vec![EntityRef::Address(7)]);
+    }
+
+    #[test]
+    fn archives_are_merged_by_uid() {
+        let database = TestDatabase::new();
+        let documents = SqliteDocumentRepository::with_file_name(database.path.as_str());
+        let references = SqliteReferenceRepository::with_file_name(database.path.as_str());
+        let sources = SqliteSourceDocumentRepository::with_file_name(database.path.as_str());
+        let organization_id = create_organization(&database);
+        let directory = Path::new(&database.path)
+            .parent()
+            .expect("database directory")
+            .to_path_buf();
+
+        let gpzu_id = zdnp_core::create_source_document(
+            &sources,
+            &references,
+            &SourceDocumentDto {
+                kind: Some(SourceDocumentKind::Gpzu),
+                number: Some("РФ-77-4-53-3-12-2026-1234".into()),
+                date: Some("2026-09-15".into()),
+                issuer_id: organization_id,
+                title: None,
+                uid: None,
+            },
+        )
+        .expect("gpzu");
+        let document_id = zdnp_core::create_document(
+            &documents,
+            &references,
+            &DocumentDto {
+                name: Some("ЗНП".into()),
+                author: Some(DocumentAuthor::Organization {
+                    id: organization_id,
+                }),
+                ..Default::default()
+            },
+        )
+        .expect("document");
+        zdnp_core::attach_source_document(&documents, &references, document_id, gpzu_id)
+            .expect("attach");
+        let scan = directory.join("ГПЗУ.pdf");
+        fs::write(&scan, b"%PDF-1.7 gpzu").expect("scan");
+        zdnp_core::add_attachment(&documents, document_id, &scan).expect("attachment");
+        Runtime::new().unwrap().block_on(async {
+            let pool =
+                SqlitePool::connect_with(SqliteConnectOptions::new().filename(&database.path))
+                    .await
+                    .unwrap();
+            sqlx::query("UPDATE document SET type_note = 'Реставрация фасада'")
+                .execute(&pool)
+                .await
+                .unwrap();
+            pool.close().await;
+        });
+
+        let path = directory.join("export.zdnp");
+        let archive = SqliteArchive::with_file_name(database.path.as_str())
+            .export(&path)
+            .expect("export");
+        assert_eq!(archive.documents.len(), 1);
+        assert_eq!(
+            archive.documents[0].type_note.as_deref(),
+            Some("Реставрация фасада")
+        );
+        assert_eq!(SqliteArchive::read(&path), Ok(archive.clone()));
+
+        let report = SqliteArchive::with_file_name(database.path.as_str())
+            .import(&path)
+            .expect("import into the same database");
+        assert_eq!(report.created, Vec::new());
+        assert_eq!(report.conflicts, Vec::new());
+
+        let other = TestDatabase::new();
+        let other_archive = SqliteArchive::with_file_name(other.path.as_str());
+        let report = other_archive.import(&path).expect("import");
+        assert_eq!(report.conflicts, Vec::new());
+        let imported = report
+            .created
+            .iter()
+            .find(|entry| entry.kind == ArchiveEntityKind::Document)
+            .expect("imported document");
+        assert_eq!(imported.uid, archive.documents[0].uid);
+
+        let other_documents = SqliteDocumentRepository::with_file_name(other.path.as_str());
+        let attachments =
+            zdnp_core::list_attachments(&other_documents, imported.id).expect("attachments");
+        let extracted = directory.join("extracted.pdf");
+        zdnp_core::extract_attachment(&other_documents, attachments[0].id, &extracted)
+            .expect("extract");
+        assert_eq!(fs::read(&extracted).expect("read"), b"%PDF-1.7 gpzu");
+
+        let round_trip = other_archive
+            .export(&directory.join("round-trip.zdnp"))
+            .expect("export again");
+        assert_eq!(round_trip.documents, archive.documents);
+        assert_eq!(round_trip.organizations, archive.organizations);
+
+        let runtime = Runtime::new().unwrap();
+        runtime.block_on(async {
+            let pool = SqlitePool::connect_with(SqliteConnectOptions::new().filename(&other.path))
+                .await
+                .unwrap();
+            sqlx::query("UPDATE organization SET email = 'new@example.com'")
+                .execute(&pool)
+                .await
+                .unwrap();
+            pool.close().await;
+        });
+        let report = other_archive.import(&path).expect("import again");
+        assert_eq!(report.created, Vec::new());
+        assert_eq!(report.conflicts.len(), 1);
+        assert_eq!(report.conflicts[0].kind, ArchiveEntityKind::Organization);
+        assert!(report.conflicts[0].message.contains("email"));
+    }
+
+    #[test]
+    fn archives_with_forged_attachment_hashes_are_rejected() {
+        let database = TestDatabase::new();
+        let documents = SqliteDocumentRepository::with_file_name(database.path.as_str());
+        let references = SqliteReferenceRepository::with_file_name(database.path.as_str());
+        let organization_id = create_organization(&database);
+        let directory = Path::new(&database.path)
+            .parent()
+            .expect("database directory")
+            .to_path_buf();
+
+        let document_id = zdnp_core::create_document(
+            &documents,
+            &references,
+            &DocumentDto {
+                name: Some("ЗНП".into()),
+                author: Some(DocumentAuthor::Organization {
+                    id: organization_id,
+                }),
+                ..Default::default()
+            },
+        )
+        .expect("document");
+        let scan = directory.join("ГПЗУ.pdf");
+        fs::write(&scan, b"%PDF-1.7 gpzu").expect("scan");
+        let attachment =
+            zdnp_core::add_attachment(&documents, document_id, &scan).expect("attachment");
+        let exported = directory.join("export.zdnp");
+        SqliteArchive::with_file_name(database.path.as_str())
+            .export(&exported)
+            .expect("export");
+        let mut json = String::new();
+        zip::ZipArchive::new(fs::File::open(&exported).expect("open"))
+            .expect("zip")
+            .by_name("archive.json")
+            .expect("archive.json")
+            .read_to_string(&mut json)
+            .expect("read");
+
+        let forge = |name: &str, json: &str, entries: &[(&str, &[u8])]| {
+            let path = directory.join(name);
+            let mut zip = zip::ZipWriter::new(fs::File::create(&path).expect("create"));
+            let options = zip::write::SimpleFileOptions::default();
+            zip.start_file("archive.json", options).expect("entry");
+            zip.write_all(json.as_bytes()).expect("write");
+            for (name, content) in entries {
+                zip.start_file(*name, options).expect("entry");
+                zip.write_all(content).expect("write");
+            }
+            zip.finish().expect("finish");
+            path
+        };
+
+        let other = TestDatabase::new();
+        let other_archive = SqliteArchive::with_file_name(other.path.as_str());
+        let traversal = forge(
+            "traversal.zdnp",
+            &json,
+            &[("attachments/../../../escape", b"escape")],
+        );
+        assert!(matches!(
+            other_archive.import(&traversal),
+            Err(ArchiveError::Malformed(message)) if message.contains("Invalid attachment entry")
+        ));
+
+        let forged = forge(
+            "forged.zdnp",
+            &json,
+            &[(
+                format!("attachments/{}", attachment.sha256).as_str(),
+                b"%PDF-1.7 forged",
+            )],
+        );
+        assert!(matches!(
+            other_archive.import(&forged),
+            Err(ArchiveError::Malformed(message)) if message.contains("does not match")
+        ));
+
+        let bad_hash = forge(
+            "bad-hash.zdnp",
+            &json.replace(&attachment.sha256, "../../escape"),
+            &[],
+        );
+        assert!(matches!(
+            SqliteArchive::read(&bad_hash),
+            Err(ArchiveError::Malformed(message)) if message.contains("Invalid hash")
+        ));
+        assert!(matches!(
+            other_archive.import(&bad_hash),
+            Err(ArchiveError::Malformed(_))
+        ));
+
+        let other_root = Path::new(&other.path).parent().expect("other directory");
+        assert!(
+            !other_root
+                .join("attachments")
+                .join(&attachment.sha256[..2])
+                .exists()
+        );
+        assert!(!other_root.join("escape").exists());
+        assert!(!other_root.parent().expect("parent").join("escape").exists());
+        assert_eq!(
+            SqliteDocumentRepository::with_file_name(other.path.as_str()).list(),
+            Ok(Vec::new())
+        );
+    }
+}
@@ -0,0 +1,323 @@
+use std::fs::{self, File};
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use sha2::{Digest, Sha256};
+use sqlx::{Row, SqlitePool, sqlite::SqliteConnectOptions, sqlite::SqliteRow};
+use tokio::runtime::Runtime;
+use zdnp_core::{Attachment, AttachmentRepository, AttachmentRepositoryError};
+
+use crate::SqliteDocumentRepository;
+
+// ---------------- Attachment Store ----------------
+
+/// Directory next to the database file that holds the attachment content.
+const ATTACHMENTS_DIRECTORY: &str = "attachments";
+
+static INCOMING_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+pub(crate) fn store_directory(database_path: &Path) -> PathBuf {
+    database_path
+        .parent()
+        .unwrap_or_else(|| Path::new("."))
+        .join(ATTACHMENTS_DIRECTORY)
+}
+
+/// Whether the value is a hex SHA-256 as the store names its files: 64 lowercase hex digits.
+/// Hashes read from outside the database are checked before they become paths.
+pub(crate) fn is_sha256(value: &str) -> bool {
+    value.len() == 64
+        && value
+            .bytes()
+            .all(|byte| byte.is_ascii_digit() || (b'a'..=b'f').contains(&byte))
+}
+
+/// Content is sharded by the first two hex digits of its hash to keep directories small.
+pub(crate) fn content_path(store: &Path, sha256: &str) -> PathBuf {
+    store.join(&sha256[..2]).join(sha256)
+}
+
+/// Copies `source` into `destination` and returns the hex SHA-256 and the size of the content.
+fn copy_hashing(source: &Path, destination: &Path) -> io::Result<(String, i64)> {
+    let mut input = File::open(source)?;
+    let mut output = File::create(destination)?;
+    let mut hasher = Sha256::new();
+    let mut buffer = vec![0u8; 64 * 1024];
+    let mut size = 0i64;
+
+    loop {
+        let read = input.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+        output.write_all(&buffer[..read])?;
+        size += read as i64;
+    }
+    output.sync_all()?;
+
+    Ok((hex::encode(hasher.finalize()), size))
+}
+
+/// Copies the file into the store under its hash. Returns the hash, the size and whether the
+/// content was not stored before.
+pub(crate) fn store_content(store: &Path, source: &Path) -> io::Result<(String, i64, bool)> {
+    fs::create_dir_all(store)?;
+
+    let incoming = store.join(format!(
+        ".incoming-{}-{}",
+        std::process::id(),
+        INCOMING_COUNTER.fetch_add(1, Ordering::Relaxed)
+    ));
+    let (sha256, size) = match copy_hashing(source, &incoming) {
+        Ok(result) => result,
+        Err(error) => {
+            let _ = fs::remove_file(&incoming);
+            return Err(error);
+        }
+    };
+
+    let target = content_path(store, &sha256);
+    if target.is_file() {
+        fs::remove_file(&incoming)?;
+        return Ok((sha256, size, false));
+    }
+
+    if let Some(parent) = target.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::rename(&incoming, &target)?;
+
+    Ok((sha256, size, true))
+}
+
+pub(crate) fn attachment_from_row(row: &SqliteRow) -> Attachment {
+    Attachment {
+        id: row.get("id"),
+        document_id: row.get("document_id"),
+        file_name: row.get("file_name"),
+        mime_type: row.get("mime_type"),
+        size: row.get("size"),
+        sha256: row.get("sha256"),
+        added_at: row.get("added_at"),
+    }
+}
+
+impl SqliteDocumentRepository {
+    fn attachment_paths(
+        &self,
+    ) -> Result<(Runtime, SqliteConnectOptions, PathBuf), AttachmentRepositoryError> {
+        let database_path = self
+            .database_path()
+            .map_err(|error| AttachmentRepositoryError::storage(error.to_string()))?;
+        let runtime = Runtime::new()
+            .map_err(|error| AttachmentRepositoryError::storage(error.to_string()))?;
+        let store = store_directory(&database_path);
+        let options = SqliteConnectOptions::new()
+            .filename(database_path)
+            .create_if_missing(true);
+
+        Ok((runtime, options, store))
+    }
+}
+
+impl AttachmentRepository for SqliteDocumentRepository {
+    fn add_attachment(
+        &self,
+        document_id: i64,
+        source: &Path,
+        file_name: &str,
+        mime_type: &str,
+    ) -> Result<Attachment, AttachmentRepositoryError> {
+        let (runtime, options, store) = self.attachment_paths()?;
+
+        let (sha256, size, created) = store_content(&store, source)
+            .map_err(|error| AttachmentRepositoryError::storage(error.to_string()))?;
+
+        let result = runtime.block_on(async {
+            let pool = SqlitePool::connect_with(options)
+                .await
+                .map_err(|error| AttachmentRepositoryError::storage(error.to_string()))?;
+
+            let row = sqlx::query(
+                r#"INSERT INTO attachment (document_id, file_name, mime_type, size, sha256)
+                   VALUES (?1, ?2, ?3, ?4, ?5)
+                   RETURNING *"#,
+            )
+            .bind(document_id)
+            .bind(file_name)
+            .bind(mime_type)
+            .bind(size)
+            .bind(&sha256)
+            .fetch_one(&pool)
+            .await
+            .map_err(|error| AttachmentRepositoryError::storage(error.to_string()))?;
+
+            pool.close().await;
+
+            Ok::<Attachment, AttachmentRepositoryError>(attachment_from_row(&row))
+        });
+
+        // Content stored for a row that was never written is not referenced by anything.
+        if result.is_err() && created {
+            let _ = fs::remove_file(content_path(&store, &sha256));
+        }
+
+        result
+    }
+
+    fn find_attachment(&self, id: i64) -> Result<Option<Attachment>, AttachmentRepositoryError> {
+        let (runtime, options, _) = self.attachment_paths()?;
+
+        runtime.block_on(async move {
+            let pool = SqlitePool::connect_with(options)
+                .await
+                .map_err(|error| AttachmentRepositoryError::storage(error.to_string()))?;
+
+            let row = sqlx::query("SELECT * FROM attachment WHERE id = ?1")
+                .bind(id)
+                .fetch_optional(&pool)
+                .await
+                .map_err(|error| AttachmentRepositoryError::storage(error.to_string()))?;
+
+            pool.close().await;
+
+            Ok::<Option<Attachment>, AttachmentRepositoryError>(
+                row.as_ref().map(attachment_from_row),
+            )
+        })
+    }
+
+    fn attachments(&self, document_id: i64) -> Result<Vec<Attachment>, AttachmentRepositoryError> {
+        let (runtime, options, _) = self.attachment_paths()?;
+
+        runtime.block_on(async move {
+            let pool = SqlitePool::connect_with(options)
+                .await
+                .map_err(|error| AttachmentRepositoryError::storage(error.to_string()))?;
+
+            let rows = sqlx::query("SELECT * FROM attachment WHERE document_id = ?1 ORDER BY id")
+                .bind(document_id)
+                .fetch_all(&pool)
+                .await
+                .map_err(|error| AttachmentRepositoryError::storage(error.to_string()))?;
+
+            pool.close().await;
+
+            Ok::<Vec<Attachment>, AttachmentRepositoryError>(
+                rows.iter().map(attachment_from_row).collect(),
+            )
+        })
+    }
+
+    fn extract_attachment(
+        &self,
+        id: i64,
+        destination: &Path,
+    ) -> Result<(), AttachmentRepositoryError> {
+        let (_, _, store) = self.attachment_paths()?;
+        let attachment = self.find_attachment(id)?.ok_or_else(|| {
+            AttachmentRepositoryError::storage(format!("Attachment {id} not found"))
+        })?;
+
+        let source = content_path(&store, &attachment.sha256);
+        if !source.is_file() {
+            return Err(AttachmentRepositoryError::Missing(id));
+        }
+
+        // The content is written next to the destination and renamed into place only once its
+        // hash matches, so an existing file is never replaced with a partial or corrupted copy.
+        let file_name = destination
+            .file_name()
+            .ok_or_else(|| {
+                AttachmentRepositoryError::storage(format!(
+                    "Invalid destination {}",
+                    destination.display()
+                ))
+            })?
+            .to_string_lossy();
+        let incoming = destination.with_file_name(format!(
+            ".{file_name}.extracting-{}-{}",
+            std::process::id(),
+            INCOMING_COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+
+        let error = match copy_hashing(&source, &incoming) {
+            Ok((sha256, _)) if sha256 == attachment.sha256 => {
+                match fs::rename(&incoming, destination) {
+                    Ok(()) => return Ok(()),
+                    Err(error) => AttachmentRepositoryError::storage(error.to_string()),
+                }
+            }
+            Ok(_) => AttachmentRepositoryError::Corrupted(id),
+            Err(error) => AttachmentRepositoryError::storage(error.to_string()),
+        };
+
+        match fs::remove_file(&incoming) {
+            Ok(()) => Err(error),
+            Err(cleanup) if cleanup.kind() == io::ErrorKind::NotFound => Err(error),
+            Err(cleanup) => Err(AttachmentRepositoryError::storage(format!(
+                "{error}; the incomplete copy {} could not be removed: {cleanup}",
+                incoming.display()
+            ))),
+        }
+    }
+
+    fn remove_attachment(&self, id: i64) -> Result<bool, AttachmentRepositoryError> {
+        let (runtime, options, store) = self.attachment_paths()?;
+
+        let removed = runtime.block_on(async move {
+            let pool = SqlitePool::connect_with(options)
+                .await
+                .map_err(|error| AttachmentRepositoryError::storage(error.to_string()))?;
+
+            let mut transaction = pool
+                .begin()
+                .await
+                .map_err(|error| AttachmentRepositoryError::storage(error.to_string()))?;
+
+            let row = sqlx::query("DELETE FROM attachment WHERE id = ?1 RETURNING sha256")
+                .bind(id)
+                .fetch_optional(&mut *transaction)
+                .await
+                .map_err(|error| AttachmentRepositoryError::storage(error.to_string()))?;
+
+            let Some(row) = row else {
+                return Ok(None);
+            };
+            let sha256: String = row.get("sha256");
+
+            let remaining: i64 =
+                sqlx::query_scalar("SELECT COUNT(*) FROM attachment WHERE sha256 = ?1")
+                    .bind(&sha256)
+                    .fetch_one(&mut *transaction)
+                    .await
+                    .map_err(|error| AttachmentRepositoryError::storage(error.to_string()))?;
+
+            transaction
+                .commit()
+                .await
+                .map_err(|error| AttachmentRepositoryError::storage(error.to_string()))?;
+
+            pool.close().await;
+
+            Ok::<Option<(String, i64)>, AttachmentRepositoryError>(Some((sha256, remaining)))
+        })?;
+
+        match removed {
+            None => Ok(false),
+            Some((sha256, 0)) => {
+                match fs::remove_file(content_path(&store, &sha256)) {
+                    Ok(()) => {}
+                    Err(error) if error.kind() == io::ErrorKind::NotFound => {}
+                    Err(error) => {
+                        return Err(AttachmentRepositoryError::storage(error.to_string()));
+                    }
+                }
+                Ok(true)
+            }
+            Some(_) => Ok(true),
+        }
+    }
+}
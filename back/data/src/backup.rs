@@ -0,0 +1,517 @@
+use std::collections::BTreeSet;
+use std::ffi::CStr;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use libsqlite3_sys::{
+    SQLITE_BUSY, SQLITE_DONE, SQLITE_LOCKED, SQLITE_OK, sqlite3_backup_finish, sqlite3_backup_init,
+    sqlite3_backup_step, sqlite3_errmsg, sqlite3_errstr,
+};
+use serde::Serialize;
+use sqlx::{Connection, Row, SqliteConnection, sqlite::SqliteConnectOptions};
+use tokio::runtime::Runtime;
+
+use crate::{DEFAULT_DATABASE_FILE_NAME, MIGRATOR};
+
+// ---------------- Database Backups ----------------
+
+/// Directory next to the database file that holds the backups.
+const BACKUPS_DIRECTORY: &str = "backups";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BackupError {
+    Io(String),
+    Storage(String),
+    /// The file is not a database of this application.
+    NotABackup(PathBuf),
+    /// The backup holds migrations this version of the application does not know.
+    NewerSchema {
+        backup: i64,
+        supported: i64,
+    },
+}
+
+impl std::fmt::Display for BackupError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(message) => write!(f, "I/O error: {message}"),
+            Self::Storage(message) => f.write_str(message),
+            Self::NotABackup(path) => write!(f, "{} is not a database backup", path.display()),
+            Self::NewerSchema { backup, supported } => write!(
+                f,
+                "Backup schema version {backup} is newer than the supported version {supported}"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for BackupError {}
+
+impl From<sqlx::Error> for BackupError {
+    fn from(error: sqlx::Error) -> Self {
+        Self::Storage(error.to_string())
+    }
+}
+
+impl From<std::io::Error> for BackupError {
+    fn from(error: std::io::Error) -> Self {
+        Self::Io(error.to_string())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct BackupInfo {
+    pub path: PathBuf,
+    /// Local time the backup was taken, `YYYY-MM-DD HH:MM:SS`.
+    pub created_at: String,
+    pub size: u64,
+    /// Latest migration applied to the backed up database.
+    pub schema_version: i64,
+}
+
+/// How many backups are kept when rotating.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BackupPolicy {
+    /// The latest backup of each of this many most recent days is kept; older and intermediate
+    /// backups are deleted.
+    pub keep_daily: usize,
+}
+
+/// Online backups of the database file into `backups/` next to it, named
+/// `<database>-YYYYMMDD-HHMMSS.sqlite`. Attachment content is stored outside the database and
+/// is not part of the backups.
+pub struct SqliteBackups {
+    database_file_name: String,
+}
+
+impl Default for SqliteBackups {
+    fn default() -> Self {
+        Self {
+            database_file_name: DEFAULT_DATABASE_FILE_NAME.to_string(),
+        }
+    }
+}
+
+impl SqliteBackups {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_file_name<S: Into<String>>(file_name: S) -> Self {
+        Self {
+            database_file_name: file_name.into(),
+        }
+    }
+
+    fn database_path(&self) -> Result<PathBuf, BackupError> {
+        let executable = std::env::current_exe()?;
+        let directory = executable
+            .parent()
+            .ok_or_else(|| BackupError::Io("Failed to determine application directory".into()))?;
+
+        Ok(directory.join(&self.database_file_name))
+    }
+
+    fn backups_directory(database_path: &Path) -> PathBuf {
+        database_path
+            .parent()
+            .unwrap_or_else(|| Path::new("."))
+            .join(BACKUPS_DIRECTORY)
+    }
+
+    /// Prefix of the backup file names, the database file name without extension.
+    fn prefix(database_path: &Path) -> String {
+        let stem = database_path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        format!("{stem}-")
+    }
+
+    /// Copies the database into a new timestamped backup while it stays in use.
+    pub fn backup_now(&self) -> Result<BackupInfo, BackupError> {
+        let database_path = self.database_path()?;
+        let directory = Self::backups_directory(&database_path);
+        std::fs::create_dir_all(&directory)?;
+        let runtime = Runtime::new()?;
+
+        let path = runtime.block_on(async {
+            let mut connection = connect(&database_path).await?;
+            let timestamp: String =
+                sqlx::query("SELECT strftime('%Y%m%d-%H%M%S', 'now', 'localtime') AS timestamp")
+                    .fetch_one(&mut connection)
+                    .await?
+                    .get("timestamp");
+            connection.close().await?;
+
+            let prefix = Self::prefix(&database_path);
+            let mut path = directory.join(format!("{prefix}{timestamp}.sqlite"));
+            let mut attempt = 1;
+            while path.exists() {
+                attempt += 1;
+                path = directory.join(format!("{prefix}{timestamp}-{attempt}.sqlite"));
+            }
+
+            copy_database(&database_path, &path).await?;
+            Ok::<PathBuf, BackupError>(path)
+        })?;
+
+        backup_info(&runtime, &database_path, &path)
+    }
+
+    /// Takes a backup unless one was already taken today, then rotates the backups. Meant to be
+    /// called on start-up or by a timer of the application.
+    pub fn backup_daily(&self, policy: &BackupPolicy) -> Result<Option<BackupInfo>, BackupError> {
+        let database_path = self.database_path()?;
+        let runtime = Runtime::new()?;
+        let today: String = runtime.block_on(async {
+            let mut connection = connect(&database_path).await?;
+            let today = sqlx::query("SELECT strftime('%Y-%m-%d', 'now', 'localtime') AS today")
+                .fetch_one(&mut connection)
+                .await?
+                .get("today");
+            connection.close().await?;
+            Ok::<String, BackupError>(today)
+        })?;
+
+        let taken_today = self
+            .list()?
+            .iter()
+            .any(|backup| backup.created_at.starts_with(&today));
+        let backup = if taken_today {
+            None
+        } else {
+            Some(self.backup_now()?)
+        };
+
+        self.rotate(policy)?;
+        Ok(backup)
+    }
+
+    /// Deletes the backups the policy does not keep and returns their paths.
+    pub fn rotate(&self, policy: &BackupPolicy) -> Result<Vec<PathBuf>, BackupError> {
+        let database_path = self.database_path()?;
+        let mut files = backup_files(&database_path)?;
+        // Newest first: the first backup of a day is the one kept.
+        files.reverse();
+
+        let mut days = BTreeSet::new();
+        let mut removed = Vec::new();
+        for (path, stamp) in files {
+            let day = stamp[..8].to_string();
+            if days.contains(&day) || days.len() >= policy.keep_daily {
+                std::fs::remove_file(&path)?;
+                removed.push(path);
+            } else {
+                days.insert(day);
+            }
+        }
+
+        Ok(removed)
+    }
+
+    /// Backups from the oldest to the newest.
+    pub fn list(&self) -> Result<Vec<BackupInfo>, BackupError> {
+        let database_path = self.database_path()?;
+        let runtime = Runtime::new()?;
+
+        backup_files(&database_path)?
+            .into_iter()
+            .map(|(path, _)| backup_info(&runtime, &database_path, &path))
+            .collect()
+    }
+
+    /// Replaces the database with the backup. The current database is backed up first, and a
+    /// backup of an older schema is migrated afterwards; backups of a newer schema are refused.
+    pub fn restore(&self, backup: &Path) -> Result<(), BackupError> {
+        let database_path = self.database_path()?;
+        let runtime = Runtime::new()?;
+
+        let supported = MIGRATOR
+            .iter()
+            .map(|migration| migration.version)
+            .collect::<BTreeSet<_>>();
+        let applied = runtime.block_on(applied_migrations(backup))?;
+        if let Some(&unknown) = applied.iter().find(|version| !supported.contains(version)) {
+            return Err(BackupError::NewerSchema {
+                backup: applied.last().copied().unwrap_or(unknown),
+                supported: supported.last().copied().unwrap_or_default(),
+            });
+        }
+
+        if database_path.exists() {
+            self.backup_now()?;
+        }
+
+        runtime.block_on(async {
+            copy_database(backup, &database_path).await?;
+
+            let mut connection = connect(&database_path).await?;
+            MIGRATOR
+                .run(&mut connection)
+                .await
+                .map_err(|error| BackupError::Storage(error.to_string()))?;
+            connection.close().await?;
+            Ok(())
+        })
+    }
+}
+
+async fn connect(path: &Path) -> Result<SqliteConnection, BackupError> {
+    let options = SqliteConnectOptions::new()
+        .filename(path)
+        .create_if_missing(true);
+    Ok(SqliteConnection::connect_with(&options).await?)
+}
+
+/// Backup files of the database with the timestamp of their names, oldest first.
+fn backup_files(database_path: &Path) -> Result<Vec<(PathBuf, String)>, BackupError> {
+    let directory = SqliteBackups::backups_directory(database_path);
+    if !directory.exists() {
+        return Ok(Vec::new());
+    }
+
+    let prefix = SqliteBackups::prefix(database_path);
+    let mut files = Vec::new();
+    for entry in std::fs::read_dir(&directory)? {
+        let path = entry?.path();
+        let Some(stamp) = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .and_then(|name| name.strip_prefix(prefix.as_str()))
+            .and_then(|name| name.strip_suffix(".sqlite"))
+            .map(str::to_string)
+        else {
+            continue;
+        };
+        let valid = stamp.len() >= 15
+            && stamp.is_char_boundary(15)
+            && stamp[..15].char_indices().all(|(index, c)| {
+                if index == 8 {
+                    c == '-'
+                } else {
+                    c.is_ascii_digit()
+                }
+            });
+        if valid {
+            files.push((path, stamp));
+        }
+    }
+
+    files.sort_by(|(_, left), (_, right)| {
+        let order = |stamp: &str| {
+            let attempt = stamp
+                .get(16..)
+                .and_then(|attempt| attempt.parse::<u32>().ok())
+                .unwrap_or(1);
+            (stamp[..15].to_string(), attempt)
+        };
+        order(left).cmp(&order(right))
+    });
+    Ok(files)
+}
+
+fn backup_info(
+    runtime: &Runtime,
+    database_path: &Path,
+    path: &Path,
+) -> Result<BackupInfo, BackupError> {
+    let stamp = path
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .and_then(|stem| stem.strip_prefix(SqliteBackups::prefix(database_path).as_str()))
+        .unwrap_or_default();
+    let created_at = match stamp.get(..15) {
+        Some(stamp) => format!(
+            "{}-{}-{} {}:{}:{}",
+            &stamp[0..4],
+            &stamp[4..6],
+            &stamp[6..8],
+            &stamp[9..11],
+            &stamp[11..13],
+            &stamp[13..15]
+        ),
+        None => String::new(),
+    };
+    let schema_version = runtime
+        .block_on(applied_migrations(path))?
+        .last()
+        .copied()
+        .unwrap_or_default();
+
+    Ok(BackupInfo {
+        path: path.to_path_buf(),
+        created_at,
+        size: std::fs::metadata(path)?.len(),
+        schema_version,
+    })
+}
+
+/// Versions of the migrations applied to the database file, which must already exist.
+async fn applied_migrations(path: &Path) -> Result<Vec<i64>, BackupError> {
+    if !path.is_file() {
+        return Err(BackupError::NotABackup(path.to_path_buf()));
+    }
+    let options = SqliteConnectOptions::new().filename(path);
+    let mut connection = SqliteConnection::connect_with(&options)
+        .await
+        .map_err(|_| BackupError::NotABackup(path.to_path_buf()))?;
+
+    let versions =
+        sqlx::query("SELECT version FROM _sqlx_migrations WHERE success ORDER BY version")
+            .fetch_all(&mut connection)
+            .await
+            .map_err(|_| BackupError::NotABackup(path.to_path_buf()))?
+            .iter()
+            .map(|row| row.get("version"))
+            .collect();
+    connection.close().await?;
+
+    Ok(versions)
+}
+
+/// Copies the whole `source` database into `destination` with the SQLite online backup API,
+/// so the copy is consistent even while the source is being written to.
+async fn copy_database(source: &Path, destination: &Path) -> Result<(), BackupError> {
+    let mut source = connect(source).await?;
+    let mut destination = connect(destination).await?;
+
+    {
+        let mut source_handle = source.lock_handle().await?;
+        let mut destination_handle = destination.lock_handle().await?;
+        let source_db = source_handle.as_raw_handle().as_ptr();
+        let destination_db = destination_handle.as_raw_handle().as_ptr();
+
+        // SAFETY: Both handles are locked for the duration of the backup, so no other statement
+        // runs on these connections; the backup object is finished before they are released.
+        unsafe {
+            let backup = sqlite3_backup_init(
+                destination_db,
+                c"main".as_ptr(),
+                source_db,
+                c"main".as_ptr(),
+            );
+            if backup.is_null() {
+                let message = CStr::from_ptr(sqlite3_errmsg(destination_db));
+                return Err(BackupError::Storage(message.to_string_lossy().into_owned()));
+            }
+
+            let mut code = sqlite3_backup_step(backup, -1);
+            while code == SQLITE_BUSY || code == SQLITE_LOCKED {
+                std::thread::sleep(Duration::from_millis(50));
+                code = sqlite3_backup_step(backup, -1);
+            }
+            let finished = sqlite3_backup_finish(backup);
+
+            if code != SQLITE_DONE || finished != SQLITE_OK {
+                let code = if code != SQLITE_DONE { code } else { finished };
+                let message = CStr::from_ptr(sqlite3_errstr(code));
+                return Err(BackupError::Storage(message.to_string_lossy().into_owned()));
+            }
+        }
+    }
+
+    source.close().await?;
+    destination.close().await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SqliteOrganizationRepository;
+    use crate::tests::{TestDatabase, create_organization};
+    use sqlx::SqlitePool;
+    use zdnp_core::{ListOptions, OrganizationRepository};
+
+    #[test]
+    fn backups_are_listed_rotated_and_restored() {
+        let database = TestDatabase::new();
+        let backups = SqliteBackups::with_file_name(database.path.as_str());
+        let organizations = SqliteOrganizationRepository::with_file_name(database.path.as_str());
+        let latest = MIGRATOR.iter().map(|migration| migration.version).max();
+
+        create_organization(&database);
+        let first = backups.backup_now().expect("backup");
+        assert_eq!(Some(first.schema_version), latest);
+        create_organization(&database);
+        let second = backups.backup_now().expect("backup");
+        assert_eq!(backups.list(), Ok(vec![first.clone(), second.clone()]));
+
+        backups.restore(&first.path).expect("restore");
+        assert_eq!(
+            organizations.list(&ListOptions::default()).unwrap().len(),
+            1
+        );
+        let listed = backups.list().expect("list");
+        assert_eq!(listed.len(), 3, "the replaced database is backed up first");
+
+        let removed = backups
+            .rotate(&BackupPolicy { keep_daily: 1 })
+            .expect("rotate");
+        assert_eq!(removed, vec![second.path, first.path]);
+        assert_eq!(backups.list(), Ok(vec![listed[2].clone()]));
+        assert_eq!(
+            backups.backup_daily(&BackupPolicy { keep_daily: 1 }),
+            Ok(None)
+        );
+
+        let garbage = Path::new(&database.path).with_extension("txt");
+        std::fs::write(&garbage, "not a database").unwrap();
+        assert_eq!(
+            backups.restore(&garbage),
+            Err(BackupError::NotABackup(garbage.clone()))
+        );
+        assert_eq!(
+            organizations.list(&ListOptions::default()).unwrap().len(),
+            1
+        );
+    }
+
+    #[test]
+    fn backups_of_a_newer_schema_are_not_restored() {
+        let database = TestDatabase::new();
+        let backups = SqliteBackups::with_file_name(database.path.as_str());
+        let organizations = SqliteOrganizationRepository::with_file_name(database.path.as_str());
+        let latest = MIGRATOR
+            .iter()
+            .map(|migration| migration.version)
+            .max()
+            .unwrap();
+
+        let backup = backups.backup_now().expect("backup");
+        create_organization(&database);
+
+        let runtime = Runtime::new().unwrap();
+        runtime.block_on(async {
+            let pool = SqlitePool::connect_with(SqliteConnectOptions::new().filename(&backup.path))
+                .await
+                .unwrap();
+            sqlx::query(
+                r#"INSERT INTO _sqlx_migrations (version, description, success, checksum, execution_time)
+                   VALUES (99991231000000, 'future', TRUE, X'00', 0)"#,
+            )
+            .execute(&pool)
+            .await
+            .unwrap();
+            pool.close().await;
+        });
+        assert_eq!(
+            backups.restore(&backup.path),
+            Err(BackupError::NewerSchema {
+                backup: 99991231000000,
+                supported: latest,
+            })
+        );
+        assert_eq!(
+            organizations.list(&ListOptions::default()).unwrap().len(),
+            1,
+            "the current database is left in place"
+        );
+        assert_eq!(
+            backups.list().expect("list").len(),
+            1,
+            "the current database is not backed up"
+        );
+    }
+}
@@ -0,0 +1,175 @@
+use sqlx::{Row, SqlitePool, sqlite::SqliteConnectOptions};
+use tokio::runtime::Runtime;
+use zdnp_core::{
+    ConstructionObject, ConstructionObjectDto, ConstructionObjectRepository,
+    ConstructionObjectRepositoryError, WorkType,
+};
+
+use crate::SqliteDocumentRepository;
+
+// ---------------- Construction Object Data Repository ----------------
+impl ConstructionObjectRepository for SqliteDocumentRepository {
+    fn save(
+        &self,
+        document_id: i64,
+        dto: &ConstructionObjectDto,
+    ) -> Result<Option<i64>, ConstructionObjectRepositoryError> {
+        let database_path = self
+            .database_path()
+            .map_err(|error| ConstructionObjectRepositoryError::storage(error.to_string()))?;
+        let runtime = Runtime::new()
+            .map_err(|error| ConstructionObjectRepositoryError::storage(error.to_string()))?;
+
+        runtime.block_on(async move {
+            let options = SqliteConnectOptions::new()
+                .filename(&database_path)
+                .create_if_missing(true);
+
+            let pool = SqlitePool::connect_with(options)
+                .await
+                .map_err(|error| ConstructionObjectRepositoryError::storage(error.to_string()))?;
+
+            let name = dto
+                .name
+                .as_deref()
+                .ok_or_else(|| ConstructionObjectRepositoryError::storage("Name is required"))?;
+            let purpose_code = dto.purpose_code.as_deref().ok_or_else(|| {
+                ConstructionObjectRepositoryError::storage("Purpose code is required")
+            })?;
+            let work_type = dto.work_type.ok_or_else(|| {
+                ConstructionObjectRepositoryError::storage("Work type is required")
+            })?;
+
+            let mut transaction = pool
+                .begin()
+                .await
+                .map_err(|error| ConstructionObjectRepositoryError::storage(error.to_string()))?;
+
+            let row = sqlx::query(
+                r#"INSERT INTO construction_object (
+                    document_id, name, purpose_code, address_id, work_type
+                )
+                SELECT ?1, ?2, ?3, ?4, ?5
+                WHERE EXISTS (SELECT 1 FROM document WHERE id = ?1 AND status = 'draft')
+                ON CONFLICT (document_id) DO UPDATE SET
+                    name = excluded.name,
+                    purpose_code = excluded.purpose_code,
+                    address_id = excluded.address_id,
+                    work_type = excluded.work_type
+                RETURNING id"#,
+            )
+            .bind(document_id)
+            .bind(name)
+            .bind(purpose_code)
+            .bind(dto.address_id)
+            .bind(work_type.as_str())
+            .fetch_optional(&mut *transaction)
+            .await
+            .map_err(|error| ConstructionObjectRepositoryError::storage(error.to_string()))?;
+            let Some(row) = row else {
+                return Ok(None);
+            };
+            let id: i64 = row.get("id");
+
+            sqlx::query(
+                "DELETE FROM construction_object_land_plot WHERE construction_object_id = ?1",
+            )
+            .bind(id)
+            .execute(&mut *transaction)
+            .await
+            .map_err(|error| ConstructionObjectRepositoryError::storage(error.to_string()))?;
+
+            for (position, cadastral_number) in dto.cadastral_numbers.iter().enumerate() {
+                sqlx::query(
+                    r#"INSERT INTO construction_object_land_plot (
+                        construction_object_id, cadastral_number, position
+                    ) VALUES (?1, ?2, ?3)"#,
+                )
+                .bind(id)
+                .bind(cadastral_number.as_str())
+                .bind(position as i64)
+                .execute(&mut *transaction)
+                .await
+                .map_err(|error| ConstructionObjectRepositoryError::storage(error.to_string()))?;
+            }
+
+            transaction
+                .commit()
+                .await
+                .map_err(|error| ConstructionObjectRepositoryError::storage(error.to_string()))?;
+
+            pool.close().await;
+
+            Ok::<Option<i64>, ConstructionObjectRepositoryError>(Some(id))
+        })
+    }
+
+    fn find_by_document(
+        &self,
+        document_id: i64,
+    ) -> Result<Option<ConstructionObject>, ConstructionObjectRepositoryError> {
+        let database_path = self
+            .database_path()
+            .map_err(|error| ConstructionObjectRepositoryError::storage(error.to_string()))?;
+        let runtime = Runtime::new()
+            .map_err(|error| ConstructionObjectRepositoryError::storage(error.to_string()))?;
+
+        runtime.block_on(async move {
+            let options = SqliteConnectOptions::new()
+                .filename(&database_path)
+                .create_if_missing(true);
+
+            let pool = SqlitePool::connect_with(options)
+                .await
+                .map_err(|error| ConstructionObjectRepositoryError::storage(error.to_string()))?;
+
+            let row = sqlx::query(
+                r#"SELECT id, document_id, name, purpose_code, address_id, work_type
+                   FROM construction_object WHERE document_id = ?1"#,
+            )
+            .bind(document_id)
+            .fetch_optional(&pool)
+            .await
+            .map_err(|error| ConstructionObjectRepositoryError::storage(error.to_string()))?;
+
+            let Some(row) = row else {
+                pool.close().await;
+                return Ok(None);
+            };
+
+            let id: i64 = row.get("id");
+            let work_type: String = row.get("work_type");
+            let work_type = WorkType::parse(&work_type).ok_or_else(|| {
+                ConstructionObjectRepositoryError::storage(format!(
+                    "Unknown work type: {work_type}"
+                ))
+            })?;
+
+            let cadastral_numbers = sqlx::query(
+                r#"SELECT cadastral_number FROM construction_object_land_plot
+                   WHERE construction_object_id = ?1 ORDER BY position"#,
+            )
+            .bind(id)
+            .fetch_all(&pool)
+            .await
+            .map_err(|error| ConstructionObjectRepositoryError::storage(error.to_string()))?
+            .iter()
+            .map(|row| row.get("cadastral_number"))
+            .collect();
+
+            pool.close().await;
+
+            Ok::<Option<ConstructionObject>, ConstructionObjectRepositoryError>(Some(
+                ConstructionObject {
+                    id,
+                    document_id: row.get("document_id"),
+                    name: row.get("name"),
+                    purpose_code: row.get("purpose_code"),
+                    address_id: row.get("address_id"),
+                    cadastral_numbers,
+                    work_type,
+                },
+            ))
+        })
+    }
+}
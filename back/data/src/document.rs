@@ -0,0 +1,1030 @@
+use std::path::PathBuf;
+
+use sqlx::{Row, SqliteConnection, SqlitePool, sqlite::SqliteConnectOptions, sqlite::SqliteRow};
+use tokio::runtime::Runtime;
+use zdnp_core::{
+    Document, DocumentAuthor, DocumentDeveloper, DocumentDto, DocumentNumbering,
+    DocumentRepository, DocumentRepositoryError, DocumentSection, DocumentSnapshot, DocumentStatus,
+    DocumentType, EntityRef, NumberingScheme, ReferenceEntity, ReferenceRepository,
+    ReferenceRepositoryError, ReferenceState,
+};
+
+use crate::source_document::source_document_from_row;
+use crate::{
+    DEFAULT_DATABASE_FILE_NAME, address_from_row, entrepreneur_from_row, organization_from_row,
+    person_from_row,
+};
+
+// ---------------- Reference Data Lookups ----------------
+pub struct SqliteReferenceRepository {
+    database_file_name: String,
+}
+
+impl SqliteReferenceRepository {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_file_name<S: Into<String>>(file_name: S) -> Self {
+        Self {
+            database_file_name: file_name.into(),
+        }
+    }
+
+    pub(crate) fn database_path(&self) -> Result<PathBuf, ReferenceRepositoryError> {
+        let executable = std::env::current_exe()
+            .map_err(|error| ReferenceRepositoryError::storage(error.to_string()))?;
+        let directory = executable.parent().ok_or_else(|| {
+            ReferenceRepositoryError::storage("Failed to determine application directory")
+        })?;
+
+        Ok(directory.join(&self.database_file_name))
+    }
+}
+
+impl Default for SqliteReferenceRepository {
+    fn default() -> Self {
+        Self {
+            database_file_name: DEFAULT_DATABASE_FILE_NAME.to_string(),
+        }
+    }
+}
+
+impl ReferenceRepository for SqliteReferenceRepository {
+    fn state(&self, reference: EntityRef) -> Result<ReferenceState, ReferenceRepositoryError> {
+        let database_path = self.database_path()?;
+        let runtime =
+            Runtime::new().map_err(|error| ReferenceRepositoryError::storage(error.to_string()))?;
+
+        let (table, id) = match reference {
+            EntityRef::Address(id) => ("address", id),
+            EntityRef::Organization(id) => ("organization", id),
+            EntityRef::Entrepreneur(id) => ("entrepreneur", id),
+            EntityRef::Person(id) => ("person", id),
+            EntityRef::SourceDocument(id) => ("source_document", id),
+        };
+
+        runtime.block_on(async move {
+            let options = SqliteConnectOptions::new()
+                .filename(&database_path)
+                .create_if_missing(true);
+
+            let pool = SqlitePool::connect_with(options)
+                .await
+                .map_err(|error| ReferenceRepositoryError::storage(error.to_string()))?;
+
+            let row = sqlx::query(&format!("SELECT archived_at FROM {table} WHERE id = ?1"))
+                .bind(id)
+                .fetch_optional(&pool)
+                .await
+                .map_err(|error| ReferenceRepositoryError::storage(error.to_string()))?;
+
+            pool.close().await;
+
+            let state = match row {
+                None => ReferenceState::Missing,
+                Some(row) => {
+                    let archived_at: Option<String> = row.get("archived_at");
+                    if archived_at.is_some() {
+                        ReferenceState::Archived
+                    } else {
+                        ReferenceState::Active
+                    }
+                }
+            };
+
+            Ok::<ReferenceState, ReferenceRepositoryError>(state)
+        })
+    }
+
+    fn load(
+        &self,
+        reference: EntityRef,
+    ) -> Result<Option<ReferenceEntity>, ReferenceRepositoryError> {
+        let database_path = self.database_path()?;
+        let runtime =
+            Runtime::new().map_err(|error| ReferenceRepositoryError::storage(error.to_string()))?;
+
+        let (table, id) = match reference {
+            EntityRef::Address(id) => ("address", id),
+            EntityRef::Organization(id) => ("organization", id),
+            EntityRef::Entrepreneur(id) => ("entrepreneur", id),
+            EntityRef::Person(id) => ("person", id),
+            EntityRef::SourceDocument(id) => ("source_document", id),
+        };
+
+        runtime.block_on(async move {
+            let options = SqliteConnectOptions::new()
+                .filename(&database_path)
+                .create_if_missing(true);
+
+            let pool = SqlitePool::connect_with(options)
+                .await
+                .map_err(|error| ReferenceRepositoryError::storage(error.to_string()))?;
+
+            let row = sqlx::query(&format!("SELECT * FROM {table} WHERE id = ?1"))
+                .bind(id)
+                .fetch_optional(&pool)
+                .await
+                .map_err(|error| ReferenceRepositoryError::storage(error.to_string()))?;
+
+            pool.close().await;
+
+            let Some(row) = row else {
+                return Ok(None);
+            };
+            let entity = match reference {
+                EntityRef::Address(_) => ReferenceEntity::Address(address_from_row(&row)),
+                EntityRef::Organization(_) => {
+                    ReferenceEntity::Organization(organization_from_row(&row))
+                }
+                EntityRef::Entrepreneur(_) => {
+                    ReferenceEntity::Entrepreneur(entrepreneur_from_row(&row))
+                }
+                EntityRef::Person(_) => ReferenceEntity::Person(person_from_row(&row)),
+                EntityRef::SourceDocument(_) => ReferenceEntity::SourceDocument(
+                    source_document_from_row(&row).map_err(ReferenceRepositoryError::storage)?,
+                ),
+            };
+
+            Ok::<Option<ReferenceEntity>, ReferenceRepositoryError>(Some(entity))
+        })
+    }
+
+    fn find_by_uid(&self, uid: &str) -> Result<Option<EntityRef>, ReferenceRepositoryError> {
+        let database_path = self.database_path()?;
+        let runtime =
+            Runtime::new().map_err(|error| ReferenceRepositoryError::storage(error.to_string()))?;
+
+        runtime.block_on(async move {
+            let options = SqliteConnectOptions::new()
+                .filename(&database_path)
+                .create_if_missing(true);
+
+            let pool = SqlitePool::connect_with(options)
+                .await
+                .map_err(|error| ReferenceRepositoryError::storage(error.to_string()))?;
+
+            let row = sqlx::query(
+                r#"SELECT 'address' AS kind, id FROM address WHERE uid = ?1
+                   UNION ALL SELECT 'organization', id FROM organization WHERE uid = ?1
+                   UNION ALL SELECT 'entrepreneur', id FROM entrepreneur WHERE uid = ?1
+                   UNION ALL SELECT 'person', id FROM person WHERE uid = ?1
+                   UNION ALL SELECT 'source_document', id FROM source_document WHERE uid = ?1"#,
+            )
+            .bind(uid)
+            .fetch_optional(&pool)
+            .await
+            .map_err(|error| ReferenceRepositoryError::storage(error.to_string()))?;
+
+            pool.close().await;
+
+            let reference = row.map(|row| {
+                let id: i64 = row.get("id");
+                match row.get::<&str, _>("kind") {
+                    "address" => EntityRef::Address(id),
+                    "organization" => EntityRef::Organization(id),
+                    "entrepreneur" => EntityRef::Entrepreneur(id),
+                    "person" => EntityRef::Person(id),
+                    _ => EntityRef::SourceDocument(id),
+                }
+            });
+
+            Ok::<Option<EntityRef>, ReferenceRepositoryError>(reference)
+        })
+    }
+}
+
+// ---------------- Document Data Repository ----------------
+pub struct SqliteDocumentRepository {
+    database_file_name: String,
+}
+
+impl SqliteDocumentRepository {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_file_name<S: Into<String>>(file_name: S) -> Self {
+        Self {
+            database_file_name: file_name.into(),
+        }
+    }
+
+    pub(crate) fn database_path(&self) -> Result<PathBuf, DocumentRepositoryError> {
+        let executable = std::env::current_exe()
+            .map_err(|error| DocumentRepositoryError::storage(error.to_string()))?;
+        let directory = executable.parent().ok_or_else(|| {
+            DocumentRepositoryError::storage("Failed to determine application directory")
+        })?;
+
+        Ok(directory.join(&self.database_file_name))
+    }
+}
+
+impl Default for SqliteDocumentRepository {
+    fn default() -> Self {
+        Self {
+            database_file_name: DEFAULT_DATABASE_FILE_NAME.to_string(),
+        }
+    }
+}
+
+pub(crate) const DOCUMENT_SELECT: &str = r#"SELECT d.id, d.name, d.number, d.date, d.change_mark, d.uid, d.type_id,
+          d.type_note, d.previous_revision_id, d.is_template, d.status, d.submitted_at,
+          d.approved_at, d.issued_at, d.superseded_at, d.cancelled_at, a.type AS author_type,
+          oa.organization_id, ea.entrepreneur_id, pa.person_id, na.name AS informal_name,
+          d.developer_id, od.organization_id AS developer_organization_id,
+          pd.person_id AS developer_person_id
+   FROM document d
+   JOIN authors a ON a.id = d.author_id
+   LEFT JOIN organization_authors oa ON oa.author_id = a.id
+   LEFT JOIN entrepreneur_authors ea ON ea.author_id = a.id
+   LEFT JOIN person_authors pa ON pa.author_id = a.id
+   LEFT JOIN neformal_author na ON na.author_id = a.id
+   LEFT JOIN organization_developer od ON od.developer_id = d.developer_id
+   LEFT JOIN person_developer pd ON pd.developer_id = d.developer_id"#;
+
+pub(crate) fn document_from_row(row: &SqliteRow) -> Result<Document, DocumentRepositoryError> {
+    let author_type: i64 = row.get("author_type");
+    let author = match author_type {
+        1 => row
+            .get::<Option<i64>, _>("organization_id")
+            .map(|id| DocumentAuthor::Organization { id }),
+        2 => row
+            .get::<Option<i64>, _>("entrepreneur_id")
+            .map(|id| DocumentAuthor::Entrepreneur { id }),
+        3 => row
+            .get::<Option<i64>, _>("person_id")
+            .map(|id| DocumentAuthor::Person { id }),
+        4 => row
+            .get::<Option<String>, _>("informal_name")
+            .map(|name| DocumentAuthor::Informal { name }),
+        _ => None,
+    }
+    .ok_or_else(|| DocumentRepositoryError::storage("Document author is inconsistent"))?;
+
+    let developer = match row.get::<Option<i64>, _>("developer_id") {
+        None => None,
+        Some(_) => Some(
+            match (
+                row.get::<Option<i64>, _>("developer_organization_id"),
+                row.get::<Option<i64>, _>("developer_person_id"),
+            ) {
+                (Some(id), None) => DocumentDeveloper::Organization { id },
+                (None, Some(id)) => DocumentDeveloper::Person { id },
+                _ => {
+                    return Err(DocumentRepositoryError::storage(
+                        "Document developer is inconsistent",
+                    ));
+                }
+            },
+        ),
+    };
+
+    let document_type = match row.get::<Option<i64>, _>("type_id") {
+        None => None,
+        Some(id) => Some(DocumentType::from_id(id).ok_or_else(|| {
+            DocumentRepositoryError::storage(format!("Unknown document type: {id}"))
+        })?),
+    };
+
+    let status: String = row.get("status");
+    let status = DocumentStatus::parse(&status).ok_or_else(|| {
+        DocumentRepositoryError::storage(format!("Unknown document status: {status}"))
+    })?;
+
+    Ok(Document {
+        id: row.get("id"),
+        name: row.get("name"),
+        number: row.get("number"),
+        date: row.get("date"),
+        change_mark: row.get("change_mark"),
+        uid: row.get("uid"),
+        document_type,
+        type_note: row.get("type_note"),
+        author,
+        developer,
+        previous_revision_id: row.get("previous_revision_id"),
+        is_template: row.get::<i64, _>("is_template") != 0,
+        status,
+        submitted_at: row.get("submitted_at"),
+        approved_at: row.get("approved_at"),
+        issued_at: row.get("issued_at"),
+        superseded_at: row.get("superseded_at"),
+        cancelled_at: row.get("cancelled_at"),
+    })
+}
+
+/// Takes the next value of the stored scheme's counter for the year. Runs inside the transaction
+/// that creates the document, so a failed insert releases the number again.
+async fn allocate_number(
+    connection: &mut SqliteConnection,
+    numbering: &DocumentNumbering,
+) -> Result<String, DocumentRepositoryError> {
+    let width: i64 = sqlx::query_scalar("SELECT width FROM numbering_scheme WHERE prefix = ?1")
+        .bind(numbering.prefix.as_str())
+        .fetch_optional(&mut *connection)
+        .await
+        .map_err(|error| DocumentRepositoryError::storage(error.to_string()))?
+        .ok_or_else(|| {
+            DocumentRepositoryError::storage(format!(
+                "unknown numbering scheme \"{}\"",
+                numbering.prefix
+            ))
+        })?;
+
+    let row = sqlx::query(
+        r#"INSERT INTO document_number_sequence (prefix, year, last_value) VALUES (?1, ?2, 1)
+           ON CONFLICT (prefix, year) DO UPDATE SET last_value = last_value + 1
+           RETURNING last_value"#,
+    )
+    .bind(numbering.prefix.as_str())
+    .bind(numbering.year)
+    .fetch_one(&mut *connection)
+    .await
+    .map_err(|error| DocumentRepositoryError::storage(error.to_string()))?;
+
+    let scheme = NumberingScheme {
+        prefix: numbering.prefix.clone(),
+        width: width as usize,
+    };
+    Ok(scheme.format(numbering.year, row.get("last_value")))
+}
+
+pub(crate) async fn insert_author(
+    connection: &mut SqliteConnection,
+    author: &DocumentAuthor,
+) -> Result<i64, DocumentRepositoryError> {
+    let author_id = sqlx::query("INSERT INTO authors (type) VALUES (?1)")
+        .bind(author.type_id())
+        .execute(&mut *connection)
+        .await
+        .map_err(|error| DocumentRepositoryError::storage(error.to_string()))?
+        .last_insert_rowid();
+
+    let link = match author {
+        DocumentAuthor::Organization { id } => sqlx::query(
+            "INSERT INTO organization_authors (organization_id, author_id) VALUES (?1, ?2)",
+        )
+        .bind(*id),
+        DocumentAuthor::Entrepreneur { id } => sqlx::query(
+            "INSERT INTO entrepreneur_authors (entrepreneur_id, author_id) VALUES (?1, ?2)",
+        )
+        .bind(*id),
+        DocumentAuthor::Person { id } => {
+            sqlx::query("INSERT INTO person_authors (person_id, author_id) VALUES (?1, ?2)")
+                .bind(*id)
+        }
+        DocumentAuthor::Informal { name } => {
+            sqlx::query("INSERT INTO neformal_author (name, author_id) VALUES (?1, ?2)")
+                .bind(name.as_str())
+        }
+    };
+
+    link.bind(author_id)
+        .execute(&mut *connection)
+        .await
+        .map_err(|error| DocumentRepositoryError::storage(error.to_string()))?;
+
+    Ok(author_id)
+}
+
+async fn delete_author(
+    connection: &mut SqliteConnection,
+    author_id: i64,
+) -> Result<(), DocumentRepositoryError> {
+    for table in [
+        "organization_authors",
+        "entrepreneur_authors",
+        "person_authors",
+        "neformal_author",
+        "authors",
+    ] {
+        let column = if table == "authors" {
+            "id"
+        } else {
+            "author_id"
+        };
+        sqlx::query(&format!("DELETE FROM {table} WHERE {column} = ?1"))
+            .bind(author_id)
+            .execute(&mut *connection)
+            .await
+            .map_err(|error| DocumentRepositoryError::storage(error.to_string()))?;
+    }
+
+    Ok(())
+}
+
+pub(crate) async fn insert_developer(
+    connection: &mut SqliteConnection,
+    developer: Option<DocumentDeveloper>,
+) -> Result<Option<i64>, DocumentRepositoryError> {
+    let Some(developer) = developer else {
+        return Ok(None);
+    };
+
+    let developer_id = sqlx::query("INSERT INTO developer DEFAULT VALUES")
+        .execute(&mut *connection)
+        .await
+        .map_err(|error| DocumentRepositoryError::storage(error.to_string()))?
+        .last_insert_rowid();
+
+    let link = match developer {
+        DocumentDeveloper::Organization { id } => sqlx::query(
+            "INSERT INTO organization_developer (organization_id, developer_id) VALUES (?1, ?2)",
+        )
+        .bind(id),
+        DocumentDeveloper::Person { id } => {
+            sqlx::query("INSERT INTO person_developer (person_id, developer_id) VALUES (?1, ?2)")
+                .bind(id)
+        }
+    };
+
+    link.bind(developer_id)
+        .execute(&mut *connection)
+        .await
+        .map_err(|error| DocumentRepositoryError::storage(error.to_string()))?;
+
+    Ok(Some(developer_id))
+}
+
+async fn delete_developer(
+    connection: &mut SqliteConnection,
+    developer_id: Option<i64>,
+) -> Result<(), DocumentRepositoryError> {
+    let Some(developer_id) = developer_id else {
+        return Ok(());
+    };
+
+    for table in ["organization_developer", "person_developer", "developer"] {
+        let column = if table == "developer" {
+            "id"
+        } else {
+            "developer_id"
+        };
+        sqlx::query(&format!("DELETE FROM {table} WHERE {column} = ?1"))
+            .bind(developer_id)
+            .execute(&mut *connection)
+            .await
+            .map_err(|error| DocumentRepositoryError::storage(error.to_string()))?;
+    }
+
+    Ok(())
+}
+
+impl DocumentRepository for SqliteDocumentRepository {
+    fn create(&self, dto: &DocumentDto) -> Result<i64, DocumentRepositoryError> {
+        let database_path = self.database_path()?;
+        let runtime =
+            Runtime::new().map_err(|error| DocumentRepositoryError::storage(error.to_string()))?;
+
+        runtime.block_on(async move {
+            let options = SqliteConnectOptions::new()
+                .filename(&database_path)
+                .create_if_missing(true);
+
+            let pool = SqlitePool::connect_with(options)
+                .await
+                .map_err(|error| DocumentRepositoryError::storage(error.to_string()))?;
+
+            let name = dto
+                .name
+                .as_deref()
+                .ok_or_else(|| DocumentRepositoryError::storage("Name is required"))?;
+            let uid = dto
+                .uid
+                .as_deref()
+                .ok_or_else(|| DocumentRepositoryError::storage("UID is required"))?;
+            let author = dto
+                .author
+                .as_ref()
+                .ok_or_else(|| DocumentRepositoryError::storage("Author is required"))?;
+
+            let mut transaction = pool
+                .begin()
+                .await
+                .map_err(|error| DocumentRepositoryError::storage(error.to_string()))?;
+
+            let number = match (&dto.number, &dto.numbering) {
+                (None, Some(numbering)) => {
+                    Some(allocate_number(&mut transaction, numbering).await?)
+                }
+                (number, _) => number.clone(),
+            };
+            let author_id = insert_author(&mut transaction, author).await?;
+            let developer_id = insert_developer(&mut transaction, dto.developer).await?;
+
+            let result = sqlx::query(
+                r#"INSERT INTO document (
+                    name, number, date, change_mark, uid, author_id, type_id, previous_revision_id,
+                    developer_id
+                ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)"#,
+            )
+            .bind(name)
+            .bind(number)
+            .bind(dto.date.as_deref())
+            .bind(dto.change_mark.as_deref())
+            .bind(uid)
+            .bind(author_id)
+            .bind(dto.document_type.map(DocumentType::id))
+            .bind(dto.previous_revision_id)
+            .bind(developer_id)
+            .execute(&mut *transaction)
+            .await
+            .map_err(|error| DocumentRepositoryError::storage(error.to_string()))?;
+
+            transaction
+                .commit()
+                .await
+                .map_err(|error| DocumentRepositoryError::storage(error.to_string()))?;
+
+            let id = result.last_insert_rowid();
+
+            pool.close().await;
+
+            Ok::<i64, DocumentRepositoryError>(id)
+        })
+    }
+
+    fn update(&self, id: i64, dto: &DocumentDto) -> Result<bool, DocumentRepositoryError> {
+        let database_path = self.database_path()?;
+        let runtime =
+            Runtime::new().map_err(|error| DocumentRepositoryError::storage(error.to_string()))?;
+
+        runtime.block_on(async move {
+            let options = SqliteConnectOptions::new()
+                .filename(&database_path)
+                .create_if_missing(true);
+
+            let pool = SqlitePool::connect_with(options)
+                .await
+                .map_err(|error| DocumentRepositoryError::storage(error.to_string()))?;
+
+            let name = dto
+                .name
+                .as_deref()
+                .ok_or_else(|| DocumentRepositoryError::storage("Name is required"))?;
+            let author = dto
+                .author
+                .as_ref()
+                .ok_or_else(|| DocumentRepositoryError::storage("Author is required"))?;
+
+            let mut transaction = pool
+                .begin()
+                .await
+                .map_err(|error| DocumentRepositoryError::storage(error.to_string()))?;
+
+            let Some(row) = sqlx::query(
+                "SELECT author_id, developer_id FROM document WHERE id = ?1 AND status = 'draft'",
+            )
+            .bind(id)
+            .fetch_optional(&mut *transaction)
+            .await
+            .map_err(|error| DocumentRepositoryError::storage(error.to_string()))?
+            else {
+                return Ok(false);
+            };
+            let old_author_id: i64 = row.get("author_id");
+            let old_developer_id: Option<i64> = row.get("developer_id");
+
+            let author_id = insert_author(&mut transaction, author).await?;
+            let developer_id = insert_developer(&mut transaction, dto.developer).await?;
+
+            sqlx::query(
+                r#"UPDATE document
+                   SET name = ?1, number = ?2, date = ?3, type_id = ?4, author_id = ?5,
+                       developer_id = ?6
+                   WHERE id = ?7"#,
+            )
+            .bind(name)
+            .bind(dto.number.as_deref())
+            .bind(dto.date.as_deref())
+            .bind(dto.document_type.map(DocumentType::id))
+            .bind(author_id)
+            .bind(developer_id)
+            .bind(id)
+            .execute(&mut *transaction)
+            .await
+            .map_err(|error| DocumentRepositoryError::storage(error.to_string()))?;
+
+            delete_author(&mut transaction, old_author_id).await?;
+            delete_developer(&mut transaction, old_developer_id).await?;
+
+            transaction
+                .commit()
+                .await
+                .map_err(|error| DocumentRepositoryError::storage(error.to_string()))?;
+
+            pool.close().await;
+
+            Ok::<bool, DocumentRepositoryError>(true)
+        })
+    }
+
+    fn delete_draft(&self, id: i64) -> Result<bool, DocumentRepositoryError> {
+        let database_path = self.database_path()?;
+        let runtime =
+            Runtime::new().map_err(|error| DocumentRepositoryError::storage(error.to_string()))?;
+
+        runtime.block_on(async move {
+            let options = SqliteConnectOptions::new()
+                .filename(&database_path)
+                .create_if_missing(true);
+
+            let pool = SqlitePool::connect_with(options)
+                .await
+                .map_err(|error| DocumentRepositoryError::storage(error.to_string()))?;
+
+            let mut transaction = pool
+                .begin()
+                .await
+                .map_err(|error| DocumentRepositoryError::storage(error.to_string()))?;
+
+            // Sections go with the document through ON DELETE CASCADE; the author and developer
+            // rows belong to the document alone.
+            let Some(row) = sqlx::query(
+                r#"DELETE FROM document WHERE id = ?1 AND status = 'draft'
+                   RETURNING author_id, developer_id"#,
+            )
+            .bind(id)
+            .fetch_optional(&mut *transaction)
+            .await
+            .map_err(|error| DocumentRepositoryError::storage(error.to_string()))?
+            else {
+                return Ok(false);
+            };
+
+            delete_author(&mut transaction, row.get("author_id")).await?;
+            delete_developer(&mut transaction, row.get("developer_id")).await?;
+
+            transaction
+                .commit()
+                .await
+                .map_err(|error| DocumentRepositoryError::storage(error.to_string()))?;
+
+            pool.close().await;
+
+            Ok::<bool, DocumentRepositoryError>(true)
+        })
+    }
+
+    fn find(&self, id: i64) -> Result<Option<Document>, DocumentRepositoryError> {
+        let database_path = self.database_path()?;
+        let runtime =
+            Runtime::new().map_err(|error| DocumentRepositoryError::storage(error.to_string()))?;
+
+        runtime.block_on(async move {
+            let options = SqliteConnectOptions::new()
+                .filename(&database_path)
+                .create_if_missing(true);
+
+            let pool = SqlitePool::connect_with(options)
+                .await
+                .map_err(|error| DocumentRepositoryError::storage(error.to_string()))?;
+
+            let row = sqlx::query(&format!("{DOCUMENT_SELECT} WHERE d.id = ?1"))
+                .bind(id)
+                .fetch_optional(&pool)
+                .await
+                .map_err(|error| DocumentRepositoryError::storage(error.to_string()))?;
+
+            pool.close().await;
+
+            row.as_ref().map(document_from_row).transpose()
+        })
+    }
+
+    fn list(&self) -> Result<Vec<Document>, DocumentRepositoryError> {
+        let database_path = self.database_path()?;
+        let runtime =
+            Runtime::new().map_err(|error| DocumentRepositoryError::storage(error.to_string()))?;
+
+        runtime.block_on(async move {
+            let options = SqliteConnectOptions::new()
+                .filename(&database_path)
+                .create_if_missing(true);
+
+            let pool = SqlitePool::connect_with(options)
+                .await
+                .map_err(|error| DocumentRepositoryError::storage(error.to_string()))?;
+
+            let rows = sqlx::query(&format!("{DOCUMENT_SELECT} ORDER BY d.id"))
+                .fetch_all(&pool)
+                .await
+                .map_err(|error| DocumentRepositoryError::storage(error.to_string()))?;
+
+            pool.close().await;
+
+            rows.iter().map(document_from_row).collect()
+        })
+    }
+
+    fn next_revision(&self, id: i64) -> Result<Option<i64>, DocumentRepositoryError> {
+        let database_path = self.database_path()?;
+        let runtime =
+            Runtime::new().map_err(|error| DocumentRepositoryError::storage(error.to_string()))?;
+
+        runtime.block_on(async move {
+            let options = SqliteConnectOptions::new()
+                .filename(&database_path)
+                .create_if_missing(true);
+
+            let pool = SqlitePool::connect_with(options)
+                .await
+                .map_err(|error| DocumentRepositoryError::storage(error.to_string()))?;
+
+            let row = sqlx::query("SELECT id FROM document WHERE previous_revision_id = ?1")
+                .bind(id)
+                .fetch_optional(&pool)
+                .await
+                .map_err(|error| DocumentRepositoryError::storage(error.to_string()))?;
+
+            pool.close().await;
+
+            Ok::<Option<i64>, DocumentRepositoryError>(row.map(|row| row.get("id")))
+        })
+    }
+
+    fn set_status(
+        &self,
+        id: i64,
+        from: DocumentStatus,
+        to: DocumentStatus,
+    ) -> Result<bool, DocumentRepositoryError> {
+        let database_path = self.database_path()?;
+        let runtime =
+            Runtime::new().map_err(|error| DocumentRepositoryError::storage(error.to_string()))?;
+
+        let timestamps = match to {
+            DocumentStatus::Draft => "submitted_at = NULL, approved_at = NULL",
+            DocumentStatus::ReadyForReview => "submitted_at = datetime('now')",
+            DocumentStatus::Approved => "approved_at = datetime('now')",
+            DocumentStatus::Issued => "issued_at = datetime('now')",
+            DocumentStatus::Superseded => "superseded_at = datetime('now')",
+            DocumentStatus::Cancelled => "cancelled_at = datetime('now')",
+        };
+
+        runtime.block_on(async move {
+            let options = SqliteConnectOptions::new()
+                .filename(&database_path)
+                .create_if_missing(true);
+
+            let pool = SqlitePool::connect_with(options)
+                .await
+                .map_err(|error| DocumentRepositoryError::storage(error.to_string()))?;
+
+            let result = sqlx::query(&format!(
+                "UPDATE document SET status = ?1, {timestamps} WHERE id = ?2 AND status = ?3"
+            ))
+            .bind(to.as_str())
+            .bind(id)
+            .bind(from.as_str())
+            .execute(&pool)
+            .await
+            .map_err(|error| DocumentRepositoryError::storage(error.to_string()))?;
+
+            pool.close().await;
+
+            Ok::<bool, DocumentRepositoryError>(result.rows_affected() > 0)
+        })
+    }
+
+    fn set_template(&self, id: i64, template: bool) -> Result<bool, DocumentRepositoryError> {
+        let database_path = self.database_path()?;
+        let runtime =
+            Runtime::new().map_err(|error| DocumentRepositoryError::storage(error.to_string()))?;
+
+        runtime.block_on(async move {
+            let options = SqliteConnectOptions::new()
+                .filename(&database_path)
+                .create_if_missing(true);
+
+            let pool = SqlitePool::connect_with(options)
+                .await
+                .map_err(|error| DocumentRepositoryError::storage(error.to_string()))?;
+
+            let result = sqlx::query("UPDATE document SET is_template = ?1 WHERE id = ?2")
+                .bind(template)
+                .bind(id)
+                .execute(&pool)
+                .await
+                .map_err(|error| DocumentRepositoryError::storage(error.to_string()))?;
+
+            pool.close().await;
+
+            Ok::<bool, DocumentRepositoryError>(result.rows_affected() > 0)
+        })
+    }
+
+    fn issue(&self, id: i64, snapshot: &DocumentSnapshot) -> Result<(), DocumentRepositoryError> {
+        let database_path = self.database_path()?;
+        let runtime =
+            Runtime::new().map_err(|error| DocumentRepositoryError::storage(error.to_string()))?;
+
+        let content = serde_json::to_string(snapshot)
+            .map_err(|error| DocumentRepositoryError::storage(error.to_string()))?;
+
+        runtime.block_on(async move {
+            let options = SqliteConnectOptions::new()
+                .filename(&database_path)
+                .create_if_missing(true);
+
+            let pool = SqlitePool::connect_with(options)
+                .await
+                .map_err(|error| DocumentRepositoryError::storage(error.to_string()))?;
+
+            let mut transaction = pool
+                .begin()
+                .await
+                .map_err(|error| DocumentRepositoryError::storage(error.to_string()))?;
+
+            let result = sqlx::query(
+                r#"UPDATE document SET status = 'issued', issued_at = datetime('now')
+                   WHERE id = ?1 AND status = 'approved'"#,
+            )
+            .bind(id)
+            .execute(&mut *transaction)
+            .await
+            .map_err(|error| DocumentRepositoryError::storage(error.to_string()))?;
+
+            if result.rows_affected() == 0 {
+                return Err(DocumentRepositoryError::storage(format!(
+                    "Document {id} is missing or not approved"
+                )));
+            }
+
+            sqlx::query("INSERT INTO document_snapshot (document_id, content) VALUES (?1, ?2)")
+                .bind(id)
+                .bind(content)
+                .execute(&mut *transaction)
+                .await
+                .map_err(|error| DocumentRepositoryError::storage(error.to_string()))?;
+
+            sqlx::query(
+                r#"UPDATE document SET status = 'superseded', superseded_at = datetime('now')
+                   WHERE status = 'issued'
+                     AND id = (SELECT previous_revision_id FROM document WHERE id = ?1)"#,
+            )
+            .bind(id)
+            .execute(&mut *transaction)
+            .await
+            .map_err(|error| DocumentRepositoryError::storage(error.to_string()))?;
+
+            transaction
+                .commit()
+                .await
+                .map_err(|error| DocumentRepositoryError::storage(error.to_string()))?;
+
+            pool.close().await;
+
+            Ok::<(), DocumentRepositoryError>(())
+        })
+    }
+
+    fn snapshot(&self, id: i64) -> Result<Option<DocumentSnapshot>, DocumentRepositoryError> {
+        let database_path = self.database_path()?;
+        let runtime =
+            Runtime::new().map_err(|error| DocumentRepositoryError::storage(error.to_string()))?;
+
+        runtime.block_on(async move {
+            let options = SqliteConnectOptions::new()
+                .filename(&database_path)
+                .create_if_missing(true);
+
+            let pool = SqlitePool::connect_with(options)
+                .await
+                .map_err(|error| DocumentRepositoryError::storage(error.to_string()))?;
+
+            let row = sqlx::query("SELECT content FROM document_snapshot WHERE document_id = ?1")
+                .bind(id)
+                .fetch_optional(&pool)
+                .await
+                .map_err(|error| DocumentRepositoryError::storage(error.to_string()))?;
+
+            pool.close().await;
+
+            row.map(|row| {
+                let content: String = row.get("content");
+                serde_json::from_str(&content)
+                    .map_err(|error| DocumentRepositoryError::storage(error.to_string()))
+            })
+            .transpose()
+        })
+    }
+
+    fn sections(&self, id: i64) -> Result<Vec<DocumentSection>, DocumentRepositoryError> {
+        let database_path = self.database_path()?;
+        let runtime =
+            Runtime::new().map_err(|error| DocumentRepositoryError::storage(error.to_string()))?;
+
+        runtime.block_on(async move {
+            let options = SqliteConnectOptions::new()
+                .filename(&database_path)
+                .create_if_missing(true);
+
+            let pool = SqlitePool::connect_with(options)
+                .await
+                .map_err(|error| DocumentRepositoryError::storage(error.to_string()))?;
+
+            let row = sqlx::query(
+                r#"SELECT
+                    EXISTS (SELECT 1 FROM construction_object WHERE document_id = ?1)
+                        AS construction_object,
+                    EXISTS (SELECT 1 FROM technical_indicator WHERE document_id = ?1)
+                        AS indicators,
+                    EXISTS (SELECT 1 FROM requirement_section WHERE document_id = ?1)
+                        AS requirements,
+                    EXISTS (SELECT 1 FROM document_source_document WHERE document_id = ?1)
+                        AS source_documents"#,
+            )
+            .bind(id)
+            .fetch_one(&pool)
+            .await
+            .map_err(|error| DocumentRepositoryError::storage(error.to_string()))?;
+
+            pool.close().await;
+
+            let mut sections = Vec::new();
+            if row.get::<bool, _>("construction_object") {
+                sections.push(DocumentSection::ConstructionObject);
+            }
+            if row.get::<bool, _>("indicators") {
+                sections.push(DocumentSection::Indicators);
+            }
+            if row.get::<bool, _>("requirements") {
+                sections.push(DocumentSection::Requirements);
+            }
+            if row.get::<bool, _>("source_documents") {
+                sections.push(DocumentSection::SourceDocuments);
+            }
+
+            Ok::<Vec<DocumentSection>, DocumentRepositoryError>(sections)
+        })
+    }
+
+    fn numbering_schemes(&self) -> Result<Vec<NumberingScheme>, DocumentRepositoryError> {
+        let database_path = self.database_path()?;
+        let runtime =
+            Runtime::new().map_err(|error| DocumentRepositoryError::storage(error.to_string()))?;
+
+        runtime.block_on(async move {
+            let options = SqliteConnectOptions::new()
+                .filename(&database_path)
+                .create_if_missing(true);
+
+            let pool = SqlitePool::connect_with(options)
+                .await
+                .map_err(|error| DocumentRepositoryError::storage(error.to_string()))?;
+
+            let rows = sqlx::query("SELECT prefix, width FROM numbering_scheme ORDER BY prefix")
+                .fetch_all(&pool)
+                .await
+                .map_err(|error| DocumentRepositoryError::storage(error.to_string()))?;
+
+            pool.close().await;
+
+            Ok::<Vec<NumberingScheme>, DocumentRepositoryError>(
+                rows.iter()
+                    .map(|row| NumberingScheme {
+                        prefix: row.get("prefix"),
+                        width: row.get::<i64, _>("width") as usize,
+                    })
+                    .collect(),
+            )
+        })
+    }
+
+    fn create_numbering_scheme(
+        &self,
+        scheme: &NumberingScheme,
+    ) -> Result<bool, DocumentRepositoryError> {
+        let database_path = self.database_path()?;
+        let runtime =
+            Runtime::new().map_err(|error| DocumentRepositoryError::storage(error.to_string()))?;
+
+        runtime.block_on(async move {
+            let options = SqliteConnectOptions::new()
+                .filename(&database_path)
+                .create_if_missing(true);
+
+            let pool = SqlitePool::connect_with(options)
+                .await
+                .map_err(|error| DocumentRepositoryError::storage(error.to_string()))?;
+
+            let result = sqlx::query(
+                r#"INSERT INTO numbering_scheme (prefix, width) VALUES (?1, ?2)
+                   ON CONFLICT (prefix) DO NOTHING"#,
+            )
+            .bind(scheme.prefix.as_str())
+            .bind(scheme.width as i64)
+            .execute(&pool)
+            .await
+            .map_err(|error| DocumentRepositoryError::storage(error.to_string()))?;
+
+            pool.close().await;
+
+            Ok::<bool, DocumentRepositoryError>(result.rows_affected() > 0)
+        })
+    }
+}
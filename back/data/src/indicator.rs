@@ -0,0 +1,234 @@
+use sqlx::{Row, SqlitePool, sqlite::SqliteConnectOptions, sqlite::SqliteRow};
+use tokio::runtime::Runtime;
+use zdnp_core::{
+    TechnicalIndicator, TechnicalIndicatorDto, TechnicalIndicatorRepository,
+    TechnicalIndicatorRepositoryError,
+};
+
+use crate::SqliteDocumentRepository;
+
+// ---------------- Technical Indicator Data Repository ----------------
+fn indicator_from_row(row: &SqliteRow) -> TechnicalIndicator {
+    TechnicalIndicator {
+        id: row.get("id"),
+        document_id: row.get("document_id"),
+        position: row.get("position"),
+        name: row.get("name"),
+        value: row.get("value"),
+        unit_okei: row.get("unit_okei"),
+    }
+}
+
+impl SqliteDocumentRepository {
+    fn indicator_pool_options(
+        &self,
+    ) -> Result<(Runtime, SqliteConnectOptions), TechnicalIndicatorRepositoryError> {
+        let database_path = self
+            .database_path()
+            .map_err(|error| TechnicalIndicatorRepositoryError::storage(error.to_string()))?;
+        let runtime = Runtime::new()
+            .map_err(|error| TechnicalIndicatorRepositoryError::storage(error.to_string()))?;
+        let options = SqliteConnectOptions::new()
+            .filename(database_path)
+            .create_if_missing(true);
+
+        Ok((runtime, options))
+    }
+}
+
+impl TechnicalIndicatorRepository for SqliteDocumentRepository {
+    fn add_indicator(
+        &self,
+        document_id: i64,
+        dto: &TechnicalIndicatorDto,
+    ) -> Result<Option<i64>, TechnicalIndicatorRepositoryError> {
+        let (runtime, options) = self.indicator_pool_options()?;
+
+        runtime.block_on(async move {
+            let pool = SqlitePool::connect_with(options)
+                .await
+                .map_err(|error| TechnicalIndicatorRepositoryError::storage(error.to_string()))?;
+
+            let result = sqlx::query(
+                r#"INSERT INTO technical_indicator (document_id, position, name, value, unit_okei)
+                   SELECT
+                       ?1,
+                       (SELECT COALESCE(MAX(position) + 1, 0)
+                        FROM technical_indicator WHERE document_id = ?1),
+                       ?2, ?3, ?4
+                   WHERE EXISTS (SELECT 1 FROM document WHERE id = ?1 AND status = 'draft')"#,
+            )
+            .bind(document_id)
+            .bind(dto.name.as_deref())
+            .bind(dto.value.as_deref())
+            .bind(dto.unit_okei.as_deref())
+            .execute(&pool)
+            .await
+            .map_err(|error| TechnicalIndicatorRepositoryError::storage(error.to_string()))?;
+
+            pool.close().await;
+
+            Ok::<Option<i64>, TechnicalIndicatorRepositoryError>(
+                (result.rows_affected() > 0).then(|| result.last_insert_rowid()),
+            )
+        })
+    }
+
+    fn update_indicator(
+        &self,
+        id: i64,
+        dto: &TechnicalIndicatorDto,
+    ) -> Result<bool, TechnicalIndicatorRepositoryError> {
+        let (runtime, options) = self.indicator_pool_options()?;
+
+        runtime.block_on(async move {
+            let pool = SqlitePool::connect_with(options)
+                .await
+                .map_err(|error| TechnicalIndicatorRepositoryError::storage(error.to_string()))?;
+
+            let result = sqlx::query(
+                r#"UPDATE technical_indicator SET name = ?1, value = ?2, unit_okei = ?3
+                   WHERE id = ?4
+                     AND document_id IN (SELECT id FROM document WHERE status = 'draft')"#,
+            )
+            .bind(dto.name.as_deref())
+            .bind(dto.value.as_deref())
+            .bind(dto.unit_okei.as_deref())
+            .bind(id)
+            .execute(&pool)
+            .await
+            .map_err(|error| TechnicalIndicatorRepositoryError::storage(error.to_string()))?;
+
+            pool.close().await;
+
+            Ok::<bool, TechnicalIndicatorRepositoryError>(result.rows_affected() > 0)
+        })
+    }
+
+    fn delete_indicator(&self, id: i64) -> Result<bool, TechnicalIndicatorRepositoryError> {
+        let (runtime, options) = self.indicator_pool_options()?;
+
+        runtime.block_on(async move {
+            let pool = SqlitePool::connect_with(options)
+                .await
+                .map_err(|error| TechnicalIndicatorRepositoryError::storage(error.to_string()))?;
+
+            let result = sqlx::query(
+                r#"DELETE FROM technical_indicator
+                   WHERE id = ?1
+                     AND document_id IN (SELECT id FROM document WHERE status = 'draft')"#,
+            )
+            .bind(id)
+            .execute(&pool)
+            .await
+            .map_err(|error| TechnicalIndicatorRepositoryError::storage(error.to_string()))?;
+
+            pool.close().await;
+
+            Ok::<bool, TechnicalIndicatorRepositoryError>(result.rows_affected() > 0)
+        })
+    }
+
+    fn find_indicator(
+        &self,
+        id: i64,
+    ) -> Result<Option<TechnicalIndicator>, TechnicalIndicatorRepositoryError> {
+        let (runtime, options) = self.indicator_pool_options()?;
+
+        runtime.block_on(async move {
+            let pool = SqlitePool::connect_with(options)
+                .await
+                .map_err(|error| TechnicalIndicatorRepositoryError::storage(error.to_string()))?;
+
+            let row = sqlx::query("SELECT * FROM technical_indicator WHERE id = ?1")
+                .bind(id)
+                .fetch_optional(&pool)
+                .await
+                .map_err(|error| TechnicalIndicatorRepositoryError::storage(error.to_string()))?;
+
+            pool.close().await;
+
+            Ok::<Option<TechnicalIndicator>, TechnicalIndicatorRepositoryError>(
+                row.as_ref().map(indicator_from_row),
+            )
+        })
+    }
+
+    fn indicators(
+        &self,
+        document_id: i64,
+    ) -> Result<Vec<TechnicalIndicator>, TechnicalIndicatorRepositoryError> {
+        let (runtime, options) = self.indicator_pool_options()?;
+
+        runtime.block_on(async move {
+            let pool = SqlitePool::connect_with(options)
+                .await
+                .map_err(|error| TechnicalIndicatorRepositoryError::storage(error.to_string()))?;
+
+            let rows = sqlx::query(
+                "SELECT * FROM technical_indicator WHERE document_id = ?1 ORDER BY position, id",
+            )
+            .bind(document_id)
+            .fetch_all(&pool)
+            .await
+            .map_err(|error| TechnicalIndicatorRepositoryError::storage(error.to_string()))?;
+
+            pool.close().await;
+
+            Ok::<Vec<TechnicalIndicator>, TechnicalIndicatorRepositoryError>(
+                rows.iter().map(indicator_from_row).collect(),
+            )
+        })
+    }
+
+    fn reorder_indicators(
+        &self,
+        document_id: i64,
+        ids: &[i64],
+    ) -> Result<bool, TechnicalIndicatorRepositoryError> {
+        let (runtime, options) = self.indicator_pool_options()?;
+
+        runtime.block_on(async move {
+            let pool = SqlitePool::connect_with(options)
+                .await
+                .map_err(|error| TechnicalIndicatorRepositoryError::storage(error.to_string()))?;
+
+            let mut transaction = pool
+                .begin()
+                .await
+                .map_err(|error| TechnicalIndicatorRepositoryError::storage(error.to_string()))?;
+
+            let draft: bool = sqlx::query_scalar(
+                "SELECT EXISTS (SELECT 1 FROM document WHERE id = ?1 AND status = 'draft')",
+            )
+            .bind(document_id)
+            .fetch_one(&mut *transaction)
+            .await
+            .map_err(|error| TechnicalIndicatorRepositoryError::storage(error.to_string()))?;
+            if !draft {
+                return Ok(false);
+            }
+
+            for (position, id) in ids.iter().enumerate() {
+                sqlx::query(
+                    "UPDATE technical_indicator SET position = ?1 WHERE id = ?2 AND document_id = ?3",
+                )
+                .bind(position as i64)
+                .bind(*id)
+                .bind(document_id)
+                .execute(&mut *transaction)
+                .await
+                .map_err(|error| TechnicalIndicatorRepositoryError::storage(error.to_string()))?;
+            }
+
+            transaction
+                .commit()
+                .await
+                .map_err(|error| TechnicalIndicatorRepositoryError::storage(error.to_string()))?;
+
+            pool.close().await;
+
+            Ok::<bool, TechnicalIndicatorRepositoryError>(true)
+        })
+    }
+}
@@ -1,18 +1,48 @@
 use std::fs::{self, File};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
-use sqlx::{Row, SqlitePool, migrate::Migrator, sqlite::SqliteConnectOptions};
+use sqlx::{
+    Row, SqlitePool,
+    migrate::Migrator,
+    sqlite::{SqliteConnectOptions, SqliteRow},
+};
 use tokio::runtime::Runtime;
 use zdnp_core::{
     Address, AddressDto, AddressRepository, AddressRepositoryError, Entrepreneur, EntrepreneurDto,
-    EntrepreneurRepository, EntrepreneurRepositoryError, Migrations, MigrationsResult,
+    EntrepreneurRepository, EntrepreneurRepositoryError, ListOptions, Migrations, MigrationsResult,
     Organization, OrganizationDto, OrganizationRepository, OrganizationRepositoryError, Person,
     PersonDto, PersonRepository, PersonRepositoryError,
 };
 
+mod archive;
+mod attachment;
+mod backup;
+mod construction_object;
+mod document;
+mod indicator;
+mod reference_import;
+mod requirement;
+mod signatory;
+mod source_document;
+mod sync;
+
+pub use archive::{
+    ARCHIVE_FORMAT, ARCHIVE_VERSION, Archive, ArchiveConflict, ArchiveEntityKind, ArchiveEntry,
+    ArchiveError, ArchiveImportReport, SqliteArchive,
+};
+pub use backup::{BackupError, BackupInfo, BackupPolicy, SqliteBackups};
+pub use document::{SqliteDocumentRepository, SqliteReferenceRepository};
+pub use requirement::SqliteRequirementTemplateRepository;
+pub use source_document::SqliteSourceDocumentRepository;
+pub use sync::{
+    ChangeSet, FieldChange, FieldVersion, SYNC_FORMAT, SYNC_VERSION, SqliteSync, SyncConflict,
+    SyncError, SyncReport, SyncSide,
+};
+
 static MIGRATOR: Migrator = sqlx::migrate!("./migrations");
 
-const DEFAULT_DATABASE_FILE_NAME: &str = "zdnp.sqlite";
+/// Database file used by the repositories created with `new`, next to the executable.
+pub const DEFAULT_DATABASE_FILE_NAME: &str = "zdnp.sqlite";
 
 pub struct SqliteMigrations {
     database_file_name: String,
@@ -39,12 +69,9 @@ impl SqliteMigrations {
 
     fn database_path(&self) -> MigrationsResult<PathBuf> {
         let executable = std::env::current_exe()?;
-        let directory = executable.parent().ok_or_else(|| {
-            std::io::Error::new(
-                std::io::ErrorKind::Other,
-                "Failed to determine application directory",
-            )
-        })?;
+        let directory = executable
+            .parent()
+            .ok_or_else(|| std::io::Error::other("Failed to determine application directory"))?;
 
         Ok(directory.join(&self.database_file_name))
     }
@@ -79,6 +106,45 @@ impl Migrations for SqliteMigrations {
     }
 }
 
+/// Sets or clears `archived_at` of a reference data row. Returns `false` when the row does not
+/// exist. Archiving an already archived row keeps its original timestamp.
+fn set_archived(
+    database_path: &Path,
+    table: &str,
+    id: i64,
+    archived: bool,
+) -> Result<bool, String> {
+    let runtime = Runtime::new().map_err(|error| error.to_string())?;
+
+    runtime.block_on(async move {
+        let options = SqliteConnectOptions::new()
+            .filename(database_path)
+            .create_if_missing(true);
+
+        let pool = SqlitePool::connect_with(options)
+            .await
+            .map_err(|error| error.to_string())?;
+
+        let statement = if archived {
+            format!(
+                "UPDATE {table} SET archived_at = COALESCE(archived_at, datetime('now')) WHERE id = ?1"
+            )
+        } else {
+            format!("UPDATE {table} SET archived_at = NULL WHERE id = ?1")
+        };
+
+        let result = sqlx::query(&statement)
+            .bind(id)
+            .execute(&pool)
+            .await
+            .map_err(|error| error.to_string())?;
+
+        pool.close().await;
+
+        Ok(result.rows_affected() > 0)
+    })
+}
+
 pub struct SqliteAddressRepository {
     database_file_name: String,
 }
@@ -113,6 +179,23 @@ impl Default for SqliteAddressRepository {
     }
 }
 
+pub(crate) fn address_from_row(row: &SqliteRow) -> Address {
+    Address {
+        id: row.get("id"),
+        uid: row.get("uid"),
+        region_code: row.get("region_code"),
+        note: row.get("note"),
+        country: row.get("country"),
+        district: row.get("district"),
+        city: row.get("city"),
+        settlement: row.get("settlement"),
+        street: row.get("street"),
+        building: row.get("building"),
+        room: row.get("room"),
+        archived_at: row.get("archived_at"),
+    }
+}
+
 impl AddressRepository for SqliteAddressRepository {
     fn create(&self, dto: &AddressDto) -> Result<i64, AddressRepositoryError> {
         let database_path = self.database_path()?;
@@ -132,11 +215,16 @@ impl AddressRepository for SqliteAddressRepository {
                 .region_code
                 .as_deref()
                 .ok_or_else(|| AddressRepositoryError::storage("Region code is required"))?;
+            let uid = dto
+                .uid
+                .as_deref()
+                .ok_or_else(|| AddressRepositoryError::storage("UID is required"))?;
 
             let result = sqlx::query(
                 r#"INSERT INTO address (
-                    region_code, note, country, district, city, settlement, street, building, room
-                ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)"#,
+                    region_code, note, country, district, city, settlement, street, building, room,
+                    uid
+                ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)"#,
             )
             .bind(region_code)
             .bind(dto.note.as_deref())
@@ -147,6 +235,7 @@ impl AddressRepository for SqliteAddressRepository {
             .bind(dto.street.as_deref())
             .bind(dto.building.as_deref())
             .bind(dto.room.as_deref())
+            .bind(uid)
             .execute(&pool)
             .await
             .map_err(|error| AddressRepositoryError::storage(error.to_string()))?;
@@ -159,7 +248,52 @@ impl AddressRepository for SqliteAddressRepository {
         })
     }
 
-    fn list(&self) -> Result<Vec<Address>, AddressRepositoryError> {
+    fn update(&self, id: i64, dto: &AddressDto) -> Result<bool, AddressRepositoryError> {
+        let database_path = self.database_path()?;
+        let runtime =
+            Runtime::new().map_err(|error| AddressRepositoryError::storage(error.to_string()))?;
+
+        runtime.block_on(async move {
+            let options = SqliteConnectOptions::new()
+                .filename(&database_path)
+                .create_if_missing(true);
+
+            let pool = SqlitePool::connect_with(options)
+                .await
+                .map_err(|error| AddressRepositoryError::storage(error.to_string()))?;
+
+            let region_code = dto
+                .region_code
+                .as_deref()
+                .ok_or_else(|| AddressRepositoryError::storage("Region code is required"))?;
+
+            let result = sqlx::query(
+                r#"UPDATE address
+                   SET region_code = ?1, note = ?2, country = ?3, district = ?4, city = ?5,
+                       settlement = ?6, street = ?7, building = ?8, room = ?9
+                   WHERE id = ?10"#,
+            )
+            .bind(region_code)
+            .bind(dto.note.as_deref())
+            .bind(dto.country.as_deref())
+            .bind(dto.district.as_deref())
+            .bind(dto.city.as_deref())
+            .bind(dto.settlement.as_deref())
+            .bind(dto.street.as_deref())
+            .bind(dto.building.as_deref())
+            .bind(dto.room.as_deref())
+            .bind(id)
+            .execute(&pool)
+            .await
+            .map_err(|error| AddressRepositoryError::storage(error.to_string()))?;
+
+            pool.close().await;
+
+            Ok::<bool, AddressRepositoryError>(result.rows_affected() > 0)
+        })
+    }
+
+    fn list(&self, list_options: &ListOptions) -> Result<Vec<Address>, AddressRepositoryError> {
         let database_path = self.database_path()?;
         let runtime =
             Runtime::new().map_err(|error| AddressRepositoryError::storage(error.to_string()))?;
@@ -174,35 +308,34 @@ impl AddressRepository for SqliteAddressRepository {
                 .map_err(|error| AddressRepositoryError::storage(error.to_string()))?;
 
             let rows = sqlx::query(
-                r#"SELECT id, region_code, note, country, district, city, settlement, street, building, room
+                r#"SELECT id, uid, region_code, note, country, district, city, settlement, street,
+                          building, room, archived_at
                    FROM address
+                   WHERE ?1 OR archived_at IS NULL
                    ORDER BY id"#,
             )
+            .bind(list_options.include_archived)
             .fetch_all(&pool)
             .await
             .map_err(|error| AddressRepositoryError::storage(error.to_string()))?;
 
             pool.close().await;
 
-            let addresses = rows
-                .into_iter()
-                .map(|row| Address {
-                    id: row.get("id"),
-                    region_code: row.get("region_code"),
-                    note: row.get("note"),
-                    country: row.get("country"),
-                    district: row.get("district"),
-                    city: row.get("city"),
-                    settlement: row.get("settlement"),
-                    street: row.get("street"),
-                    building: row.get("building"),
-                    room: row.get("room"),
-                })
-                .collect();
+            let addresses = rows.iter().map(address_from_row).collect();
 
             Ok::<Vec<Address>, AddressRepositoryError>(addresses)
         })
     }
+
+    fn archive(&self, id: i64) -> Result<bool, AddressRepositoryError> {
+        let database_path = self.database_path()?;
+        set_archived(&database_path, "address", id, true).map_err(AddressRepositoryError::storage)
+    }
+
+    fn restore(&self, id: i64) -> Result<bool, AddressRepositoryError> {
+        let database_path = self.database_path()?;
+        set_archived(&database_path, "address", id, false).map_err(AddressRepositoryError::storage)
+    }
 }
 
 // ---------------- Organization Data Repository ----------------
@@ -240,6 +373,98 @@ impl Default for SqliteOrganizationRepository {
     }
 }
 
+pub(crate) fn organization_from_row(row: &SqliteRow) -> Organization {
+    let ogrn: Option<i64> = row.get("ogrn");
+    let rafp: Option<i64> = row.get("rafp");
+    let inn: i64 = row.get("inn");
+    let kpp: i64 = row.get("kpp");
+
+    Organization {
+        id: row.get("id"),
+        uid: row.get("uid"),
+        full_name: row.get("full_name"),
+        abbreviated_name: row.get("abbreviated_name"),
+        ogrn: ogrn.map(|value| value.to_string()),
+        rafp: rafp.map(|value| value.to_string()),
+        inn: inn.to_string(),
+        kpp: kpp.to_string(),
+        address_id: row.get("address_id"),
+        email: row.get("email"),
+        archived_at: row.get("archived_at"),
+    }
+}
+
+/// Values of the organization columns; numeric identifiers are stored as INTEGER.
+struct OrganizationColumns<'a> {
+    full_name: &'a str,
+    abbreviated_name: &'a str,
+    ogrn: Option<i64>,
+    rafp: Option<i64>,
+    inn: i64,
+    kpp: i64,
+    email: &'a str,
+}
+
+fn organization_columns(
+    dto: &OrganizationDto,
+) -> Result<OrganizationColumns<'_>, OrganizationRepositoryError> {
+    // Required string fields
+    let full_name = dto
+        .full_name
+        .as_deref()
+        .ok_or_else(|| OrganizationRepositoryError::storage("Full name is required"))?;
+
+    let abbreviated_name = dto
+        .abbreviated_name
+        .as_deref()
+        .ok_or_else(|| OrganizationRepositoryError::storage("Abbreviated name is required"))?;
+
+    let inn = dto
+        .inn
+        .as_deref()
+        .ok_or_else(|| OrganizationRepositoryError::storage("INN is required"))?;
+
+    let kpp = dto
+        .kpp
+        .as_deref()
+        .ok_or_else(|| OrganizationRepositoryError::storage("KPP is required"))?;
+
+    let email = dto
+        .email
+        .as_deref()
+        .ok_or_else(|| OrganizationRepositoryError::storage("Email is required"))?;
+
+    // Parse numeric fields for INTEGER columns; on parse failure for optional fields, store NULL
+    let ogrn = dto
+        .ogrn
+        .as_deref()
+        .and_then(|v| v.trim().parse::<i64>().ok());
+
+    let rafp = dto
+        .rafp
+        .as_deref()
+        .and_then(|v| v.trim().parse::<i64>().ok());
+
+    let inn = inn
+        .trim()
+        .parse::<i64>()
+        .map_err(|e| OrganizationRepositoryError::storage(format!("Invalid INN: {e}")))?;
+    let kpp = kpp
+        .trim()
+        .parse::<i64>()
+        .map_err(|e| OrganizationRepositoryError::storage(format!("Invalid KPP: {e}")))?;
+
+    Ok(OrganizationColumns {
+        full_name,
+        abbreviated_name,
+        ogrn,
+        rafp,
+        inn,
+        kpp,
+        email,
+    })
+}
+
 impl OrganizationRepository for SqliteOrganizationRepository {
     fn create(&self, dto: &OrganizationDto) -> Result<i64, OrganizationRepositoryError> {
         let database_path = self.database_path()?;
@@ -255,77 +480,83 @@ impl OrganizationRepository for SqliteOrganizationRepository {
                 .await
                 .map_err(|error| OrganizationRepositoryError::storage(error.to_string()))?;
 
-            // Required string fields
-            let full_name = dto
-                .full_name
+            let columns = organization_columns(dto)?;
+            let uid = dto
+                .uid
                 .as_deref()
-                .ok_or_else(|| OrganizationRepositoryError::storage("Full name is required"))?;
+                .ok_or_else(|| OrganizationRepositoryError::storage("UID is required"))?;
 
-            let abbreviated_name = dto.abbreviated_name.as_deref().ok_or_else(|| {
-                OrganizationRepositoryError::storage("Abbreviated name is required")
-            })?;
+            let result = sqlx::query(
+                r#"INSERT INTO organization (
+                    full_name, abbreviated_name, ogrn, rafp, inn, kpp, address_id, email, uid
+                ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)"#,
+            )
+            .bind(columns.full_name)
+            .bind(columns.abbreviated_name)
+            .bind(columns.ogrn)
+            .bind(columns.rafp)
+            .bind(columns.inn)
+            .bind(columns.kpp)
+            .bind(dto.address_id)
+            .bind(columns.email)
+            .bind(uid)
+            .execute(&pool)
+            .await
+            .map_err(|error| OrganizationRepositoryError::storage(error.to_string()))?;
 
-            let inn = dto
-                .inn
-                .as_deref()
-                .ok_or_else(|| OrganizationRepositoryError::storage("INN is required"))?;
+            let id = result.last_insert_rowid();
 
-            let kpp = dto
-                .kpp
-                .as_deref()
-                .ok_or_else(|| OrganizationRepositoryError::storage("KPP is required"))?;
+            pool.close().await;
 
-            let email = dto
-                .email
-                .as_deref()
-                .ok_or_else(|| OrganizationRepositoryError::storage("Email is required"))?;
+            Ok::<i64, OrganizationRepositoryError>(id)
+        })
+    }
 
-            // Parse numeric fields for INTEGER columns; on parse failure for optional fields, store NULL
-            let ogrn_int: Option<i64> = dto
-                .ogrn
-                .as_deref()
-                .and_then(|v| v.trim().parse::<i64>().ok());
+    fn update(&self, id: i64, dto: &OrganizationDto) -> Result<bool, OrganizationRepositoryError> {
+        let database_path = self.database_path()?;
+        let runtime = Runtime::new()
+            .map_err(|error| OrganizationRepositoryError::storage(error.to_string()))?;
 
-            let rafp_int: Option<i64> = dto
-                .rafp
-                .as_deref()
-                .and_then(|v| v.trim().parse::<i64>().ok());
+        runtime.block_on(async move {
+            let options = SqliteConnectOptions::new()
+                .filename(&database_path)
+                .create_if_missing(true);
+
+            let pool = SqlitePool::connect_with(options)
+                .await
+                .map_err(|error| OrganizationRepositoryError::storage(error.to_string()))?;
 
-            let inn_int: i64 = inn
-                .trim()
-                .parse::<i64>()
-                .map_err(|e| OrganizationRepositoryError::storage(format!("Invalid INN: {e}")))?;
-            let kpp_int: i64 = kpp
-                .trim()
-                .parse::<i64>()
-                .map_err(|e| OrganizationRepositoryError::storage(format!("Invalid KPP: {e}")))?;
+            let columns = organization_columns(dto)?;
 
             let result = sqlx::query(
-                r#"INSERT INTO organization (
-                    full_name, abbreviated_name, ogrn, rafp, inn, kpp, address_id, email
-                ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)"#,
+                r#"UPDATE organization
+                   SET full_name = ?1, abbreviated_name = ?2, ogrn = ?3, rafp = ?4, inn = ?5,
+                       kpp = ?6, address_id = ?7, email = ?8
+                   WHERE id = ?9"#,
             )
-            .bind(full_name)
-            .bind(abbreviated_name)
-            .bind(ogrn_int)
-            .bind(rafp_int)
-            .bind(inn_int)
-            .bind(kpp_int)
+            .bind(columns.full_name)
+            .bind(columns.abbreviated_name)
+            .bind(columns.ogrn)
+            .bind(columns.rafp)
+            .bind(columns.inn)
+            .bind(columns.kpp)
             .bind(dto.address_id)
-            .bind(email)
+            .bind(columns.email)
+            .bind(id)
             .execute(&pool)
             .await
             .map_err(|error| OrganizationRepositoryError::storage(error.to_string()))?;
 
-            let id = result.last_insert_rowid();
-
             pool.close().await;
 
-            Ok::<i64, OrganizationRepositoryError>(id)
+            Ok::<bool, OrganizationRepositoryError>(result.rows_affected() > 0)
         })
     }
 
-    fn list(&self) -> Result<Vec<Organization>, OrganizationRepositoryError> {
+    fn list(
+        &self,
+        list_options: &ListOptions,
+    ) -> Result<Vec<Organization>, OrganizationRepositoryError> {
         let database_path = self.database_path()?;
         let runtime = Runtime::new()
             .map_err(|error| OrganizationRepositoryError::storage(error.to_string()))?;
@@ -340,41 +571,36 @@ impl OrganizationRepository for SqliteOrganizationRepository {
                 .map_err(|error| OrganizationRepositoryError::storage(error.to_string()))?;
 
             let rows = sqlx::query(
-                r#"SELECT id, full_name, abbreviated_name, ogrn, rafp, inn, kpp, address_id, email
+                r#"SELECT id, uid, full_name, abbreviated_name, ogrn, rafp, inn, kpp, address_id, email,
+                          archived_at
                    FROM organization
+                   WHERE ?1 OR archived_at IS NULL
                    ORDER BY id"#,
             )
+            .bind(list_options.include_archived)
             .fetch_all(&pool)
             .await
             .map_err(|error| OrganizationRepositoryError::storage(error.to_string()))?;
 
             pool.close().await;
 
-            let organizations = rows
-                .into_iter()
-                .map(|row| {
-                    let ogrn: Option<i64> = row.get("ogrn");
-                    let rafp: Option<i64> = row.get("rafp");
-                    let inn: i64 = row.get("inn");
-                    let kpp: i64 = row.get("kpp");
-
-                    Organization {
-                        id: row.get("id"),
-                        full_name: row.get("full_name"),
-                        abbreviated_name: row.get("abbreviated_name"),
-                        ogrn: ogrn.map(|value| value.to_string()),
-                        rafp: rafp.map(|value| value.to_string()),
-                        inn: inn.to_string(),
-                        kpp: kpp.to_string(),
-                        address_id: row.get("address_id"),
-                        email: row.get("email"),
-                    }
-                })
-                .collect();
+            let organizations = rows.iter().map(organization_from_row).collect();
 
             Ok::<Vec<Organization>, OrganizationRepositoryError>(organizations)
         })
     }
+
+    fn archive(&self, id: i64) -> Result<bool, OrganizationRepositoryError> {
+        let database_path = self.database_path()?;
+        set_archived(&database_path, "organization", id, true)
+            .map_err(OrganizationRepositoryError::storage)
+    }
+
+    fn restore(&self, id: i64) -> Result<bool, OrganizationRepositoryError> {
+        let database_path = self.database_path()?;
+        set_archived(&database_path, "organization", id, false)
+            .map_err(OrganizationRepositoryError::storage)
+    }
 }
 
 // ---------------- Entrepreneur Data Repository ----------------
@@ -412,6 +638,68 @@ impl Default for SqliteEntrepreneurRepository {
     }
 }
 
+pub(crate) fn entrepreneur_from_row(row: &SqliteRow) -> Entrepreneur {
+    let ogrnip: i64 = row.get("ogrnip");
+    let inn: i64 = row.get("inn");
+
+    Entrepreneur {
+        id: row.get("id"),
+        uid: row.get("uid"),
+        surname: row.get("surname"),
+        name: row.get("name"),
+        patronymic: row.get("patronymic"),
+        ogrnip: ogrnip.to_string(),
+        inn: inn.to_string(),
+        address_id: row.get("address_id"),
+        email: row.get("email"),
+        archived_at: row.get("archived_at"),
+    }
+}
+
+/// Values of the required entrepreneur columns; numeric identifiers are stored as INTEGER.
+struct EntrepreneurColumns<'a> {
+    surname: &'a str,
+    name: &'a str,
+    ogrnip: i64,
+    inn: i64,
+}
+
+fn entrepreneur_columns(
+    dto: &EntrepreneurDto,
+) -> Result<EntrepreneurColumns<'_>, EntrepreneurRepositoryError> {
+    let surname = dto
+        .surname
+        .as_deref()
+        .ok_or_else(|| EntrepreneurRepositoryError::storage("Surname is required"))?;
+    let name = dto
+        .name
+        .as_deref()
+        .ok_or_else(|| EntrepreneurRepositoryError::storage("Name is required"))?;
+    let ogrnip = dto
+        .ogrnip
+        .as_deref()
+        .ok_or_else(|| EntrepreneurRepositoryError::storage("OGRNIP is required"))?;
+    let inn = dto
+        .inn
+        .as_deref()
+        .ok_or_else(|| EntrepreneurRepositoryError::storage("INN is required"))?;
+
+    let ogrnip: i64 = ogrnip.trim().parse().map_err(|error| {
+        EntrepreneurRepositoryError::storage(format!("Invalid OGRNIP: {error}"))
+    })?;
+    let inn: i64 = inn
+        .trim()
+        .parse()
+        .map_err(|error| EntrepreneurRepositoryError::storage(format!("Invalid INN: {error}")))?;
+
+    Ok(EntrepreneurColumns {
+        surname,
+        name,
+        ogrnip,
+        inn,
+    })
+}
+
 impl EntrepreneurRepository for SqliteEntrepreneurRepository {
     fn create(&self, dto: &EntrepreneurDto) -> Result<i64, EntrepreneurRepositoryError> {
         let database_path = self.database_path()?;
@@ -427,42 +715,25 @@ impl EntrepreneurRepository for SqliteEntrepreneurRepository {
                 .await
                 .map_err(|error| EntrepreneurRepositoryError::storage(error.to_string()))?;
 
-            let surname = dto
-                .surname
-                .as_deref()
-                .ok_or_else(|| EntrepreneurRepositoryError::storage("Surname is required"))?;
-            let name = dto
-                .name
+            let columns = entrepreneur_columns(dto)?;
+            let uid = dto
+                .uid
                 .as_deref()
-                .ok_or_else(|| EntrepreneurRepositoryError::storage("Name is required"))?;
-            let ogrnip = dto
-                .ogrnip
-                .as_deref()
-                .ok_or_else(|| EntrepreneurRepositoryError::storage("OGRNIP is required"))?;
-            let inn = dto
-                .inn
-                .as_deref()
-                .ok_or_else(|| EntrepreneurRepositoryError::storage("INN is required"))?;
-
-            let ogrnip_int: i64 = ogrnip.trim().parse().map_err(|error| {
-                EntrepreneurRepositoryError::storage(format!("Invalid OGRNIP: {error}"))
-            })?;
-            let inn_int: i64 = inn.trim().parse().map_err(|error| {
-                EntrepreneurRepositoryError::storage(format!("Invalid INN: {error}"))
-            })?;
+                .ok_or_else(|| EntrepreneurRepositoryError::storage("UID is required"))?;
 
             let result = sqlx::query(
                 r#"INSERT INTO entrepreneur (
-                    surname, name, patronymic, ogrnip, inn, address_id, email
-                ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)"#,
+                    surname, name, patronymic, ogrnip, inn, address_id, email, uid
+                ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)"#,
             )
-            .bind(surname)
-            .bind(name)
+            .bind(columns.surname)
+            .bind(columns.name)
             .bind(dto.patronymic.as_deref())
-            .bind(ogrnip_int)
-            .bind(inn_int)
+            .bind(columns.ogrnip)
+            .bind(columns.inn)
             .bind(dto.address_id)
             .bind(dto.email.as_deref())
+            .bind(uid)
             .execute(&pool)
             .await
             .map_err(|error| EntrepreneurRepositoryError::storage(error.to_string()))?;
@@ -475,7 +746,50 @@ impl EntrepreneurRepository for SqliteEntrepreneurRepository {
         })
     }
 
-    fn list(&self) -> Result<Vec<Entrepreneur>, EntrepreneurRepositoryError> {
+    fn update(&self, id: i64, dto: &EntrepreneurDto) -> Result<bool, EntrepreneurRepositoryError> {
+        let database_path = self.database_path()?;
+        let runtime = Runtime::new()
+            .map_err(|error| EntrepreneurRepositoryError::storage(error.to_string()))?;
+
+        runtime.block_on(async move {
+            let options = SqliteConnectOptions::new()
+                .filename(&database_path)
+                .create_if_missing(true);
+
+            let pool = SqlitePool::connect_with(options)
+                .await
+                .map_err(|error| EntrepreneurRepositoryError::storage(error.to_string()))?;
+
+            let columns = entrepreneur_columns(dto)?;
+
+            let result = sqlx::query(
+                r#"UPDATE entrepreneur
+                   SET surname = ?1, name = ?2, patronymic = ?3, ogrnip = ?4, inn = ?5,
+                       address_id = ?6, email = ?7
+                   WHERE id = ?8"#,
+            )
+            .bind(columns.surname)
+            .bind(columns.name)
+            .bind(dto.patronymic.as_deref())
+            .bind(columns.ogrnip)
+            .bind(columns.inn)
+            .bind(dto.address_id)
+            .bind(dto.email.as_deref())
+            .bind(id)
+            .execute(&pool)
+            .await
+            .map_err(|error| EntrepreneurRepositoryError::storage(error.to_string()))?;
+
+            pool.close().await;
+
+            Ok::<bool, EntrepreneurRepositoryError>(result.rows_affected() > 0)
+        })
+    }
+
+    fn list(
+        &self,
+        list_options: &ListOptions,
+    ) -> Result<Vec<Entrepreneur>, EntrepreneurRepositoryError> {
         let database_path = self.database_path()?;
         let runtime = Runtime::new()
             .map_err(|error| EntrepreneurRepositoryError::storage(error.to_string()))?;
@@ -490,38 +804,36 @@ impl EntrepreneurRepository for SqliteEntrepreneurRepository {
                 .map_err(|error| EntrepreneurRepositoryError::storage(error.to_string()))?;
 
             let rows = sqlx::query(
-                r#"SELECT id, surname, name, patronymic, ogrnip, inn, address_id, email
+                r#"SELECT id, uid, surname, name, patronymic, ogrnip, inn, address_id, email,
+                          archived_at
                    FROM entrepreneur
+                   WHERE ?1 OR archived_at IS NULL
                    ORDER BY id"#,
             )
+            .bind(list_options.include_archived)
             .fetch_all(&pool)
             .await
             .map_err(|error| EntrepreneurRepositoryError::storage(error.to_string()))?;
 
             pool.close().await;
 
-            let entrepreneurs = rows
-                .into_iter()
-                .map(|row| {
-                    let ogrnip: i64 = row.get("ogrnip");
-                    let inn: i64 = row.get("inn");
-
-                    Entrepreneur {
-                        id: row.get("id"),
-                        surname: row.get("surname"),
-                        name: row.get("name"),
-                        patronymic: row.get("patronymic"),
-                        ogrnip: ogrnip.to_string(),
-                        inn: inn.to_string(),
-                        address_id: row.get("address_id"),
-                        email: row.get("email"),
-                    }
-                })
-                .collect();
+            let entrepreneurs = rows.iter().map(entrepreneur_from_row).collect();
 
             Ok::<Vec<Entrepreneur>, EntrepreneurRepositoryError>(entrepreneurs)
         })
     }
+
+    fn archive(&self, id: i64) -> Result<bool, EntrepreneurRepositoryError> {
+        let database_path = self.database_path()?;
+        set_archived(&database_path, "entrepreneur", id, true)
+            .map_err(EntrepreneurRepositoryError::storage)
+    }
+
+    fn restore(&self, id: i64) -> Result<bool, EntrepreneurRepositoryError> {
+        let database_path = self.database_path()?;
+        set_archived(&database_path, "entrepreneur", id, false)
+            .map_err(EntrepreneurRepositoryError::storage)
+    }
 }
 
 // ---------------- Person Data Repository ----------------
@@ -560,6 +872,22 @@ impl Default for SqlitePersonRepository {
     }
 }
 
+pub(crate) fn person_from_row(row: &SqliteRow) -> Person {
+    let snils: i64 = row.get("snils");
+
+    Person {
+        id: row.get("id"),
+        uid: row.get("uid"),
+        name: row.get("name"),
+        patronymic: row.get("patronymic"),
+        surname: row.get("surname"),
+        snils: snils.to_string(),
+        email: row.get("email"),
+        address_id: row.get("address_id"),
+        archived_at: row.get("archived_at"),
+    }
+}
+
 impl PersonRepository for SqlitePersonRepository {
     fn create(&self, dto: &PersonDto) -> Result<i64, PersonRepositoryError> {
         let database_path = self.database_path()?;
@@ -591,6 +919,10 @@ impl PersonRepository for SqlitePersonRepository {
                 .email
                 .as_deref()
                 .ok_or_else(|| PersonRepositoryError::storage("Email is required"))?;
+            let uid = dto
+                .uid
+                .as_deref()
+                .ok_or_else(|| PersonRepositoryError::storage("UID is required"))?;
 
             let snils_int: i64 = snils.trim().parse().map_err(|error| {
                 PersonRepositoryError::storage(format!("Invalid SNILS: {error}"))
@@ -598,8 +930,8 @@ impl PersonRepository for SqlitePersonRepository {
 
             let result = sqlx::query(
                 r#"INSERT INTO person (
-                    name, patronymic, surname, snils, email, address_id
-                ) VALUES (?1, ?2, ?3, ?4, ?5, ?6)"#,
+                    name, patronymic, surname, snils, email, address_id, uid
+                ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)"#,
             )
             .bind(name)
             .bind(dto.patronymic.as_deref())
@@ -607,6 +939,7 @@ impl PersonRepository for SqlitePersonRepository {
             .bind(snils_int)
             .bind(email)
             .bind(dto.address_id)
+            .bind(uid)
             .execute(&pool)
             .await
             .map_err(|error| PersonRepositoryError::storage(error.to_string()))?;
@@ -619,7 +952,65 @@ impl PersonRepository for SqlitePersonRepository {
         })
     }
 
-    fn list(&self) -> Result<Vec<Person>, PersonRepositoryError> {
+    fn update(&self, id: i64, dto: &PersonDto) -> Result<bool, PersonRepositoryError> {
+        let database_path = self.database_path()?;
+        let runtime =
+            Runtime::new().map_err(|error| PersonRepositoryError::storage(error.to_string()))?;
+
+        runtime.block_on(async move {
+            let options = SqliteConnectOptions::new()
+                .filename(&database_path)
+                .create_if_missing(true);
+
+            let pool = SqlitePool::connect_with(options)
+                .await
+                .map_err(|error| PersonRepositoryError::storage(error.to_string()))?;
+
+            let name = dto
+                .name
+                .as_deref()
+                .ok_or_else(|| PersonRepositoryError::storage("Name is required"))?;
+            let surname = dto
+                .surname
+                .as_deref()
+                .ok_or_else(|| PersonRepositoryError::storage("Surname is required"))?;
+            let snils = dto
+                .snils
+                .as_deref()
+                .ok_or_else(|| PersonRepositoryError::storage("SNILS is required"))?;
+            let email = dto
+                .email
+                .as_deref()
+                .ok_or_else(|| PersonRepositoryError::storage("Email is required"))?;
+
+            let snils_int: i64 = snils.trim().parse().map_err(|error| {
+                PersonRepositoryError::storage(format!("Invalid SNILS: {error}"))
+            })?;
+
+            let result = sqlx::query(
+                r#"UPDATE person
+                   SET name = ?1, patronymic = ?2, surname = ?3, snils = ?4, email = ?5,
+                       address_id = ?6
+                   WHERE id = ?7"#,
+            )
+            .bind(name)
+            .bind(dto.patronymic.as_deref())
+            .bind(surname)
+            .bind(snils_int)
+            .bind(email)
+            .bind(dto.address_id)
+            .bind(id)
+            .execute(&pool)
+            .await
+            .map_err(|error| PersonRepositoryError::storage(error.to_string()))?;
+
+            pool.close().await;
+
+            Ok::<bool, PersonRepositoryError>(result.rows_affected() > 0)
+        })
+    }
+
+    fn list(&self, list_options: &ListOptions) -> Result<Vec<Person>, PersonRepositoryError> {
         let database_path = self.database_path()?;
         let runtime =
             Runtime::new().map_err(|error| PersonRepositoryError::storage(error.to_string()))?;
@@ -634,34 +1025,1138 @@ impl PersonRepository for SqlitePersonRepository {
                 .map_err(|error| PersonRepositoryError::storage(error.to_string()))?;
 
             let rows = sqlx::query(
-                r#"SELECT id, name, patronymic, surname, snils, email, address_id
+                r#"SELECT id, uid, name, patronymic, surname, snils, email, address_id, archived_at
                    FROM person
+                   WHERE ?1 OR archived_at IS NULL
                    ORDER BY id"#,
             )
+            .bind(list_options.include_archived)
             .fetch_all(&pool)
             .await
             .map_err(|error| PersonRepositoryError::storage(error.to_string()))?;
 
             pool.close().await;
 
-            let persons = rows
-                .into_iter()
-                .map(|row| {
-                    let snils: i64 = row.get("snils");
-
-                    Person {
-                        id: row.get("id"),
-                        name: row.get("name"),
-                        patronymic: row.get("patronymic"),
-                        surname: row.get("surname"),
-                        snils: snils.to_string(),
-                        email: row.get("email"),
-                        address_id: row.get("address_id"),
-                    }
-                })
-                .collect();
+            let persons = rows.iter().map(person_from_row).collect();
 
             Ok::<Vec<Person>, PersonRepositoryError>(persons)
         })
     }
+
+    fn archive(&self, id: i64) -> Result<bool, PersonRepositoryError> {
+        let database_path = self.database_path()?;
+        set_archived(&database_path, "person", id, true).map_err(PersonRepositoryError::storage)
+    }
+
+    fn restore(&self, id: i64) -> Result<bool, PersonRepositoryError> {
+        let database_path = self.database_path()?;
+        set_archived(&database_path, "person", id, false).map_err(PersonRepositoryError::storage)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use zdnp_core::{
+        CompletenessIssue, ConstructionObjectDto, ConstructionObjectRepository, DocumentAuthor,
+        DocumentDeveloper, DocumentDto, DocumentError, DocumentNumbering, DocumentRepository,
+        DocumentSourceRepository, DocumentStatus, DocumentType, NumberingScheme, ReferenceEntity,
+        ReferenceError, ReferenceRepository, RequirementCategory, RequirementRepository,
+        RequirementSectionDto, SignatoryDto, SignatoryRepository, SigningRole, SourceDocumentDto,
+        SourceDocumentKind, TechnicalIndicatorDto, TechnicalIndicatorRepository, WorkType,
+    };
+
+    pub(crate) struct TestDatabase {
+        _directory: tempfile::TempDir,
+        pub(crate) path: String,
+    }
+
+    impl TestDatabase {
+        pub(crate) fn new() -> Self {
+            let directory = tempfile::tempdir().expect("temp dir");
+            let path = directory
+                .path()
+                .join(DEFAULT_DATABASE_FILE_NAME)
+                .to_string_lossy()
+                .into_owned();
+
+            SqliteMigrations::with_file_name(path.as_str())
+                .run()
+                .expect("migrations should run");
+
+            Self {
+                _directory: directory,
+                path,
+            }
+        }
+    }
+
+    pub(crate) fn create_organization(database: &TestDatabase) -> i64 {
+        let address_id = zdnp_core::create_address(
+            &SqliteAddressRepository::with_file_name(database.path.as_str()),
+            &AddressDto {
+                region_code: Some("77".into()),
+                city: Some("Москва".into()),
+                ..Default::default()
+            },
+        )
+        .expect("address");
+
+        zdnp_core::create_organization(
+            &SqliteOrganizationRepository::with_file_name(database.path.as_str()),
+            &OrganizationDto {
+                full_name: Some("ООО \"Проект\"".into()),
+                abbreviated_name: Some("Проект".into()),
+                inn: Some("7700000000".into()),
+                kpp: Some("770001001".into()),
+                address_id,
+                email: Some("info@example.com".into()),
+                ..Default::default()
+            },
+        )
+        .expect("organization")
+    }
+
+    #[test]
+    fn archived_organizations_are_hidden_and_cannot_author_documents() {
+        let database = TestDatabase::new();
+        let organizations = SqliteOrganizationRepository::with_file_name(database.path.as_str());
+        let documents = SqliteDocumentRepository::with_file_name(database.path.as_str());
+        let references = SqliteReferenceRepository::with_file_name(database.path.as_str());
+        let organization_id = create_organization(&database);
+
+        zdnp_core::archive_organization(&organizations, organization_id).expect("archive");
+
+        let visible =
+            zdnp_core::list_organizations(&organizations, &ListOptions::default()).expect("list");
+        assert!(visible.is_empty());
+
+        let all = zdnp_core::list_organizations(
+            &organizations,
+            &ListOptions {
+                include_archived: true,
+            },
+        )
+        .expect("list");
+        assert_eq!(all.len(), 1);
+        assert!(all[0].archived_at.is_some());
+
+        let dto = DocumentDto {
+            name: Some("ЗНП".into()),
+            date: Some("2026-10-01".into()),
+            uid: Some("4f7a1c8e-0000-4000-8000-000000000001".into()),
+            author: Some(DocumentAuthor::Organization {
+                id: organization_id,
+            }),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            zdnp_core::create_document(&documents, &references, &dto),
+            Err(DocumentError::Reference(ReferenceError::Archived(
+                zdnp_core::EntityRef::Organization(organization_id)
+            )))
+        );
+
+        zdnp_core::restore_organization(&organizations, organization_id).expect("restore");
+
+        let id = zdnp_core::create_document(&documents, &references, &dto).expect("document");
+        let document = documents.find(id).expect("find").expect("document exists");
+        assert_eq!(
+            document.author,
+            DocumentAuthor::Organization {
+                id: organization_id
+            }
+        );
+        assert_eq!(document.date.as_deref(), Some("2026-10-01"));
+    }
+    #[test]
+    fn issued_document_is_exported_from_its_snapshot() {
+        let database = TestDatabase::new();
+        let documents = SqliteDocumentRepository::with_file_name(database.path.as_str());
+        let references = SqliteReferenceRepository::with_file_name(database.path.as_str());
+        let organization_id = create_organization(&database);
+
+        let id = zdnp_core::create_document(
+            &documents,
+            &references,
+            &DocumentDto {
+                name: Some("ЗНП".into()),
+                number: Some("42".into()),
+                date: Some("2026-10-01".into()),
+                uid: Some("4f7a1c8e-0000-4000-8000-000000000002".into()),
+                author: Some(DocumentAuthor::Organization {
+                    id: organization_id,
+                }),
+                developer: Some(DocumentDeveloper::Organization {
+                    id: organization_id,
+                }),
+                ..Default::default()
+            },
+        )
+        .expect("document");
+
+        let person_id = zdnp_core::create_person(
+            &SqlitePersonRepository::with_file_name(database.path.as_str()),
+            &PersonDto {
+                name: Some("Иван".into()),
+                surname: Some("Иванов".into()),
+                snils: Some("11223344595".into()),
+                email: Some("ivanov@example.com".into()),
+                address_id: 1,
+                ..Default::default()
+            },
+        )
+        .expect("person");
+        zdnp_core::add_signatory(
+            &documents,
+            &references,
+            id,
+            &SignatoryDto {
+                person_id,
+                position: Some("Генеральный директор".into()),
+                role: Some(SigningRole::Approval),
+                signed_on: Some("2026-10-02".into()),
+            },
+        )
+        .expect("approval signatory");
+
+        zdnp_core::submit_for_review(&documents, id).expect("submit");
+        assert_eq!(
+            zdnp_core::approve_document(&documents, id),
+            Err(DocumentError::Incomplete(vec![
+                CompletenessIssue::MissingSignatory {
+                    role: SigningRole::Agreement
+                }
+            ]))
+        );
+        zdnp_core::return_to_draft(&documents, id).expect("return to draft");
+        zdnp_core::add_signatory(
+            &documents,
+            &references,
+            id,
+            &SignatoryDto {
+                person_id,
+                position: Some("Главный инженер проекта".into()),
+                role: Some(SigningRole::Agreement),
+                signed_on: None,
+            },
+        )
+        .expect("agreement signatory");
+
+        zdnp_core::submit_for_review(&documents, id).expect("submit");
+        zdnp_core::approve_document(&documents, id).expect("approve");
+        zdnp_core::issue_document(&documents, &references, id).expect("issue");
+
+        let export = zdnp_core::export_document(&documents, &references, id).expect("export");
+        assert!(export.from_snapshot);
+        assert!(export.document.issued_at.is_some());
+        assert_eq!(
+            export
+                .entities
+                .iter()
+                .map(|entity| entity.reference())
+                .collect::<Vec<_>>(),
+            vec![
+                zdnp_core::EntityRef::Organization(organization_id),
+                zdnp_core::EntityRef::Address(1),
+                zdnp_core::EntityRef::Person(person_id),
+            ]
+        );
+        assert_eq!(
+            export
+                .signatories
+                .iter()
+                .map(|signatory| (signatory.role, signatory.signed_on.as_deref()))
+                .collect::<Vec<_>>(),
+            vec![
+                (SigningRole::Approval, Some("2026-10-02")),
+                (SigningRole::Agreement, None),
+            ]
+        );
+        assert_eq!(
+            zdnp_core::snapshot_changes(&documents, &references, id),
+            Ok(Vec::new())
+        );
+
+        let runtime = Runtime::new().unwrap();
+        let execute = |sql: &str| {
+            runtime.block_on(async {
+                let pool =
+                    SqlitePool::connect_with(SqliteConnectOptions::new().filename(&database.path))
+                        .await
+                        .unwrap();
+                sqlx::query(sql).execute(&pool).await.unwrap();
+                pool.close().await;
+            })
+        };
+        execute(
+            r#"CREATE TRIGGER fail_signatory BEFORE INSERT ON signatory
+               BEGIN SELECT RAISE(ABORT, 'disk is full'); END"#,
+        );
+        assert!(matches!(
+            zdnp_core::issue_revision(&documents, id, ""),
+            Err(DocumentError::Repository(_))
+        ));
+        assert_eq!(
+            documents.list().expect("list").len(),
+            1,
+            "the partial revision is deleted"
+        );
+        execute("DROP TRIGGER fail_signatory");
+
+        let revision_id = zdnp_core::issue_revision(&documents, id, "").expect("revision");
+        zdnp_core::submit_for_review(&documents, revision_id).expect("submit revision");
+        zdnp_core::approve_document(&documents, revision_id).expect("approve revision");
+        zdnp_core::issue_document(&documents, &references, revision_id).expect("issue revision");
+
+        let original = zdnp_core::find_document(&documents, id).expect("original");
+        assert_eq!(original.status, DocumentStatus::Superseded);
+        assert!(original.superseded_at.is_some());
+        assert_eq!(
+            zdnp_core::find_document(&documents, revision_id)
+                .expect("revision")
+                .status,
+            DocumentStatus::Issued
+        );
+    }
+
+    #[test]
+    fn only_draft_documents_are_updated() {
+        let database = TestDatabase::new();
+        let documents = SqliteDocumentRepository::with_file_name(database.path.as_str());
+        let references = SqliteReferenceRepository::with_file_name(database.path.as_str());
+        let organization_id = create_organization(&database);
+
+        let mut dto = DocumentDto {
+            name: Some("ЗНП".into()),
+            uid: Some("4f7a1c8e-0000-4000-8000-000000000003".into()),
+            author: Some(DocumentAuthor::Informal {
+                name: "Иванов И. И.".into(),
+            }),
+            ..Default::default()
+        };
+        let id = zdnp_core::create_document(&documents, &references, &dto).expect("document");
+
+        dto.number = Some("42".into());
+        dto.date = Some("2026-10-01".into());
+        dto.author = Some(DocumentAuthor::Organization {
+            id: organization_id,
+        });
+        dto.developer = Some(DocumentDeveloper::Organization {
+            id: organization_id,
+        });
+        zdnp_core::update_document(&documents, &references, id, &dto).expect("update");
+
+        let document = documents.find(id).expect("find").expect("document exists");
+        assert_eq!(document.status, DocumentStatus::Draft);
+        assert_eq!(
+            document.developer,
+            Some(DocumentDeveloper::Organization {
+                id: organization_id
+            })
+        );
+
+        let indicator = TechnicalIndicatorDto {
+            name: Some("Этажность".into()),
+            value: Some("4".into()),
+            unit_okei: Some("796".into()),
+        };
+        let indicator_id = zdnp_core::add_indicator(&documents, id, &indicator).expect("indicator");
+
+        zdnp_core::submit_for_review(&documents, id).expect("submit");
+        assert_eq!(
+            zdnp_core::update_document(&documents, &references, id, &dto),
+            Err(DocumentError::ReadOnly(id))
+        );
+
+        // The section repositories refuse the writes themselves, whoever calls them.
+        assert_eq!(documents.add_indicator(id, &indicator), Ok(None));
+        assert_eq!(
+            documents.update_indicator(indicator_id, &indicator),
+            Ok(false)
+        );
+        assert_eq!(documents.reorder_indicators(id, &[indicator_id]), Ok(false));
+        assert_eq!(documents.delete_indicator(indicator_id), Ok(false));
+        assert_eq!(
+            documents.save(
+                id,
+                &ConstructionObjectDto {
+                    name: Some("Школа".into()),
+                    purpose_code: Some("19.7.1.2".into()),
+                    work_type: Some(WorkType::Construction),
+                    address_id: 1,
+                    ..Default::default()
+                }
+            ),
+            Ok(None)
+        );
+        assert_eq!(
+            documents.add_requirement(
+                id,
+                &RequirementSectionDto {
+                    category: Some(RequirementCategory::FireSafety),
+                    title: Some("Пожарная безопасность".into()),
+                    text: Some("По СП 4.13130".into()),
+                    template_id: None,
+                }
+            ),
+            Ok(None)
+        );
+        assert_eq!(documents.attach_source(id, 1), Ok(false));
+        assert_eq!(
+            documents.add_signatory(
+                id,
+                &SignatoryDto {
+                    person_id: 1,
+                    position: Some("Главный инженер проекта".into()),
+                    role: Some(SigningRole::Agreement),
+                    signed_on: None,
+                }
+            ),
+            Ok(None)
+        );
+        assert_eq!(
+            zdnp_core::list_indicators(&documents, id).map(|indicators| indicators.len()),
+            Ok(1)
+        );
+
+        zdnp_core::return_to_draft(&documents, id).expect("return to draft");
+        let document = documents.find(id).expect("find").expect("document exists");
+        assert_eq!(document.status, DocumentStatus::Draft);
+        assert!(document.submitted_at.is_none());
+    }
+
+    #[test]
+    fn document_numbers_are_allocated_without_gaps() {
+        let database = TestDatabase::new();
+        let documents = SqliteDocumentRepository::with_file_name(database.path.as_str());
+        let references = SqliteReferenceRepository::with_file_name(database.path.as_str());
+        let organization_id = create_organization(&database);
+
+        let dto = |author_id| DocumentDto {
+            name: Some("ЗНП".into()),
+            document_type: Some(DocumentType::LinearObject),
+            author: Some(DocumentAuthor::Organization { id: author_id }),
+            numbering: Some(DocumentNumbering {
+                prefix: "ЗНП".into(),
+                year: 2026,
+            }),
+            ..Default::default()
+        };
+
+        let first = zdnp_core::create_document(&documents, &references, &dto(organization_id))
+            .expect("first");
+        // The author does not exist: the insert fails and the counter is rolled back.
+        assert!(documents.create(&dto(organization_id + 100)).is_err());
+        let second = zdnp_core::create_document(&documents, &references, &dto(organization_id))
+            .expect("second");
+
+        let first = documents
+            .find(first)
+            .expect("find")
+            .expect("document exists");
+        let second = documents
+            .find(second)
+            .expect("find")
+            .expect("document exists");
+        assert_eq!(first.number.as_deref(), Some("ЗНП-2026-0001"));
+        assert_eq!(second.number.as_deref(), Some("ЗНП-2026-0002"));
+        assert_eq!(first.document_type, Some(DocumentType::LinearObject));
+        assert_eq!(first.uid.len(), 36);
+        assert_ne!(first.uid, second.uid);
+
+        zdnp_core::create_numbering_scheme(
+            &documents,
+            &NumberingScheme {
+                prefix: "ТЗ".into(),
+                width: 3,
+            },
+        )
+        .expect("scheme");
+        assert_eq!(
+            zdnp_core::list_numbering_schemes(&documents).expect("schemes"),
+            vec![
+                NumberingScheme {
+                    prefix: "ЗНП".into(),
+                    width: 4,
+                },
+                NumberingScheme {
+                    prefix: "ТЗ".into(),
+                    width: 3,
+                },
+            ]
+        );
+
+        let mut other = dto(organization_id);
+        other.numbering = Some(DocumentNumbering {
+            prefix: "ТЗ".into(),
+            year: 2026,
+        });
+        let third = zdnp_core::create_document(&documents, &references, &other).expect("third");
+        let third = documents
+            .find(third)
+            .expect("find")
+            .expect("document exists");
+        assert_eq!(third.number.as_deref(), Some("ТЗ-2026-001"));
+
+        other.numbering = Some(DocumentNumbering {
+            prefix: "ПЗ".into(),
+            year: 2026,
+        });
+        assert_eq!(
+            zdnp_core::create_document(&documents, &references, &other),
+            Err(DocumentError::InvalidNumbering(
+                "unknown numbering scheme \"ПЗ\"".into()
+            ))
+        );
+    }
+
+    #[test]
+    fn construction_object_is_stored_with_its_land_plots() {
+        let database = TestDatabase::new();
+        let documents = SqliteDocumentRepository::with_file_name(database.path.as_str());
+        let references = SqliteReferenceRepository::with_file_name(database.path.as_str());
+        let organization_id = create_organization(&database);
+
+        let id = zdnp_core::create_document(
+            &documents,
+            &references,
+            &DocumentDto {
+                name: Some("ЗНП".into()),
+                author: Some(DocumentAuthor::Organization {
+                    id: organization_id,
+                }),
+                ..Default::default()
+            },
+        )
+        .expect("document");
+
+        let mut dto = ConstructionObjectDto {
+            name: Some("Школа на 550 мест".into()),
+            purpose_code: Some("19.7.1.1".into()),
+            address_id: 1,
+            cadastral_numbers: vec!["77:01:0001001:2".into(), "77:01:0001001:1".into()],
+            work_type: Some(WorkType::Construction),
+        };
+        let object_id =
+            zdnp_core::save_construction_object(&documents, &references, id, &dto).expect("object");
+
+        dto.cadastral_numbers.pop();
+        dto.work_type = Some(WorkType::Reconstruction);
+        assert_eq!(
+            zdnp_core::save_construction_object(&documents, &references, id, &dto),
+            Ok(object_id)
+        );
+
+        let object = zdnp_core::find_construction_object(&documents, id)
+            .expect("find")
+            .expect("object exists");
+        assert_eq!(object.cadastral_numbers, vec!["77:01:0001001:2"]);
+        assert_eq!(object.work_type, WorkType::Reconstruction);
+        assert_eq!(
+            documents.sections(id),
+            Ok(vec![zdnp_core::DocumentSection::ConstructionObject])
+        );
+
+        let export = zdnp_core::export_document(&documents, &references, id).expect("export");
+        assert_eq!(export.construction_object, Some(object));
+    }
+
+    #[test]
+    fn technical_indicators_keep_their_order() {
+        let database = TestDatabase::new();
+        let documents = SqliteDocumentRepository::with_file_name(database.path.as_str());
+        let references = SqliteReferenceRepository::with_file_name(database.path.as_str());
+        let organization_id = create_organization(&database);
+
+        let id = zdnp_core::create_document(
+            &documents,
+            &references,
+            &DocumentDto {
+                name: Some("ЗНП".into()),
+                author: Some(DocumentAuthor::Organization {
+                    id: organization_id,
+                }),
+                ..Default::default()
+            },
+        )
+        .expect("document");
+
+        let indicator = |name: &str, value: &str, unit: &str| TechnicalIndicatorDto {
+            name: Some(name.into()),
+            value: Some(value.into()),
+            unit_okei: Some(unit.into()),
+        };
+        let area =
+            zdnp_core::add_indicator(&documents, id, &indicator("Площадь", "1250,50", "055"))
+                .expect("area");
+        let floors = zdnp_core::add_indicator(&documents, id, &indicator("Этажность", "4", "796"))
+            .expect("floors");
+        assert_eq!(
+            documents.sections(id),
+            Ok(vec![zdnp_core::DocumentSection::Indicators])
+        );
+
+        zdnp_core::reorder_indicators(&documents, id, &[floors, area]).expect("reorder");
+        zdnp_core::update_indicator(&documents, area, &indicator("Общая площадь", "1300", "055"))
+            .expect("update");
+
+        let indicators = zdnp_core::list_indicators(&documents, id).expect("list");
+        let names: Vec<&str> = indicators.iter().map(|item| item.name.as_str()).collect();
+        assert_eq!(names, vec!["Этажность", "Общая площадь"]);
+        assert_eq!(indicators[1].value, "1300");
+
+        zdnp_core::delete_indicator(&documents, floors).expect("delete");
+        assert_eq!(
+            zdnp_core::list_indicators(&documents, id).map(|list| list.len()),
+            Ok(1)
+        );
+    }
+
+    #[test]
+    fn requirement_templates_are_rendered_with_document_data() {
+        let database = TestDatabase::new();
+        let documents = SqliteDocumentRepository::with_file_name(database.path.as_str());
+        let references = SqliteReferenceRepository::with_file_name(database.path.as_str());
+        let templates = SqliteRequirementTemplateRepository::with_file_name(database.path.as_str());
+        let organization_id = create_organization(&database);
+
+        let id = zdnp_core::create_document(
+            &documents,
+            &references,
+            &DocumentDto {
+                name: Some("ЗНП".into()),
+                author: Some(DocumentAuthor::Organization {
+                    id: organization_id,
+                }),
+                developer: Some(DocumentDeveloper::Organization {
+                    id: organization_id,
+                }),
+                ..Default::default()
+            },
+        )
+        .expect("document");
+        zdnp_core::save_construction_object(
+            &documents,
+            &references,
+            id,
+            &ConstructionObjectDto {
+                name: Some("Школа на 550 мест".into()),
+                purpose_code: Some("19.7.1.1".into()),
+                address_id: 1,
+                cadastral_numbers: Vec::new(),
+                work_type: Some(WorkType::Construction),
+            },
+        )
+        .expect("object");
+
+        let library = zdnp_core::list_requirement_templates(
+            &templates,
+            Some(RequirementCategory::Accessibility),
+        )
+        .expect("templates");
+        assert_eq!(library.len(), 1);
+
+        let requirement_id =
+            zdnp_core::insert_requirement_template(&documents, &templates, id, library[0].id)
+                .expect("insert");
+        zdnp_core::delete_requirement_template(&templates, library[0].id).expect("delete");
+
+        let requirements = zdnp_core::list_requirements(&documents, id).expect("list");
+        assert_eq!(requirements[0].id, requirement_id);
+        assert_eq!(requirements[0].template_id, None);
+        assert!(requirements[0].text.contains("{{object_name}}"));
+        assert_eq!(
+            documents.sections(id),
+            Ok(vec![
+                zdnp_core::DocumentSection::ConstructionObject,
+                zdnp_core::DocumentSection::Requirements,
+            ])
+        );
+
+        let export = zdnp_core::export_document(&documents, &references, id).expect("export");
+        assert!(
+            export.requirements[0]
+                .text
+                .contains("к объекту «Школа на 550 мест»")
+        );
+    }
+
+    #[test]
+    fn source_documents_are_shared_between_documents() {
+        let database = TestDatabase::new();
+        let documents = SqliteDocumentRepository::with_file_name(database.path.as_str());
+        let references = SqliteReferenceRepository::with_file_name(database.path.as_str());
+        let sources = SqliteSourceDocumentRepository::with_file_name(database.path.as_str());
+        let organization_id = create_organization(&database);
+
+        let gpzu_id = zdnp_core::create_source_document(
+            &sources,
+            &references,
+            &SourceDocumentDto {
+                kind: Some(SourceDocumentKind::Gpzu),
+                number: Some("РФ-77-4-53-3-12-2026-1234".into()),
+                date: Some("2026-09-15".into()),
+                issuer_id: organization_id,
+                title: None,
+                uid: None,
+            },
+        )
+        .expect("gpzu");
+
+        let create = || {
+            zdnp_core::create_document(
+                &documents,
+                &references,
+                &DocumentDto {
+                    name: Some("ЗНП".into()),
+                    author: Some(DocumentAuthor::Organization {
+                        id: organization_id,
+                    }),
+                    ..Default::default()
+                },
+            )
+            .expect("document")
+        };
+        let first = create();
+        let second = create();
+        let third = create();
+
+        for id in [first, second] {
+            zdnp_core::attach_source_document(&documents, &references, id, gpzu_id)
+                .expect("attach");
+        }
+        assert_eq!(
+            documents.sections(first),
+            Ok(vec![zdnp_core::DocumentSection::SourceDocuments])
+        );
+
+        let using = zdnp_core::documents_using_source_document(&documents, gpzu_id)
+            .expect("documents using gpzu");
+        assert_eq!(
+            using.iter().map(|document| document.id).collect::<Vec<_>>(),
+            vec![first, second]
+        );
+
+        zdnp_core::archive_source_document(&sources, gpzu_id).expect("archive");
+        assert_eq!(
+            zdnp_core::attach_source_document(&documents, &references, third, gpzu_id),
+            Err(zdnp_core::SourceDocumentError::Reference(
+                ReferenceError::Archived(zdnp_core::EntityRef::SourceDocument(gpzu_id))
+            ))
+        );
+
+        let export = zdnp_core::export_document(&documents, &references, first).expect("export");
+        assert!(export.entities.iter().any(|entity| matches!(
+            entity,
+            ReferenceEntity::SourceDocument(source) if source.id == gpzu_id
+        )));
+    }
+
+    #[test]
+    fn attachments_are_stored_by_content_and_verified_on_read() {
+        let database = TestDatabase::new();
+        let documents = SqliteDocumentRepository::with_file_name(database.path.as_str());
+        let references = SqliteReferenceRepository::with_file_name(database.path.as_str());
+        let organization_id = create_organization(&database);
+        let directory = Path::new(&database.path)
+            .parent()
+            .expect("database directory")
+            .to_path_buf();
+
+        let create = || {
+            zdnp_core::create_document(
+                &documents,
+                &references,
+                &DocumentDto {
+                    name: Some("ЗНП".into()),
+                    author: Some(DocumentAuthor::Organization {
+                        id: organization_id,
+                    }),
+                    ..Default::default()
+                },
+            )
+            .expect("document")
+        };
+        let first = create();
+        let second = create();
+
+        let scan = directory.join("ГПЗУ.pdf");
+        fs::write(&scan, b"%PDF-1.7 gpzu").expect("scan");
+
+        let attachment = zdnp_core::add_attachment(&documents, first, &scan).expect("attach");
+        let copy = zdnp_core::add_attachment(&documents, second, &scan).expect("attach copy");
+        assert_eq!(attachment.file_name, "ГПЗУ.pdf");
+        assert_eq!(attachment.mime_type, "application/pdf");
+        assert_eq!(attachment.size, 13);
+        assert_eq!(attachment.sha256, copy.sha256);
+
+        let stored = directory
+            .join("attachments")
+            .join(&attachment.sha256[..2])
+            .join(&attachment.sha256);
+        assert!(stored.is_file());
+
+        let extracted = directory.join("extracted.pdf");
+        zdnp_core::extract_attachment(&documents, attachment.id, &extracted).expect("extract");
+        assert_eq!(fs::read(&extracted).expect("read"), b"%PDF-1.7 gpzu");
+
+        zdnp_core::remove_attachment(&documents, attachment.id).expect("remove");
+        assert!(
+            stored.is_file(),
+            "content is still used by the second document"
+        );
+
+        fs::write(&stored, b"tampered").expect("tamper");
+        assert_eq!(
+            zdnp_core::extract_attachment(&documents, copy.id, &extracted),
+            Err(zdnp_core::AttachmentError::Repository(
+                zdnp_core::AttachmentRepositoryError::Corrupted(copy.id)
+            ))
+        );
+        assert_eq!(
+            fs::read(&extracted).expect("read"),
+            b"%PDF-1.7 gpzu",
+            "the earlier copy is not replaced with corrupted content"
+        );
+        let leftovers = fs::read_dir(&directory)
+            .expect("list")
+            .filter_map(Result::ok)
+            .filter(|entry| entry.file_name().to_string_lossy().contains(".extracting-"))
+            .count();
+        assert_eq!(leftovers, 0);
+
+        zdnp_core::remove_attachment(&documents, copy.id).expect("remove copy");
+        assert!(!stored.exists());
+        assert_eq!(
+            zdnp_core::list_attachments(&documents, second),
+            Ok(Vec::new())
+        );
+    }
+
+    #[test]
+    fn templates_are_cloned_with_their_sections() {
+        let database = TestDatabase::new();
+        let documents = SqliteDocumentRepository::with_file_name(database.path.as_str());
+        let references = SqliteReferenceRepository::with_file_name(database.path.as_str());
+        let organization_id = create_organization(&database);
+
+        let id = zdnp_core::create_document(
+            &documents,
+            &references,
+            &DocumentDto {
+                name: Some("ЗНП на школу".into()),
+                number: Some("ЗНП-2026-0001".into()),
+                author: Some(DocumentAuthor::Organization {
+                    id: organization_id,
+                }),
+                developer: Some(DocumentDeveloper::Organization {
+                    id: organization_id,
+                }),
+                ..Default::default()
+            },
+        )
+        .expect("document");
+        zdnp_core::save_construction_object(
+            &documents,
+            &references,
+            id,
+            &ConstructionObjectDto {
+                name: Some("Школа на 550 мест".into()),
+                purpose_code: Some("19.7.1.1".into()),
+                address_id: 1,
+                cadastral_numbers: vec!["77:01:0001001:1".into()],
+                work_type: Some(WorkType::Construction),
+            },
+        )
+        .expect("object");
+        zdnp_core::add_indicator(
+            &documents,
+            id,
+            &TechnicalIndicatorDto {
+                name: Some("Этажность".into()),
+                value: Some("4".into()),
+                unit_okei: Some("796".into()),
+            },
+        )
+        .expect("indicator");
+        zdnp_core::set_document_template(&documents, id, true).expect("template");
+
+        let clone_id = zdnp_core::clone_document(
+            &documents,
+            &references,
+            id,
+            &zdnp_core::CloneOverrides {
+                object_name: Some("Школа на 1100 мест".into()),
+                ..Default::default()
+            },
+        )
+        .expect("clone");
+
+        let original = documents.find(id).expect("find").expect("original");
+        let clone = documents.find(clone_id).expect("find").expect("clone");
+        assert!(original.is_template);
+        assert!(!clone.is_template);
+        assert_eq!(clone.name, "ЗНП на школу");
+        assert_eq!(clone.number, None);
+        assert_ne!(clone.uid, original.uid);
+        assert_eq!(clone.developer, original.developer);
+        assert_eq!(
+            documents.sections(clone_id),
+            Ok(vec![
+                zdnp_core::DocumentSection::ConstructionObject,
+                zdnp_core::DocumentSection::Indicators,
+            ])
+        );
+
+        let object = zdnp_core::find_construction_object(&documents, clone_id)
+            .expect("find")
+            .expect("object copied");
+        assert_eq!(object.name, "Школа на 1100 мест");
+        assert_eq!(object.cadastral_numbers, vec!["77:01:0001001:1"]);
+
+        let templates = zdnp_core::list_document_templates(&documents).expect("templates");
+        assert_eq!(templates, vec![original]);
+
+        let runtime = Runtime::new().unwrap();
+        let count = |table: &str| {
+            let sql = format!("SELECT COUNT(*) FROM {table}");
+            runtime.block_on(async {
+                let pool =
+                    SqlitePool::connect_with(SqliteConnectOptions::new().filename(&database.path))
+                        .await
+                        .unwrap();
+                let count: i64 = sqlx::query_scalar(&sql).fetch_one(&pool).await.unwrap();
+                pool.close().await;
+                count
+            })
+        };
+        let before = [
+            count("document"),
+            count("authors"),
+            count("developer"),
+            count("construction_object"),
+        ];
+        runtime.block_on(async {
+            let pool =
+                SqlitePool::connect_with(SqliteConnectOptions::new().filename(&database.path))
+                    .await
+                    .unwrap();
+            sqlx::query(
+                r#"CREATE TRIGGER fail_indicator BEFORE INSERT ON technical_indicator
+                   BEGIN SELECT RAISE(ABORT, 'disk is full'); END"#,
+            )
+            .execute(&pool)
+            .await
+            .unwrap();
+            pool.close().await;
+        });
+
+        assert!(matches!(
+            zdnp_core::clone_document(
+                &documents,
+                &references,
+                id,
+                &zdnp_core::CloneOverrides::default()
+            ),
+            Err(DocumentError::Repository(_))
+        ));
+        assert_eq!(
+            [
+                count("document"),
+                count("authors"),
+                count("developer"),
+                count("construction_object"),
+            ],
+            before,
+            "the partial clone is deleted"
+        );
+    }
+
+    #[test]
+    fn reference_import_stores_all_records_or_none() {
+        let database = TestDatabase::new();
+        let references = SqliteReferenceRepository::with_file_name(database.path.as_str());
+        let addresses = SqliteAddressRepository::with_file_name(database.path.as_str());
+        let address = AddressDto {
+            region_code: Some("77".into()),
+            city: Some("Москва".into()),
+            ..Default::default()
+        };
+        let organization = |inn: &str| zdnp_core::ReferenceRecord::Organization {
+            organization: OrganizationDto {
+                full_name: Some("ООО \"Проект\"".into()),
+                abbreviated_name: Some("Проект".into()),
+                inn: Some(inn.into()),
+                kpp: Some("770001001".into()),
+                email: Some("info@example.com".into()),
+                ..Default::default()
+            },
+            address: address.clone(),
+        };
+
+        let result = zdnp_core::import_reference_records(
+            &references,
+            &[organization("7700000000"), organization("77-00")],
+        );
+        assert!(matches!(
+            result,
+            Err(zdnp_core::ReferenceImportError::Repository(_))
+        ));
+        assert_eq!(addresses.list(&ListOptions::default()), Ok(Vec::new()));
+
+        let imported = zdnp_core::import_reference_records(
+            &references,
+            &[
+                organization("7700000000"),
+                zdnp_core::ReferenceRecord::Address {
+                    address: address.clone(),
+                },
+            ],
+        )
+        .expect("import");
+        assert_eq!(
+            imported,
+            vec![
+                zdnp_core::EntityRef::Organization(1),
+                zdnp_core::EntityRef::Address(2)
+            ]
+        );
+        let organizations = SqliteOrganizationRepository::with_file_name(database.path.as_str())
+            .list(&ListOptions::default())
+            .expect("organizations");
+        assert_eq!(organizations[0].address_id, 1);
+    }
+
+    #[test]
+    fn reference_data_is_found_by_uid() {
+        let database = TestDatabase::new();
+        let references = SqliteReferenceRepository::with_file_name(database.path.as_str());
+        let organization_id = create_organization(&database);
+
+        let organizations = SqliteOrganizationRepository::with_file_name(database.path.as_str())
+            .list(&ListOptions::default())
+            .expect("organizations");
+        let addresses = SqliteAddressRepository::with_file_name(database.path.as_str())
+            .list(&ListOptions::default())
+            .expect("addresses");
+        let uid = organizations[0].uid.clone();
+        assert_eq!(uid.len(), 36);
+        assert_ne!(uid, addresses[0].uid);
+
+        let found = zdnp_core::find_reference_by_uid(&references, &uid).expect("find");
+        assert_eq!(
+            found.as_ref().map(ReferenceEntity::reference),
+            Some(zdnp_core::EntityRef::Organization(organization_id))
+        );
+        assert_eq!(
+            references.find_by_uid(&addresses[0].uid),
+            Ok(Some(zdnp_core::EntityRef::Address(addresses[0].id)))
+        );
+        assert_eq!(references.find_by_uid("missing"), Ok(None));
+
+        // Writers that bypass the services cannot store rows without a uid.
+        let runtime = Runtime::new().unwrap();
+        let errors = runtime.block_on(async {
+            let pool =
+                SqlitePool::connect_with(SqliteConnectOptions::new().filename(&database.path))
+                    .await
+                    .unwrap();
+            let mut errors = Vec::new();
+            for sql in [
+                "INSERT INTO address (region_code) VALUES ('77')",
+                "INSERT INTO address (region_code, uid) VALUES ('77', ' ')",
+                "UPDATE organization SET uid = NULL",
+            ] {
+                errors.push(
+                    sqlx::query(sql)
+                        .execute(&pool)
+                        .await
+                        .err()
+                        .map(|error| error.to_string()),
+                );
+            }
+            pool.close().await;
+            errors
+        });
+        assert!(
+            errors.iter().all(|error| error
+                .as_deref()
+                .is_some_and(|error| error.contains("uid is required"))),
+            "{errors:?}"
+        );
+    }
+
+    #[test]
+    fn type_migration_keeps_unmapped_document_types() {
+        const TYPE_NOTE_MIGRATION: i64 = 20261005120000;
+        let directory = tempfile::tempdir().expect("temp dir");
+        let path = directory.path().join(DEFAULT_DATABASE_FILE_NAME);
+        let options = SqliteConnectOptions::new()
+            .filename(&path)
+            .create_if_missing(true);
+
+        let runtime = Runtime::new().unwrap();
+        let rows = runtime.block_on(async {
+            let pool = SqlitePool::connect_with(options).await.unwrap();
+            let apply = |before: bool| {
+                MIGRATOR
+                    .iter()
+                    .filter(move |migration| (migration.version < TYPE_NOTE_MIGRATION) == before)
+                    .map(|migration| migration.sql.to_string())
+            };
+
+            for sql in apply(true) {
+                sqlx::raw_sql(&sql).execute(&pool).await.unwrap();
+            }
+            sqlx::raw_sql(
+                r#"INSERT INTO authors (id, type) VALUES (1, 4);
+                   INSERT INTO neformal_author (author_id, name) VALUES (1, 'Иванов И. И.');
+                   INSERT INTO document (name, uid, author_id, type) VALUES
+                       ('ЗНП 1', 'a', 1, ' ЗНП на реконструкцию '),
+                       ('ЗНП 2', 'b', 1, 'Реставрация фасада'),
+                       ('ЗНП 3', 'c', 1, NULL)"#,
+            )
+            .execute(&pool)
+            .await
+            .unwrap();
+            for sql in apply(false) {
+                sqlx::raw_sql(&sql).execute(&pool).await.unwrap();
+            }
+
+            let rows: Vec<(String, Option<i64>, Option<String>)> =
+                sqlx::query_as("SELECT uid, type_id, type_note FROM document ORDER BY uid")
+                    .fetch_all(&pool)
+                    .await
+                    .unwrap();
+            pool.close().await;
+            rows
+        });
+
+        assert_eq!(
+            rows,
+            vec![
+                ("a".to_string(), Some(2), None),
+                (
+                    "b".to_string(),
+                    None,
+                    Some("Реставрация фасада".to_string())
+                ),
+                ("c".to_string(), None, None),
+            ]
+        );
+
+        let documents = SqliteDocumentRepository::with_file_name(path.to_string_lossy());
+        let notes: Vec<_> = documents
+            .list()
+            .expect("documents")
+            .into_iter()
+            .map(|document| document.type_note)
+            .collect();
+        assert_eq!(
+            notes,
+            vec![None, Some("Реставрация фасада".to_string()), None]
+        );
+    }
 }
@@ -0,0 +1,182 @@
+use sqlx::{SqliteConnection, SqlitePool, sqlite::SqliteConnectOptions};
+use tokio::runtime::Runtime;
+use zdnp_core::{
+    AddressDto, EntityRef, ReferenceImportRepository, ReferenceImportRepositoryError,
+    ReferenceRecord,
+};
+
+use crate::{SqliteReferenceRepository, entrepreneur_columns, organization_columns};
+
+// ---------------- Reference Data Import ----------------
+impl ReferenceImportRepository for SqliteReferenceRepository {
+    fn import(
+        &self,
+        records: &[ReferenceRecord],
+    ) -> Result<Vec<EntityRef>, ReferenceImportRepositoryError> {
+        let database_path = self
+            .database_path()
+            .map_err(|error| ReferenceImportRepositoryError::storage(error.to_string()))?;
+        let runtime = Runtime::new()
+            .map_err(|error| ReferenceImportRepositoryError::storage(error.to_string()))?;
+
+        runtime.block_on(async move {
+            let options = SqliteConnectOptions::new()
+                .filename(&database_path)
+                .create_if_missing(true);
+
+            let pool = SqlitePool::connect_with(options)
+                .await
+                .map_err(|error| ReferenceImportRepositoryError::storage(error.to_string()))?;
+
+            let mut transaction = pool
+                .begin()
+                .await
+                .map_err(|error| ReferenceImportRepositoryError::storage(error.to_string()))?;
+
+            let mut imported = Vec::with_capacity(records.len());
+            for (index, record) in records.iter().enumerate() {
+                let reference =
+                    insert_record(&mut transaction, record)
+                        .await
+                        .map_err(|message| {
+                            ReferenceImportRepositoryError::storage(format!(
+                                "Record {}: {message}",
+                                index + 1
+                            ))
+                        })?;
+                imported.push(reference);
+            }
+
+            transaction
+                .commit()
+                .await
+                .map_err(|error| ReferenceImportRepositoryError::storage(error.to_string()))?;
+
+            pool.close().await;
+
+            Ok::<Vec<EntityRef>, ReferenceImportRepositoryError>(imported)
+        })
+    }
+}
+
+async fn insert_record(
+    connection: &mut SqliteConnection,
+    record: &ReferenceRecord,
+) -> Result<EntityRef, String> {
+    let address_id = insert_address(connection, record.address()).await?;
+
+    let reference = match record {
+        ReferenceRecord::Address { .. } => EntityRef::Address(address_id),
+        ReferenceRecord::Organization { organization, .. } => {
+            let columns = organization_columns(organization).map_err(|error| error.to_string())?;
+            let id = sqlx::query(
+                r#"INSERT INTO organization (
+                    full_name, abbreviated_name, ogrn, rafp, inn, kpp, address_id, email, uid
+                ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)"#,
+            )
+            .bind(columns.full_name)
+            .bind(columns.abbreviated_name)
+            .bind(columns.ogrn)
+            .bind(columns.rafp)
+            .bind(columns.inn)
+            .bind(columns.kpp)
+            .bind(address_id)
+            .bind(columns.email)
+            .bind(required_uid(&organization.uid)?)
+            .execute(&mut *connection)
+            .await
+            .map_err(|error| error.to_string())?
+            .last_insert_rowid();
+            EntityRef::Organization(id)
+        }
+        ReferenceRecord::Entrepreneur { entrepreneur, .. } => {
+            let columns = entrepreneur_columns(entrepreneur).map_err(|error| error.to_string())?;
+            let id = sqlx::query(
+                r#"INSERT INTO entrepreneur (
+                    surname, name, patronymic, ogrnip, inn, address_id, email, uid
+                ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)"#,
+            )
+            .bind(columns.surname)
+            .bind(columns.name)
+            .bind(entrepreneur.patronymic.as_deref())
+            .bind(columns.ogrnip)
+            .bind(columns.inn)
+            .bind(address_id)
+            .bind(entrepreneur.email.as_deref())
+            .bind(required_uid(&entrepreneur.uid)?)
+            .execute(&mut *connection)
+            .await
+            .map_err(|error| error.to_string())?
+            .last_insert_rowid();
+            EntityRef::Entrepreneur(id)
+        }
+        ReferenceRecord::Person { person, .. } => {
+            let (Some(name), Some(surname), Some(snils), Some(email)) = (
+                person.name.as_deref(),
+                person.surname.as_deref(),
+                person.snils.as_deref(),
+                person.email.as_deref(),
+            ) else {
+                return Err("Name, surname, SNILS and email are required".to_string());
+            };
+            let snils: i64 = snils
+                .trim()
+                .parse()
+                .map_err(|error| format!("Invalid SNILS: {error}"))?;
+            let id = sqlx::query(
+                r#"INSERT INTO person (
+                    name, patronymic, surname, snils, email, address_id, uid
+                ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)"#,
+            )
+            .bind(name)
+            .bind(person.patronymic.as_deref())
+            .bind(surname)
+            .bind(snils)
+            .bind(email)
+            .bind(address_id)
+            .bind(required_uid(&person.uid)?)
+            .execute(&mut *connection)
+            .await
+            .map_err(|error| error.to_string())?
+            .last_insert_rowid();
+            EntityRef::Person(id)
+        }
+    };
+
+    Ok(reference)
+}
+
+fn required_uid(uid: &Option<String>) -> Result<&str, String> {
+    uid.as_deref().ok_or_else(|| "UID is required".to_string())
+}
+
+async fn insert_address(
+    connection: &mut SqliteConnection,
+    dto: &AddressDto,
+) -> Result<i64, String> {
+    let region_code = dto
+        .region_code
+        .as_deref()
+        .ok_or_else(|| "Region code is required".to_string())?;
+
+    let result = sqlx::query(
+        r#"INSERT INTO address (
+            region_code, note, country, district, city, settlement, street, building, room, uid
+        ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)"#,
+    )
+    .bind(region_code)
+    .bind(dto.note.as_deref())
+    .bind(dto.country.as_deref())
+    .bind(dto.district.as_deref())
+    .bind(dto.city.as_deref())
+    .bind(dto.settlement.as_deref())
+    .bind(dto.street.as_deref())
+    .bind(dto.building.as_deref())
+    .bind(dto.room.as_deref())
+    .bind(required_uid(&dto.uid)?)
+    .execute(&mut *connection)
+    .await
+    .map_err(|error| error.to_string())?;
+
+    Ok(result.last_insert_rowid())
+}
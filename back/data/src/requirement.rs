@@ -0,0 +1,434 @@
+use std::path::PathBuf;
+
+use sqlx::{Row, SqlitePool, sqlite::SqliteConnectOptions, sqlite::SqliteRow};
+use tokio::runtime::Runtime;
+use zdnp_core::{
+    RequirementCategory, RequirementRepository, RequirementRepositoryError, RequirementSection,
+    RequirementSectionDto, RequirementTemplate, RequirementTemplateDto,
+    RequirementTemplateRepository, RequirementTemplateRepositoryError,
+};
+
+use crate::{DEFAULT_DATABASE_FILE_NAME, SqliteDocumentRepository};
+
+fn category_from_row(row: &SqliteRow) -> Result<RequirementCategory, String> {
+    let category: String = row.get("category");
+    RequirementCategory::parse(&category)
+        .ok_or_else(|| format!("Unknown requirement category: {category}"))
+}
+
+fn category_value(category: Option<RequirementCategory>) -> Option<&'static str> {
+    category.map(RequirementCategory::as_str)
+}
+
+// ---------------- Requirement Section Data Repository ----------------
+fn requirement_from_row(row: &SqliteRow) -> Result<RequirementSection, RequirementRepositoryError> {
+    Ok(RequirementSection {
+        id: row.get("id"),
+        document_id: row.get("document_id"),
+        position: row.get("position"),
+        category: category_from_row(row).map_err(RequirementRepositoryError::storage)?,
+        title: row.get("title"),
+        text: row.get("text"),
+        template_id: row.get("template_id"),
+    })
+}
+
+impl SqliteDocumentRepository {
+    fn requirement_pool_options(
+        &self,
+    ) -> Result<(Runtime, SqliteConnectOptions), RequirementRepositoryError> {
+        let database_path = self
+            .database_path()
+            .map_err(|error| RequirementRepositoryError::storage(error.to_string()))?;
+        let runtime = Runtime::new()
+            .map_err(|error| RequirementRepositoryError::storage(error.to_string()))?;
+        let options = SqliteConnectOptions::new()
+            .filename(database_path)
+            .create_if_missing(true);
+
+        Ok((runtime, options))
+    }
+}
+
+impl RequirementRepository for SqliteDocumentRepository {
+    fn add_requirement(
+        &self,
+        document_id: i64,
+        dto: &RequirementSectionDto,
+    ) -> Result<Option<i64>, RequirementRepositoryError> {
+        let (runtime, options) = self.requirement_pool_options()?;
+
+        runtime.block_on(async move {
+            let pool = SqlitePool::connect_with(options)
+                .await
+                .map_err(|error| RequirementRepositoryError::storage(error.to_string()))?;
+
+            let result = sqlx::query(
+                r#"INSERT INTO requirement_section (
+                       document_id, position, category, title, text, template_id
+                   )
+                   SELECT
+                       ?1,
+                       (SELECT COALESCE(MAX(position) + 1, 0)
+                        FROM requirement_section WHERE document_id = ?1),
+                       ?2, ?3, ?4, ?5
+                   WHERE EXISTS (SELECT 1 FROM document WHERE id = ?1 AND status = 'draft')"#,
+            )
+            .bind(document_id)
+            .bind(category_value(dto.category))
+            .bind(dto.title.as_deref())
+            .bind(dto.text.as_deref())
+            .bind(dto.template_id)
+            .execute(&pool)
+            .await
+            .map_err(|error| RequirementRepositoryError::storage(error.to_string()))?;
+
+            pool.close().await;
+
+            Ok::<Option<i64>, RequirementRepositoryError>(
+                (result.rows_affected() > 0).then(|| result.last_insert_rowid()),
+            )
+        })
+    }
+
+    fn update_requirement(
+        &self,
+        id: i64,
+        dto: &RequirementSectionDto,
+    ) -> Result<bool, RequirementRepositoryError> {
+        let (runtime, options) = self.requirement_pool_options()?;
+
+        runtime.block_on(async move {
+            let pool = SqlitePool::connect_with(options)
+                .await
+                .map_err(|error| RequirementRepositoryError::storage(error.to_string()))?;
+
+            let result = sqlx::query(
+                r#"UPDATE requirement_section SET category = ?1, title = ?2, text = ?3
+                   WHERE id = ?4
+                     AND document_id IN (SELECT id FROM document WHERE status = 'draft')"#,
+            )
+            .bind(category_value(dto.category))
+            .bind(dto.title.as_deref())
+            .bind(dto.text.as_deref())
+            .bind(id)
+            .execute(&pool)
+            .await
+            .map_err(|error| RequirementRepositoryError::storage(error.to_string()))?;
+
+            pool.close().await;
+
+            Ok::<bool, RequirementRepositoryError>(result.rows_affected() > 0)
+        })
+    }
+
+    fn delete_requirement(&self, id: i64) -> Result<bool, RequirementRepositoryError> {
+        let (runtime, options) = self.requirement_pool_options()?;
+
+        runtime.block_on(async move {
+            let pool = SqlitePool::connect_with(options)
+                .await
+                .map_err(|error| RequirementRepositoryError::storage(error.to_string()))?;
+
+            let result = sqlx::query(
+                r#"DELETE FROM requirement_section
+                   WHERE id = ?1
+                     AND document_id IN (SELECT id FROM document WHERE status = 'draft')"#,
+            )
+            .bind(id)
+            .execute(&pool)
+            .await
+            .map_err(|error| RequirementRepositoryError::storage(error.to_string()))?;
+
+            pool.close().await;
+
+            Ok::<bool, RequirementRepositoryError>(result.rows_affected() > 0)
+        })
+    }
+
+    fn find_requirement(
+        &self,
+        id: i64,
+    ) -> Result<Option<RequirementSection>, RequirementRepositoryError> {
+        let (runtime, options) = self.requirement_pool_options()?;
+
+        runtime.block_on(async move {
+            let pool = SqlitePool::connect_with(options)
+                .await
+                .map_err(|error| RequirementRepositoryError::storage(error.to_string()))?;
+
+            let row = sqlx::query("SELECT * FROM requirement_section WHERE id = ?1")
+                .bind(id)
+                .fetch_optional(&pool)
+                .await
+                .map_err(|error| RequirementRepositoryError::storage(error.to_string()))?;
+
+            pool.close().await;
+
+            row.as_ref().map(requirement_from_row).transpose()
+        })
+    }
+
+    fn requirements(
+        &self,
+        document_id: i64,
+    ) -> Result<Vec<RequirementSection>, RequirementRepositoryError> {
+        let (runtime, options) = self.requirement_pool_options()?;
+
+        runtime.block_on(async move {
+            let pool = SqlitePool::connect_with(options)
+                .await
+                .map_err(|error| RequirementRepositoryError::storage(error.to_string()))?;
+
+            let rows = sqlx::query(
+                "SELECT * FROM requirement_section WHERE document_id = ?1 ORDER BY position, id",
+            )
+            .bind(document_id)
+            .fetch_all(&pool)
+            .await
+            .map_err(|error| RequirementRepositoryError::storage(error.to_string()))?;
+
+            pool.close().await;
+
+            rows.iter().map(requirement_from_row).collect()
+        })
+    }
+
+    fn reorder_requirements(
+        &self,
+        document_id: i64,
+        ids: &[i64],
+    ) -> Result<bool, RequirementRepositoryError> {
+        let (runtime, options) = self.requirement_pool_options()?;
+
+        runtime.block_on(async move {
+            let pool = SqlitePool::connect_with(options)
+                .await
+                .map_err(|error| RequirementRepositoryError::storage(error.to_string()))?;
+
+            let mut transaction = pool
+                .begin()
+                .await
+                .map_err(|error| RequirementRepositoryError::storage(error.to_string()))?;
+
+            let draft: bool = sqlx::query_scalar(
+                "SELECT EXISTS (SELECT 1 FROM document WHERE id = ?1 AND status = 'draft')",
+            )
+            .bind(document_id)
+            .fetch_one(&mut *transaction)
+            .await
+            .map_err(|error| RequirementRepositoryError::storage(error.to_string()))?;
+            if !draft {
+                return Ok(false);
+            }
+
+            for (position, id) in ids.iter().enumerate() {
+                sqlx::query(
+                    "UPDATE requirement_section SET position = ?1 WHERE id = ?2 AND document_id = ?3",
+                )
+                .bind(position as i64)
+                .bind(*id)
+                .bind(document_id)
+                .execute(&mut *transaction)
+                .await
+                .map_err(|error| RequirementRepositoryError::storage(error.to_string()))?;
+            }
+
+            transaction
+                .commit()
+                .await
+                .map_err(|error| RequirementRepositoryError::storage(error.to_string()))?;
+
+            pool.close().await;
+
+            Ok::<bool, RequirementRepositoryError>(true)
+        })
+    }
+}
+
+// ---------------- Requirement Template Data Repository ----------------
+pub struct SqliteRequirementTemplateRepository {
+    database_file_name: String,
+}
+
+impl SqliteRequirementTemplateRepository {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_file_name<S: Into<String>>(file_name: S) -> Self {
+        Self {
+            database_file_name: file_name.into(),
+        }
+    }
+
+    fn database_path(&self) -> Result<PathBuf, RequirementTemplateRepositoryError> {
+        let executable = std::env::current_exe()
+            .map_err(|error| RequirementTemplateRepositoryError::storage(error.to_string()))?;
+        let directory = executable.parent().ok_or_else(|| {
+            RequirementTemplateRepositoryError::storage("Failed to determine application directory")
+        })?;
+
+        Ok(directory.join(&self.database_file_name))
+    }
+
+    fn pool_options(
+        &self,
+    ) -> Result<(Runtime, SqliteConnectOptions), RequirementTemplateRepositoryError> {
+        let database_path = self.database_path()?;
+        let runtime = Runtime::new()
+            .map_err(|error| RequirementTemplateRepositoryError::storage(error.to_string()))?;
+        let options = SqliteConnectOptions::new()
+            .filename(database_path)
+            .create_if_missing(true);
+
+        Ok((runtime, options))
+    }
+}
+
+impl Default for SqliteRequirementTemplateRepository {
+    fn default() -> Self {
+        Self {
+            database_file_name: DEFAULT_DATABASE_FILE_NAME.to_string(),
+        }
+    }
+}
+
+fn template_from_row(
+    row: &SqliteRow,
+) -> Result<RequirementTemplate, RequirementTemplateRepositoryError> {
+    Ok(RequirementTemplate {
+        id: row.get("id"),
+        category: category_from_row(row).map_err(RequirementTemplateRepositoryError::storage)?,
+        title: row.get("title"),
+        text: row.get("text"),
+    })
+}
+
+impl RequirementTemplateRepository for SqliteRequirementTemplateRepository {
+    fn create(
+        &self,
+        dto: &RequirementTemplateDto,
+    ) -> Result<i64, RequirementTemplateRepositoryError> {
+        let (runtime, options) = self.pool_options()?;
+
+        runtime.block_on(async move {
+            let pool = SqlitePool::connect_with(options)
+                .await
+                .map_err(|error| RequirementTemplateRepositoryError::storage(error.to_string()))?;
+
+            let result = sqlx::query(
+                "INSERT INTO requirement_template (category, title, text) VALUES (?1, ?2, ?3)",
+            )
+            .bind(category_value(dto.category))
+            .bind(dto.title.as_deref())
+            .bind(dto.text.as_deref())
+            .execute(&pool)
+            .await
+            .map_err(|error| RequirementTemplateRepositoryError::storage(error.to_string()))?;
+
+            pool.close().await;
+
+            Ok::<i64, RequirementTemplateRepositoryError>(result.last_insert_rowid())
+        })
+    }
+
+    fn update(
+        &self,
+        id: i64,
+        dto: &RequirementTemplateDto,
+    ) -> Result<bool, RequirementTemplateRepositoryError> {
+        let (runtime, options) = self.pool_options()?;
+
+        runtime.block_on(async move {
+            let pool = SqlitePool::connect_with(options)
+                .await
+                .map_err(|error| RequirementTemplateRepositoryError::storage(error.to_string()))?;
+
+            let result = sqlx::query(
+                "UPDATE requirement_template SET category = ?1, title = ?2, text = ?3 WHERE id = ?4",
+            )
+            .bind(category_value(dto.category))
+            .bind(dto.title.as_deref())
+            .bind(dto.text.as_deref())
+            .bind(id)
+            .execute(&pool)
+            .await
+            .map_err(|error| RequirementTemplateRepositoryError::storage(error.to_string()))?;
+
+            pool.close().await;
+
+            Ok::<bool, RequirementTemplateRepositoryError>(result.rows_affected() > 0)
+        })
+    }
+
+    fn delete(&self, id: i64) -> Result<bool, RequirementTemplateRepositoryError> {
+        let (runtime, options) = self.pool_options()?;
+
+        runtime.block_on(async move {
+            let pool = SqlitePool::connect_with(options)
+                .await
+                .map_err(|error| RequirementTemplateRepositoryError::storage(error.to_string()))?;
+
+            let result = sqlx::query("DELETE FROM requirement_template WHERE id = ?1")
+                .bind(id)
+                .execute(&pool)
+                .await
+                .map_err(|error| RequirementTemplateRepositoryError::storage(error.to_string()))?;
+
+            pool.close().await;
+
+            Ok::<bool, RequirementTemplateRepositoryError>(result.rows_affected() > 0)
+        })
+    }
+
+    fn find(
+        &self,
+        id: i64,
+    ) -> Result<Option<RequirementTemplate>, RequirementTemplateRepositoryError> {
+        let (runtime, options) = self.pool_options()?;
+
+        runtime.block_on(async move {
+            let pool = SqlitePool::connect_with(options)
+                .await
+                .map_err(|error| RequirementTemplateRepositoryError::storage(error.to_string()))?;
+
+            let row = sqlx::query("SELECT * FROM requirement_template WHERE id = ?1")
+                .bind(id)
+                .fetch_optional(&pool)
+                .await
+                .map_err(|error| RequirementTemplateRepositoryError::storage(error.to_string()))?;
+
+            pool.close().await;
+
+            row.as_ref().map(template_from_row).transpose()
+        })
+    }
+
+    fn list(
+        &self,
+        category: Option<RequirementCategory>,
+    ) -> Result<Vec<RequirementTemplate>, RequirementTemplateRepositoryError> {
+        let (runtime, options) = self.pool_options()?;
+
+        runtime.block_on(async move {
+            let pool = SqlitePool::connect_with(options)
+                .await
+                .map_err(|error| RequirementTemplateRepositoryError::storage(error.to_string()))?;
+
+            let rows = sqlx::query(
+                r#"SELECT * FROM requirement_template
+                   WHERE ?1 IS NULL OR category = ?1
+                   ORDER BY category, title, id"#,
+            )
+            .bind(category_value(category))
+            .fetch_all(&pool)
+            .await
+            .map_err(|error| RequirementTemplateRepositoryError::storage(error.to_string()))?;
+
+            pool.close().await;
+
+            rows.iter().map(template_from_row).collect()
+        })
+    }
+}
@@ -0,0 +1,176 @@
+use sqlx::{Row, SqlitePool, sqlite::SqliteConnectOptions, sqlite::SqliteRow};
+use tokio::runtime::Runtime;
+use zdnp_core::{
+    Signatory, SignatoryDto, SignatoryRepository, SignatoryRepositoryError, SigningRole,
+};
+
+use crate::SqliteDocumentRepository;
+
+// ---------------- Signatory Data Repository ----------------
+fn signatory_from_row(row: &SqliteRow) -> Result<Signatory, SignatoryRepositoryError> {
+    let role: String = row.get("role");
+
+    Ok(Signatory {
+        id: row.get("id"),
+        document_id: row.get("document_id"),
+        person_id: row.get("person_id"),
+        position: row.get("position"),
+        role: SigningRole::parse(&role).ok_or_else(|| {
+            SignatoryRepositoryError::storage(format!("Unknown signing role: {role}"))
+        })?,
+        signed_on: row.get("signed_on"),
+    })
+}
+
+impl SqliteDocumentRepository {
+    fn signatory_pool_options(
+        &self,
+    ) -> Result<(Runtime, SqliteConnectOptions), SignatoryRepositoryError> {
+        let database_path = self
+            .database_path()
+            .map_err(|error| SignatoryRepositoryError::storage(error.to_string()))?;
+        let runtime =
+            Runtime::new().map_err(|error| SignatoryRepositoryError::storage(error.to_string()))?;
+        let options = SqliteConnectOptions::new()
+            .filename(database_path)
+            .create_if_missing(true);
+
+        Ok((runtime, options))
+    }
+}
+
+impl SignatoryRepository for SqliteDocumentRepository {
+    fn add_signatory(
+        &self,
+        document_id: i64,
+        dto: &SignatoryDto,
+    ) -> Result<Option<i64>, SignatoryRepositoryError> {
+        let (runtime, options) = self.signatory_pool_options()?;
+
+        runtime.block_on(async move {
+            let pool = SqlitePool::connect_with(options)
+                .await
+                .map_err(|error| SignatoryRepositoryError::storage(error.to_string()))?;
+
+            let result = sqlx::query(
+                r#"INSERT INTO signatory (document_id, person_id, position, role, signed_on)
+                   SELECT ?1, ?2, ?3, ?4, ?5
+                   WHERE EXISTS (SELECT 1 FROM document WHERE id = ?1 AND status = 'draft')"#,
+            )
+            .bind(document_id)
+            .bind(dto.person_id)
+            .bind(dto.position.as_deref())
+            .bind(dto.role.map(SigningRole::as_str))
+            .bind(dto.signed_on.as_deref())
+            .execute(&pool)
+            .await
+            .map_err(|error| SignatoryRepositoryError::storage(error.to_string()))?;
+
+            pool.close().await;
+
+            Ok::<Option<i64>, SignatoryRepositoryError>(
+                (result.rows_affected() > 0).then(|| result.last_insert_rowid()),
+            )
+        })
+    }
+
+    fn update_signatory(
+        &self,
+        id: i64,
+        dto: &SignatoryDto,
+    ) -> Result<bool, SignatoryRepositoryError> {
+        let (runtime, options) = self.signatory_pool_options()?;
+
+        runtime.block_on(async move {
+            let pool = SqlitePool::connect_with(options)
+                .await
+                .map_err(|error| SignatoryRepositoryError::storage(error.to_string()))?;
+
+            let result = sqlx::query(
+                r#"UPDATE signatory
+                   SET person_id = ?1, position = ?2, role = ?3, signed_on = ?4
+                   WHERE id = ?5
+                     AND document_id IN (SELECT id FROM document WHERE status = 'draft')"#,
+            )
+            .bind(dto.person_id)
+            .bind(dto.position.as_deref())
+            .bind(dto.role.map(SigningRole::as_str))
+            .bind(dto.signed_on.as_deref())
+            .bind(id)
+            .execute(&pool)
+            .await
+            .map_err(|error| SignatoryRepositoryError::storage(error.to_string()))?;
+
+            pool.close().await;
+
+            Ok::<bool, SignatoryRepositoryError>(result.rows_affected() > 0)
+        })
+    }
+
+    fn delete_signatory(&self, id: i64) -> Result<bool, SignatoryRepositoryError> {
+        let (runtime, options) = self.signatory_pool_options()?;
+
+        runtime.block_on(async move {
+            let pool = SqlitePool::connect_with(options)
+                .await
+                .map_err(|error| SignatoryRepositoryError::storage(error.to_string()))?;
+
+            let result = sqlx::query(
+                r#"DELETE FROM signatory
+                   WHERE id = ?1
+                     AND document_id IN (SELECT id FROM document WHERE status = 'draft')"#,
+            )
+            .bind(id)
+            .execute(&pool)
+            .await
+            .map_err(|error| SignatoryRepositoryError::storage(error.to_string()))?;
+
+            pool.close().await;
+
+            Ok::<bool, SignatoryRepositoryError>(result.rows_affected() > 0)
+        })
+    }
+
+    fn find_signatory(&self, id: i64) -> Result<Option<Signatory>, SignatoryRepositoryError> {
+        let (runtime, options) = self.signatory_pool_options()?;
+
+        runtime.block_on(async move {
+            let pool = SqlitePool::connect_with(options)
+                .await
+                .map_err(|error| SignatoryRepositoryError::storage(error.to_string()))?;
+
+            let row = sqlx::query("SELECT * FROM signatory WHERE id = ?1")
+                .bind(id)
+                .fetch_optional(&pool)
+                .await
+                .map_err(|error| SignatoryRepositoryError::storage(error.to_string()))?;
+
+            pool.close().await;
+
+            row.as_ref().map(signatory_from_row).transpose()
+        })
+    }
+
+    fn signatories(&self, document_id: i64) -> Result<Vec<Signatory>, SignatoryRepositoryError> {
+        let (runtime, options) = self.signatory_pool_options()?;
+
+        runtime.block_on(async move {
+            let pool = SqlitePool::connect_with(options)
+                .await
+                .map_err(|error| SignatoryRepositoryError::storage(error.to_string()))?;
+
+            let rows = sqlx::query(
+                r#"SELECT * FROM signatory WHERE document_id = ?1
+                   ORDER BY role = 'agreement', id"#,
+            )
+            .bind(document_id)
+            .fetch_all(&pool)
+            .await
+            .map_err(|error| SignatoryRepositoryError::storage(error.to_string()))?;
+
+            pool.close().await;
+
+            rows.iter().map(signatory_from_row).collect()
+        })
+    }
+}
@@ -0,0 +1,329 @@
+use std::path::PathBuf;
+
+use sqlx::{Row, SqlitePool, sqlite::SqliteConnectOptions, sqlite::SqliteRow};
+use tokio::runtime::Runtime;
+use zdnp_core::{
+    DocumentSourceRepository, ListOptions, SourceDocument, SourceDocumentDto, SourceDocumentKind,
+    SourceDocumentRepository, SourceDocumentRepositoryError,
+};
+
+use crate::{DEFAULT_DATABASE_FILE_NAME, SqliteDocumentRepository, set_archived};
+
+// ---------------- Source Document Data Repository ----------------
+pub struct SqliteSourceDocumentRepository {
+    database_file_name: String,
+}
+
+impl SqliteSourceDocumentRepository {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_file_name<S: Into<String>>(file_name: S) -> Self {
+        Self {
+            database_file_name: file_name.into(),
+        }
+    }
+
+    fn database_path(&self) -> Result<PathBuf, SourceDocumentRepositoryError> {
+        let executable = std::env::current_exe()
+            .map_err(|error| SourceDocumentRepositoryError::storage(error.to_string()))?;
+        let directory = executable.parent().ok_or_else(|| {
+            SourceDocumentRepositoryError::storage("Failed to determine application directory")
+        })?;
+
+        Ok(directory.join(&self.database_file_name))
+    }
+}
+
+impl Default for SqliteSourceDocumentRepository {
+    fn default() -> Self {
+        Self {
+            database_file_name: DEFAULT_DATABASE_FILE_NAME.to_string(),
+        }
+    }
+}
+
+pub(crate) fn source_document_from_row(row: &SqliteRow) -> Result<SourceDocument, String> {
+    let kind: String = row.get("kind");
+
+    Ok(SourceDocument {
+        id: row.get("id"),
+        uid: row.get("uid"),
+        kind: SourceDocumentKind::parse(&kind)
+            .ok_or_else(|| format!("Unknown source document kind: {kind}"))?,
+        number: row.get("number"),
+        date: row.get("date"),
+        issuer_id: row.get("issuer_id"),
+        title: row.get("title"),
+        archived_at: row.get("archived_at"),
+    })
+}
+
+impl SourceDocumentRepository for SqliteSourceDocumentRepository {
+    fn create(&self, dto: &SourceDocumentDto) -> Result<i64, SourceDocumentRepositoryError> {
+        let database_path = self.database_path()?;
+        let runtime = Runtime::new()
+            .map_err(|error| SourceDocumentRepositoryError::storage(error.to_string()))?;
+
+        runtime.block_on(async move {
+            let options = SqliteConnectOptions::new()
+                .filename(&database_path)
+                .create_if_missing(true);
+
+            let pool = SqlitePool::connect_with(options)
+                .await
+                .map_err(|error| SourceDocumentRepositoryError::storage(error.to_string()))?;
+
+            let uid = dto
+                .uid
+                .as_deref()
+                .ok_or_else(|| SourceDocumentRepositoryError::storage("UID is required"))?;
+
+            let result = sqlx::query(
+                r#"INSERT INTO source_document (kind, number, date, issuer_id, title, uid)
+                   VALUES (?1, ?2, ?3, ?4, ?5, ?6)"#,
+            )
+            .bind(dto.kind.map(SourceDocumentKind::as_str))
+            .bind(dto.number.as_deref())
+            .bind(dto.date.as_deref())
+            .bind(dto.issuer_id)
+            .bind(dto.title.as_deref())
+            .bind(uid)
+            .execute(&pool)
+            .await
+            .map_err(|error| SourceDocumentRepositoryError::storage(error.to_string()))?;
+
+            pool.close().await;
+
+            Ok::<i64, SourceDocumentRepositoryError>(result.last_insert_rowid())
+        })
+    }
+
+    fn update(
+        &self,
+        id: i64,
+        dto: &SourceDocumentDto,
+    ) -> Result<bool, SourceDocumentRepositoryError> {
+        let database_path = self.database_path()?;
+        let runtime = Runtime::new()
+            .map_err(|error| SourceDocumentRepositoryError::storage(error.to_string()))?;
+
+        runtime.block_on(async move {
+            let options = SqliteConnectOptions::new()
+                .filename(&database_path)
+                .create_if_missing(true);
+
+            let pool = SqlitePool::connect_with(options)
+                .await
+                .map_err(|error| SourceDocumentRepositoryError::storage(error.to_string()))?;
+
+            let result = sqlx::query(
+                r#"UPDATE source_document
+                   SET kind = ?1, number = ?2, date = ?3, issuer_id = ?4, title = ?5
+                   WHERE id = ?6"#,
+            )
+            .bind(dto.kind.map(SourceDocumentKind::as_str))
+            .bind(dto.number.as_deref())
+            .bind(dto.date.as_deref())
+            .bind(dto.issuer_id)
+            .bind(dto.title.as_deref())
+            .bind(id)
+            .execute(&pool)
+            .await
+            .map_err(|error| SourceDocumentRepositoryError::storage(error.to_string()))?;
+
+            pool.close().await;
+
+            Ok::<bool, SourceDocumentRepositoryError>(result.rows_affected() > 0)
+        })
+    }
+
+    fn list(
+        &self,
+        list_options: &ListOptions,
+    ) -> Result<Vec<SourceDocument>, SourceDocumentRepositoryError> {
+        let database_path = self.database_path()?;
+        let runtime = Runtime::new()
+            .map_err(|error| SourceDocumentRepositoryError::storage(error.to_string()))?;
+
+        runtime.block_on(async move {
+            let options = SqliteConnectOptions::new()
+                .filename(&database_path)
+                .create_if_missing(true);
+
+            let pool = SqlitePool::connect_with(options)
+                .await
+                .map_err(|error| SourceDocumentRepositoryError::storage(error.to_string()))?;
+
+            let rows = sqlx::query(
+                r#"SELECT * FROM source_document
+                   WHERE ?1 OR archived_at IS NULL
+                   ORDER BY date DESC, id"#,
+            )
+            .bind(list_options.include_archived)
+            .fetch_all(&pool)
+            .await
+            .map_err(|error| SourceDocumentRepositoryError::storage(error.to_string()))?;
+
+            pool.close().await;
+
+            rows.iter()
+                .map(|row| {
+                    source_document_from_row(row).map_err(SourceDocumentRepositoryError::storage)
+                })
+                .collect()
+        })
+    }
+
+    fn archive(&self, id: i64) -> Result<bool, SourceDocumentRepositoryError> {
+        let database_path = self.database_path()?;
+        set_archived(&database_path, "source_document", id, true)
+            .map_err(SourceDocumentRepositoryError::storage)
+    }
+
+    fn restore(&self, id: i64) -> Result<bool, SourceDocumentRepositoryError> {
+        let database_path = self.database_path()?;
+        set_archived(&database_path, "source_document", id, false)
+            .map_err(SourceDocumentRepositoryError::storage)
+    }
+}
+
+// ---------------- Document Source Links ----------------
+impl SqliteDocumentRepository {
+    fn source_pool_options(
+        &self,
+    ) -> Result<(Runtime, SqliteConnectOptions), SourceDocumentRepositoryError> {
+        let database_path = self
+            .database_path()
+            .map_err(|error| SourceDocumentRepositoryError::storage(error.to_string()))?;
+        let runtime = Runtime::new()
+            .map_err(|error| SourceDocumentRepositoryError::storage(error.to_string()))?;
+        let options = SqliteConnectOptions::new()
+            .filename(database_path)
+            .create_if_missing(true);
+
+        Ok((runtime, options))
+    }
+}
+
+impl DocumentSourceRepository for SqliteDocumentRepository {
+    fn attach_source(
+        &self,
+        document_id: i64,
+        source_id: i64,
+    ) -> Result<bool, SourceDocumentRepositoryError> {
+        let (runtime, options) = self.source_pool_options()?;
+
+        runtime.block_on(async move {
+            let pool = SqlitePool::connect_with(options)
+                .await
+                .map_err(|error| SourceDocumentRepositoryError::storage(error.to_string()))?;
+
+            let result = sqlx::query(
+                r#"INSERT INTO document_source_document (document_id, source_document_id, position)
+                   SELECT
+                       ?1, ?2,
+                       (SELECT COALESCE(MAX(position) + 1, 0)
+                        FROM document_source_document WHERE document_id = ?1)
+                   WHERE EXISTS (SELECT 1 FROM document WHERE id = ?1 AND status = 'draft')
+                   ON CONFLICT (document_id, source_document_id) DO NOTHING"#,
+            )
+            .bind(document_id)
+            .bind(source_id)
+            .execute(&pool)
+            .await
+            .map_err(|error| SourceDocumentRepositoryError::storage(error.to_string()))?;
+
+            pool.close().await;
+
+            Ok::<bool, SourceDocumentRepositoryError>(result.rows_affected() > 0)
+        })
+    }
+
+    fn detach_source(
+        &self,
+        document_id: i64,
+        source_id: i64,
+    ) -> Result<bool, SourceDocumentRepositoryError> {
+        let (runtime, options) = self.source_pool_options()?;
+
+        runtime.block_on(async move {
+            let pool = SqlitePool::connect_with(options)
+                .await
+                .map_err(|error| SourceDocumentRepositoryError::storage(error.to_string()))?;
+
+            let result = sqlx::query(
+                r#"DELETE FROM document_source_document
+                   WHERE document_id = ?1 AND source_document_id = ?2
+                     AND EXISTS (SELECT 1 FROM document WHERE id = ?1 AND status = 'draft')"#,
+            )
+            .bind(document_id)
+            .bind(source_id)
+            .execute(&pool)
+            .await
+            .map_err(|error| SourceDocumentRepositoryError::storage(error.to_string()))?;
+
+            pool.close().await;
+
+            Ok::<bool, SourceDocumentRepositoryError>(result.rows_affected() > 0)
+        })
+    }
+
+    fn source_ids(&self, document_id: i64) -> Result<Vec<i64>, SourceDocumentRepositoryError> {
+        let (runtime, options) = self.source_pool_options()?;
+
+        runtime.block_on(async move {
+            let pool = SqlitePool::connect_with(options)
+                .await
+                .map_err(|error| SourceDocumentRepositoryError::storage(error.to_string()))?;
+
+            let rows = sqlx::query(
+                r#"SELECT source_document_id FROM document_source_document
+                   WHERE document_id = ?1
+                   ORDER BY position"#,
+            )
+            .bind(document_id)
+            .fetch_all(&pool)
+            .await
+            .map_err(|error| SourceDocumentRepositoryError::storage(error.to_string()))?;
+
+            pool.close().await;
+
+            Ok::<Vec<i64>, SourceDocumentRepositoryError>(
+                rows.iter()
+                    .map(|row| row.get("source_document_id"))
+                    .collect(),
+            )
+        })
+    }
+
+    fn documents_using_source(
+        &self,
+        source_id: i64,
+    ) -> Result<Vec<i64>, SourceDocumentRepositoryError> {
+        let (runtime, options) = self.source_pool_options()?;
+
+        runtime.block_on(async move {
+            let pool = SqlitePool::connect_with(options)
+                .await
+                .map_err(|error| SourceDocumentRepositoryError::storage(error.to_string()))?;
+
+            let rows = sqlx::query(
+                r#"SELECT document_id FROM document_source_document
+                   WHERE source_document_id = ?1
+                   ORDER BY document_id"#,
+            )
+            .bind(source_id)
+            .fetch_all(&pool)
+            .await
+            .map_err(|error| SourceDocumentRepositoryError::storage(error.to_string()))?;
+
+            pool.close().await;
+
+            Ok::<Vec<i64>, SourceDocumentRepositoryError>(
+                rows.iter().map(|row| row.get("document_id")).collect(),
+            )
+        })
+    }
+}
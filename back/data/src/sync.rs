@@ -0,0 +1,1097 @@
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use sqlx::{Connection, Row, SqliteConnection};
+use tokio::runtime::Runtime;
+
+use crate::DEFAULT_DATABASE_FILE_NAME;
+use crate::archive::{
+    ArchiveEntityKind, ArchiveError, Ids, connect, read_archive, store_address, store_entrepreneur,
+    store_organization, store_person, store_source_document,
+};
+
+// ---------------- Workstation Synchronization ----------------
+
+/// Value of [`ChangeSet::format`].
+pub const SYNC_FORMAT: &str = "zdnp-sync";
+/// Current version of the change set format.
+pub const SYNC_VERSION: u32 = 1;
+
+/// Changes of the reference data picked up by one synchronization of a workstation. Change sets
+/// are written to `<shared folder>/<workstation uid>/<sequence>.json` and never modified.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChangeSet {
+    pub format: String,
+    pub version: u32,
+    pub workstation: String,
+    pub sequence: i64,
+    pub created_at: String,
+    pub changes: Vec<FieldChange>,
+}
+
+/// New value of one field of an address, organization, entrepreneur, person or source document,
+/// in the form of the portable archive: references to other entities are their uids.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FieldChange {
+    pub entity: ArchiveEntityKind,
+    pub uid: String,
+    pub field: String,
+    pub value: Value,
+    pub changed_at: String,
+    /// Version of the field the workstation had before the change; `None` for a new entity.
+    pub base: Option<FieldVersion>,
+}
+
+/// Where and when a field value was written. Of two versions the later one wins; ties are broken
+/// by the workstation uid so that every workstation picks the same winner.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct FieldVersion {
+    pub changed_at: String,
+    pub workstation: String,
+    pub sequence: i64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SyncSide {
+    Local,
+    Remote,
+}
+
+impl SyncSide {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Local => "local",
+            Self::Remote => "remote",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "local" => Some(Self::Local),
+            "remote" => Some(Self::Remote),
+            _ => None,
+        }
+    }
+}
+
+/// A field changed on another workstation that had not seen the value of this one. The value of
+/// the `winner` is already stored; the conflict stays queued until it is resolved by hand.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SyncConflict {
+    pub id: i64,
+    pub entity: ArchiveEntityKind,
+    pub uid: String,
+    pub field: String,
+    pub local_value: Value,
+    pub remote_value: Value,
+    pub remote_workstation: String,
+    pub winner: SyncSide,
+    pub detected_at: String,
+    pub resolved_at: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize)]
+pub struct SyncReport {
+    pub workstation: String,
+    /// Local field changes written to the shared folder.
+    pub exported_changes: usize,
+    pub applied_change_sets: usize,
+    /// Remote field changes that won and were stored.
+    pub applied_changes: usize,
+    /// Conflicts queued by this synchronization.
+    pub conflicts: Vec<SyncConflict>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SyncError {
+    Io(String),
+    Storage(String),
+    /// A change set cannot be read or refers to entities this database does not have.
+    Malformed(String),
+    UnsupportedVersion(u32),
+}
+
+impl std::fmt::Display for SyncError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(message) => write!(f, "I/O error: {message}"),
+            Self::Storage(message) => f.write_str(message),
+            Self::Malformed(message) => write!(f, "Malformed change set: {message}"),
+            Self::UnsupportedVersion(version) => {
+                write!(f, "Change set version {version} is not supported")
+            }
+        }
+    }
+}
+
+impl std::error::Error for SyncError {}
+
+impl From<sqlx::Error> for SyncError {
+    fn from(error: sqlx::Error) -> Self {
+        Self::Storage(error.to_string())
+    }
+}
+
+impl From<std::io::Error> for SyncError {
+    fn from(error: std::io::Error) -> Self {
+        Self::Io(error.to_string())
+    }
+}
+
+impl From<serde_json::Error> for SyncError {
+    fn from(error: serde_json::Error) -> Self {
+        Self::Malformed(error.to_string())
+    }
+}
+
+impl From<ArchiveError> for SyncError {
+    fn from(error: ArchiveError) -> Self {
+        match error {
+            ArchiveError::Io(message) => Self::Io(message),
+            ArchiveError::Storage(message) => Self::Storage(message),
+            ArchiveError::Malformed(message) => Self::Malformed(message),
+            ArchiveError::UnsupportedVersion(version) => Self::UnsupportedVersion(version),
+        }
+    }
+}
+
+/// Fields of the reference data entities by kind and uid, without the uid itself.
+type Entities = BTreeMap<(ArchiveEntityKind, String), Map<String, Value>>;
+type FieldKey = (ArchiveEntityKind, String, String);
+
+/// The last value of a field this workstation knows of, with its version.
+struct Known {
+    value: Value,
+    version: FieldVersion,
+}
+
+/// Synchronization of the reference data of several workstations through a shared folder.
+///
+/// Only addresses, organizations, entrepreneurs, persons and source documents are synchronized;
+/// documents and requirement templates stay on the workstation that created them and are moved
+/// with archives.
+///
+/// Changes are detected by comparing the data with the last known version of every field and are
+/// dated by the `updated_at` time the database records on every write. Remote changes are applied
+/// field by field, the last writer wins unless the change was made knowing the value of this
+/// workstation; a change made without knowing it is also queued as a [`SyncConflict`].
+pub struct SqliteSync {
+    database_file_name: String,
+}
+
+impl Default for SqliteSync {
+    fn default() -> Self {
+        Self {
+            database_file_name: DEFAULT_DATABASE_FILE_NAME.to_string(),
+        }
+    }
+}
+
+impl SqliteSync {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_file_name<S: Into<String>>(file_name: S) -> Self {
+        Self {
+            database_file_name: file_name.into(),
+        }
+    }
+
+    fn database_path(&self) -> Result<PathBuf, SyncError> {
+        let executable = std::env::current_exe()?;
+        let directory = executable
+            .parent()
+            .ok_or_else(|| SyncError::Io("Failed to determine application directory".into()))?;
+
+        Ok(directory.join(&self.database_file_name))
+    }
+
+    fn run<T>(
+        &self,
+        work: impl AsyncFnOnce(&mut SqliteConnection) -> Result<T, SyncError>,
+    ) -> Result<T, SyncError> {
+        let database_path = self.database_path()?;
+        let runtime = Runtime::new()?;
+
+        runtime.block_on(async {
+            let mut connection = connect(&database_path).await?;
+            let mut transaction = connection.begin().await?;
+            let result = work(&mut *transaction).await?;
+            transaction.commit().await?;
+            connection.close().await?;
+            Ok(result)
+        })
+    }
+
+    /// Uid of this workstation, the name of its folder in the shared folder.
+    pub fn workstation(&self) -> Result<String, SyncError> {
+        self.run(async |connection| Ok(workstation(connection).await?.0))
+    }
+
+    /// Picks up the local changes since the previous synchronization and applies the change sets
+    /// of the other workstations not applied yet, all in one transaction. The local change set is
+    /// written to the shared folder only once that transaction is committed; one that could not
+    /// be written is written by the next synchronization.
+    pub fn sync(&self, shared: &Path) -> Result<SyncReport, SyncError> {
+        let report = self.run(async |connection| {
+            let (workstation, last_sequence) = workstation(connection).await?;
+            let now = now(connection).await?;
+            let sequence = last_sequence + 1;
+
+            let changes = capture(connection, &workstation, sequence, &now).await?;
+            let mut report = SyncReport {
+                workstation: workstation.clone(),
+                exported_changes: changes.len(),
+                ..SyncReport::default()
+            };
+            if !changes.is_empty() {
+                sqlx::query("UPDATE sync_workstation SET last_sequence = ?1")
+                    .bind(sequence)
+                    .execute(&mut *connection)
+                    .await?;
+                let change_set = ChangeSet {
+                    format: SYNC_FORMAT.to_string(),
+                    version: SYNC_VERSION,
+                    workstation: workstation.clone(),
+                    sequence,
+                    created_at: now.clone(),
+                    changes,
+                };
+                sqlx::query("INSERT INTO sync_outbox (sequence, change_set) VALUES (?1, ?2)")
+                    .bind(sequence)
+                    .bind(serde_json::to_string(&change_set)?)
+                    .execute(&mut *connection)
+                    .await?;
+            }
+
+            apply(connection, shared, &workstation, &now, &mut report).await?;
+            Ok(report)
+        })?;
+
+        self.publish(shared)?;
+        Ok(report)
+    }
+
+    /// Writes the committed change sets of this workstation to the shared folder, oldest first,
+    /// and drops them from the outbox.
+    fn publish(&self, shared: &Path) -> Result<(), SyncError> {
+        self.run(async |connection| {
+            let rows =
+                sqlx::query("SELECT sequence, change_set FROM sync_outbox ORDER BY sequence")
+                    .fetch_all(&mut *connection)
+                    .await?;
+            for row in rows {
+                let change_set: String = row.get("change_set");
+                write_change_set(shared, &serde_json::from_str(&change_set)?)?;
+                sqlx::query("DELETE FROM sync_outbox WHERE sequence = ?1")
+                    .bind(row.get::<i64, _>("sequence"))
+                    .execute(&mut *connection)
+                    .await?;
+            }
+            Ok(())
+        })
+    }
+
+    /// Conflicts waiting for manual resolution, oldest first.
+    pub fn conflicts(&self) -> Result<Vec<SyncConflict>, SyncError> {
+        self.run(async |connection| {
+            let rows = sqlx::query(
+                r#"SELECT * FROM sync_conflict
+                   WHERE resolved_at IS NULL
+                   ORDER BY id"#,
+            )
+            .fetch_all(&mut *connection)
+            .await?;
+            rows.iter().map(conflict_from_row).collect()
+        })
+    }
+
+    /// Resolves the conflict by keeping the value of one side. The kept value is stored if it is
+    /// not current and reaches the other workstations with the next synchronization. Returns
+    /// `false` when there is no such unresolved conflict.
+    pub fn resolve(&self, id: i64, keep: SyncSide) -> Result<bool, SyncError> {
+        self.run(async |connection| {
+            let row = sqlx::query("SELECT * FROM sync_conflict WHERE id = ?1")
+                .bind(id)
+                .fetch_optional(&mut *connection)
+                .await?;
+            let Some(conflict) = row.as_ref().map(conflict_from_row).transpose()? else {
+                return Ok(false);
+            };
+            if conflict.resolved_at.is_some() {
+                return Ok(false);
+            }
+
+            let value = match keep {
+                SyncSide::Local => conflict.local_value,
+                SyncSide::Remote => conflict.remote_value,
+            };
+            let key = (conflict.entity, conflict.uid);
+            let (entities, _) = current_entities(connection).await?;
+            let current = entities
+                .get(&key)
+                .and_then(|fields| fields.get(&conflict.field));
+            if current != Some(&value) {
+                let mut written = Entities::new();
+                written
+                    .entry(key)
+                    .or_default()
+                    .insert(conflict.field, value);
+                store_entities(connection, written).await?;
+            }
+
+            let now = now(connection).await?;
+            sqlx::query("UPDATE sync_conflict SET resolved_at = ?1 WHERE id = ?2")
+                .bind(now)
+                .bind(id)
+                .execute(&mut *connection)
+                .await?;
+            Ok(true)
+        })
+    }
+}
+
+/// Uid of this workstation, created on first use, and the sequence of its last change set.
+async fn workstation(connection: &mut SqliteConnection) -> Result<(String, i64), SyncError> {
+    let row = sqlx::query("SELECT uid, last_sequence FROM sync_workstation WHERE id = 1")
+        .fetch_optional(&mut *connection)
+        .await?;
+    if let Some(row) = row {
+        return Ok((row.get("uid"), row.get("last_sequence")));
+    }
+
+    let uid = zdnp_core::generate_uid();
+    sqlx::query("INSERT INTO sync_workstation (id, uid) VALUES (1, ?1)")
+        .bind(&uid)
+        .execute(&mut *connection)
+        .await?;
+    Ok((uid, 0))
+}
+
+async fn now(connection: &mut SqliteConnection) -> Result<String, SyncError> {
+    Ok(
+        sqlx::query("SELECT strftime('%Y-%m-%dT%H:%M:%fZ', 'now') AS now")
+            .fetch_one(&mut *connection)
+            .await?
+            .get("now"),
+    )
+}
+
+/// Tables of the synchronized entities, which are named after their kinds.
+const SYNCHRONIZED_KINDS: [ArchiveEntityKind; 5] = [
+    ArchiveEntityKind::Address,
+    ArchiveEntityKind::Organization,
+    ArchiveEntityKind::Entrepreneur,
+    ArchiveEntityKind::Person,
+    ArchiveEntityKind::SourceDocument,
+];
+
+/// Time of the last write of every synchronized entity, kept up by the `updated_at` triggers.
+async fn modification_times(
+    connection: &mut SqliteConnection,
+) -> Result<HashMap<(ArchiveEntityKind, String), String>, SyncError> {
+    let mut times = HashMap::new();
+    for kind in SYNCHRONIZED_KINDS {
+        let rows = sqlx::query(&format!(
+            "SELECT uid, updated_at FROM {} WHERE updated_at IS NOT NULL",
+            kind.as_str()
+        ))
+        .fetch_all(&mut *connection)
+        .await?;
+        for row in rows {
+            times.insert((kind, row.get("uid")), row.get("updated_at"));
+        }
+    }
+    Ok(times)
+}
+
+async fn current_entities(connection: &mut SqliteConnection) -> Result<(Entities, Ids), SyncError> {
+    let (archive, ids) = read_archive(connection).await?;
+    let mut entities = Entities::new();
+    collect(
+        &mut entities,
+        ArchiveEntityKind::Address,
+        &archive.addresses,
+    )?;
+    collect(
+        &mut entities,
+        ArchiveEntityKind::Organization,
+        &archive.organizations,
+    )?;
+    collect(
+        &mut entities,
+        ArchiveEntityKind::Entrepreneur,
+        &archive.entrepreneurs,
+    )?;
+    collect(&mut entities, ArchiveEntityKind::Person, &archive.persons)?;
+    collect(
+        &mut entities,
+        ArchiveEntityKind::SourceDocument,
+        &archive.source_documents,
+    )?;
+    Ok((entities, ids))
+}
+
+fn collect<T: Serialize>(
+    entities: &mut Entities,
+    kind: ArchiveEntityKind,
+    archived: &[T],
+) -> Result<(), SyncError> {
+    for entity in archived {
+        let Value::Object(mut fields) = serde_json::to_value(entity)? else {
+            continue;
+        };
+        let uid = match fields.remove("uid") {
+            Some(Value::String(uid)) => uid,
+            _ => continue,
+        };
+        entities.insert((kind, uid), fields);
+    }
+    Ok(())
+}
+
+async fn known_versions(
+    connection: &mut SqliteConnection,
+) -> Result<HashMap<FieldKey, Known>, SyncError> {
+    let rows = sqlx::query("SELECT * FROM sync_field_version")
+        .fetch_all(&mut *connection)
+        .await?;
+
+    let mut known = HashMap::new();
+    for row in rows {
+        let entity: String = row.get("entity");
+        let entity = ArchiveEntityKind::parse(&entity)
+            .ok_or_else(|| SyncError::Storage(format!("Unknown entity: {entity}")))?;
+        let value: String = row.get("value");
+        known.insert(
+            (entity, row.get("uid"), row.get("field")),
+            Known {
+                value: serde_json::from_str(&value)?,
+                version: FieldVersion {
+                    changed_at: row.get("changed_at"),
+                    workstation: row.get("workstation"),
+                    sequence: row.get("sequence"),
+                },
+            },
+        );
+    }
+    Ok(known)
+}
+
+async fn store_version(
+    connection: &mut SqliteConnection,
+    (entity, uid, field): &FieldKey,
+    known: &Known,
+) -> Result<(), SyncError> {
+    sqlx::query(
+        r#"INSERT INTO sync_field_version (
+            entity, uid, field, value, workstation, sequence, changed_at
+        ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+        ON CONFLICT (entity, uid, field) DO UPDATE SET
+            value = excluded.value,
+            workstation = excluded.workstation,
+            sequence = excluded.sequence,
+            changed_at = excluded.changed_at"#,
+    )
+    .bind(entity.as_str())
+    .bind(uid)
+    .bind(field)
+    .bind(serde_json::to_string(&known.value)?)
+    .bind(&known.version.workstation)
+    .bind(known.version.sequence)
+    .bind(&known.version.changed_at)
+    .execute(&mut *connection)
+    .await?;
+    Ok(())
+}
+
+/// Fields that differ from their last known version become changes of the change set `sequence`
+/// of this workstation, dated by the last write of their entity.
+async fn capture(
+    connection: &mut SqliteConnection,
+    workstation: &str,
+    sequence: i64,
+    now: &str,
+) -> Result<Vec<FieldChange>, SyncError> {
+    let (entities, _) = current_entities(connection).await?;
+    let known = known_versions(connection).await?;
+    let modified = modification_times(connection).await?;
+
+    let mut changes = Vec::new();
+    for ((entity, uid), fields) in entities {
+        let changed_at = modified
+            .get(&(entity, uid.clone()))
+            .map_or(now, String::as_str);
+        for (field, value) in fields {
+            let key = (entity, uid.clone(), field.clone());
+            let previous = known.get(&key);
+            if previous.is_some_and(|previous| previous.value == value) {
+                continue;
+            }
+
+            let current = Known {
+                value: value.clone(),
+                version: FieldVersion {
+                    changed_at: changed_at.to_string(),
+                    workstation: workstation.to_string(),
+                    sequence,
+                },
+            };
+            store_version(connection, &key, &current).await?;
+            changes.push(FieldChange {
+                entity,
+                uid: uid.clone(),
+                field,
+                value,
+                changed_at: changed_at.to_string(),
+                base: previous.map(|previous| previous.version.clone()),
+            });
+        }
+    }
+    Ok(changes)
+}
+
+fn change_set_file_name(sequence: i64) -> String {
+    format!("{sequence:010}.json")
+}
+
+/// Writes the change set under a temporary name first, so that other workstations never read a
+/// partially written file.
+fn write_change_set(shared: &Path, change_set: &ChangeSet) -> Result<(), SyncError> {
+    let directory = shared.join(&change_set.workstation);
+    std::fs::create_dir_all(&directory)?;
+
+    let file_name = change_set_file_name(change_set.sequence);
+    let incoming = directory.join(format!(".{file_name}.tmp"));
+    std::fs::write(&incoming, serde_json::to_vec_pretty(change_set)?)?;
+    std::fs::rename(&incoming, directory.join(file_name))?;
+    Ok(())
+}
+
+/// Change sets of the other workstations following the last applied ones, in the order they
+/// were created. The change sets of a workstation are taken up to the first missing one, which
+/// may not have reached the shared folder yet.
+fn pending_change_sets(
+    shared: &Path,
+    workstation: &str,
+    applied: &HashMap<String, i64>,
+) -> Result<Vec<ChangeSet>, SyncError> {
+    let mut change_sets = Vec::new();
+    if !shared.is_dir() {
+        return Ok(change_sets);
+    }
+
+    for entry in std::fs::read_dir(shared)? {
+        let entry = entry?;
+        let Some(remote) = entry.file_name().to_str().map(str::to_string) else {
+            continue;
+        };
+        if remote == workstation || !entry.file_type()?.is_dir() {
+            continue;
+        }
+
+        let mut sequence = applied.get(&remote).copied().unwrap_or(0) + 1;
+        loop {
+            let path = entry.path().join(change_set_file_name(sequence));
+            if !path.is_file() {
+                break;
+            }
+
+            let change_set: ChangeSet = serde_json::from_slice(&std::fs::read(&path)?)?;
+            if change_set.format != SYNC_FORMAT {
+                return Err(SyncError::Malformed(format!(
+                    "Unknown format: {}",
+                    change_set.format
+                )));
+            }
+            if change_set.version > SYNC_VERSION {
+                return Err(SyncError::UnsupportedVersion(change_set.version));
+            }
+            if change_set.workstation != remote || change_set.sequence != sequence {
+                return Err(SyncError::Malformed(format!(
+                    "{} is not change set {sequence} of workstation {remote}",
+                    path.display()
+                )));
+            }
+            change_sets.push(change_set);
+            sequence += 1;
+        }
+    }
+
+    change_sets.sort_by(|a, b| {
+        (&a.created_at, &a.workstation, a.sequence).cmp(&(
+            &b.created_at,
+            &b.workstation,
+            b.sequence,
+        ))
+    });
+    Ok(change_sets)
+}
+
+async fn apply(
+    connection: &mut SqliteConnection,
+    shared: &Path,
+    workstation: &str,
+    now: &str,
+    report: &mut SyncReport,
+) -> Result<(), SyncError> {
+    let applied = sqlx::query("SELECT workstation, last_sequence FROM sync_applied")
+        .fetch_all(&mut *connection)
+        .await?
+        .iter()
+        .map(|row| (row.get("workstation"), row.get("last_sequence")))
+        .collect();
+    let change_sets = pending_change_sets(shared, workstation, &applied)?;
+    if change_sets.is_empty() {
+        return Ok(());
+    }
+
+    let mut known = known_versions(connection).await?;
+    let mut written = Entities::new();
+    for change_set in &change_sets {
+        for change in &change_set.changes {
+            let version = FieldVersion {
+                changed_at: change.changed_at.clone(),
+                workstation: change_set.workstation.clone(),
+                sequence: change_set.sequence,
+            };
+            let key = (change.entity, change.uid.clone(), change.field.clone());
+            let local = known.get(&key);
+            // A change made on top of the local version follows it, whatever the clocks say.
+            let wins = local.is_none_or(|local| {
+                change.base.as_ref() == Some(&local.version) || version > local.version
+            });
+
+            if let Some(local) = local
+                && local.value != change.value
+                && change.base.as_ref() != Some(&local.version)
+            {
+                let conflict =
+                    queue_conflict(connection, change, &local.value, &version, wins, now).await?;
+                report.conflicts.push(conflict);
+            }
+
+            if wins {
+                let current = Known {
+                    value: change.value.clone(),
+                    version,
+                };
+                store_version(connection, &key, &current).await?;
+                known.insert(key, current);
+                written
+                    .entry((change.entity, change.uid.clone()))
+                    .or_default()
+                    .insert(change.field.clone(), change.value.clone());
+                report.applied_changes += 1;
+            }
+        }
+
+        sqlx::query(
+            r#"INSERT INTO sync_applied (workstation, last_sequence) VALUES (?1, ?2)
+               ON CONFLICT (workstation) DO UPDATE SET last_sequence = excluded.last_sequence"#,
+        )
+        .bind(&change_set.workstation)
+        .bind(change_set.sequence)
+        .execute(&mut *connection)
+        .await?;
+        report.applied_change_sets += 1;
+    }
+
+    store_entities(connection, written).await
+}
+
+async fn queue_conflict(
+    connection: &mut SqliteConnection,
+    change: &FieldChange,
+    local_value: &Value,
+    remote: &FieldVersion,
+    remote_wins: bool,
+    now: &str,
+) -> Result<SyncConflict, SyncError> {
+    let winner = if remote_wins {
+        SyncSide::Remote
+    } else {
+        SyncSide::Local
+    };
+    let id = sqlx::query(
+        r#"INSERT INTO sync_conflict (
+            entity, uid, field, local_value, remote_value, remote_workstation, winner, detected_at
+        ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)"#,
+    )
+    .bind(change.entity.as_str())
+    .bind(&change.uid)
+    .bind(&change.field)
+    .bind(serde_json::to_string(local_value)?)
+    .bind(serde_json::to_string(&change.value)?)
+    .bind(&remote.workstation)
+    .bind(winner.as_str())
+    .bind(now)
+    .execute(&mut *connection)
+    .await?
+    .last_insert_rowid();
+
+    Ok(SyncConflict {
+        id,
+        entity: change.entity,
+        uid: change.uid.clone(),
+        field: change.field.clone(),
+        local_value: local_value.clone(),
+        remote_value: change.value.clone(),
+        remote_workstation: remote.workstation.clone(),
+        winner,
+        detected_at: now.to_string(),
+        resolved_at: None,
+    })
+}
+
+fn conflict_from_row(row: &sqlx::sqlite::SqliteRow) -> Result<SyncConflict, SyncError> {
+    let entity: String = row.get("entity");
+    let winner: String = row.get("winner");
+    let local_value: String = row.get("local_value");
+    let remote_value: String = row.get("remote_value");
+
+    Ok(SyncConflict {
+        id: row.get("id"),
+        entity: ArchiveEntityKind::parse(&entity)
+            .ok_or_else(|| SyncError::Storage(format!("Unknown entity: {entity}")))?,
+        uid: row.get("uid"),
+        field: row.get("field"),
+        local_value: serde_json::from_str(&local_value)?,
+        remote_value: serde_json::from_str(&remote_value)?,
+        remote_workstation: row.get("remote_workstation"),
+        winner: SyncSide::parse(&winner)
+            .ok_or_else(|| SyncError::Storage(format!("Unknown winner: {winner}")))?,
+        detected_at: row.get("detected_at"),
+        resolved_at: row.get("resolved_at"),
+    })
+}
+
+/// Overwrites the given fields of the entities, creating the entities this database does not
+/// have yet. Entities are stored in the order of their kinds, so that addresses and organizations
+/// exist before the entities referring to them.
+async fn store_entities(
+    connection: &mut SqliteConnection,
+    written: Entities,
+) -> Result<(), SyncError> {
+    if written.is_empty() {
+        return Ok(());
+    }
+
+    let (mut entities, mut ids) = current_entities(connection).await?;
+    for ((kind, uid), fields) in written {
+        let key = (kind, uid);
+        let id = ids.get(&key).copied();
+        let mut entity = entities.remove(&key).unwrap_or_default();
+        entity.extend(fields);
+        entity.insert("uid".to_string(), Value::String(key.1.clone()));
+
+        let entity = Value::Object(entity);
+        let malformed = |error: serde_json::Error| {
+            SyncError::Malformed(format!("{} {}: {error}", kind.as_str(), key.1))
+        };
+        let id = match kind {
+            ArchiveEntityKind::Address => {
+                let address = serde_json::from_value(entity).map_err(malformed)?;
+                store_address(connection, id, &address).await?
+            }
+            ArchiveEntityKind::Organization => {
+                let organization = serde_json::from_value(entity).map_err(malformed)?;
+                store_organization(connection, id, &organization, &ids).await?
+            }
+            ArchiveEntityKind::Entrepreneur => {
+                let entrepreneur = serde_json::from_value(entity).map_err(malformed)?;
+                store_entrepreneur(connection, id, &entrepreneur, &ids).await?
+            }
+            ArchiveEntityKind::Person => {
+                let person = serde_json::from_value(entity).map_err(malformed)?;
+                store_person(connection, id, &person, &ids).await?
+            }
+            ArchiveEntityKind::SourceDocument => {
+                let source = serde_json::from_value(entity).map_err(malformed)?;
+                store_source_document(connection, id, &source, &ids).await?
+            }
+            ArchiveEntityKind::RequirementTemplate | ArchiveEntityKind::Document => {
+                return Err(SyncError::Malformed(format!(
+                    "{} is not synchronized",
+                    kind.as_str()
+                )));
+            }
+        };
+        ids.insert(key, id);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{TestDatabase, create_organization};
+    use sqlx::SqlitePool;
+    use sqlx::sqlite::SqliteConnectOptions;
+    use std::fs;
+
+    /// Runs `sql` and returns the email and the modification time of the only organization.
+    fn organization_email(database: &TestDatabase, sql: &str) -> (String, String) {
+        let runtime = Runtime::new().unwrap();
+        runtime.block_on(async {
+            let pool =
+                SqlitePool::connect_with(SqliteConnectOptions::new().filename(&database.path))
+                    .await
+                    .unwrap();
+            sqlx::query(sql).execute(&pool).await.unwrap();
+            let row = sqlx::query("SELECT email, updated_at FROM organization")
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+            pool.close().await;
+            (row.get("email"), row.get("updated_at"))
+        })
+    }
+
+    /// Two workstations sharing one organization, whose email both of them change before seeing
+    /// each other's change: the first one publishes its change, then the second one edits.
+    fn conflicting_edits(shared: &Path) -> (TestDatabase, TestDatabase) {
+        let first = TestDatabase::new();
+        let second = TestDatabase::new();
+        let first_sync = SqliteSync::with_file_name(first.path.as_str());
+        create_organization(&first);
+        first_sync.sync(shared).expect("first sync");
+        SqliteSync::with_file_name(second.path.as_str())
+            .sync(shared)
+            .expect("second sync");
+
+        organization_email(
+            &first,
+            "UPDATE organization SET email = 'first@example.com'",
+        );
+        first_sync.sync(shared).expect("first change");
+        std::thread::sleep(std::time::Duration::from_millis(20));
+        organization_email(
+            &second,
+            "UPDATE organization SET email = 'second@example.com'",
+        );
+        (first, second)
+    }
+
+    #[test]
+    fn change_sets_are_published_only_after_the_local_commit() {
+        let first = TestDatabase::new();
+        let second = TestDatabase::new();
+        let shared = tempfile::tempdir().expect("shared folder");
+        let first_sync = SqliteSync::with_file_name(first.path.as_str());
+        let workstation = first_sync.workstation().expect("workstation");
+        let published = shared.path().join(&workstation);
+        let change_set = published.join("0000000001.json");
+        create_organization(&first);
+
+        // Applying a broken remote change set fails, so nothing is published.
+        let remote = shared.path().join("remote");
+        fs::create_dir_all(&remote).expect("remote folder");
+        fs::write(remote.join("0000000001.json"), "not a change set").expect("broken");
+        assert!(matches!(
+            first_sync.sync(shared.path()),
+            Err(SyncError::Malformed(_))
+        ));
+        assert!(!published.exists());
+        fs::remove_dir_all(&remote).expect("remove remote");
+
+        // The shared folder cannot be written: the change set stays queued.
+        fs::write(&published, "").expect("block");
+        assert!(matches!(
+            first_sync.sync(shared.path()),
+            Err(SyncError::Io(_))
+        ));
+        fs::remove_file(&published).expect("unblock");
+
+        let report = first_sync.sync(shared.path()).expect("sync");
+        assert_eq!(report.exported_changes, 0, "changes were captured once");
+        let written: serde_json::Value =
+            serde_json::from_slice(&fs::read(&change_set).expect("published")).expect("json");
+        assert_eq!(written["sequence"], 1);
+        assert_eq!(fs::read_dir(&published).expect("list").count(), 1);
+
+        let report = SqliteSync::with_file_name(second.path.as_str())
+            .sync(shared.path())
+            .expect("second sync");
+        assert_eq!(report.applied_change_sets, 1);
+    }
+
+    #[test]
+    fn workstations_are_synchronized_through_a_shared_folder() {
+        let first = TestDatabase::new();
+        let second = TestDatabase::new();
+        let shared = tempfile::tempdir().expect("shared folder");
+        let first_sync = SqliteSync::with_file_name(first.path.as_str());
+        let second_sync = SqliteSync::with_file_name(second.path.as_str());
+        create_organization(&first);
+
+        let report = first_sync.sync(shared.path()).expect("first sync");
+        assert!(report.exported_changes > 0);
+        let report = second_sync.sync(shared.path()).expect("second sync");
+        assert_eq!(report.exported_changes, 0);
+        assert_eq!(report.applied_change_sets, 1);
+        let created = second_sync
+            .sync(shared.path())
+            .expect("nothing to send back");
+        assert_eq!(created.exported_changes, 0);
+        assert_eq!(created.applied_change_sets, 0);
+        assert_eq!(
+            organization_email(&second, "SELECT 1").0,
+            "info@example.com"
+        );
+
+        organization_email(
+            &second,
+            "UPDATE organization SET email = 'second@example.com'",
+        );
+        let report = second_sync.sync(shared.path()).expect("second change");
+        assert_eq!(report.exported_changes, 1);
+        let report = first_sync.sync(shared.path()).expect("change applied");
+        assert_eq!(report.applied_changes, 1);
+        assert_eq!(report.conflicts, Vec::new());
+        assert_eq!(
+            organization_email(&first, "SELECT 1").0,
+            "second@example.com"
+        );
+    }
+
+    #[test]
+    fn the_winner_of_a_sync_conflict_is_kept() {
+        let shared = tempfile::tempdir().expect("shared folder");
+        let (first, second) = conflicting_edits(shared.path());
+        let first_sync = SqliteSync::with_file_name(first.path.as_str());
+        let second_sync = SqliteSync::with_file_name(second.path.as_str());
+
+        let report = second_sync.sync(shared.path()).expect("second change");
+        assert_eq!(report.applied_changes, 0);
+        assert_eq!(report.conflicts.len(), 1);
+        assert_eq!(report.conflicts[0].winner, SyncSide::Local);
+        let report = first_sync.sync(shared.path()).expect("last writer wins");
+        assert_eq!(report.applied_changes, 1);
+        assert_eq!(report.conflicts.len(), 1);
+        assert_eq!(report.conflicts[0].field, "email");
+        assert_eq!(report.conflicts[0].local_value, "first@example.com");
+        assert_eq!(report.conflicts[0].remote_value, "second@example.com");
+        assert_eq!(report.conflicts[0].winner, SyncSide::Remote);
+
+        // Further synchronizations neither send the winner back nor bring the loser again.
+        for sync in [&first_sync, &second_sync, &first_sync] {
+            let report = sync.sync(shared.path()).expect("quiet sync");
+            assert_eq!(report.exported_changes, 0);
+            assert_eq!(report.applied_changes, 0);
+            assert_eq!(report.conflicts, Vec::new());
+        }
+        assert_eq!(
+            organization_email(&first, "SELECT 1").0,
+            "second@example.com"
+        );
+        assert_eq!(
+            organization_email(&second, "SELECT 1").0,
+            "second@example.com"
+        );
+    }
+
+    #[test]
+    fn queued_conflicts_are_resolved_by_hand() {
+        let shared = tempfile::tempdir().expect("shared folder");
+        let (first, second) = conflicting_edits(shared.path());
+        let first_sync = SqliteSync::with_file_name(first.path.as_str());
+        let second_sync = SqliteSync::with_file_name(second.path.as_str());
+        second_sync.sync(shared.path()).expect("second change");
+        first_sync.sync(shared.path()).expect("last writer wins");
+
+        // The first workstation keeps its value by hand; it reaches the second one as an
+        // ordinary change.
+        let conflicts = first_sync.conflicts().expect("conflicts");
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].winner, SyncSide::Remote);
+        assert!(
+            first_sync
+                .resolve(conflicts[0].id, SyncSide::Local)
+                .expect("resolve")
+        );
+        assert!(
+            !first_sync
+                .resolve(conflicts[0].id, SyncSide::Local)
+                .expect("resolved once")
+        );
+        assert!(
+            !first_sync
+                .resolve(i64::MAX, SyncSide::Local)
+                .expect("unknown")
+        );
+        assert_eq!(first_sync.conflicts().expect("no conflicts"), Vec::new());
+        assert_eq!(
+            organization_email(&first, "SELECT 1").0,
+            "first@example.com"
+        );
+        assert_eq!(
+            first_sync
+                .sync(shared.path())
+                .expect("resolution")
+                .exported_changes,
+            1
+        );
+        let report = second_sync.sync(shared.path()).expect("resolution applied");
+        assert_eq!(report.applied_changes, 1);
+        assert_eq!(report.conflicts, Vec::new());
+        assert_eq!(
+            organization_email(&second, "SELECT 1").0,
+            "first@example.com"
+        );
+    }
+
+    #[test]
+    fn sync_changes_are_dated_by_their_modification_time() {
+        let first = TestDatabase::new();
+        let second = TestDatabase::new();
+        let shared = tempfile::tempdir().expect("shared folder");
+        let first_sync = SqliteSync::with_file_name(first.path.as_str());
+        let second_sync = SqliteSync::with_file_name(second.path.as_str());
+        create_organization(&first);
+        first_sync.sync(shared.path()).expect("first sync");
+        second_sync.sync(shared.path()).expect("second sync");
+
+        // The first workstation edits first but synchronizes last.
+        let (_, created_at) = organization_email(&first, "SELECT 1");
+        let (_, edited_at) = organization_email(
+            &first,
+            "UPDATE organization SET email = 'first@example.com'",
+        );
+        assert!(edited_at > created_at);
+        std::thread::sleep(std::time::Duration::from_millis(20));
+        organization_email(
+            &second,
+            "UPDATE organization SET email = 'second@example.com'",
+        );
+        second_sync.sync(shared.path()).expect("second change");
+
+        let report = first_sync.sync(shared.path()).expect("first change");
+        assert_eq!(report.applied_changes, 1);
+        assert_eq!(report.conflicts.len(), 1);
+        assert_eq!(report.conflicts[0].winner, SyncSide::Remote);
+        let report = second_sync.sync(shared.path()).expect("earlier change");
+        assert_eq!(report.applied_changes, 0);
+        assert_eq!(report.conflicts.len(), 1);
+        assert_eq!(report.conflicts[0].winner, SyncSide::Local);
+        assert_eq!(
+            organization_email(&first, "SELECT 1").0,
+            "second@example.com"
+        );
+        assert_eq!(
+            organization_email(&second, "SELECT 1").0,
+            "second@example.com"
+        );
+
+        // A write that sets the time itself keeps it.
+        let (_, updated_at) = organization_email(
+            &first,
+            "UPDATE organization SET updated_at = '2026-01-05T10:00:00.000Z'",
+        );
+        assert_eq!(updated_at, "2026-01-05T10:00:00.000Z");
+    }
+}
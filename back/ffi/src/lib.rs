@@ -1,13 +1,25 @@
 use std::ffi::{CStr, CString, c_char};
 use std::str::Utf8Error;
 
+use serde::Serialize;
 use serde_json::to_string;
-use zdnp_core::{self, AddressDto, EntrepreneurDto, Migrations, OrganizationDto, PersonDto};
+use zdnp_core::{
+    self, AddressDto, CloneOverrides, ConstructionObjectDto, DocumentAuthor, DocumentDeveloper,
+    DocumentDto, DocumentNumbering, DocumentType, EntrepreneurDto, ListOptions, Migrations,
+    NumberingScheme, OrganizationDto, PersonDto, RequirementCategory, RequirementSectionDto,
+    RequirementTemplateDto, SignatoryDto, SigningRole, SourceDocumentDto, SourceDocumentKind,
+    TechnicalIndicatorDto, WorkType,
+};
 
 /// Errors that can occur while converting FFI data into safe Rust structures.
 #[derive(Debug)]
 pub enum FfiConversionError {
     InvalidUtf8,
+    UnknownDocumentType(i64),
+    UnknownWorkType(String),
+    UnknownRequirementCategory(String),
+    UnknownSourceDocumentKind(String),
+    UnknownSigningRole(String),
 }
 
 impl From<Utf8Error> for FfiConversionError {
@@ -16,6 +28,39 @@ impl From<Utf8Error> for FfiConversionError {
     }
 }
 
+/// Reads an optional string argument. Empty strings are treated as absent.
+///
+/// # Safety
+/// The pointer must either be null or reference a valid null-terminated UTF-8 string.
+unsafe fn read_c_str(ptr: *const c_char) -> Result<Option<String>, FfiConversionError> {
+    if ptr.is_null() {
+        return Ok(None);
+    }
+
+    // SAFETY: The caller guarantees that the pointer is valid for reads and points to a
+    // null-terminated string.
+    let c_str = unsafe { CStr::from_ptr(ptr) };
+    let utf8 = c_str.to_str()?;
+    if utf8.is_empty() {
+        Ok(None)
+    } else {
+        Ok(Some(utf8.to_owned()))
+    }
+}
+
+/// Serializes the value into a newly allocated JSON C string, or null on failure.
+fn json_c_string<T: Serialize + ?Sized>(value: &T) -> *mut c_char {
+    let json = match to_string(value) {
+        Ok(json) => json,
+        Err(_) => return std::ptr::null_mut(),
+    };
+
+    match CString::new(json) {
+        Ok(c_string) => c_string.into_raw(),
+        Err(_) => std::ptr::null_mut(),
+    }
+}
+
 #[repr(C)]
 pub struct AddressDtoFfi {
     pub region_code: *const c_char,
@@ -61,10 +106,29 @@ impl AddressDtoFfi {
             street: read_field(self.street)?,
             building: read_field(self.building)?,
             room: read_field(self.room)?,
+            uid: None,
         })
     }
 }
 
+#[repr(C)]
+pub struct ListOptionsFfi {
+    pub include_archived: bool,
+}
+
+impl ListOptionsFfi {
+    /// # Safety
+    /// The pointer must either be null or reference a valid [`ListOptionsFfi`].
+    unsafe fn read(ptr: *const ListOptionsFfi) -> ListOptions {
+        match unsafe { ptr.as_ref() } {
+            Some(options) => ListOptions {
+                include_archived: options.include_archived,
+            },
+            None => ListOptions::default(),
+        }
+    }
+}
+
 #[repr(C)]
 pub struct OrganizationDtoFfi {
     pub full_name: *const c_char,
@@ -103,6 +167,7 @@ impl OrganizationDtoFfi {
             kpp: read_field(self.kpp)?,
             address_id: self.address_id,
             email: read_field(self.email)?,
+            uid: None,
         })
     }
 }
@@ -144,6 +209,7 @@ impl EntrepreneurDtoFfi {
             inn: read_field(self.inn)?,
             address_id: self.address_id,
             email: read_field(self.email)?,
+            uid: None,
         })
     }
 }
@@ -183,10 +249,12 @@ impl PersonDtoFfi {
             snils: read_field(self.snils)?,
             email: read_field(self.email)?,
             address_id: self.address_id,
+            uid: None,
         })
     }
 }
 
+#[allow(clippy::missing_safety_doc)]
 #[unsafe(no_mangle)]
 pub unsafe extern "C" fn core_create_organization(
     dto: *const OrganizationDtoFfi,
@@ -218,6 +286,7 @@ pub unsafe extern "C" fn core_create_organization(
     }
 }
 
+#[allow(clippy::missing_safety_doc)]
 #[unsafe(no_mangle)]
 pub unsafe extern "C" fn core_create_entrepreneur(
     dto: *const EntrepreneurDtoFfi,
@@ -249,6 +318,7 @@ pub unsafe extern "C" fn core_create_entrepreneur(
     }
 }
 
+#[allow(clippy::missing_safety_doc)]
 #[unsafe(no_mangle)]
 pub unsafe extern "C" fn core_create_person(dto: *const PersonDtoFfi, out_id: *mut i64) -> bool {
     if let Some(slot) = unsafe { out_id.as_mut() } {
@@ -318,6 +388,7 @@ pub unsafe extern "C" fn core_free_string(ptr: *mut c_char) {
     };
 }
 
+#[allow(clippy::missing_safety_doc)]
 #[unsafe(no_mangle)]
 pub unsafe extern "C" fn core_create_address(dto: *const AddressDtoFfi, out_id: *mut i64) -> bool {
     if let Some(slot) = unsafe { out_id.as_mut() } {
@@ -347,11 +418,28 @@ pub unsafe extern "C" fn core_create_address(dto: *const AddressDtoFfi, out_id:
     }
 }
 
+/// Returns non-archived addresses as a JSON array.
+///
+/// # Safety
+/// The returned string must be released with [`core_free_string`].
 #[unsafe(no_mangle)]
 pub unsafe extern "C" fn core_list_addresses() -> *mut c_char {
+    unsafe { core_list_addresses_with_options(std::ptr::null()) }
+}
+
+/// Returns addresses as a JSON array, optionally including archived rows.
+///
+/// # Safety
+/// `options` must be null or point to a valid [`ListOptionsFfi`]. The returned string must be
+/// released with [`core_free_string`].
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn core_list_addresses_with_options(
+    options: *const ListOptionsFfi,
+) -> *mut c_char {
+    let options = unsafe { ListOptionsFfi::read(options) };
     let repository = zdnp_data::SqliteAddressRepository::new();
 
-    let addresses = match zdnp_core::list_addresses(&repository) {
+    let addresses = match zdnp_core::list_addresses(&repository, &options) {
         Ok(addresses) => addresses,
         Err(_) => return std::ptr::null_mut(),
     };
@@ -367,11 +455,49 @@ pub unsafe extern "C" fn core_list_addresses() -> *mut c_char {
     }
 }
 
+/// Archives the address: it disappears from the lists and can no longer be attached to new
+/// documents, while the documents that already use it keep it. Returns `false` when the address
+/// does not exist or the database cannot be written.
+///
+/// Takes no pointers and returns no memory owned by the caller.
+#[unsafe(no_mangle)]
+pub extern "C" fn core_archive_address(id: i64) -> bool {
+    let repository = zdnp_data::SqliteAddressRepository::new();
+    zdnp_core::archive_address(&repository, id).is_ok()
+}
+
+/// Restores an archived address. Returns `false` when the address does not exist or the database
+/// cannot be written.
+///
+/// Takes no pointers and returns no memory owned by the caller.
+#[unsafe(no_mangle)]
+pub extern "C" fn core_restore_address(id: i64) -> bool {
+    let repository = zdnp_data::SqliteAddressRepository::new();
+    zdnp_core::restore_address(&repository, id).is_ok()
+}
+
+/// Returns non-archived organizations as a JSON array.
+///
+/// # Safety
+/// The returned string must be released with [`core_free_string`].
 #[unsafe(no_mangle)]
 pub unsafe extern "C" fn core_list_organizations() -> *mut c_char {
+    unsafe { core_list_organizations_with_options(std::ptr::null()) }
+}
+
+/// Returns organizations as a JSON array, optionally including archived rows.
+///
+/// # Safety
+/// `options` must be null or point to a valid [`ListOptionsFfi`]. The returned string must be
+/// released with [`core_free_string`].
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn core_list_organizations_with_options(
+    options: *const ListOptionsFfi,
+) -> *mut c_char {
+    let options = unsafe { ListOptionsFfi::read(options) };
     let repository = zdnp_data::SqliteOrganizationRepository::new();
 
-    let organizations = match zdnp_core::list_organizations(&repository) {
+    let organizations = match zdnp_core::list_organizations(&repository, &options) {
         Ok(organizations) => organizations,
         Err(_) => return std::ptr::null_mut(),
     };
@@ -387,11 +513,49 @@ pub unsafe extern "C" fn core_list_organizations() -> *mut c_char {
     }
 }
 
+/// Archives the organization: it disappears from the lists and can no longer be attached to new
+/// documents, while the documents that already use it keep it. Returns `false` when the
+/// organization does not exist or the database cannot be written.
+///
+/// Takes no pointers and returns no memory owned by the caller.
+#[unsafe(no_mangle)]
+pub extern "C" fn core_archive_organization(id: i64) -> bool {
+    let repository = zdnp_data::SqliteOrganizationRepository::new();
+    zdnp_core::archive_organization(&repository, id).is_ok()
+}
+
+/// Restores an archived organization. Returns `false` when the organization does not exist or the
+/// database cannot be written.
+///
+/// Takes no pointers and returns no memory owned by the caller.
+#[unsafe(no_mangle)]
+pub extern "C" fn core_restore_organization(id: i64) -> bool {
+    let repository = zdnp_data::SqliteOrganizationRepository::new();
+    zdnp_core::restore_organization(&repository, id).is_ok()
+}
+
+/// Returns non-archived entrepreneurs as a JSON array.
+///
+/// # Safety
+/// The returned string must be released with [`core_free_string`].
 #[unsafe(no_mangle)]
 pub unsafe extern "C" fn core_list_entrepreneurs() -> *mut c_char {
+    unsafe { core_list_entrepreneurs_with_options(std::ptr::null()) }
+}
+
+/// Returns entrepreneurs as a JSON array, optionally including archived rows.
+///
+/// # Safety
+/// `options` must be null or point to a valid [`ListOptionsFfi`]. The returned string must be
+/// released with [`core_free_string`].
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn core_list_entrepreneurs_with_options(
+    options: *const ListOptionsFfi,
+) -> *mut c_char {
+    let options = unsafe { ListOptionsFfi::read(options) };
     let repository = zdnp_data::SqliteEntrepreneurRepository::new();
 
-    let entrepreneurs = match zdnp_core::list_entrepreneurs(&repository) {
+    let entrepreneurs = match zdnp_core::list_entrepreneurs(&repository, &options) {
         Ok(entrepreneurs) => entrepreneurs,
         Err(_) => return std::ptr::null_mut(),
     };
@@ -407,11 +571,49 @@ pub unsafe extern "C" fn core_list_entrepreneurs() -> *mut c_char {
     }
 }
 
+/// Archives the entrepreneur: it disappears from the lists and can no longer be attached to new
+/// documents, while the documents that already use it keep it. Returns `false` when the
+/// entrepreneur does not exist or the database cannot be written.
+///
+/// Takes no pointers and returns no memory owned by the caller.
+#[unsafe(no_mangle)]
+pub extern "C" fn core_archive_entrepreneur(id: i64) -> bool {
+    let repository = zdnp_data::SqliteEntrepreneurRepository::new();
+    zdnp_core::archive_entrepreneur(&repository, id).is_ok()
+}
+
+/// Restores an archived entrepreneur. Returns `false` when the entrepreneur does not exist or the
+/// database cannot be written.
+///
+/// Takes no pointers and returns no memory owned by the caller.
+#[unsafe(no_mangle)]
+pub extern "C" fn core_restore_entrepreneur(id: i64) -> bool {
+    let repository = zdnp_data::SqliteEntrepreneurRepository::new();
+    zdnp_core::restore_entrepreneur(&repository, id).is_ok()
+}
+
+/// Returns non-archived persons as a JSON array.
+///
+/// # Safety
+/// The returned string must be released with [`core_free_string`].
 #[unsafe(no_mangle)]
 pub unsafe extern "C" fn core_list_persons() -> *mut c_char {
+    unsafe { core_list_persons_with_options(std::ptr::null()) }
+}
+
+/// Returns persons as a JSON array, optionally including archived rows.
+///
+/// # Safety
+/// `options` must be null or point to a valid [`ListOptionsFfi`]. The returned string must be
+/// released with [`core_free_string`].
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn core_list_persons_with_options(
+    options: *const ListOptionsFfi,
+) -> *mut c_char {
+    let options = unsafe { ListOptionsFfi::read(options) };
     let repository = zdnp_data::SqlitePersonRepository::new();
 
-    let persons = match zdnp_core::list_persons(&repository) {
+    let persons = match zdnp_core::list_persons(&repository, &options) {
         Ok(persons) => persons,
         Err(_) => return std::ptr::null_mut(),
     };
@@ -427,20 +629,1947 @@ pub unsafe extern "C" fn core_list_persons() -> *mut c_char {
     }
 }
 
+/// Archives the person: it disappears from the lists and can no longer be attached to new
+/// documents, while the documents that already use it keep it. Returns `false` when the person does
+/// not exist or the database cannot be written.
+///
+/// Takes no pointers and returns no memory owned by the caller.
 #[unsafe(no_mangle)]
-pub extern "C" fn core_add(a: i32, b: i32) -> i32 {
-    zdnp_core::add_i32(a, b)
+pub extern "C" fn core_archive_person(id: i64) -> bool {
+    let repository = zdnp_data::SqlitePersonRepository::new();
+    zdnp_core::archive_person(&repository, id).is_ok()
 }
 
-/// Returns a pointer to a null-terminated static string with version info.
-/// Do NOT attempt to free this pointer on the C# side.
+/// Restores an archived person. Returns `false` when the person does not exist or the database
+/// cannot be written.
+///
+/// Takes no pointers and returns no memory owned by the caller.
 #[unsafe(no_mangle)]
-pub extern "C" fn core_version() -> *const c_char {
-    b"0.1.0\0".as_ptr() as *const c_char
+pub extern "C" fn core_restore_person(id: i64) -> bool {
+    let repository = zdnp_data::SqlitePersonRepository::new();
+    zdnp_core::restore_person(&repository, id).is_ok()
 }
 
+/// Finds an address, organization, entrepreneur, person or source document by its uid. Returns
+/// the entity as JSON tagged with its `kind`, or null when there is none.
+///
+/// # Safety
+/// `uid` must be null or a null-terminated UTF-8 string. The returned string must be released
+/// with [`core_free_string`].
 #[unsafe(no_mangle)]
-pub extern "C" fn migrations_run() -> bool {
-    let migrations = zdnp_data::SqliteMigrations::new();
-    migrations.run().is_ok()
+pub unsafe extern "C" fn core_find_reference_by_uid(uid: *const c_char) -> *mut c_char {
+    let uid = match unsafe { read_c_str(uid) } {
+        Ok(Some(uid)) => uid,
+        _ => return std::ptr::null_mut(),
+    };
+    let references = zdnp_data::SqliteReferenceRepository::new();
+
+    match zdnp_core::find_reference_by_uid(&references, &uid) {
+        Ok(Some(entity)) => json_c_string(&entity),
+        _ => std::ptr::null_mut(),
+    }
+}
+
+// ---------------- Documents ----------------
+
+/// Document fields passed from the front end. `author_type` follows the `author_type` table
+/// (1 — organization, 2 — entrepreneur, 3 — person, 4 — informal author named by
+/// `author_name`). `developer_type` is 0 when the developer is not chosen yet, 1 for an
+/// organization and 2 for a person. `document_type` is an identifier from
+/// [`core_list_document_types`] or 0 when the type is not chosen yet. When `number` is empty
+/// and `numbering_prefix` names a scheme from [`core_list_numbering_schemes`], the number is
+/// allocated from the counter of that prefix for `numbering_year` (0 — the year of `date`). An
+/// empty `uid` is replaced with a generated UUID.
+#[repr(C)]
+pub struct DocumentDtoFfi {
+    pub name: *const c_char,
+    pub number: *const c_char,
+    pub date: *const c_char,
+    pub uid: *const c_char,
+    pub document_type: i64,
+    pub author_type: i32,
+    pub author_id: i64,
+    pub author_name: *const c_char,
+    pub developer_type: i32,
+    pub developer_id: i64,
+    pub numbering_prefix: *const c_char,
+    pub numbering_year: i32,
+}
+
+impl DocumentDtoFfi {
+    /// # Safety
+    /// All pointers must either be null or reference valid null-terminated UTF-8 strings.
+    unsafe fn try_into_core(&self) -> Result<DocumentDto, FfiConversionError> {
+        let author = match self.author_type {
+            1 => Some(DocumentAuthor::Organization { id: self.author_id }),
+            2 => Some(DocumentAuthor::Entrepreneur { id: self.author_id }),
+            3 => Some(DocumentAuthor::Person { id: self.author_id }),
+            4 => Some(DocumentAuthor::Informal {
+                name: unsafe { read_c_str(self.author_name) }?.unwrap_or_default(),
+            }),
+            _ => None,
+        };
+        let developer = match self.developer_type {
+            1 => Some(DocumentDeveloper::Organization {
+                id: self.developer_id,
+            }),
+            2 => Some(DocumentDeveloper::Person {
+                id: self.developer_id,
+            }),
+            _ => None,
+        };
+        let document_type = match self.document_type {
+            0 => None,
+            id => {
+                Some(DocumentType::from_id(id).ok_or(FfiConversionError::UnknownDocumentType(id))?)
+            }
+        };
+        let date = unsafe { read_c_str(self.date) }?;
+        let numbering = numbering(
+            unsafe { read_c_str(self.numbering_prefix) }?,
+            self.numbering_year,
+            date.as_deref(),
+        );
+
+        Ok(DocumentDto {
+            name: unsafe { read_c_str(self.name) }?,
+            number: unsafe { read_c_str(self.number) }?,
+            date,
+            uid: unsafe { read_c_str(self.uid) }?,
+            document_type,
+            author,
+            developer,
+            numbering,
+            ..Default::default()
+        })
+    }
+}
+
+/// Builds the numbering of a new document: the counter of the stored scheme `prefix` for `year`,
+/// or for the year of `date` when `year` is 0.
+fn numbering(prefix: Option<String>, year: i32, date: Option<&str>) -> Option<DocumentNumbering> {
+    let prefix = prefix?;
+    match year {
+        0 => date.and_then(|date| DocumentNumbering::for_date(&prefix, date)),
+        year => Some(DocumentNumbering { prefix, year }),
+    }
+}
+
+/// Returns the stored numbering schemes with the counter width of each prefix as a JSON array.
+///
+/// # Safety
+/// The returned string must be released with [`core_free_string`].
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn core_list_numbering_schemes() -> *mut c_char {
+    let repository = zdnp_data::SqliteDocumentRepository::new();
+
+    match zdnp_core::list_numbering_schemes(&repository) {
+        Ok(schemes) => json_c_string(&schemes),
+        Err(_) => std::ptr::null_mut(),
+    }
+}
+
+/// Stores a numbering scheme whose counter is padded to `width` digits (1 to 10). Returns `false`
+/// when the prefix is empty or contains whitespace, the width is out of range or a scheme with
+/// the prefix already exists.
+///
+/// # Safety
+/// `prefix` must be null or a null-terminated UTF-8 string.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn core_create_numbering_scheme(prefix: *const c_char, width: i32) -> bool {
+    let prefix = match unsafe { read_c_str(prefix) } {
+        Ok(Some(prefix)) => prefix,
+        _ => return false,
+    };
+    let width = match usize::try_from(width) {
+        Ok(width) => width,
+        Err(_) => return false,
+    };
+
+    let repository = zdnp_data::SqliteDocumentRepository::new();
+
+    zdnp_core::create_numbering_scheme(&repository, &NumberingScheme { prefix, width }).is_ok()
+}
+
+/// Returns the available document types with their required sections as a JSON array.
+///
+/// # Safety
+/// The returned string must be released with [`core_free_string`].
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn core_list_document_types() -> *mut c_char {
+    json_c_string(&zdnp_core::list_document_types())
+}
+
+/// Creates a draft document and writes the new identifier into `out_id`.
+///
+/// # Safety
+/// `dto` must be null or point to a valid [`DocumentDtoFfi`] whose string fields are null or
+/// null-terminated UTF-8. `out_id` must be null or valid for writes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn core_create_document(
+    dto: *const DocumentDtoFfi,
+    out_id: *mut i64,
+) -> bool {
+    if let Some(slot) = unsafe { out_id.as_mut() } {
+        *slot = -1;
+    }
+
+    let dto = match unsafe { dto.as_ref() } {
+        Some(dto) => dto,
+        None => return false,
+    };
+    let dto = match unsafe { dto.try_into_core() } {
+        Ok(dto) => dto,
+        Err(_) => return false,
+    };
+
+    let documents = zdnp_data::SqliteDocumentRepository::new();
+    let references = zdnp_data::SqliteReferenceRepository::new();
+
+    match zdnp_core::create_document(&documents, &references, &dto) {
+        Ok(id) => {
+            if let Some(slot) = unsafe { out_id.as_mut() } {
+                *slot = id;
+            }
+            true
+        }
+        Err(_) => false,
+    }
+}
+
+/// Returns a new RFC 4122 UUID for a document.
+///
+/// # Safety
+/// The returned string must be released with [`core_free_string`].
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn core_generate_document_uid() -> *mut c_char {
+    match CString::new(zdnp_core::generate_uid()) {
+        Ok(uid) => uid.into_raw(),
+        Err(_) => std::ptr::null_mut(),
+    }
+}
+
+/// Updates a draft document. Documents past the draft stage are read-only.
+///
+/// # Safety
+/// `dto` must be null or point to a valid [`DocumentDtoFfi`] whose string fields are null or
+/// null-terminated UTF-8.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn core_update_document(id: i64, dto: *const DocumentDtoFfi) -> bool {
+    let dto = match unsafe { dto.as_ref() } {
+        Some(dto) => dto,
+        None => return false,
+    };
+    let dto = match unsafe { dto.try_into_core() } {
+        Ok(dto) => dto,
+        Err(_) => return false,
+    };
+
+    let documents = zdnp_data::SqliteDocumentRepository::new();
+    let references = zdnp_data::SqliteReferenceRepository::new();
+
+    zdnp_core::update_document(&documents, &references, id, &dto).is_ok()
+}
+
+/// Returns what is missing before the document can be submitted for review as a JSON array.
+///
+/// # Safety
+/// The returned string must be released with [`core_free_string`].
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn core_check_document_completeness(id: i64) -> *mut c_char {
+    let repository = zdnp_data::SqliteDocumentRepository::new();
+
+    match zdnp_core::document_completeness(&repository, id) {
+        Ok(issues) => json_c_string(&issues),
+        Err(_) => std::ptr::null_mut(),
+    }
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn core_submit_document_for_review(id: i64) -> bool {
+    let repository = zdnp_data::SqliteDocumentRepository::new();
+    zdnp_core::submit_for_review(&repository, id).is_ok()
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn core_return_document_to_draft(id: i64) -> bool {
+    let repository = zdnp_data::SqliteDocumentRepository::new();
+    zdnp_core::return_to_draft(&repository, id).is_ok()
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn core_approve_document(id: i64) -> bool {
+    let repository = zdnp_data::SqliteDocumentRepository::new();
+    zdnp_core::approve_document(&repository, id).is_ok()
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn core_cancel_document(id: i64) -> bool {
+    let repository = zdnp_data::SqliteDocumentRepository::new();
+    zdnp_core::cancel_document(&repository, id).is_ok()
+}
+
+/// Construction object fields passed from the front end. `cadastral_numbers` lists the numbers
+/// separated by `;` or line breaks. `work_type` is `construction`, `reconstruction` or
+/// `capital_repair`.
+#[repr(C)]
+pub struct ConstructionObjectDtoFfi {
+    pub name: *const c_char,
+    pub purpose_code: *const c_char,
+    pub address_id: i64,
+    pub cadastral_numbers: *const c_char,
+    pub work_type: *const c_char,
+}
+
+impl ConstructionObjectDtoFfi {
+    /// # Safety
+    /// All pointers must either be null or reference valid null-terminated UTF-8 strings.
+    unsafe fn try_into_core(&self) -> Result<ConstructionObjectDto, FfiConversionError> {
+        let cadastral_numbers = unsafe { read_c_str(self.cadastral_numbers) }?
+            .map(|numbers| {
+                numbers
+                    .split([';', '\n'])
+                    .map(|number| number.trim().to_string())
+                    .filter(|number| !number.is_empty())
+                    .collect()
+            })
+            .unwrap_or_default();
+        let work_type = match unsafe { read_c_str(self.work_type) }? {
+            None => None,
+            Some(code) => Some(
+                WorkType::parse(code.trim()).ok_or(FfiConversionError::UnknownWorkType(code))?,
+            ),
+        };
+
+        Ok(ConstructionObjectDto {
+            name: unsafe { read_c_str(self.name) }?,
+            purpose_code: unsafe { read_c_str(self.purpose_code) }?,
+            address_id: self.address_id,
+            cadastral_numbers,
+            work_type,
+        })
+    }
+}
+
+/// Fills in the construction object of a draft document and writes its identifier into
+/// `out_id`.
+///
+/// # Safety
+/// `dto` must be null or point to a valid [`ConstructionObjectDtoFfi`] whose string fields are
+/// null or null-terminated UTF-8. `out_id` must be null or valid for writes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn core_save_construction_object(
+    document_id: i64,
+    dto: *const ConstructionObjectDtoFfi,
+    out_id: *mut i64,
+) -> bool {
+    if let Some(slot) = unsafe { out_id.as_mut() } {
+        *slot = -1;
+    }
+
+    let dto = match unsafe { dto.as_ref() } {
+        Some(dto) => dto,
+        None => return false,
+    };
+    let dto = match unsafe { dto.try_into_core() } {
+        Ok(dto) => dto,
+        Err(_) => return false,
+    };
+
+    let documents = zdnp_data::SqliteDocumentRepository::new();
+    let references = zdnp_data::SqliteReferenceRepository::new();
+
+    match zdnp_core::save_construction_object(&documents, &references, document_id, &dto) {
+        Ok(id) => {
+            if let Some(slot) = unsafe { out_id.as_mut() } {
+                *slot = id;
+            }
+            true
+        }
+        Err(_) => false,
+    }
+}
+
+/// Returns the construction object of the document as JSON, `null` when it is not filled in.
+///
+/// # Safety
+/// The returned string must be released with [`core_free_string`].
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn core_get_construction_object(document_id: i64) -> *mut c_char {
+    let repository = zdnp_data::SqliteDocumentRepository::new();
+
+    match zdnp_core::find_construction_object(&repository, document_id) {
+        Ok(object) => json_c_string(&object),
+        Err(_) => std::ptr::null_mut(),
+    }
+}
+
+/// Indicator as passed in from the front end. `value` may use a comma as the decimal
+/// separator; `unit_okei` is a code from [`core_list_okei_units`].
+#[repr(C)]
+pub struct TechnicalIndicatorDtoFfi {
+    pub name: *const c_char,
+    pub value: *const c_char,
+    pub unit_okei: *const c_char,
+}
+
+impl TechnicalIndicatorDtoFfi {
+    /// # Safety
+    /// All pointers must either be null or reference valid null-terminated UTF-8 strings.
+    unsafe fn try_into_core(&self) -> Result<TechnicalIndicatorDto, FfiConversionError> {
+        Ok(TechnicalIndicatorDto {
+            name: unsafe { read_c_str(self.name) }?,
+            value: unsafe { read_c_str(self.value) }?,
+            unit_okei: unsafe { read_c_str(self.unit_okei) }?,
+        })
+    }
+}
+
+/// Returns the supported OKEI units of measurement as a JSON array.
+///
+/// # Safety
+/// The returned string must be released with [`core_free_string`].
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn core_list_okei_units() -> *mut c_char {
+    json_c_string(&zdnp_core::OKEI_UNITS)
+}
+
+/// Appends an indicator to a draft document and writes its identifier into `out_id`.
+///
+/// # Safety
+/// `dto` must be null or point to a valid [`TechnicalIndicatorDtoFfi`] whose string fields are
+/// null or null-terminated UTF-8. `out_id` must be null or valid for writes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn core_add_technical_indicator(
+    document_id: i64,
+    dto: *const TechnicalIndicatorDtoFfi,
+    out_id: *mut i64,
+) -> bool {
+    if let Some(slot) = unsafe { out_id.as_mut() } {
+        *slot = -1;
+    }
+
+    let dto = match unsafe { dto.as_ref() } {
+        Some(dto) => dto,
+        None => return false,
+    };
+    let dto = match unsafe { dto.try_into_core() } {
+        Ok(dto) => dto,
+        Err(_) => return false,
+    };
+
+    let repository = zdnp_data::SqliteDocumentRepository::new();
+
+    match zdnp_core::add_indicator(&repository, document_id, &dto) {
+        Ok(id) => {
+            if let Some(slot) = unsafe { out_id.as_mut() } {
+                *slot = id;
+            }
+            true
+        }
+        Err(_) => false,
+    }
+}
+
+/// Updates an indicator of a draft document.
+///
+/// # Safety
+/// `dto` must be null or point to a valid [`TechnicalIndicatorDtoFfi`] whose string fields are
+/// null or null-terminated UTF-8.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn core_update_technical_indicator(
+    id: i64,
+    dto: *const TechnicalIndicatorDtoFfi,
+) -> bool {
+    let dto = match unsafe { dto.as_ref() } {
+        Some(dto) => dto,
+        None => return false,
+    };
+    let dto = match unsafe { dto.try_into_core() } {
+        Ok(dto) => dto,
+        Err(_) => return false,
+    };
+
+    let repository = zdnp_data::SqliteDocumentRepository::new();
+
+    zdnp_core::update_indicator(&repository, id, &dto).is_ok()
+}
+
+/// Removes an indicator from a draft document.
+#[unsafe(no_mangle)]
+pub extern "C" fn core_delete_technical_indicator(id: i64) -> bool {
+    let repository = zdnp_data::SqliteDocumentRepository::new();
+
+    zdnp_core::delete_indicator(&repository, id).is_ok()
+}
+
+/// Returns the indicators of the document as a JSON array in their display order.
+///
+/// # Safety
+/// The returned string must be released with [`core_free_string`].
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn core_list_technical_indicators(document_id: i64) -> *mut c_char {
+    let repository = zdnp_data::SqliteDocumentRepository::new();
+
+    match zdnp_core::list_indicators(&repository, document_id) {
+        Ok(indicators) => json_c_string(&indicators),
+        Err(_) => std::ptr::null_mut(),
+    }
+}
+
+/// Puts the indicators of a draft document in the order of `ids`, which must list every
+/// indicator of the document exactly once.
+///
+/// # Safety
+/// `ids` must be null or point to `count` readable identifiers.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn core_reorder_technical_indicators(
+    document_id: i64,
+    ids: *const i64,
+    count: usize,
+) -> bool {
+    let ids = if count == 0 {
+        &[][..]
+    } else if ids.is_null() {
+        return false;
+    } else {
+        unsafe { std::slice::from_raw_parts(ids, count) }
+    };
+
+    let repository = zdnp_data::SqliteDocumentRepository::new();
+
+    zdnp_core::reorder_indicators(&repository, document_id, ids).is_ok()
+}
+
+/// Reads a requirement category code such as `fire_safety`.
+///
+/// # Safety
+/// The pointer must either be null or reference a valid null-terminated UTF-8 string.
+unsafe fn read_requirement_category(
+    ptr: *const c_char,
+) -> Result<Option<RequirementCategory>, FfiConversionError> {
+    match unsafe { read_c_str(ptr) }? {
+        None => Ok(None),
+        Some(code) => RequirementCategory::parse(code.trim())
+            .map(Some)
+            .ok_or(FfiConversionError::UnknownRequirementCategory(code)),
+    }
+}
+
+/// Requirement section or template as passed in from the front end. `category` is a code from
+/// [`core_list_requirement_categories`]; `text` may contain the placeholders listed by
+/// [`core_list_requirement_placeholders`].
+#[repr(C)]
+pub struct RequirementDtoFfi {
+    pub category: *const c_char,
+    pub title: *const c_char,
+    pub text: *const c_char,
+}
+
+impl RequirementDtoFfi {
+    /// # Safety
+    /// All pointers must either be null or reference valid null-terminated UTF-8 strings.
+    unsafe fn try_into_section(&self) -> Result<RequirementSectionDto, FfiConversionError> {
+        Ok(RequirementSectionDto {
+            category: unsafe { read_requirement_category(self.category) }?,
+            title: unsafe { read_c_str(self.title) }?,
+            text: unsafe { read_c_str(self.text) }?,
+            template_id: None,
+        })
+    }
+
+    /// # Safety
+    /// All pointers must either be null or reference valid null-terminated UTF-8 strings.
+    unsafe fn try_into_template(&self) -> Result<RequirementTemplateDto, FfiConversionError> {
+        Ok(RequirementTemplateDto {
+            category: unsafe { read_requirement_category(self.category) }?,
+            title: unsafe { read_c_str(self.title) }?,
+            text: unsafe { read_c_str(self.text) }?,
+        })
+    }
+}
+
+/// Returns the requirement categories as a JSON array of `{ "code", "name" }` objects.
+///
+/// # Safety
+/// The returned string must be released with [`core_free_string`].
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn core_list_requirement_categories() -> *mut c_char {
+    json_c_string(&zdnp_core::list_requirement_categories())
+}
+
+/// Returns the placeholders available in requirement texts as a JSON array of
+/// `{ "key", "name" }` objects.
+///
+/// # Safety
+/// The returned string must be released with [`core_free_string`].
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn core_list_requirement_placeholders() -> *mut c_char {
+    json_c_string(&zdnp_core::PLACEHOLDERS)
+}
+
+/// Appends a requirement section to a draft document and writes its identifier into `out_id`.
+///
+/// # Safety
+/// `dto` must be null or point to a valid [`RequirementDtoFfi`] whose string fields are null or
+/// null-terminated UTF-8. `out_id` must be null or valid for writes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn core_add_requirement(
+    document_id: i64,
+    dto: *const RequirementDtoFfi,
+    out_id: *mut i64,
+) -> bool {
+    if let Some(slot) = unsafe { out_id.as_mut() } {
+        *slot = -1;
+    }
+
+    let dto = match unsafe { dto.as_ref() } {
+        Some(dto) => dto,
+        None => return false,
+    };
+    let dto = match unsafe { dto.try_into_section() } {
+        Ok(dto) => dto,
+        Err(_) => return false,
+    };
+
+    let repository = zdnp_data::SqliteDocumentRepository::new();
+
+    match zdnp_core::add_requirement(&repository, document_id, &dto) {
+        Ok(id) => {
+            if let Some(slot) = unsafe { out_id.as_mut() } {
+                *slot = id;
+            }
+            true
+        }
+        Err(_) => false,
+    }
+}
+
+/// Appends a copy of a library template to a draft document and writes the identifier of the
+/// new section into `out_id`.
+///
+/// # Safety
+/// `out_id` must be null or valid for writes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn core_insert_requirement_template(
+    document_id: i64,
+    template_id: i64,
+    out_id: *mut i64,
+) -> bool {
+    if let Some(slot) = unsafe { out_id.as_mut() } {
+        *slot = -1;
+    }
+
+    let documents = zdnp_data::SqliteDocumentRepository::new();
+    let templates = zdnp_data::SqliteRequirementTemplateRepository::new();
+
+    match zdnp_core::insert_requirement_template(&documents, &templates, document_id, template_id) {
+        Ok(id) => {
+            if let Some(slot) = unsafe { out_id.as_mut() } {
+                *slot = id;
+            }
+            true
+        }
+        Err(_) => false,
+    }
+}
+
+/// Updates a requirement section of a draft document.
+///
+/// # Safety
+/// `dto` must be null or point to a valid [`RequirementDtoFfi`] whose string fields are null or
+/// null-terminated UTF-8.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn core_update_requirement(id: i64, dto: *const RequirementDtoFfi) -> bool {
+    let dto = match unsafe { dto.as_ref() } {
+        Some(dto) => dto,
+        None => return false,
+    };
+    let dto = match unsafe { dto.try_into_section() } {
+        Ok(dto) => dto,
+        Err(_) => return false,
+    };
+
+    let repository = zdnp_data::SqliteDocumentRepository::new();
+
+    zdnp_core::update_requirement(&repository, id, &dto).is_ok()
+}
+
+/// Removes a requirement section from a draft document.
+#[unsafe(no_mangle)]
+pub extern "C" fn core_delete_requirement(id: i64) -> bool {
+    let repository = zdnp_data::SqliteDocumentRepository::new();
+
+    zdnp_core::delete_requirement(&repository, id).is_ok()
+}
+
+/// Returns the requirement sections of the document as a JSON array in their display order.
+/// The texts keep their placeholders.
+///
+/// # Safety
+/// The returned string must be released with [`core_free_string`].
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn core_list_requirements(document_id: i64) -> *mut c_char {
+    let repository = zdnp_data::SqliteDocumentRepository::new();
+
+    match zdnp_core::list_requirements(&repository, document_id) {
+        Ok(requirements) => json_c_string(&requirements),
+        Err(_) => std::ptr::null_mut(),
+    }
+}
+
+/// Puts the requirement sections of a draft document in the order of `ids`, which must list
+/// every section of the document exactly once.
+///
+/// # Safety
+/// `ids` must be null or point to `count` readable identifiers.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn core_reorder_requirements(
+    document_id: i64,
+    ids: *const i64,
+    count: usize,
+) -> bool {
+    let ids = if count == 0 {
+        &[][..]
+    } else if ids.is_null() {
+        return false;
+    } else {
+        unsafe { std::slice::from_raw_parts(ids, count) }
+    };
+
+    let repository = zdnp_data::SqliteDocumentRepository::new();
+
+    zdnp_core::reorder_requirements(&repository, document_id, ids).is_ok()
+}
+
+/// Adds a template to the requirement library and writes its identifier into `out_id`.
+///
+/// # Safety
+/// `dto` must be null or point to a valid [`RequirementDtoFfi`] whose string fields are null or
+/// null-terminated UTF-8. `out_id` must be null or valid for writes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn core_create_requirement_template(
+    dto: *const RequirementDtoFfi,
+    out_id: *mut i64,
+) -> bool {
+    if let Some(slot) = unsafe { out_id.as_mut() } {
+        *slot = -1;
+    }
+
+    let dto = match unsafe { dto.as_ref() } {
+        Some(dto) => dto,
+        None => return false,
+    };
+    let dto = match unsafe { dto.try_into_template() } {
+        Ok(dto) => dto,
+        Err(_) => return false,
+    };
+
+    let repository = zdnp_data::SqliteRequirementTemplateRepository::new();
+
+    match zdnp_core::create_requirement_template(&repository, &dto) {
+        Ok(id) => {
+            if let Some(slot) = unsafe { out_id.as_mut() } {
+                *slot = id;
+            }
+            true
+        }
+        Err(_) => false,
+    }
+}
+
+/// Updates a template of the requirement library.
+///
+/// # Safety
+/// `dto` must be null or point to a valid [`RequirementDtoFfi`] whose string fields are null or
+/// null-terminated UTF-8.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn core_update_requirement_template(
+    id: i64,
+    dto: *const RequirementDtoFfi,
+) -> bool {
+    let dto = match unsafe { dto.as_ref() } {
+        Some(dto) => dto,
+        None => return false,
+    };
+    let dto = match unsafe { dto.try_into_template() } {
+        Ok(dto) => dto,
+        Err(_) => return false,
+    };
+
+    let repository = zdnp_data::SqliteRequirementTemplateRepository::new();
+
+    zdnp_core::update_requirement_template(&repository, id, &dto).is_ok()
+}
+
+/// Removes a template from the requirement library.
+#[unsafe(no_mangle)]
+pub extern "C" fn core_delete_requirement_template(id: i64) -> bool {
+    let repository = zdnp_data::SqliteRequirementTemplateRepository::new();
+
+    zdnp_core::delete_requirement_template(&repository, id).is_ok()
+}
+
+/// Returns the requirement library as a JSON array. `category` narrows the list to one
+/// category; null or empty lists every template.
+///
+/// # Safety
+/// `category` must be null or a null-terminated UTF-8 string. The returned string must be
+/// released with [`core_free_string`].
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn core_list_requirement_templates(category: *const c_char) -> *mut c_char {
+    let category = match unsafe { read_requirement_category(category) } {
+        Ok(category) => category,
+        Err(_) => return std::ptr::null_mut(),
+    };
+
+    let repository = zdnp_data::SqliteRequirementTemplateRepository::new();
+
+    match zdnp_core::list_requirement_templates(&repository, category) {
+        Ok(templates) => json_c_string(&templates),
+        Err(_) => std::ptr::null_mut(),
+    }
+}
+
+/// Source document as passed in from the front end. `kind` is a code from
+/// [`core_list_source_document_kinds`], `date` is `YYYY-MM-DD` and `issuer_id` points to an
+/// organization.
+#[repr(C)]
+pub struct SourceDocumentDtoFfi {
+    pub kind: *const c_char,
+    pub number: *const c_char,
+    pub date: *const c_char,
+    pub issuer_id: i64,
+    pub title: *const c_char,
+}
+
+impl SourceDocumentDtoFfi {
+    /// # Safety
+    /// All pointers must either be null or reference valid null-terminated UTF-8 strings.
+    unsafe fn try_into_core(&self) -> Result<SourceDocumentDto, FfiConversionError> {
+        let kind = match unsafe { read_c_str(self.kind) }? {
+            None => None,
+            Some(code) => Some(
+                SourceDocumentKind::parse(code.trim())
+                    .ok_or(FfiConversionError::UnknownSourceDocumentKind(code))?,
+            ),
+        };
+
+        Ok(SourceDocumentDto {
+            kind,
+            number: unsafe { read_c_str(self.number) }?,
+            date: unsafe { read_c_str(self.date) }?,
+            issuer_id: self.issuer_id,
+            title: unsafe { read_c_str(self.title) }?,
+            uid: None,
+        })
+    }
+}
+
+/// Returns the source document kinds as a JSON array of `{ "code", "name" }` objects.
+///
+/// # Safety
+/// The returned string must be released with [`core_free_string`].
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn core_list_source_document_kinds() -> *mut c_char {
+    json_c_string(&zdnp_core::list_source_document_kinds())
+}
+
+/// Adds a source document to the register and writes its identifier into `out_id`.
+///
+/// # Safety
+/// `dto` must be null or point to a valid [`SourceDocumentDtoFfi`] whose string fields are null
+/// or null-terminated UTF-8. `out_id` must be null or valid for writes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn core_create_source_document(
+    dto: *const SourceDocumentDtoFfi,
+    out_id: *mut i64,
+) -> bool {
+    if let Some(slot) = unsafe { out_id.as_mut() } {
+        *slot = -1;
+    }
+
+    let dto = match unsafe { dto.as_ref() } {
+        Some(dto) => dto,
+        None => return false,
+    };
+    let dto = match unsafe { dto.try_into_core() } {
+        Ok(dto) => dto,
+        Err(_) => return false,
+    };
+
+    let repository = zdnp_data::SqliteSourceDocumentRepository::new();
+    let references = zdnp_data::SqliteReferenceRepository::new();
+
+    match zdnp_core::create_source_document(&repository, &references, &dto) {
+        Ok(id) => {
+            if let Some(slot) = unsafe { out_id.as_mut() } {
+                *slot = id;
+            }
+            true
+        }
+        Err(_) => false,
+    }
+}
+
+/// Returns the source documents register as a JSON array, optionally including archived rows.
+///
+/// # Safety
+/// `options` must be null or point to a valid [`ListOptionsFfi`]. The returned string must be
+/// released with [`core_free_string`].
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn core_list_source_documents(options: *const ListOptionsFfi) -> *mut c_char {
+    let options = unsafe { ListOptionsFfi::read(options) };
+    let repository = zdnp_data::SqliteSourceDocumentRepository::new();
+
+    match zdnp_core::list_source_documents(&repository, &options) {
+        Ok(sources) => json_c_string(&sources),
+        Err(_) => std::ptr::null_mut(),
+    }
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn core_archive_source_document(id: i64) -> bool {
+    let repository = zdnp_data::SqliteSourceDocumentRepository::new();
+    zdnp_core::archive_source_document(&repository, id).is_ok()
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn core_restore_source_document(id: i64) -> bool {
+    let repository = zdnp_data::SqliteSourceDocumentRepository::new();
+    zdnp_core::restore_source_document(&repository, id).is_ok()
+}
+
+/// Links a source document to a draft document.
+#[unsafe(no_mangle)]
+pub extern "C" fn core_attach_source_document(document_id: i64, source_id: i64) -> bool {
+    let documents = zdnp_data::SqliteDocumentRepository::new();
+    let references = zdnp_data::SqliteReferenceRepository::new();
+
+    zdnp_core::attach_source_document(&documents, &references, document_id, source_id).is_ok()
+}
+
+/// Unlinks a source document from a draft document.
+#[unsafe(no_mangle)]
+pub extern "C" fn core_detach_source_document(document_id: i64, source_id: i64) -> bool {
+    let documents = zdnp_data::SqliteDocumentRepository::new();
+
+    zdnp_core::detach_source_document(&documents, document_id, source_id).is_ok()
+}
+
+/// Returns the source documents the document relies on as a JSON array.
+///
+/// # Safety
+/// The returned string must be released with [`core_free_string`].
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn core_list_document_source_documents(document_id: i64) -> *mut c_char {
+    let documents = zdnp_data::SqliteDocumentRepository::new();
+    let references = zdnp_data::SqliteReferenceRepository::new();
+
+    match zdnp_core::document_source_documents(&documents, &references, document_id) {
+        Ok(sources) => json_c_string(&sources),
+        Err(_) => std::ptr::null_mut(),
+    }
+}
+
+/// Returns the documents that rely on the source document as a JSON array, e.g. every ZNP
+/// based on a GPZU.
+///
+/// # Safety
+/// The returned string must be released with [`core_free_string`].
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn core_list_documents_using_source_document(source_id: i64) -> *mut c_char {
+    let documents = zdnp_data::SqliteDocumentRepository::new();
+
+    match zdnp_core::documents_using_source_document(&documents, source_id) {
+        Ok(using) => json_c_string(&using),
+        Err(_) => std::ptr::null_mut(),
+    }
+}
+
+/// Signatory as passed in from the front end. `role` is a code from [`core_list_signing_roles`]
+/// and `signed_on` is `YYYY-MM-DD` or null while the document is not signed.
+#[repr(C)]
+pub struct SignatoryDtoFfi {
+    pub person_id: i64,
+    pub position: *const c_char,
+    pub role: *const c_char,
+    pub signed_on: *const c_char,
+}
+
+impl SignatoryDtoFfi {
+    /// # Safety
+    /// All pointers must either be null or reference valid null-terminated UTF-8 strings.
+    unsafe fn try_into_core(&self) -> Result<SignatoryDto, FfiConversionError> {
+        let role = match unsafe { read_c_str(self.role) }? {
+            None => None,
+            Some(code) => Some(
+                SigningRole::parse(code.trim())
+                    .ok_or(FfiConversionError::UnknownSigningRole(code))?,
+            ),
+        };
+
+        Ok(SignatoryDto {
+            person_id: self.person_id,
+            position: unsafe { read_c_str(self.position) }?,
+            role,
+            signed_on: unsafe { read_c_str(self.signed_on) }?,
+        })
+    }
+}
+
+/// Returns the signing roles as a JSON array of `{ "code", "name" }` objects.
+///
+/// # Safety
+/// The returned string must be released with [`core_free_string`].
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn core_list_signing_roles() -> *mut c_char {
+    json_c_string(&zdnp_core::list_signing_roles())
+}
+
+/// Adds a signatory to a draft document and writes its identifier into `out_id`.
+///
+/// # Safety
+/// `dto` must be null or point to a valid [`SignatoryDtoFfi`] whose string fields are null or
+/// null-terminated UTF-8. `out_id` must be null or valid for writes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn core_add_signatory(
+    document_id: i64,
+    dto: *const SignatoryDtoFfi,
+    out_id: *mut i64,
+) -> bool {
+    if let Some(slot) = unsafe { out_id.as_mut() } {
+        *slot = -1;
+    }
+
+    let dto = match unsafe { dto.as_ref() } {
+        Some(dto) => dto,
+        None => return false,
+    };
+    let dto = match unsafe { dto.try_into_core() } {
+        Ok(dto) => dto,
+        Err(_) => return false,
+    };
+
+    let documents = zdnp_data::SqliteDocumentRepository::new();
+    let references = zdnp_data::SqliteReferenceRepository::new();
+
+    match zdnp_core::add_signatory(&documents, &references, document_id, &dto) {
+        Ok(id) => {
+            if let Some(slot) = unsafe { out_id.as_mut() } {
+                *slot = id;
+            }
+            true
+        }
+        Err(_) => false,
+    }
+}
+
+/// Updates a signatory of a draft document.
+///
+/// # Safety
+/// `dto` must be null or point to a valid [`SignatoryDtoFfi`] whose string fields are null or
+/// null-terminated UTF-8.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn core_update_signatory(id: i64, dto: *const SignatoryDtoFfi) -> bool {
+    let dto = match unsafe { dto.as_ref() } {
+        Some(dto) => dto,
+        None => return false,
+    };
+    let dto = match unsafe { dto.try_into_core() } {
+        Ok(dto) => dto,
+        Err(_) => return false,
+    };
+
+    let documents = zdnp_data::SqliteDocumentRepository::new();
+    let references = zdnp_data::SqliteReferenceRepository::new();
+
+    zdnp_core::update_signatory(&documents, &references, id, &dto).is_ok()
+}
+
+/// Removes a signatory from a draft document.
+#[unsafe(no_mangle)]
+pub extern "C" fn core_delete_signatory(id: i64) -> bool {
+    let repository = zdnp_data::SqliteDocumentRepository::new();
+
+    zdnp_core::delete_signatory(&repository, id).is_ok()
+}
+
+/// Returns the signatories of the document as a JSON array, the approving one first.
+///
+/// # Safety
+/// The returned string must be released with [`core_free_string`].
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn core_list_signatories(document_id: i64) -> *mut c_char {
+    let repository = zdnp_data::SqliteDocumentRepository::new();
+
+    match zdnp_core::list_signatories(&repository, document_id) {
+        Ok(signatories) => json_c_string(&signatories),
+        Err(_) => std::ptr::null_mut(),
+    }
+}
+
+/// Copies a file into the attachment store, attaches it to the document and writes the
+/// identifier of the attachment into `out_id`.
+///
+/// # Safety
+/// `path` must be null or a null-terminated UTF-8 string. `out_id` must be null or valid for
+/// writes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn core_add_attachment(
+    document_id: i64,
+    path: *const c_char,
+    out_id: *mut i64,
+) -> bool {
+    if let Some(slot) = unsafe { out_id.as_mut() } {
+        *slot = -1;
+    }
+
+    let path = match unsafe { read_c_str(path) } {
+        Ok(Some(path)) => path,
+        _ => return false,
+    };
+
+    let repository = zdnp_data::SqliteDocumentRepository::new();
+
+    match zdnp_core::add_attachment(&repository, document_id, std::path::Path::new(&path)) {
+        Ok(attachment) => {
+            if let Some(slot) = unsafe { out_id.as_mut() } {
+                *slot = attachment.id;
+            }
+            true
+        }
+        Err(_) => false,
+    }
+}
+
+/// Returns the attachments of the document as a JSON array.
+///
+/// # Safety
+/// The returned string must be released with [`core_free_string`].
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn core_list_attachments(document_id: i64) -> *mut c_char {
+    let repository = zdnp_data::SqliteDocumentRepository::new();
+
+    match zdnp_core::list_attachments(&repository, document_id) {
+        Ok(attachments) => json_c_string(&attachments),
+        Err(_) => std::ptr::null_mut(),
+    }
+}
+
+/// Writes the content of an attachment to `destination`. Fails when the stored content does not
+/// match its hash.
+///
+/// # Safety
+/// `destination` must be null or a null-terminated UTF-8 string.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn core_extract_attachment(id: i64, destination: *const c_char) -> bool {
+    let destination = match unsafe { read_c_str(destination) } {
+        Ok(Some(destination)) => destination,
+        _ => return false,
+    };
+
+    let repository = zdnp_data::SqliteDocumentRepository::new();
+
+    zdnp_core::extract_attachment(&repository, id, std::path::Path::new(&destination)).is_ok()
+}
+
+/// Removes an attachment from a document that is not issued.
+#[unsafe(no_mangle)]
+pub extern "C" fn core_remove_attachment(id: i64) -> bool {
+    let repository = zdnp_data::SqliteDocumentRepository::new();
+
+    zdnp_core::remove_attachment(&repository, id).is_ok()
+}
+
+/// Renders the document as a PDF for printing and writes it to `path`. Issued documents are
+/// rendered from their snapshot.
+///
+/// # Safety
+/// `path` must be null or a null-terminated UTF-8 string.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn core_render_document_pdf(id: i64, path: *const c_char) -> bool {
+    let path = match unsafe { read_c_str(path) } {
+        Ok(Some(path)) => path,
+        _ => return false,
+    };
+
+    let documents = zdnp_data::SqliteDocumentRepository::new();
+    let references = zdnp_data::SqliteReferenceRepository::new();
+
+    zdnp_render::render_document_pdf(&documents, &references, id, std::path::Path::new(&path))
+        .is_ok()
+}
+
+/// Returns the merge fields a DOCX template may use as a JSON array of keys, written in the
+/// template as `{{key}}`.
+///
+/// # Safety
+/// The returned string must be released with [`core_free_string`].
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn core_list_docx_merge_fields() -> *mut c_char {
+    json_c_string(&zdnp_render::MERGE_FIELDS)
+}
+
+/// Exports the document as an editable DOCX to `path`. When `template` names a .docx file its
+/// merge fields are filled in and a paragraph holding only `{{content}}` receives the document
+/// body; a null or empty `template` produces a standalone document.
+///
+/// # Safety
+/// `template` and `path` must be null or null-terminated UTF-8 strings.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn core_render_document_docx(
+    id: i64,
+    template: *const c_char,
+    path: *const c_char,
+) -> bool {
+    let template = match unsafe { read_c_str(template) } {
+        Ok(template) => template,
+        Err(_) => return false,
+    };
+    let path = match unsafe { read_c_str(path) } {
+        Ok(Some(path)) => path,
+        _ => return false,
+    };
+
+    let documents = zdnp_data::SqliteDocumentRepository::new();
+    let references = zdnp_data::SqliteReferenceRepository::new();
+
+    zdnp_render::render_document_docx(
+        &documents,
+        &references,
+        id,
+        template.as_deref().map(std::path::Path::new),
+        std::path::Path::new(&path),
+    )
+    .is_ok()
+}
+
+#[derive(Serialize)]
+struct SignatureVerification {
+    valid: bool,
+    error: Option<String>,
+    signers: Vec<VerifiedSigner>,
+}
+
+#[derive(Serialize)]
+struct VerifiedSigner {
+    #[serde(flatten)]
+    report: zdnp_signature::SignerReport,
+    /// Organizations, entrepreneurs and people the certificate was issued to.
+    entities: Vec<zdnp_core::EntityRef>,
+}
+
+/// Verifies the detached CMS signature `signature_path` of the file `content_path` offline.
+/// Returns `{"valid", "error", "signers"}` as JSON; every signer carries its certificate and
+/// the reference entities (including archived ones) it matches by ИНН, ОГРН, ОГРНИП or СНИЛС.
+/// GOST R 34.10-2012 signatures are not verified: `valid` is `false` and `error` names the
+/// GOST algorithm.
+///
+/// # Safety
+/// `content_path` and `signature_path` must be null or null-terminated UTF-8 strings. The
+/// returned string must be released with [`core_free_string`].
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn core_verify_detached_signature(
+    content_path: *const c_char,
+    signature_path: *const c_char,
+) -> *mut c_char {
+    let content_path = match unsafe { read_c_str(content_path) } {
+        Ok(Some(path)) => path,
+        _ => return std::ptr::null_mut(),
+    };
+    let signature_path = match unsafe { read_c_str(signature_path) } {
+        Ok(Some(path)) => path,
+        _ => return std::ptr::null_mut(),
+    };
+
+    let reports = match zdnp_signature::verify_detached_file(
+        std::path::Path::new(&content_path),
+        std::path::Path::new(&signature_path),
+    ) {
+        Ok(reports) => reports,
+        Err(error) => {
+            return json_c_string(&SignatureVerification {
+                valid: false,
+                error: Some(error.to_string()),
+                signers: Vec::new(),
+            });
+        }
+    };
+
+    let options = ListOptions {
+        include_archived: true,
+    };
+    let organizations = zdnp_data::SqliteOrganizationRepository::new();
+    let entrepreneurs = zdnp_data::SqliteEntrepreneurRepository::new();
+    let persons = zdnp_data::SqlitePersonRepository::new();
+    let mut entities = Vec::new();
+    match zdnp_core::list_organizations(&organizations, &options) {
+        Ok(rows) => entities.extend(
+            rows.into_iter()
+                .map(zdnp_core::ReferenceEntity::Organization),
+        ),
+        Err(_) => return std::ptr::null_mut(),
+    }
+    match zdnp_core::list_entrepreneurs(&entrepreneurs, &options) {
+        Ok(rows) => entities.extend(
+            rows.into_iter()
+                .map(zdnp_core::ReferenceEntity::Entrepreneur),
+        ),
+        Err(_) => return std::ptr::null_mut(),
+    }
+    match zdnp_core::list_persons(&persons, &options) {
+        Ok(rows) => entities.extend(rows.into_iter().map(zdnp_core::ReferenceEntity::Person)),
+        Err(_) => return std::ptr::null_mut(),
+    }
+
+    let signers = reports
+        .into_iter()
+        .map(|report| VerifiedSigner {
+            entities: zdnp_signature::matching_entities(&report.certificate, &entities),
+            report,
+        })
+        .collect();
+
+    json_c_string(&SignatureVerification {
+        valid: true,
+        error: None,
+        signers,
+    })
+}
+
+/// Signs a file exported from the document with a PKCS#12 key file and writes the detached
+/// CMS signature next to it as `<file>.sig`. Fails when the certificate of the key was not
+/// issued to a signatory of the document, or names an organization other than the developer
+/// for an approving signatory or the author for an agreeing one. GOST keys are not supported.
+///
+/// # Safety
+/// `content_path`, `key_path` and `password` must be null or null-terminated UTF-8 strings.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn core_sign_document_file(
+    id: i64,
+    content_path: *const c_char,
+    key_path: *const c_char,
+    password: *const c_char,
+) -> bool {
+    let content_path = match unsafe { read_c_str(content_path) } {
+        Ok(Some(path)) => path,
+        _ => return false,
+    };
+    let key_path = match unsafe { read_c_str(key_path) } {
+        Ok(Some(path)) => path,
+        _ => return false,
+    };
+    let password = match unsafe { read_c_str(password) } {
+        Ok(password) => password.unwrap_or_default(),
+        Err(_) => return false,
+    };
+
+    let key = match zdnp_signature::SigningKey::from_pkcs12_file(
+        std::path::Path::new(&key_path),
+        &password,
+    ) {
+        Ok(key) => key,
+        Err(_) => return false,
+    };
+
+    let documents = zdnp_data::SqliteDocumentRepository::new();
+    let references = zdnp_data::SqliteReferenceRepository::new();
+
+    zdnp_signature::sign_document_file(
+        &documents,
+        &references,
+        id,
+        std::path::Path::new(&content_path),
+        &key,
+    )
+    .is_ok()
+}
+
+/// Parses an ЕГРЮЛ/ЕГРИП XML extract and compares its records with the stored organizations
+/// and entrepreneurs. Returns a JSON array of previews, each with the parsed record, the action
+/// the import would take (`create`, `update` with the changed fields, or `unchanged`) and the
+/// validation error that would stop it, or null when the file cannot be read.
+///
+/// # Safety
+/// `path` must be null or a null-terminated UTF-8 string. The returned string must be released
+/// with [`core_free_string`].
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn core_preview_registry_extract(path: *const c_char) -> *mut c_char {
+    let path = match unsafe { read_c_str(path) } {
+        Ok(Some(path)) => path,
+        _ => return std::ptr::null_mut(),
+    };
+    let records = match zdnp_registry::parse_extract_file(std::path::Path::new(&path)) {
+        Ok(records) => records,
+        Err(_) => return std::ptr::null_mut(),
+    };
+
+    let addresses = zdnp_data::SqliteAddressRepository::new();
+    let organizations = zdnp_data::SqliteOrganizationRepository::new();
+    let entrepreneurs = zdnp_data::SqliteEntrepreneurRepository::new();
+
+    match zdnp_registry::preview_import(&addresses, &organizations, &entrepreneurs, &records) {
+        Ok(previews) => json_c_string(&previews),
+        Err(_) => std::ptr::null_mut(),
+    }
+}
+
+/// Imports an ЕГРЮЛ/ЕГРИП XML extract: matched organizations and entrepreneurs are updated,
+/// the others are created. Returns the imported entities as a JSON array, or null when the
+/// file cannot be read or any record is invalid, in which case nothing is stored.
+///
+/// # Safety
+/// `path` must be null or a null-terminated UTF-8 string. The returned string must be released
+/// with [`core_free_string`].
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn core_import_registry_extract(path: *const c_char) -> *mut c_char {
+    let path = match unsafe { read_c_str(path) } {
+        Ok(Some(path)) => path,
+        _ => return std::ptr::null_mut(),
+    };
+    let records = match zdnp_registry::parse_extract_file(std::path::Path::new(&path)) {
+        Ok(records) => records,
+        Err(_) => return std::ptr::null_mut(),
+    };
+
+    let addresses = zdnp_data::SqliteAddressRepository::new();
+    let organizations = zdnp_data::SqliteOrganizationRepository::new();
+    let entrepreneurs = zdnp_data::SqliteEntrepreneurRepository::new();
+
+    match zdnp_registry::import_records(&addresses, &organizations, &entrepreneurs, &records) {
+        Ok(references) => json_c_string(&references),
+        Err(_) => std::ptr::null_mut(),
+    }
+}
+
+/// Reads a `.csv` or `.xlsx` file and the JSON column mapping (see
+/// `zdnp_spreadsheet::ColumnMapping`); `sheet` selects a worksheet of a workbook, the first one
+/// when null.
+///
+/// # Safety
+/// `path`, `sheet` and `mapping_json` must be null or null-terminated UTF-8 strings.
+unsafe fn read_spreadsheet_import(
+    path: *const c_char,
+    sheet: *const c_char,
+    mapping_json: *const c_char,
+) -> Option<(zdnp_spreadsheet::Table, zdnp_spreadsheet::ColumnMapping)> {
+    let path = unsafe { read_c_str(path) }.ok()??;
+    let sheet = unsafe { read_c_str(sheet) }.ok()?;
+    let mapping_json = unsafe { read_c_str(mapping_json) }.ok()??;
+
+    let mapping = serde_json::from_str(&mapping_json).ok()?;
+    let table =
+        zdnp_spreadsheet::read_table_file(std::path::Path::new(&path), sheet.as_deref()).ok()?;
+
+    Some((table, mapping))
+}
+
+/// Dry run of a spreadsheet import: returns a JSON array with the mapped record and the
+/// validation error, if any, of every non-empty row. Nothing is stored. Returns null when the
+/// file cannot be read or the mapping does not fit it.
+///
+/// # Safety
+/// `path`, `sheet` and `mapping_json` must be null or null-terminated UTF-8 strings. The
+/// returned string must be released with [`core_free_string`].
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn core_preview_spreadsheet_import(
+    path: *const c_char,
+    sheet: *const c_char,
+    mapping_json: *const c_char,
+) -> *mut c_char {
+    let Some((table, mapping)) = (unsafe { read_spreadsheet_import(path, sheet, mapping_json) })
+    else {
+        return std::ptr::null_mut();
+    };
+
+    match zdnp_spreadsheet::validate_rows(&table, &mapping) {
+        Ok(validations) => json_c_string(&validations),
+        Err(_) => std::ptr::null_mut(),
+    }
+}
+
+/// Imports all valid rows of a spreadsheet in one transaction. Returns `{"imported",
+/// "skipped"}` as JSON with the created entities and the row numbers of the invalid rows, or
+/// null when nothing was imported.
+///
+/// # Safety
+/// `path`, `sheet` and `mapping_json` must be null or null-terminated UTF-8 strings. The
+/// returned string must be released with [`core_free_string`].
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn core_import_spreadsheet(
+    path: *const c_char,
+    sheet: *const c_char,
+    mapping_json: *const c_char,
+) -> *mut c_char {
+    let Some((table, mapping)) = (unsafe { read_spreadsheet_import(path, sheet, mapping_json) })
+    else {
+        return std::ptr::null_mut();
+    };
+
+    let repository = zdnp_data::SqliteReferenceRepository::new();
+
+    match zdnp_spreadsheet::import_rows(&repository, &table, &mapping) {
+        Ok(summary) => json_c_string(&summary),
+        Err(_) => std::ptr::null_mut(),
+    }
+}
+
+/// Returns the columns that can be exported for the list (`addresses`, `organizations`,
+/// `entrepreneurs` or `persons`) as a JSON array of `{"key", "header"}`, or null for an
+/// unknown list.
+///
+/// # Safety
+/// `list` must be null or a null-terminated UTF-8 string. The returned string must be released
+/// with [`core_free_string`].
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn core_list_export_columns(list: *const c_char) -> *mut c_char {
+    match unsafe { read_c_str(list) } {
+        Ok(Some(list)) => match zdnp_spreadsheet::ExportList::parse(&list) {
+            Some(list) => json_c_string(list.columns()),
+            None => std::ptr::null_mut(),
+        },
+        _ => std::ptr::null_mut(),
+    }
+}
+
+/// Exports a reference list to a `.csv` (UTF-8 with BOM, `;` separated) or `.xlsx` file chosen
+/// by the extension of `path`. `columns_json` is a JSON array of column keys from
+/// [`core_list_export_columns`]; all columns are exported when it is null. Archived rows are
+/// only exported with `include_archived`, as in the list queries.
+///
+/// # Safety
+/// `list`, `columns_json` and `path` must be null or null-terminated UTF-8 strings.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn core_export_list(
+    list: *const c_char,
+    columns_json: *const c_char,
+    include_archived: bool,
+    path: *const c_char,
+) -> bool {
+    let list = match unsafe { read_c_str(list) } {
+        Ok(Some(list)) => match zdnp_spreadsheet::ExportList::parse(&list) {
+            Some(list) => list,
+            None => return false,
+        },
+        _ => return false,
+    };
+    let columns = match unsafe { read_c_str(columns_json) } {
+        Ok(Some(json)) => match serde_json::from_str(&json) {
+            Ok(columns) => columns,
+            Err(_) => return false,
+        },
+        Ok(None) => Vec::new(),
+        Err(_) => return false,
+    };
+    let path = match unsafe { read_c_str(path) } {
+        Ok(Some(path)) => path,
+        _ => return false,
+    };
+
+    let options = zdnp_spreadsheet::ExportOptions {
+        columns,
+        list: ListOptions { include_archived },
+    };
+    let table = match zdnp_spreadsheet::export_table(
+        &zdnp_data::SqliteAddressRepository::new(),
+        &zdnp_data::SqliteOrganizationRepository::new(),
+        &zdnp_data::SqliteEntrepreneurRepository::new(),
+        &zdnp_data::SqlitePersonRepository::new(),
+        list,
+        &options,
+    ) {
+        Ok(table) => table,
+        Err(_) => return false,
+    };
+
+    zdnp_spreadsheet::write_table_file(std::path::Path::new(&path), &table, list.title()).is_ok()
+}
+
+/// Returns all documents as a JSON array.
+///
+/// # Safety
+/// The returned string must be released with [`core_free_string`].
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn core_list_documents() -> *mut c_char {
+    let repository = zdnp_data::SqliteDocumentRepository::new();
+
+    match zdnp_core::list_documents(&repository) {
+        Ok(documents) => json_c_string(&documents),
+        Err(_) => std::ptr::null_mut(),
+    }
+}
+
+/// Issues a new revision of the document and writes its identifier into `out_id`.
+///
+/// # Safety
+/// `uid` must be null or a null-terminated UTF-8 string. `out_id` must be null or valid for
+/// writes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn core_issue_document_revision(
+    id: i64,
+    uid: *const c_char,
+    out_id: *mut i64,
+) -> bool {
+    if let Some(slot) = unsafe { out_id.as_mut() } {
+        *slot = -1;
+    }
+
+    let uid = match unsafe { read_c_str(uid) } {
+        Ok(uid) => uid.unwrap_or_default(),
+        Err(_) => return false,
+    };
+
+    let repository = zdnp_data::SqliteDocumentRepository::new();
+
+    match zdnp_core::issue_revision(&repository, id, &uid) {
+        Ok(revision_id) => {
+            if let Some(slot) = unsafe { out_id.as_mut() } {
+                *slot = revision_id;
+            }
+            true
+        }
+        Err(_) => false,
+    }
+}
+
+/// Fields of a cloned document that differ from the original. Empty strings keep the value of
+/// the original; the number and date start empty unless given. The number may be allocated from
+/// `numbering_prefix` like in [`DocumentDtoFfi`]. `object_address_id` is 0 to keep the location
+/// of the construction object.
+#[repr(C)]
+pub struct CloneOverridesFfi {
+    pub name: *const c_char,
+    pub number: *const c_char,
+    pub date: *const c_char,
+    pub numbering_prefix: *const c_char,
+    pub numbering_year: i32,
+    pub object_name: *const c_char,
+    pub object_address_id: i64,
+}
+
+impl CloneOverridesFfi {
+    /// # Safety
+    /// All pointers must either be null or reference valid null-terminated UTF-8 strings.
+    unsafe fn try_into_core(&self) -> Result<CloneOverrides, FfiConversionError> {
+        let date = unsafe { read_c_str(self.date) }?;
+        let numbering = numbering(
+            unsafe { read_c_str(self.numbering_prefix) }?,
+            self.numbering_year,
+            date.as_deref(),
+        );
+
+        Ok(CloneOverrides {
+            name: unsafe { read_c_str(self.name) }?,
+            number: unsafe { read_c_str(self.number) }?,
+            date,
+            numbering,
+            object_name: unsafe { read_c_str(self.object_name) }?,
+            object_address_id: match self.object_address_id {
+                0 => None,
+                id => Some(id),
+            },
+        })
+    }
+}
+
+/// Copies the document with its sections into a new draft and writes its identifier into
+/// `out_id`. A null `overrides` keeps every field of the original.
+///
+/// # Safety
+/// `overrides` must be null or point to a valid [`CloneOverridesFfi`] whose string fields are
+/// null or null-terminated UTF-8. `out_id` must be null or valid for writes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn core_clone_document(
+    id: i64,
+    overrides: *const CloneOverridesFfi,
+    out_id: *mut i64,
+) -> bool {
+    if let Some(slot) = unsafe { out_id.as_mut() } {
+        *slot = -1;
+    }
+
+    let overrides = match unsafe { overrides.as_ref() } {
+        Some(overrides) => match unsafe { overrides.try_into_core() } {
+            Ok(overrides) => overrides,
+            Err(_) => return false,
+        },
+        None => CloneOverrides::default(),
+    };
+
+    let documents = zdnp_data::SqliteDocumentRepository::new();
+    let references = zdnp_data::SqliteReferenceRepository::new();
+
+    match zdnp_core::clone_document(&documents, &references, id, &overrides) {
+        Ok(clone_id) => {
+            if let Some(slot) = unsafe { out_id.as_mut() } {
+                *slot = clone_id;
+            }
+            true
+        }
+        Err(_) => false,
+    }
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn core_set_document_template(id: i64, template: bool) -> bool {
+    let repository = zdnp_data::SqliteDocumentRepository::new();
+    zdnp_core::set_document_template(&repository, id, template).is_ok()
+}
+
+/// Returns the documents marked as templates as a JSON array.
+///
+/// # Safety
+/// The returned string must be released with [`core_free_string`].
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn core_list_document_templates() -> *mut c_char {
+    let repository = zdnp_data::SqliteDocumentRepository::new();
+
+    match zdnp_core::list_document_templates(&repository) {
+        Ok(documents) => json_c_string(&documents),
+        Err(_) => std::ptr::null_mut(),
+    }
+}
+
+/// Returns the revision chain ending with the document as a JSON array, oldest first.
+///
+/// # Safety
+/// The returned string must be released with [`core_free_string`].
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn core_list_document_revisions(id: i64) -> *mut c_char {
+    let repository = zdnp_data::SqliteDocumentRepository::new();
+
+    match zdnp_core::document_revisions(&repository, id) {
+        Ok(revisions) => json_c_string(&revisions),
+        Err(_) => std::ptr::null_mut(),
+    }
+}
+
+/// Returns the header and section changes between two documents as a JSON array of
+/// `{ "field", "before", "after" }` objects.
+///
+/// # Safety
+/// The returned string must be released with [`core_free_string`].
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn core_diff_document_revisions(
+    before_id: i64,
+    after_id: i64,
+) -> *mut c_char {
+    let repository = zdnp_data::SqliteDocumentRepository::new();
+
+    match zdnp_core::diff_revisions(&repository, before_id, after_id) {
+        Ok(changes) => json_c_string(&changes),
+        Err(_) => std::ptr::null_mut(),
+    }
+}
+
+/// Issues an approved document and freezes a snapshot of the entities it refers to.
+#[unsafe(no_mangle)]
+pub extern "C" fn core_issue_document(id: i64) -> bool {
+    let documents = zdnp_data::SqliteDocumentRepository::new();
+    let references = zdnp_data::SqliteReferenceRepository::new();
+
+    zdnp_core::issue_document(&documents, &references, id).is_ok()
+}
+
+/// Returns the document with its referenced entities as JSON. Issued documents are exported
+/// from their snapshot.
+///
+/// # Safety
+/// The returned string must be released with [`core_free_string`].
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn core_export_document(id: i64) -> *mut c_char {
+    let documents = zdnp_data::SqliteDocumentRepository::new();
+    let references = zdnp_data::SqliteReferenceRepository::new();
+
+    match zdnp_core::export_document(&documents, &references, id) {
+        Ok(export) => json_c_string(&export),
+        Err(_) => std::ptr::null_mut(),
+    }
+}
+
+/// Returns the changes of the live reference data since the document was issued as JSON.
+///
+/// # Safety
+/// The returned string must be released with [`core_free_string`].
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn core_document_snapshot_changes(id: i64) -> *mut c_char {
+    let documents = zdnp_data::SqliteDocumentRepository::new();
+    let references = zdnp_data::SqliteReferenceRepository::new();
+
+    match zdnp_core::snapshot_changes(&documents, &references, id) {
+        Ok(changes) => json_c_string(&changes),
+        Err(_) => std::ptr::null_mut(),
+    }
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn core_add(a: i32, b: i32) -> i32 {
+    zdnp_core::add_i32(a, b)
+}
+
+/// Returns a pointer to a null-terminated static string with version info.
+/// Do NOT attempt to free this pointer on the C# side.
+#[unsafe(no_mangle)]
+pub extern "C" fn core_version() -> *const c_char {
+    c"0.1.0".as_ptr()
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn migrations_run() -> bool {
+    let migrations = zdnp_data::SqliteMigrations::new();
+    migrations.run().is_ok()
+}
+
+/// Backs the database up now into `backups/` next to it. When `keep_daily` is positive, only
+/// the latest backup of each of that many recent days is kept afterwards. Returns the backup
+/// as JSON (`{"path", "created_at", "size", "schema_version"}`), or null on failure.
+///
+/// # Safety
+/// The returned string must be released with [`core_free_string`].
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn core_backup_now(keep_daily: u32) -> *mut c_char {
+    let backups = zdnp_data::SqliteBackups::new();
+
+    let backup = match backups.backup_now() {
+        Ok(backup) => backup,
+        Err(_) => return std::ptr::null_mut(),
+    };
+    if keep_daily > 0 {
+        let policy = zdnp_data::BackupPolicy {
+            keep_daily: keep_daily as usize,
+        };
+        if backups.rotate(&policy).is_err() {
+            return std::ptr::null_mut();
+        }
+    }
+
+    json_c_string(&backup)
+}
+
+/// Scheduled backup: backs the database up unless that was already done today and keeps the
+/// latest backup of each of the `keep_daily` most recent days.
+#[unsafe(no_mangle)]
+pub extern "C" fn core_backup_daily(keep_daily: u32) -> bool {
+    let policy = zdnp_data::BackupPolicy {
+        keep_daily: keep_daily.max(1) as usize,
+    };
+
+    zdnp_data::SqliteBackups::new()
+        .backup_daily(&policy)
+        .is_ok()
+}
+
+/// Returns the backups from the oldest to the newest as a JSON array.
+///
+/// # Safety
+/// The returned string must be released with [`core_free_string`].
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn core_list_backups() -> *mut c_char {
+    match zdnp_data::SqliteBackups::new().list() {
+        Ok(backups) => json_c_string(&backups),
+        Err(_) => std::ptr::null_mut(),
+    }
+}
+
+/// Replaces the database with the backup at `path` after backing the current one up. Backups
+/// of an older schema are migrated; those made by a newer version of the application are
+/// refused.
+///
+/// # Safety
+/// `path` must be null or a null-terminated UTF-8 string.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn core_restore_backup(path: *const c_char) -> bool {
+    let path = match unsafe { read_c_str(path) } {
+        Ok(Some(path)) => path,
+        _ => return false,
+    };
+
+    zdnp_data::SqliteBackups::new()
+        .restore(std::path::Path::new(&path))
+        .is_ok()
+}
+
+/// Writes the whole dataset with the attachments to the archive at `path`, to be imported on
+/// another workstation with [`core_import_archive`].
+///
+/// # Safety
+/// `path` must be null or a null-terminated UTF-8 string.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn core_export_archive(path: *const c_char) -> bool {
+    let path = match unsafe { read_c_str(path) } {
+        Ok(Some(path)) => path,
+        _ => return false,
+    };
+
+    zdnp_data::SqliteArchive::new()
+        .export(std::path::Path::new(&path))
+        .is_ok()
+}
+
+/// Merges the archive at `path` into the database. Returns the report as JSON
+/// (`{"created", "matched", "conflicts"}`, entries referring to entities by kind, uid and the
+/// id in this database), or null when the archive cannot be read or stored.
+///
+/// # Safety
+/// `path` must be null or a null-terminated UTF-8 string. The returned string must be released
+/// with [`core_free_string`].
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn core_import_archive(path: *const c_char) -> *mut c_char {
+    let path = match unsafe { read_c_str(path) } {
+        Ok(Some(path)) => path,
+        _ => return std::ptr::null_mut(),
+    };
+
+    match zdnp_data::SqliteArchive::new().import(std::path::Path::new(&path)) {
+        Ok(report) => json_c_string(&report),
+        Err(_) => std::ptr::null_mut(),
+    }
+}
+
+/// Exchanges reference data changes with the other workstations through the shared folder at
+/// `path`. Returns the report as JSON (`{"workstation", "exported_changes",
+/// "applied_change_sets", "applied_changes", "conflicts"}`), or null when the synchronization
+/// fails; nothing is stored then.
+///
+/// # Safety
+/// `path` must be null or a null-terminated UTF-8 string. The returned string must be released
+/// with [`core_free_string`].
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn core_sync(path: *const c_char) -> *mut c_char {
+    let path = match unsafe { read_c_str(path) } {
+        Ok(Some(path)) => path,
+        _ => return std::ptr::null_mut(),
+    };
+
+    match zdnp_data::SqliteSync::new().sync(std::path::Path::new(&path)) {
+        Ok(report) => json_c_string(&report),
+        Err(_) => std::ptr::null_mut(),
+    }
+}
+
+/// Returns the unresolved synchronization conflicts as a JSON array, or null on failure.
+#[unsafe(no_mangle)]
+pub extern "C" fn core_list_sync_conflicts() -> *mut c_char {
+    match zdnp_data::SqliteSync::new().conflicts() {
+        Ok(conflicts) => json_c_string(&conflicts),
+        Err(_) => std::ptr::null_mut(),
+    }
+}
+
+/// Resolves a synchronization conflict by keeping the `local` or the `remote` value.
+///
+/// # Safety
+/// `keep` must be null or a null-terminated UTF-8 string.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn core_resolve_sync_conflict(id: i64, keep: *const c_char) -> bool {
+    let keep = match unsafe { read_c_str(keep) } {
+        Ok(Some(keep)) => keep,
+        _ => return false,
+    };
+    let Some(keep) = zdnp_data::SyncSide::parse(&keep) else {
+        return false;
+    };
+
+    matches!(zdnp_data::SqliteSync::new().resolve(id, keep), Ok(true))
 }
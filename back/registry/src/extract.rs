@@ -0,0 +1,368 @@
+use std::path::Path;
+
+use encoding_rs::Encoding;
+use roxmltree::{Document, Node};
+use serde::Serialize;
+use zdnp_core::{AddressDto, EntrepreneurDto, OrganizationDto};
+
+use crate::RegistryError;
+
+// ---------------- ЕГРЮЛ/ЕГРИП Extracts ----------------
+
+/// Legal entity or entrepreneur found in an extract with its address. The `address_id` of the
+/// DTO stays 0 until the address is stored.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum RegistryRecord {
+    Organization {
+        organization: OrganizationDto,
+        address: AddressDto,
+    },
+    Entrepreneur {
+        entrepreneur: EntrepreneurDto,
+        address: AddressDto,
+    },
+}
+
+impl RegistryRecord {
+    pub fn address(&self) -> &AddressDto {
+        match self {
+            Self::Organization { address, .. } | Self::Entrepreneur { address, .. } => address,
+        }
+    }
+}
+
+/// Encoding named by the XML declaration, UTF-8 when there is none.
+fn declared_encoding(bytes: &[u8]) -> Result<&'static Encoding, RegistryError> {
+    // The declaration is ASCII in every encoding the registry uses.
+    let head = String::from_utf8_lossy(&bytes[..bytes.len().min(200)]);
+    let label = head
+        .strip_prefix("<?xml")
+        .and_then(|declaration| declaration.split("?>").next())
+        .and_then(|declaration| declaration.split("encoding=").nth(1))
+        .and_then(|value| {
+            value
+                .trim_start_matches(['"', '\''])
+                .split(['"', '\''])
+                .next()
+        });
+
+    match label {
+        None => Ok(encoding_rs::UTF_8),
+        Some(label) => Encoding::for_label(label.as_bytes())
+            .ok_or_else(|| RegistryError::Encoding(label.to_string())),
+    }
+}
+
+/// Decodes the file; extracts are usually in windows-1251.
+fn decode(bytes: &[u8]) -> Result<String, RegistryError> {
+    let (encoding, bytes) = match Encoding::for_bom(bytes) {
+        Some((encoding, length)) => (encoding, &bytes[length..]),
+        None => (declared_encoding(bytes)?, bytes),
+    };
+
+    let (text, malformed) = encoding.decode_without_bom_handling(bytes);
+    if malformed {
+        return Err(RegistryError::Encoding(encoding.name().to_string()));
+    }
+
+    Ok(text.into_owned())
+}
+
+fn child<'a, 'input>(node: Node<'a, 'input>, name: &str) -> Option<Node<'a, 'input>> {
+    node.children().find(|child| child.has_tag_name(name))
+}
+
+fn attribute(node: Option<Node>, name: &str) -> Option<String> {
+    node.and_then(|node| node.attribute(name))
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(str::to_string)
+}
+
+fn text(node: Option<Node>) -> Option<String> {
+    node.and_then(|node| node.text())
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(str::to_string)
+}
+
+fn joined(parts: impl IntoIterator<Item = Option<String>>, separator: &str) -> Option<String> {
+    let parts: Vec<String> = parts.into_iter().flatten().collect();
+    (!parts.is_empty()).then(|| parts.join(separator))
+}
+
+/// Registry names are upper case; names of people are shown as «Петров-Водкин Пётр».
+fn capitalized(value: String) -> String {
+    let mut result = String::with_capacity(value.len());
+    let mut word_start = true;
+    for char in value.chars() {
+        if word_start {
+            result.extend(char.to_uppercase());
+        } else {
+            result.extend(char.to_lowercase());
+        }
+        word_start = matches!(char, ' ' | '-');
+    }
+    result
+}
+
+fn email(record: Node) -> Option<String> {
+    attribute(child(record, "СвАдрЭлПочты"), "E-mail").map(|email| email.to_lowercase())
+}
+
+/// Address in the КЛАДР form used by extracts before the switch to ФИАС.
+fn kladr_address(node: Node) -> AddressDto {
+    let typed = |name: &str, kind: &str, value: &str| {
+        let element = child(node, name);
+        joined([attribute(element, kind), attribute(element, value)], " ")
+    };
+    let region = child(node, "Регион");
+    let federal_city = (attribute(region, "ТипРегион").as_deref() == Some("ГОРОД"))
+        .then(|| typed("Регион", "ТипРегион", "НаимРегион"))
+        .flatten();
+
+    AddressDto {
+        region_code: attribute(Some(node), "КодРегион"),
+        note: attribute(Some(node), "Индекс"),
+        country: Some("Россия".into()),
+        district: typed("Район", "ТипРайон", "НаимРайон"),
+        city: typed("Город", "ТипГород", "НаимГород").or(federal_city),
+        settlement: typed("НаселПункт", "ТипНаселПункт", "НаимНаселПункт"),
+        street: typed("Улица", "ТипУлица", "НаимУлица"),
+        building: joined(
+            [
+                attribute(Some(node), "Дом"),
+                attribute(Some(node), "Корпус"),
+            ],
+            ", ",
+        ),
+        room: attribute(Some(node), "Кварт"),
+        uid: None,
+    }
+}
+
+/// Address in the ФИАС form (the municipal division) of current extracts.
+fn fias_address(node: Node) -> AddressDto {
+    let all = |name: &str| {
+        joined(
+            node.children()
+                .filter(|child| child.has_tag_name(name))
+                .map(|element| {
+                    joined(
+                        [
+                            attribute(Some(element), "Тип"),
+                            attribute(Some(element), "Номер"),
+                        ],
+                        " ",
+                    )
+                }),
+            ", ",
+        )
+    };
+    let locality = child(node, "НаселенПункт");
+    let locality_name = joined(
+        [attribute(locality, "Вид"), attribute(locality, "Наим")],
+        " ",
+    );
+    let is_city = attribute(locality, "Вид").is_some_and(|kind| {
+        let kind = kind.to_lowercase();
+        kind.starts_with("город") || kind == "г" || kind == "г."
+    });
+    let region_name = text(child(node, "НаимРегион"));
+    let federal_city = region_name.filter(|name| {
+        let name = name.to_lowercase();
+        name.starts_with("город ") || name.starts_with("г. ")
+    });
+    let street = child(node, "ЭлУлДорСети").or_else(|| child(node, "ЭлПланСтруктур"));
+
+    AddressDto {
+        region_code: attribute(Some(node), "Регион").or_else(|| text(child(node, "Регион"))),
+        note: attribute(Some(node), "Индекс"),
+        country: Some("Россия".into()),
+        district: attribute(child(node, "МуниципРайон"), "Наим"),
+        city: if is_city {
+            locality_name.clone()
+        } else {
+            federal_city
+        },
+        settlement: if is_city { None } else { locality_name },
+        street: joined([attribute(street, "Тип"), attribute(street, "Наим")], " "),
+        building: all("Здание"),
+        room: joined([all("ПомещЗдания"), all("ПомещКвартиры")], ", "),
+        uid: None,
+    }
+}
+
+/// Address held by `container` (СвАдресЮЛ, СвАдрМЖ) in either form. The postal code is kept
+/// in the note.
+fn address(container: Option<Node>) -> AddressDto {
+    let Some(container) = container else {
+        return AddressDto::default();
+    };
+
+    let fias = container
+        .children()
+        .find(|child| child.is_element() && child.tag_name().name().contains("ФИАС"));
+    match (fias, child(container, "АдресРФ")) {
+        (Some(fias), _) => fias_address(fias),
+        (None, Some(kladr)) => kladr_address(kladr),
+        (None, None) => AddressDto::default(),
+    }
+}
+
+fn organization(record: Node) -> RegistryRecord {
+    let names = child(record, "СвНаимЮЛ");
+    let full_name = attribute(names, "НаимЮЛПолн");
+    let abbreviated_name = attribute(
+        names.and_then(|names| child(names, "СвНаимЮЛСокр")),
+        "НаимСокр",
+    );
+
+    RegistryRecord::Organization {
+        organization: OrganizationDto {
+            abbreviated_name: abbreviated_name.or_else(|| full_name.clone()),
+            full_name,
+            ogrn: attribute(Some(record), "ОГРН"),
+            rafp: None,
+            inn: attribute(Some(record), "ИНН"),
+            kpp: attribute(Some(record), "КПП"),
+            address_id: 0,
+            email: email(record),
+            uid: None,
+        },
+        address: address(child(record, "СвАдресЮЛ")),
+    }
+}
+
+fn entrepreneur(record: Node) -> RegistryRecord {
+    let names = child(record, "СвФЛ").and_then(|person| child(person, "ФИОРус"));
+
+    RegistryRecord::Entrepreneur {
+        entrepreneur: EntrepreneurDto {
+            surname: attribute(names, "Фамилия").map(capitalized),
+            name: attribute(names, "Имя").map(capitalized),
+            patronymic: attribute(names, "Отчество").map(capitalized),
+            ogrnip: attribute(Some(record), "ОГРНИП"),
+            inn: attribute(Some(record), "ИННФЛ"),
+            address_id: 0,
+            email: email(record),
+            uid: None,
+        },
+        address: address(child(record, "СвАдрМЖ")),
+    }
+}
+
+/// Parses an ЕГРЮЛ or ЕГРИП extract (a single extract or a batch of them) into records.
+pub fn parse_extract(bytes: &[u8]) -> Result<Vec<RegistryRecord>, RegistryError> {
+    let text = decode(bytes)?;
+    let document = Document::parse(&text).map_err(|error| RegistryError::Xml(error.to_string()))?;
+
+    let records: Vec<RegistryRecord> = document
+        .descendants()
+        .filter_map(|node| match node.tag_name().name() {
+            "СвЮЛ" => Some(organization(node)),
+            "СвИП" => Some(entrepreneur(node)),
+            _ => None,
+        })
+        .collect();
+
+    if records.is_empty() {
+        return Err(RegistryError::NoRecords);
+    }
+
+    Ok(records)
+}
+
+pub fn parse_extract_file(path: &Path) -> Result<Vec<RegistryRecord>, RegistryError> {
+    let bytes = std::fs::read(path).map_err(|error| RegistryError::Io(error.to_string()))?;
+    parse_extract(&bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn legal_entity_extract_is_parsed() {
+        let records = parse_extract(include_bytes!("../testdata/egrul.xml")).expect("extract");
+
+        assert_eq!(
+            records,
+            vec![RegistryRecord::Organization {
+                organization: OrganizationDto {
+                    full_name: Some("ОБЩЕСТВО С ОГРАНИЧЕННОЙ ОТВЕТСТВЕННОСТЬЮ \"ПРОЕКТ\"".into()),
+                    abbreviated_name: Some("ООО \"ПРОЕКТ\"".into()),
+                    ogrn: Some("1027700000000".into()),
+                    rafp: None,
+                    inn: Some("7700000000".into()),
+                    kpp: Some("770001001".into()),
+                    address_id: 0,
+                    email: Some("info@project.example".into()),
+                    uid: None,
+                },
+                address: AddressDto {
+                    region_code: Some("77".into()),
+                    note: Some("125009".into()),
+                    country: Some("Россия".into()),
+                    district: Some("МУНИЦИПАЛЬНЫЙ ОКРУГ ТВЕРСКОЙ".into()),
+                    city: Some("ГОРОД МОСКВА".into()),
+                    settlement: None,
+                    street: Some("УЛИЦА ТВЕРСКАЯ".into()),
+                    building: Some("ДОМ 1, СТРОЕНИЕ 2".into()),
+                    room: Some("ПОМЕЩЕНИЕ 5".into()),
+                    uid: None,
+                },
+            }]
+        );
+    }
+
+    #[test]
+    fn entrepreneur_extract_is_parsed() {
+        let records = parse_extract(include_bytes!("../testdata/egrip.xml")).expect("extract");
+
+        assert_eq!(
+            records,
+            vec![RegistryRecord::Entrepreneur {
+                entrepreneur: EntrepreneurDto {
+                    surname: Some("Петров-Водкин".into()),
+                    name: Some("Пётр".into()),
+                    patronymic: Some("Петрович".into()),
+                    ogrnip: Some("304770000000012".into()),
+                    inn: Some("770000000034".into()),
+                    address_id: 0,
+                    email: None,
+                    uid: None,
+                },
+                address: AddressDto {
+                    region_code: Some("50".into()),
+                    note: Some("141000".into()),
+                    country: Some("Россия".into()),
+                    district: Some("РАЙОН МЫТИЩИНСКИЙ".into()),
+                    city: Some("ГОРОД МЫТИЩИ".into()),
+                    settlement: None,
+                    street: Some("УЛИЦА СЕМАШКО".into()),
+                    building: Some("ДОМ 3, КОРПУС 1".into()),
+                    room: Some("КВАРТИРА 12".into()),
+                    uid: None,
+                },
+            }]
+        );
+    }
+
+    #[test]
+    fn other_files_are_rejected() {
+        assert_eq!(
+            parse_extract("\u{feff}<Файл><Документ/></Файл>".as_bytes()),
+            Err(RegistryError::NoRecords)
+        );
+        assert!(matches!(
+            parse_extract(b"<?xml version=\"1.0\"?><Open>"),
+            Err(RegistryError::Xml(_))
+        ));
+        assert_eq!(
+            parse_extract(b"<?xml version=\"1.0\" encoding=\"x-unknown\"?><a/>"),
+            Err(RegistryError::Encoding("x-unknown".into()))
+        );
+    }
+}
@@ -0,0 +1,672 @@
+use serde::Serialize;
+use zdnp_core::{
+    Address, AddressDto, AddressError, AddressRepository, EntityRef, Entrepreneur, EntrepreneurDto,
+    EntrepreneurError, EntrepreneurRepository, ListOptions, Organization, OrganizationDto,
+    OrganizationError, OrganizationRepository,
+};
+
+use crate::{RegistryError, RegistryRecord};
+
+// ---------------- Registry Import ----------------
+
+/// Stored value of a field that the extract changes.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FieldChange {
+    pub field: &'static str,
+    pub current: Option<String>,
+    pub imported: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum ImportAction {
+    /// No record with the same ИНН or ОГРН(ИП) exists yet.
+    Create,
+    Update {
+        id: i64,
+        changes: Vec<FieldChange>,
+    },
+    Unchanged {
+        id: i64,
+    },
+}
+
+/// What importing a record of an extract would do.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ImportPreview {
+    pub record: RegistryRecord,
+    pub action: ImportAction,
+    /// Why the record cannot be imported, e.g. an extract without the email an organization
+    /// requires.
+    pub error: Option<String>,
+}
+
+enum Merged {
+    Organization(OrganizationDto),
+    Entrepreneur(EntrepreneurDto),
+}
+
+struct Plan {
+    preview: ImportPreview,
+    error: Option<RegistryError>,
+    /// The record with the values of the extract laid over the stored ones.
+    merged: Merged,
+    /// The legal address differs from the stored one and is stored as a new address, so
+    /// other records sharing the old one keep it.
+    new_address: bool,
+}
+
+/// Identifiers are stored as numbers, so leading zeros may be lost.
+fn same_number(left: &str, right: &str) -> bool {
+    let normalized = |value: &str| {
+        value
+            .trim()
+            .trim_start_matches('0')
+            .chars()
+            .filter(char::is_ascii_digit)
+            .collect::<String>()
+    };
+    !left.trim().is_empty() && normalized(left) == normalized(right)
+}
+
+/// Fields of a stored record that the extract changes.
+#[derive(Default)]
+struct Changes(Vec<FieldChange>);
+
+impl Changes {
+    fn push(&mut self, field: &'static str, current: Option<&str>, imported: &Option<String>) {
+        self.0.push(FieldChange {
+            field,
+            current: current.map(str::to_string),
+            imported: imported.clone(),
+        });
+    }
+
+    fn text(&mut self, field: &'static str, current: Option<&str>, imported: &Option<String>) {
+        if current != imported.as_deref() {
+            self.push(field, current, imported);
+        }
+    }
+
+    fn number(&mut self, field: &'static str, current: Option<&str>, imported: &Option<String>) {
+        let same = match (current, imported.as_deref()) {
+            (Some(current), Some(imported)) => same_number(current, imported),
+            (current, imported) => current == imported,
+        };
+        if !same {
+            self.push(field, current, imported);
+        }
+    }
+
+    /// Compares the legal address of the extract with the stored one and tells whether it
+    /// changed. Extracts without an address keep the stored one.
+    fn address(&mut self, addresses: &[Address], address_id: i64, imported: &AddressDto) -> bool {
+        let Ok(imported) = zdnp_core::sanitize_address(imported) else {
+            return false;
+        };
+        let current = addresses
+            .iter()
+            .find(|address| address.id == address_id)
+            .map(Address::formatted);
+        let imported = Some(zdnp_core::format_address(&imported));
+
+        let changed = current != imported;
+        if changed {
+            self.push("address", current.as_deref(), &imported);
+        }
+        changed
+    }
+}
+
+fn organization_plan(
+    organizations: &[Organization],
+    addresses: &[Address],
+    organization: &OrganizationDto,
+    address: &AddressDto,
+) -> (ImportAction, Merged, bool) {
+    let existing = organizations.iter().find(|existing| {
+        organization
+            .inn
+            .as_deref()
+            .is_some_and(|inn| same_number(inn, &existing.inn))
+            || organization
+                .ogrn
+                .as_deref()
+                .zip(existing.ogrn.as_deref())
+                .is_some_and(|(imported, stored)| same_number(imported, stored))
+    });
+    let Some(existing) = existing else {
+        return (
+            ImportAction::Create,
+            Merged::Organization(organization.clone()),
+            true,
+        );
+    };
+
+    let or_stored = |imported: &Option<String>, stored: &str| {
+        imported.clone().or_else(|| Some(stored.to_string()))
+    };
+    let merged = OrganizationDto {
+        full_name: or_stored(&organization.full_name, &existing.full_name),
+        abbreviated_name: or_stored(&organization.abbreviated_name, &existing.abbreviated_name),
+        ogrn: organization.ogrn.clone().or_else(|| existing.ogrn.clone()),
+        rafp: organization.rafp.clone().or_else(|| existing.rafp.clone()),
+        inn: or_stored(&organization.inn, &existing.inn),
+        kpp: or_stored(&organization.kpp, &existing.kpp),
+        address_id: existing.address_id,
+        email: or_stored(&organization.email, &existing.email),
+        uid: Some(existing.uid.clone()),
+    };
+
+    let mut changes = Changes::default();
+    changes.text("full_name", Some(&existing.full_name), &merged.full_name);
+    changes.text(
+        "abbreviated_name",
+        Some(&existing.abbreviated_name),
+        &merged.abbreviated_name,
+    );
+    changes.number("ogrn", existing.ogrn.as_deref(), &merged.ogrn);
+    changes.number("rafp", existing.rafp.as_deref(), &merged.rafp);
+    changes.number("inn", Some(&existing.inn), &merged.inn);
+    changes.number("kpp", Some(&existing.kpp), &merged.kpp);
+    changes.text("email", Some(&existing.email), &merged.email);
+    let new_address = changes.address(addresses, existing.address_id, address);
+
+    let action = if changes.0.is_empty() {
+        ImportAction::Unchanged { id: existing.id }
+    } else {
+        ImportAction::Update {
+            id: existing.id,
+            changes: changes.0,
+        }
+    };
+    (action, Merged::Organization(merged), new_address)
+}
+
+fn entrepreneur_plan(
+    entrepreneurs: &[Entrepreneur],
+    addresses: &[Address],
+    entrepreneur: &EntrepreneurDto,
+    address: &AddressDto,
+) -> (ImportAction, Merged, bool) {
+    let existing = entrepreneurs.iter().find(|existing| {
+        entrepreneur
+            .inn
+            .as_deref()
+            .is_some_and(|inn| same_number(inn, &existing.inn))
+            || entrepreneur
+                .ogrnip
+                .as_deref()
+                .is_some_and(|ogrnip| same_number(ogrnip, &existing.ogrnip))
+    });
+    let Some(existing) = existing else {
+        return (
+            ImportAction::Create,
+            Merged::Entrepreneur(entrepreneur.clone()),
+            true,
+        );
+    };
+
+    let or_stored = |imported: &Option<String>, stored: &str| {
+        imported.clone().or_else(|| Some(stored.to_string()))
+    };
+    let merged = EntrepreneurDto {
+        surname: or_stored(&entrepreneur.surname, &existing.surname),
+        name: or_stored(&entrepreneur.name, &existing.name),
+        patronymic: entrepreneur
+            .patronymic
+            .clone()
+            .or_else(|| existing.patronymic.clone()),
+        ogrnip: or_stored(&entrepreneur.ogrnip, &existing.ogrnip),
+        inn: or_stored(&entrepreneur.inn, &existing.inn),
+        address_id: existing.address_id,
+        email: entrepreneur
+            .email
+            .clone()
+            .or_else(|| existing.email.clone()),
+        uid: Some(existing.uid.clone()),
+    };
+
+    let mut changes = Changes::default();
+    changes.text("surname", Some(&existing.surname), &merged.surname);
+    changes.text("name", Some(&existing.name), &merged.name);
+    changes.text(
+        "patronymic",
+        existing.patronymic.as_deref(),
+        &merged.patronymic,
+    );
+    changes.number("ogrnip", Some(&existing.ogrnip), &merged.ogrnip);
+    changes.number("inn", Some(&existing.inn), &merged.inn);
+    changes.text("email", existing.email.as_deref(), &merged.email);
+    let new_address = changes.address(addresses, existing.address_id, address);
+
+    let action = if changes.0.is_empty() {
+        ImportAction::Unchanged { id: existing.id }
+    } else {
+        ImportAction::Update {
+            id: existing.id,
+            changes: changes.0,
+        }
+    };
+    (action, Merged::Entrepreneur(merged), new_address)
+}
+
+fn plan<A, O, E>(
+    addresses: &A,
+    organizations: &O,
+    entrepreneurs: &E,
+    records: &[RegistryRecord],
+) -> Result<Vec<Plan>, RegistryError>
+where
+    A: AddressRepository,
+    O: OrganizationRepository,
+    E: EntrepreneurRepository,
+{
+    // Archived records are matched as well, so the import does not create duplicates of them.
+    let options = ListOptions {
+        include_archived: true,
+    };
+    let stored_addresses = addresses.list(&options).map_err(AddressError::Repository)?;
+    let stored_organizations = organizations
+        .list(&options)
+        .map_err(OrganizationError::Repository)?;
+    let stored_entrepreneurs = entrepreneurs
+        .list(&options)
+        .map_err(EntrepreneurError::Repository)?;
+
+    let plans = records
+        .iter()
+        .map(|record| {
+            let (action, merged, new_address) = match record {
+                RegistryRecord::Organization {
+                    organization,
+                    address,
+                } => organization_plan(
+                    &stored_organizations,
+                    &stored_addresses,
+                    organization,
+                    address,
+                ),
+                RegistryRecord::Entrepreneur {
+                    entrepreneur,
+                    address,
+                } => entrepreneur_plan(
+                    &stored_entrepreneurs,
+                    &stored_addresses,
+                    entrepreneur,
+                    address,
+                ),
+            };
+
+            let error = new_address
+                .then(|| zdnp_core::sanitize_address(record.address()).err())
+                .flatten()
+                .map(RegistryError::from)
+                .or_else(|| match &merged {
+                    Merged::Organization(dto) => zdnp_core::sanitize_organization(dto)
+                        .err()
+                        .map(RegistryError::from),
+                    Merged::Entrepreneur(dto) => zdnp_core::sanitize_entrepreneur(dto)
+                        .err()
+                        .map(RegistryError::from),
+                });
+
+            Plan {
+                preview: ImportPreview {
+                    record: record.clone(),
+                    action,
+                    error: error.as_ref().map(ToString::to_string),
+                },
+                error,
+                merged,
+                new_address,
+            }
+        })
+        .collect();
+
+    Ok(plans)
+}
+
+/// Shows what importing the records would change, without storing anything.
+pub fn preview_import<A, O, E>(
+    addresses: &A,
+    organizations: &O,
+    entrepreneurs: &E,
+    records: &[RegistryRecord],
+) -> Result<Vec<ImportPreview>, RegistryError>
+where
+    A: AddressRepository,
+    O: OrganizationRepository,
+    E: EntrepreneurRepository,
+{
+    Ok(plan(addresses, organizations, entrepreneurs, records)?
+        .into_iter()
+        .map(|plan| plan.preview)
+        .collect())
+}
+
+/// Creates the records that do not exist yet and updates the others, see [`preview_import`].
+/// Nothing is stored when a record is invalid. Returns the created or matched entities in the
+/// order of the records.
+pub fn import_records<A, O, E>(
+    addresses: &A,
+    organizations: &O,
+    entrepreneurs: &E,
+    records: &[RegistryRecord],
+) -> Result<Vec<EntityRef>, RegistryError>
+where
+    A: AddressRepository,
+    O: OrganizationRepository,
+    E: EntrepreneurRepository,
+{
+    let plans = plan(addresses, organizations, entrepreneurs, records)?;
+    if let Some(error) = plans.iter().find_map(|plan| plan.error.clone()) {
+        return Err(error);
+    }
+
+    let mut references = Vec::new();
+    for plan in plans {
+        let id = match plan.preview.action {
+            ImportAction::Create => None,
+            ImportAction::Update { id, .. } => Some(id),
+            ImportAction::Unchanged { id } => {
+                references.push(match plan.merged {
+                    Merged::Organization(_) => EntityRef::Organization(id),
+                    Merged::Entrepreneur(_) => EntityRef::Entrepreneur(id),
+                });
+                continue;
+            }
+        };
+
+        let address_id = if plan.new_address {
+            Some(zdnp_core::create_address(
+                addresses,
+                plan.preview.record.address(),
+            )?)
+        } else {
+            None
+        };
+
+        references.push(match plan.merged {
+            Merged::Organization(mut dto) => {
+                dto.address_id = address_id.unwrap_or(dto.address_id);
+                match id {
+                    Some(id) => {
+                        zdnp_core::update_organization(organizations, id, &dto)?;
+                        EntityRef::Organization(id)
+                    }
+                    None => EntityRef::Organization(zdnp_core::create_organization(
+                        organizations,
+                        &dto,
+                    )?),
+                }
+            }
+            Merged::Entrepreneur(mut dto) => {
+                dto.address_id = address_id.unwrap_or(dto.address_id);
+                match id {
+                    Some(id) => {
+                        zdnp_core::update_entrepreneur(entrepreneurs, id, &dto)?;
+                        EntityRef::Entrepreneur(id)
+                    }
+                    None => EntityRef::Entrepreneur(zdnp_core::create_entrepreneur(
+                        entrepreneurs,
+                        &dto,
+                    )?),
+                }
+            }
+        });
+    }
+
+    Ok(references)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+
+    use super::*;
+    use zdnp_core::{
+        AddressRepositoryError, EntrepreneurRepositoryError, OrganizationRepositoryError,
+    };
+
+    /// Reference data kept in memory; archiving is not needed by the import.
+    #[derive(Default)]
+    struct Store {
+        addresses: RefCell<Vec<Address>>,
+        organizations: RefCell<Vec<Organization>>,
+        entrepreneurs: RefCell<Vec<Entrepreneur>>,
+    }
+
+    fn text(value: &Option<String>) -> String {
+        value.clone().unwrap_or_default()
+    }
+
+    impl AddressRepository for Store {
+        fn create(&self, dto: &AddressDto) -> Result<i64, AddressRepositoryError> {
+            let mut addresses = self.addresses.borrow_mut();
+            let id = addresses.len() as i64 + 1;
+            addresses.push(Address {
+                id,
+                uid: text(&dto.uid),
+                region_code: text(&dto.region_code),
+                note: dto.note.clone(),
+                country: dto.country.clone(),
+                district: dto.district.clone(),
+                city: dto.city.clone(),
+                settlement: dto.settlement.clone(),
+                street: dto.street.clone(),
+                building: dto.building.clone(),
+                room: dto.room.clone(),
+                archived_at: None,
+            });
+            Ok(id)
+        }
+
+        fn update(&self, _: i64, _: &AddressDto) -> Result<bool, AddressRepositoryError> {
+            Err(AddressRepositoryError::storage("not supported"))
+        }
+
+        fn list(&self, _: &ListOptions) -> Result<Vec<Address>, AddressRepositoryError> {
+            Ok(self.addresses.borrow().clone())
+        }
+
+        fn archive(&self, _: i64) -> Result<bool, AddressRepositoryError> {
+            Err(AddressRepositoryError::storage("not supported"))
+        }
+
+        fn restore(&self, _: i64) -> Result<bool, AddressRepositoryError> {
+            Err(AddressRepositoryError::storage("not supported"))
+        }
+    }
+
+    fn organization(id: i64, dto: &OrganizationDto) -> Organization {
+        Organization {
+            id,
+            uid: text(&dto.uid),
+            full_name: text(&dto.full_name),
+            abbreviated_name: text(&dto.abbreviated_name),
+            ogrn: dto.ogrn.clone(),
+            rafp: dto.rafp.clone(),
+            inn: text(&dto.inn),
+            kpp: text(&dto.kpp),
+            address_id: dto.address_id,
+            email: text(&dto.email),
+            archived_at: None,
+        }
+    }
+
+    impl OrganizationRepository for Store {
+        fn create(&self, dto: &OrganizationDto) -> Result<i64, OrganizationRepositoryError> {
+            let mut organizations = self.organizations.borrow_mut();
+            let id = organizations.len() as i64 + 1;
+            organizations.push(organization(id, dto));
+            Ok(id)
+        }
+
+        fn update(
+            &self,
+            id: i64,
+            dto: &OrganizationDto,
+        ) -> Result<bool, OrganizationRepositoryError> {
+            let mut organizations = self.organizations.borrow_mut();
+            let Some(stored) = organizations.iter_mut().find(|stored| stored.id == id) else {
+                return Ok(false);
+            };
+            *stored = organization(id, dto);
+            Ok(true)
+        }
+
+        fn list(&self, _: &ListOptions) -> Result<Vec<Organization>, OrganizationRepositoryError> {
+            Ok(self.organizations.borrow().clone())
+        }
+
+        fn archive(&self, _: i64) -> Result<bool, OrganizationRepositoryError> {
+            Err(OrganizationRepositoryError::storage("not supported"))
+        }
+
+        fn restore(&self, _: i64) -> Result<bool, OrganizationRepositoryError> {
+            Err(OrganizationRepositoryError::storage("not supported"))
+        }
+    }
+
+    fn entrepreneur(id: i64, dto: &EntrepreneurDto) -> Entrepreneur {
+        Entrepreneur {
+            id,
+            uid: text(&dto.uid),
+            surname: text(&dto.surname),
+            name: text(&dto.name),
+            patronymic: dto.patronymic.clone(),
+            ogrnip: text(&dto.ogrnip),
+            inn: text(&dto.inn),
+            address_id: dto.address_id,
+            email: dto.email.clone(),
+            archived_at: None,
+        }
+    }
+
+    impl EntrepreneurRepository for Store {
+        fn create(&self, dto: &EntrepreneurDto) -> Result<i64, EntrepreneurRepositoryError> {
+            let mut entrepreneurs = self.entrepreneurs.borrow_mut();
+            let id = entrepreneurs.len() as i64 + 1;
+            entrepreneurs.push(entrepreneur(id, dto));
+            Ok(id)
+        }
+
+        fn update(
+            &self,
+            id: i64,
+            dto: &EntrepreneurDto,
+        ) -> Result<bool, EntrepreneurRepositoryError> {
+            let mut entrepreneurs = self.entrepreneurs.borrow_mut();
+            let Some(stored) = entrepreneurs.iter_mut().find(|stored| stored.id == id) else {
+                return Ok(false);
+            };
+            *stored = entrepreneur(id, dto);
+            Ok(true)
+        }
+
+        fn list(&self, _: &ListOptions) -> Result<Vec<Entrepreneur>, EntrepreneurRepositoryError> {
+            Ok(self.entrepreneurs.borrow().clone())
+        }
+
+        fn archive(&self, _: i64) -> Result<bool, EntrepreneurRepositoryError> {
+            Err(EntrepreneurRepositoryError::storage("not supported"))
+        }
+
+        fn restore(&self, _: i64) -> Result<bool, EntrepreneurRepositoryError> {
+            Err(EntrepreneurRepositoryError::storage("not supported"))
+        }
+    }
+
+    fn records() -> Vec<RegistryRecord> {
+        let mut records = crate::parse_extract(include_bytes!("../testdata/egrul.xml")).unwrap();
+        records.extend(crate::parse_extract(include_bytes!("../testdata/egrip.xml")).unwrap());
+        records
+    }
+
+    #[test]
+    fn matched_records_are_updated_and_new_ones_created() {
+        let store = Store::default();
+        let address_id = AddressRepository::create(
+            &store,
+            &AddressDto {
+                region_code: Some("77".into()),
+                city: Some("Москва".into()),
+                ..AddressDto::default()
+            },
+        )
+        .unwrap();
+        OrganizationRepository::create(
+            &store,
+            &OrganizationDto {
+                full_name: Some("ООО \"Проект\"".into()),
+                abbreviated_name: Some("ООО \"Проект\"".into()),
+                ogrn: None,
+                rafp: Some("12345".into()),
+                inn: Some("7700000000".into()),
+                kpp: Some("770001001".into()),
+                address_id,
+                email: Some("office@project.example".into()),
+                uid: None,
+            },
+        )
+        .unwrap();
+
+        let records = records();
+        let previews = preview_import(&store, &store, &store, &records).expect("preview");
+        assert_eq!(previews.len(), 2);
+        assert_eq!(previews[0].error, None);
+        let ImportAction::Update { id: 1, changes } = &previews[0].action else {
+            panic!("{:?}", previews[0].action);
+        };
+        let fields: Vec<_> = changes.iter().map(|change| change.field).collect();
+        assert_eq!(
+            fields,
+            ["full_name", "abbreviated_name", "ogrn", "email", "address"]
+        );
+        assert_eq!(changes[2].current, None);
+        assert_eq!(changes[2].imported.as_deref(), Some("1027700000000"));
+        assert_eq!(previews[1].action, ImportAction::Create);
+
+        let references = import_records(&store, &store, &store, &records).expect("import");
+        assert_eq!(
+            references,
+            vec![EntityRef::Organization(1), EntityRef::Entrepreneur(1)]
+        );
+        let organization = store.organizations.borrow()[0].clone();
+        assert_eq!(organization.abbreviated_name, "ООО \"ПРОЕКТ\"");
+        // Values missing from the extract are kept; the old address is left to other users.
+        assert_eq!(organization.rafp.as_deref(), Some("12345"));
+        assert_eq!(organization.address_id, 2);
+        assert_eq!(store.addresses.borrow().len(), 3);
+        assert_eq!(store.entrepreneurs.borrow()[0].surname, "Петров-Водкин");
+
+        let previews = preview_import(&store, &store, &store, &records).expect("preview");
+        assert_eq!(previews[0].action, ImportAction::Unchanged { id: 1 });
+        assert_eq!(previews[1].action, ImportAction::Unchanged { id: 1 });
+    }
+
+    #[test]
+    fn nothing_is_imported_when_a_record_is_invalid() {
+        let store = Store::default();
+        let mut records = records();
+        if let RegistryRecord::Organization { organization, .. } = &mut records[0] {
+            organization.email = None;
+        }
+
+        let previews = preview_import(&store, &store, &store, &records).expect("preview");
+        assert_eq!(previews[0].action, ImportAction::Create);
+        assert_eq!(
+            previews[0].error.as_deref(),
+            Some("Organization error: Email is required")
+        );
+        assert_eq!(previews[1].error, None);
+
+        assert_eq!(
+            import_records(&store, &store, &store, &records),
+            Err(RegistryError::Organization(OrganizationError::MissingEmail))
+        );
+        assert!(store.addresses.borrow().is_empty());
+        assert!(store.entrepreneurs.borrow().is_empty());
+    }
+}
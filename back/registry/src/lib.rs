@@ -0,0 +1,58 @@
+//! Import of organizations and entrepreneurs from ЕГРЮЛ/ЕГРИП XML extracts downloaded from the
+//! Federal Tax Service. Extracts are parsed into the reference data DTOs, compared with the
+//! existing records matched by ИНН or ОГРН(ИП) and then created or updated.
+
+mod extract;
+mod import;
+
+pub use extract::*;
+pub use import::*;
+
+use zdnp_core::{AddressError, EntrepreneurError, OrganizationError};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RegistryError {
+    Io(String),
+    /// The file is not in a supported text encoding.
+    Encoding(String),
+    Xml(String),
+    /// The file holds neither ЕГРЮЛ nor ЕГРИП records.
+    NoRecords,
+    Address(AddressError),
+    Organization(OrganizationError),
+    Entrepreneur(EntrepreneurError),
+}
+
+impl std::fmt::Display for RegistryError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(message) => write!(f, "I/O error: {message}"),
+            Self::Encoding(label) => write!(f, "Unsupported encoding: {label}"),
+            Self::Xml(message) => write!(f, "Malformed XML: {message}"),
+            Self::NoRecords => f.write_str("File contains no ЕГРЮЛ or ЕГРИП records"),
+            Self::Address(error) => write!(f, "Address error: {error}"),
+            Self::Organization(error) => write!(f, "Organization error: {error}"),
+            Self::Entrepreneur(error) => write!(f, "Entrepreneur error: {error}"),
+        }
+    }
+}
+
+impl std::error::Error for RegistryError {}
+
+impl From<AddressError> for RegistryError {
+    fn from(error: AddressError) -> Self {
+        Self::Address(error)
+    }
+}
+
+impl From<OrganizationError> for RegistryError {
+    fn from(error: OrganizationError) -> Self {
+        Self::Organization(error)
+    }
+}
+
+impl From<EntrepreneurError> for RegistryError {
+    fn from(error: EntrepreneurError) -> Self {
+        Self::Entrepreneur(error)
+    }
+}
@@ -0,0 +1,625 @@
+use zdnp_core::{
+    DocumentAuthor, DocumentExport, DocumentSection, EntityRef, ReferenceEntity, Signatory,
+    SigningRole, find_okei_unit,
+};
+
+// ---------------- Printable Content ----------------
+
+/// Piece of the printed document, independent of the output format.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Block {
+    Title(String),
+    Subtitle(String),
+    Heading(String),
+    Subheading(String),
+    Field {
+        label: &'static str,
+        value: String,
+    },
+    Paragraph(String),
+    Table(Table),
+    /// Caption of the signing role, the position of the signatory and a line for the wet
+    /// signature followed by the name and the date of signing.
+    Signature {
+        role: &'static str,
+        position: String,
+        name: String,
+        date: Option<String>,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Table {
+    /// Column titles with their share of the text width.
+    pub columns: Vec<(&'static str, f32)>,
+    pub rows: Vec<Vec<String>>,
+}
+
+const MISSING: &str = "Сведения отсутствуют";
+
+fn entity(export: &DocumentExport, reference: EntityRef) -> Option<&ReferenceEntity> {
+    export
+        .entities
+        .iter()
+        .find(|entity| entity.reference() == reference)
+}
+
+fn address(export: &DocumentExport, id: i64) -> String {
+    match entity(export, EntityRef::Address(id)) {
+        Some(ReferenceEntity::Address(address)) => address.formatted(),
+        _ => MISSING.to_string(),
+    }
+}
+
+fn party_blocks(export: &DocumentExport, reference: EntityRef, blocks: &mut Vec<Block>) {
+    let field = |label, value: &str| Block::Field {
+        label,
+        value: value.to_string(),
+    };
+
+    match entity(export, reference) {
+        Some(ReferenceEntity::Organization(organization)) => {
+            blocks.push(field("Наименование", &organization.full_name));
+            blocks.push(field(
+                "ИНН / КПП",
+                &format!("{} / {}", organization.inn, organization.kpp),
+            ));
+            if let Some(ogrn) = &organization.ogrn {
+                blocks.push(field("ОГРН", ogrn));
+            }
+            blocks.push(field("Адрес", &address(export, organization.address_id)));
+            blocks.push(field("Электронная почта", &organization.email));
+        }
+        Some(ReferenceEntity::Entrepreneur(entrepreneur)) => {
+            blocks.push(field(
+                "Наименование",
+                &format!("ИП {}", entrepreneur.full_name()),
+            ));
+            blocks.push(field("ИНН", &entrepreneur.inn));
+            blocks.push(field("ОГРНИП", &entrepreneur.ogrnip));
+            blocks.push(field("Адрес", &address(export, entrepreneur.address_id)));
+        }
+        Some(ReferenceEntity::Person(person)) => {
+            blocks.push(field("Фамилия, имя, отчество", &person.full_name()));
+            blocks.push(field("СНИЛС", &person.snils));
+            blocks.push(field("Адрес", &address(export, person.address_id)));
+        }
+        _ => blocks.push(Block::Paragraph(MISSING.to_string())),
+    }
+}
+
+fn party_name(export: &DocumentExport, reference: EntityRef) -> String {
+    entity(export, reference)
+        .and_then(ReferenceEntity::party_name)
+        .unwrap_or_default()
+}
+
+fn signatory(export: &DocumentExport, role: SigningRole) -> Option<&Signatory> {
+    export
+        .signatories
+        .iter()
+        .find(|signatory| signatory.role == role)
+}
+
+fn signatory_name(export: &DocumentExport, signatory: &Signatory) -> String {
+    party_name(export, EntityRef::Person(signatory.person_id))
+}
+
+/// Merge fields a DOCX template may use, written as `{{key}}`.
+pub const MERGE_FIELDS: [&str; 29] = [
+    "document.name",
+    "document.number",
+    "document.date",
+    "document.change_mark",
+    "document.uid",
+    "document.type",
+    "developer.full_name",
+    "developer.inn",
+    "developer.kpp",
+    "developer.ogrn",
+    "developer.address",
+    "developer.email",
+    "author.full_name",
+    "author.inn",
+    "author.kpp",
+    "author.ogrn",
+    "author.address",
+    "author.email",
+    "object.name",
+    "object.address",
+    "object.purpose_code",
+    "object.work_type",
+    "object.cadastral_numbers",
+    "approval.full_name",
+    "approval.position",
+    "approval.signed_on",
+    "agreement.full_name",
+    "agreement.position",
+    "agreement.signed_on",
+];
+
+fn party_fields(
+    export: &DocumentExport,
+    prefix: &str,
+    reference: EntityRef,
+    fields: &mut Vec<(String, String)>,
+) {
+    let Some(entity) = entity(export, reference) else {
+        return;
+    };
+    let mut push = |key: &str, value: &str| fields.push((format!("{prefix}.{key}"), value.into()));
+
+    if let Some(name) = entity.party_name() {
+        push("full_name", &name);
+    }
+    match entity {
+        ReferenceEntity::Organization(organization) => {
+            push("inn", &organization.inn);
+            push("kpp", &organization.kpp);
+            if let Some(ogrn) = &organization.ogrn {
+                push("ogrn", ogrn);
+            }
+            push("address", &address(export, organization.address_id));
+            push("email", &organization.email);
+        }
+        ReferenceEntity::Entrepreneur(entrepreneur) => {
+            push("inn", &entrepreneur.inn);
+            push("ogrn", &entrepreneur.ogrnip);
+            push("address", &address(export, entrepreneur.address_id));
+            if let Some(email) = &entrepreneur.email {
+                push("email", email);
+            }
+        }
+        ReferenceEntity::Person(person) => {
+            push("address", &address(export, person.address_id));
+            push("email", &person.email);
+        }
+        ReferenceEntity::Address(_) | ReferenceEntity::SourceDocument(_) => {}
+    }
+}
+
+/// Values of the merge fields, see [`MERGE_FIELDS`]. Fields without a value are left out.
+pub(crate) fn merge_fields(export: &DocumentExport) -> Vec<(String, String)> {
+    let document = &export.document;
+    let mut fields = Vec::new();
+    let mut push = |key: &str, value: Option<String>| {
+        if let Some(value) = value {
+            fields.push((key.to_string(), value));
+        }
+    };
+
+    push("document.name", Some(document.name.clone()));
+    push("document.number", document.number.clone());
+    push("document.date", document.date.clone());
+    push("document.change_mark", document.change_mark.clone());
+    push("document.uid", Some(document.uid.clone()));
+    push(
+        "document.type",
+        document.document_type.map(|kind| kind.name().to_string()),
+    );
+    if let Some(object) = &export.construction_object {
+        push("object.name", Some(object.name.clone()));
+        push("object.address", Some(address(export, object.address_id)));
+        push("object.purpose_code", Some(object.purpose_code.clone()));
+        push(
+            "object.work_type",
+            Some(object.work_type.name().to_string()),
+        );
+        push(
+            "object.cadastral_numbers",
+            Some(object.cadastral_numbers.join(", ")),
+        );
+    }
+
+    if let Some(developer) = document.developer {
+        party_fields(export, "developer", developer.reference(), &mut fields);
+    }
+    match &document.author {
+        DocumentAuthor::Informal { name } => {
+            fields.push(("author.full_name".to_string(), name.clone()));
+        }
+        author => {
+            if let Some(reference) = author.reference() {
+                party_fields(export, "author", reference, &mut fields);
+            }
+        }
+    }
+
+    for role in SigningRole::ALL {
+        if let Some(signatory) = signatory(export, role) {
+            let prefix = role.as_str();
+            fields.push((
+                format!("{prefix}.full_name"),
+                signatory_name(export, signatory),
+            ));
+            fields.push((format!("{prefix}.position"), signatory.position.clone()));
+            if let Some(date) = &signatory.signed_on {
+                fields.push((format!("{prefix}.signed_on"), date.clone()));
+            }
+        }
+    }
+
+    fields
+}
+
+/// Lays out the exported document: the header, the developer and author blocks, the content
+/// sections and the signature lines.
+pub(crate) fn document_blocks(export: &DocumentExport) -> Vec<Block> {
+    let document = &export.document;
+    let mut blocks = vec![
+        Block::Title("Задание на проектирование".to_uppercase()),
+        Block::Subtitle(document.name.clone()),
+    ];
+
+    let header = [
+        (
+            "Вид документа",
+            document.document_type.map(|kind| kind.name().to_string()),
+        ),
+        ("Номер", document.number.clone()),
+        ("Дата", document.date.clone()),
+        ("Номер изменения", document.change_mark.clone()),
+        ("Идентификатор", Some(document.uid.clone())),
+    ];
+    for (label, value) in header {
+        if let Some(value) = value {
+            blocks.push(Block::Field { label, value });
+        }
+    }
+
+    blocks.push(Block::Heading(
+        "Застройщик (технический заказчик)".to_string(),
+    ));
+    match document.developer {
+        Some(developer) => party_blocks(export, developer.reference(), &mut blocks),
+        None => blocks.push(Block::Paragraph(MISSING.to_string())),
+    }
+
+    blocks.push(Block::Heading("Составитель задания".to_string()));
+    let author = match &document.author {
+        DocumentAuthor::Informal { name } => {
+            blocks.push(Block::Field {
+                label: "Наименование",
+                value: name.clone(),
+            });
+            name.clone()
+        }
+        author => match author.reference() {
+            Some(reference) => {
+                party_blocks(export, reference, &mut blocks);
+                party_name(export, reference)
+            }
+            None => String::new(),
+        },
+    };
+
+    if let Some(object) = &export.construction_object {
+        blocks.push(Block::Heading(
+            DocumentSection::ConstructionObject.name().to_string(),
+        ));
+        blocks.push(Block::Field {
+            label: "Наименование",
+            value: object.name.clone(),
+        });
+        blocks.push(Block::Field {
+            label: "Адрес (местоположение)",
+            value: address(export, object.address_id),
+        });
+        blocks.push(Block::Field {
+            label: "Функциональное назначение",
+            value: object.purpose_code.clone(),
+        });
+        blocks.push(Block::Field {
+            label: "Вид работ",
+            value: object.work_type.name().to_string(),
+        });
+        if !object.cadastral_numbers.is_empty() {
+            blocks.push(Block::Field {
+                label: "Земельные участки",
+                value: object.cadastral_numbers.join(", "),
+            });
+        }
+    }
+
+    if !export.indicators.is_empty() {
+        blocks.push(Block::Heading(
+            DocumentSection::Indicators.name().to_string(),
+        ));
+        blocks.push(Block::Table(Table {
+            columns: vec![
+                ("№", 0.08),
+                ("Наименование показателя", 0.52),
+                ("Значение", 0.2),
+                ("Ед. изм.", 0.2),
+            ],
+            rows: export
+                .indicators
+                .iter()
+                .enumerate()
+                .map(|(index, indicator)| {
+                    let unit = find_okei_unit(&indicator.unit_okei)
+                        .map(|unit| unit.symbol.to_string())
+                        .unwrap_or_else(|| indicator.unit_okei.clone());
+                    vec![
+                        (index + 1).to_string(),
+                        indicator.name.clone(),
+                        indicator.value.clone(),
+                        unit,
+                    ]
+                })
+                .collect(),
+        }));
+    }
+
+    let sources: Vec<Block> = export
+        .entities
+        .iter()
+        .filter_map(|entity| match entity {
+            ReferenceEntity::SourceDocument(source) => Some(source),
+            _ => None,
+        })
+        .enumerate()
+        .map(|(index, source)| {
+            let mut text = format!(
+                "{}. {} № {} от {}",
+                index + 1,
+                source.kind.name(),
+                source.number,
+                source.date
+            );
+            if let Some(title) = &source.title {
+                text.push_str(&format!(" «{title}»"));
+            }
+            let issuer = party_name(export, EntityRef::Organization(source.issuer_id));
+            if !issuer.is_empty() {
+                text.push_str(&format!(", выдан: {issuer}"));
+            }
+            Block::Paragraph(text)
+        })
+        .collect();
+    if !sources.is_empty() {
+        blocks.push(Block::Heading(
+            DocumentSection::SourceDocuments.name().to_string(),
+        ));
+        blocks.extend(sources);
+    }
+
+    if !export.requirements.is_empty() {
+        blocks.push(Block::Heading(
+            DocumentSection::Requirements.name().to_string(),
+        ));
+        for (index, requirement) in export.requirements.iter().enumerate() {
+            blocks.push(Block::Subheading(format!(
+                "{}. {}",
+                index + 1,
+                requirement.title
+            )));
+            blocks.extend(
+                requirement
+                    .text
+                    .lines()
+                    .map(str::trim)
+                    .filter(|line| !line.is_empty())
+                    .map(|line| Block::Paragraph(line.to_string())),
+            );
+        }
+    }
+
+    // Until the signatories are chosen, the developer approves and the author agrees the
+    // document, leaving the name for a handwritten entry.
+    for role in SigningRole::ALL {
+        blocks.push(match signatory(export, role) {
+            Some(signatory) => Block::Signature {
+                role: role.name(),
+                position: signatory.position.clone(),
+                name: signatory_name(export, signatory),
+                date: signatory.signed_on.clone(),
+            },
+            None => Block::Signature {
+                role: role.name(),
+                position: match role {
+                    SigningRole::Approval => document
+                        .developer
+                        .map(|developer| party_name(export, developer.reference()))
+                        .unwrap_or_default(),
+                    SigningRole::Agreement => author.clone(),
+                },
+                name: String::new(),
+                date: None,
+            },
+        });
+    }
+
+    blocks
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use zdnp_core::{
+        Address, ConstructionObject, Document, DocumentDeveloper, DocumentStatus, Organization,
+        Person, RequirementCategory, RequirementSection, SourceDocument, SourceDocumentKind,
+        TechnicalIndicator, WorkType,
+    };
+
+    pub(crate) fn export() -> DocumentExport {
+        DocumentExport {
+            document: Document {
+                id: 1,
+                name: "ЗНП на строительство школы".into(),
+                number: Some("ЗНП-2026-0001".into()),
+                date: Some("2026-10-01".into()),
+                change_mark: None,
+                uid: "2f1c9a8e-3b7d-4c5e-9a10-1b2c3d4e5f60".into(),
+                document_type: None,
+                type_note: None,
+                author: DocumentAuthor::Informal {
+                    name: "Иванов И. И.".into(),
+                },
+                developer: Some(DocumentDeveloper::Organization { id: 3 }),
+                previous_revision_id: None,
+                is_template: false,
+                status: DocumentStatus::Draft,
+                submitted_at: None,
+                approved_at: None,
+                issued_at: None,
+                superseded_at: None,
+                cancelled_at: None,
+            },
+            construction_object: Some(ConstructionObject {
+                id: 1,
+                document_id: 1,
+                name: "Школа на 550 мест".into(),
+                purpose_code: "19.7.1.1".into(),
+                address_id: 9,
+                cadastral_numbers: vec!["77:01:0001001:1234".into()],
+                work_type: WorkType::Construction,
+            }),
+            indicators: vec![TechnicalIndicator {
+                id: 1,
+                document_id: 1,
+                position: 1,
+                name: "Общая площадь".into(),
+                value: "5400".into(),
+                unit_okei: "055".into(),
+            }],
+            requirements: vec![RequirementSection {
+                id: 1,
+                document_id: 1,
+                position: 1,
+                category: RequirementCategory::Architecture,
+                title: "Архитектурные решения".into(),
+                text: "Фасады выполнить в кирпиче.\n\nКровля плоская.".into(),
+                template_id: None,
+            }],
+            signatories: vec![Signatory {
+                id: 1,
+                document_id: 1,
+                person_id: 5,
+                position: "Генеральный директор ООО \"Заказчик\"".into(),
+                role: SigningRole::Approval,
+                signed_on: Some("2026-10-05".into()),
+            }],
+            entities: vec![
+                ReferenceEntity::Organization(Organization {
+                    id: 3,
+                    uid: "organization-3".into(),
+                    full_name: "ООО \"Заказчик\"".into(),
+                    abbreviated_name: "Заказчик".into(),
+                    ogrn: None,
+                    rafp: None,
+                    inn: "7700000000".into(),
+                    kpp: "770001001".into(),
+                    address_id: 9,
+                    email: "info@example.com".into(),
+                    archived_at: None,
+                }),
+                ReferenceEntity::Address(Address {
+                    id: 9,
+                    uid: "address-9".into(),
+                    region_code: "77".into(),
+                    note: None,
+                    country: None,
+                    district: None,
+                    city: Some("Москва".into()),
+                    settlement: None,
+                    street: Some("Тверская".into()),
+                    building: Some("1".into()),
+                    room: None,
+                    archived_at: None,
+                }),
+                ReferenceEntity::Person(Person {
+                    id: 5,
+                    uid: "person-5".into(),
+                    name: "Пётр".into(),
+                    patronymic: Some("Петрович".into()),
+                    surname: "Петров".into(),
+                    snils: "11223344595".into(),
+                    email: "petrov@example.com".into(),
+                    address_id: 9,
+                    archived_at: None,
+                }),
+                ReferenceEntity::SourceDocument(SourceDocument {
+                    id: 20,
+                    uid: "source-document-20".into(),
+                    kind: SourceDocumentKind::Gpzu,
+                    number: "РФ-77-4-53-3-01-2026-1234".into(),
+                    date: "2026-09-15".into(),
+                    issuer_id: 3,
+                    title: None,
+                    archived_at: None,
+                }),
+            ],
+            from_snapshot: false,
+        }
+    }
+
+    #[test]
+    fn document_is_laid_out_with_parties_and_sections() {
+        let blocks = document_blocks(&export());
+
+        assert_eq!(blocks[0], Block::Title("ЗАДАНИЕ НА ПРОЕКТИРОВАНИЕ".into()));
+        assert!(blocks.contains(&Block::Field {
+            label: "Адрес (местоположение)",
+            value: "77, Москва, Тверская, 1".into(),
+        }));
+        assert!(
+            blocks.contains(&Block::Paragraph(
+                "1. Градостроительный план земельного участка № РФ-77-4-53-3-01-2026-1234 от \
+             2026-09-15, выдан: ООО \"Заказчик\""
+                    .into()
+            ))
+        );
+        assert!(blocks.contains(&Block::Paragraph("Кровля плоская.".into())));
+
+        let Some(Block::Table(table)) =
+            blocks.iter().find(|block| matches!(block, Block::Table(_)))
+        else {
+            panic!("indicators table expected");
+        };
+        assert_eq!(table.rows, vec![vec!["1", "Общая площадь", "5400", "м2"]]);
+
+        assert_eq!(
+            blocks[blocks.len() - 2..],
+            [
+                Block::Signature {
+                    role: "Утверждаю",
+                    position: "Генеральный директор ООО \"Заказчик\"".into(),
+                    name: "Петров Пётр Петрович".into(),
+                    date: Some("2026-10-05".into()),
+                },
+                Block::Signature {
+                    role: "Согласовано",
+                    position: "Иванов И. И.".into(),
+                    name: String::new(),
+                    date: None,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn merge_fields_come_from_the_document_and_its_parties() {
+        let fields = merge_fields(&export());
+        let value = |key: &str| {
+            fields
+                .iter()
+                .find(|(field, _)| field == key)
+                .map(|(_, value)| value.as_str())
+        };
+
+        assert!(
+            fields
+                .iter()
+                .all(|(key, _)| MERGE_FIELDS.contains(&key.as_str()))
+        );
+        assert_eq!(value("developer.full_name"), Some("ООО \"Заказчик\""));
+        assert_eq!(value("developer.address"), Some("77, Москва, Тверская, 1"));
+        assert_eq!(value("author.full_name"), Some("Иванов И. И."));
+        assert_eq!(value("author.inn"), None);
+        assert_eq!(value("document.change_mark"), None);
+        assert_eq!(value("approval.full_name"), Some("Петров Пётр Петрович"));
+        assert_eq!(value("approval.signed_on"), Some("2026-10-05"));
+        assert_eq!(value("agreement.position"), None);
+    }
+}
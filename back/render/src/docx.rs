@@ -0,0 +1,451 @@
+use std::io::{Cursor, Read, Write};
+use std::path::Path;
+
+use zdnp_core::DocumentExport;
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+use crate::RenderError;
+use crate::content::{Block, Table, document_blocks, merge_fields};
+
+// ---------------- DOCX Export ----------------
+
+const DOCUMENT_PART: &str = "word/document.xml";
+
+/// Paragraph of a template replaced with the generated content of the document.
+const CONTENT_FIELD: &str = "{{content}}";
+
+/// Text width of an A4 page with 30 mm left and 15 mm right margins, in twentieths of a point.
+const TEXT_WIDTH: f32 = 9355.0;
+
+const CONTENT_TYPES: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Types xmlns="http://schemas.openxmlformats.org/package/2006/content-types"><Default Extension="rels" ContentType="application/vnd.openxmlformats-package.relationships+xml"/><Default Extension="xml" ContentType="application/xml"/><Override PartName="/word/document.xml" ContentType="application/vnd.openxmlformats-officedocument.wordprocessingml.document.main+xml"/><Override PartName="/word/styles.xml" ContentType="application/vnd.openxmlformats-officedocument.wordprocessingml.styles+xml"/></Types>"#;
+
+const PACKAGE_RELATIONSHIPS: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/officeDocument" Target="word/document.xml"/></Relationships>"#;
+
+const DOCUMENT_RELATIONSHIPS: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/styles" Target="styles.xml"/></Relationships>"#;
+
+const STYLES: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<w:styles xmlns:w="http://schemas.openxmlformats.org/wordprocessingml/2006/main"><w:docDefaults><w:rPrDefault><w:rPr><w:rFonts w:ascii="Times New Roman" w:hAnsi="Times New Roman" w:cs="Times New Roman"/><w:sz w:val="24"/><w:lang w:val="ru-RU"/></w:rPr></w:rPrDefault><w:pPrDefault><w:pPr><w:spacing w:after="60"/></w:pPr></w:pPrDefault></w:docDefaults><w:style w:type="paragraph" w:default="1" w:styleId="Normal"><w:name w:val="Normal"/></w:style><w:style w:type="paragraph" w:styleId="Title"><w:name w:val="Title"/><w:basedOn w:val="Normal"/><w:next w:val="Normal"/><w:pPr><w:jc w:val="center"/><w:spacing w:after="120"/></w:pPr><w:rPr><w:b/><w:sz w:val="28"/></w:rPr></w:style><w:style w:type="paragraph" w:styleId="Subtitle"><w:name w:val="Subtitle"/><w:basedOn w:val="Normal"/><w:next w:val="Normal"/><w:pPr><w:jc w:val="center"/><w:spacing w:after="240"/></w:pPr><w:rPr><w:b/><w:sz w:val="26"/></w:rPr></w:style><w:style w:type="paragraph" w:styleId="Heading1"><w:name w:val="heading 1"/><w:basedOn w:val="Normal"/><w:next w:val="Normal"/><w:pPr><w:keepNext/><w:spacing w:before="240" w:after="120"/><w:outlineLvl w:val="0"/></w:pPr><w:rPr><w:b/><w:sz w:val="26"/></w:rPr></w:style><w:style w:type="paragraph" w:styleId="Heading2"><w:name w:val="heading 2"/><w:basedOn w:val="Normal"/><w:next w:val="Normal"/><w:pPr><w:keepNext/><w:spacing w:before="120" w:after="60"/><w:outlineLvl w:val="1"/></w:pPr><w:rPr><w:b/></w:rPr></w:style></w:styles>"#;
+
+const SECTION: &str = r#"<w:sectPr><w:pgSz w:w="11906" w:h="16838"/><w:pgMar w:top="1134" w:right="850" w:bottom="1134" w:left="1701" w:header="709" w:footer="709" w:gutter="0"/></w:sectPr>"#;
+
+fn docx_error<E: std::fmt::Display>(error: E) -> RenderError {
+    RenderError::Docx(error.to_string())
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn unescape(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+fn run(text: &str, bold: bool) -> String {
+    let properties = if bold { "<w:rPr><w:b/></w:rPr>" } else { "" };
+    format!(
+        r#"<w:r>{properties}<w:t xml:space="preserve">{}</w:t></w:r>"#,
+        escape(text)
+    )
+}
+
+/// Paragraph with a style. The formatting of the style is repeated on the runs, so the content
+/// keeps its look in a template that does not define the style.
+fn styled(style: &str, run_properties: &str, text: &str) -> String {
+    format!(
+        r#"<w:p><w:pPr><w:pStyle w:val="{style}"/></w:pPr><w:r><w:rPr>{run_properties}</w:rPr><w:t xml:space="preserve">{}</w:t></w:r></w:p>"#,
+        escape(text)
+    )
+}
+
+fn cell(text: &str, width: f32, bold: bool) -> String {
+    format!(
+        r#"<w:tc><w:tcPr><w:tcW w:w="{}" w:type="dxa"/></w:tcPr><w:p>{}</w:p></w:tc>"#,
+        width.round(),
+        run(text, bold)
+    )
+}
+
+fn table(table: &Table) -> String {
+    let widths: Vec<f32> = table
+        .columns
+        .iter()
+        .map(|(_, share)| share * TEXT_WIDTH)
+        .collect();
+    let border = r#"w:val="single" w:sz="4" w:space="0" w:color="000000""#;
+
+    let mut xml = format!(
+        r#"<w:tbl><w:tblPr><w:tblW w:w="{}" w:type="dxa"/><w:tblBorders><w:top {border}/><w:left {border}/><w:bottom {border}/><w:right {border}/><w:insideH {border}/><w:insideV {border}/></w:tblBorders></w:tblPr><w:tblGrid>"#,
+        TEXT_WIDTH.round()
+    );
+    for width in &widths {
+        xml.push_str(&format!(r#"<w:gridCol w:w="{}"/>"#, width.round()));
+    }
+    xml.push_str("</w:tblGrid>");
+
+    // The header row is repeated on every page the table spans.
+    xml.push_str("<w:tr><w:trPr><w:tblHeader/></w:trPr>");
+    for ((title, _), width) in table.columns.iter().zip(&widths) {
+        xml.push_str(&cell(title, *width, true));
+    }
+    xml.push_str("</w:tr>");
+
+    for row in &table.rows {
+        xml.push_str("<w:tr>");
+        for (text, width) in row.iter().zip(&widths) {
+            xml.push_str(&cell(text, *width, false));
+        }
+        xml.push_str("</w:tr>");
+    }
+
+    xml.push_str("</w:tbl>");
+    xml
+}
+
+fn block(block: &Block) -> String {
+    match block {
+        Block::Title(text) => styled("Title", "<w:b/><w:sz w:val=\"28\"/>", text),
+        Block::Subtitle(text) => styled("Subtitle", "<w:b/><w:sz w:val=\"26\"/>", text),
+        Block::Heading(text) => styled("Heading1", "<w:b/><w:sz w:val=\"26\"/>", text),
+        Block::Subheading(text) => styled("Heading2", "<w:b/>", text),
+        Block::Field { label, value } => format!(
+            "<w:p>{}{}</w:p>",
+            run(&format!("{label}: "), true),
+            run(value, false)
+        ),
+        Block::Paragraph(text) => format!(
+            r#"<w:p><w:pPr><w:jc w:val="both"/></w:pPr>{}</w:p>"#,
+            run(text, false)
+        ),
+        Block::Table(content) => table(content),
+        Block::Signature {
+            role,
+            position,
+            name,
+            date,
+        } => {
+            let mut xml = format!(
+                r#"<w:p><w:pPr><w:keepNext/><w:spacing w:before="480"/></w:pPr>{}</w:p>"#,
+                run(role, true)
+            );
+            if !position.is_empty() {
+                xml.push_str(&format!(
+                    "<w:p><w:pPr><w:keepNext/></w:pPr>{}</w:p>",
+                    run(position, false)
+                ));
+            }
+            // The date is right-aligned to the edge of the text area.
+            xml.push_str(&format!(
+                r#"<w:p><w:pPr><w:tabs><w:tab w:val="left" w:pos="2552"/><w:tab w:val="right" w:pos="9355"/></w:tabs><w:spacing w:before="240"/></w:pPr><w:r><w:t>____________________</w:t><w:tab/></w:r>{}<w:r><w:tab/></w:r>{}</w:p>"#,
+                run(name, false),
+                run(date.as_deref().unwrap_or("«___» __________ 20___ г."), false)
+            ));
+            xml
+        }
+    }
+}
+
+/// Body of the document: the same layout as the printed form.
+fn body(export: &DocumentExport) -> String {
+    document_blocks(export).iter().map(block).collect()
+}
+
+/// Finds the paragraphs of a WordprocessingML part as byte ranges, including the tags.
+fn paragraphs(xml: &str) -> Vec<(usize, usize)> {
+    let mut ranges = Vec::new();
+    let mut offset = 0;
+
+    while let Some(found) = xml[offset..].find("<w:p") {
+        let start = offset + found;
+        let Some(tag_end) = xml[start..].find('>').map(|end| start + end + 1) else {
+            break;
+        };
+        let after = xml[start + 4..].chars().next();
+        if !matches!(after, Some('>') | Some(' ')) || xml[..tag_end].ends_with("/>") {
+            offset = tag_end;
+            continue;
+        }
+        let Some(end) = xml[start..].find("</w:p>") else {
+            break;
+        };
+        let end = start + end + "</w:p>".len();
+        ranges.push((start, end));
+        offset = end;
+    }
+
+    ranges
+}
+
+/// Text node of a paragraph: the byte range of the opening tag and of the escaped text.
+struct TextNode {
+    tag: (usize, usize),
+    content: (usize, usize),
+    text: String,
+}
+
+fn text_nodes(paragraph: &str) -> Vec<TextNode> {
+    let mut nodes = Vec::new();
+    let mut offset = 0;
+
+    while let Some(found) = paragraph[offset..].find("<w:t") {
+        let start = offset + found;
+        let Some(tag_end) = paragraph[start..].find('>').map(|end| start + end + 1) else {
+            break;
+        };
+        let after = paragraph[start + 4..].chars().next();
+        if !matches!(after, Some('>') | Some(' ')) || paragraph[..tag_end].ends_with("/>") {
+            offset = tag_end;
+            continue;
+        }
+        let Some(end) = paragraph[tag_end..].find("</w:t>").map(|end| tag_end + end) else {
+            break;
+        };
+        nodes.push(TextNode {
+            tag: (start, tag_end),
+            content: (tag_end, end),
+            text: unescape(&paragraph[tag_end..end]),
+        });
+        offset = end;
+    }
+
+    nodes
+}
+
+/// Replaces the merge fields of a paragraph. Word often splits a field between several runs,
+/// so fields are found in the text of the whole paragraph and the replacement goes into the
+/// run where the field starts.
+fn merge_paragraph(paragraph: &str, fields: &[(String, String)]) -> Option<String> {
+    let nodes = text_nodes(paragraph);
+    let text: String = nodes.iter().map(|node| node.text.as_str()).collect();
+    if !text.contains("{{") {
+        return None;
+    }
+
+    // Byte spans of the known fields in the paragraph text with their values.
+    let mut replacements = Vec::new();
+    let mut offset = 0;
+    while let Some(start) = text[offset..].find("{{").map(|start| offset + start) {
+        let Some(end) = text[start + 2..].find("}}").map(|end| start + 2 + end + 2) else {
+            break;
+        };
+        let key = text[start + 2..end - 2].trim();
+        match fields.iter().find(|(field, _)| field == key) {
+            Some((_, value)) => {
+                replacements.push((start, end, value.as_str()));
+                offset = end;
+            }
+            None => offset = start + 2,
+        }
+    }
+    if replacements.is_empty() {
+        return None;
+    }
+
+    let mut texts: Vec<String> = vec![String::new(); nodes.len()];
+    let mut node = 0;
+    let mut node_end = nodes[0].text.len();
+    let mut replacement = replacements.iter().peekable();
+    for (position, ch) in text.char_indices() {
+        while position >= node_end && node + 1 < nodes.len() {
+            node += 1;
+            node_end += nodes[node].text.len();
+        }
+        if let Some((start, end, value)) = replacement.peek() {
+            if position == *start {
+                texts[node].push_str(value);
+            }
+            if position >= *start && position < *end {
+                if position + ch.len_utf8() == *end {
+                    replacement.next();
+                }
+                continue;
+            }
+        }
+        texts[node].push(ch);
+    }
+
+    let mut merged = paragraph.to_string();
+    for (node, text) in nodes.iter().zip(texts).rev() {
+        merged.replace_range(node.content.0..node.content.1, &escape(&text));
+        merged.replace_range(node.tag.0..node.tag.1, r#"<w:t xml:space="preserve">"#);
+    }
+    Some(merged)
+}
+
+/// Fills a template part: the paragraph holding only `{{content}}` becomes the body of the
+/// document and the merge fields of the other paragraphs are replaced. Unknown fields are left
+/// in place so the gap is visible.
+fn merge_template(xml: &str, export: &DocumentExport) -> String {
+    let fields = merge_fields(export);
+    let mut merged = xml.to_string();
+
+    for (start, end) in paragraphs(xml).into_iter().rev() {
+        let paragraph = &xml[start..end];
+        let text: String = text_nodes(paragraph)
+            .iter()
+            .map(|node| node.text.as_str())
+            .collect();
+
+        if text.trim() == CONTENT_FIELD {
+            merged.replace_range(start..end, &body(export));
+        } else if let Some(paragraph) = merge_paragraph(paragraph, &fields) {
+            merged.replace_range(start..end, &paragraph);
+        }
+    }
+
+    merged
+}
+
+fn write_part<W: Write + std::io::Seek>(
+    writer: &mut ZipWriter<W>,
+    name: &str,
+    content: &str,
+) -> Result<(), RenderError> {
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+    writer.start_file(name, options).map_err(docx_error)?;
+    writer.write_all(content.as_bytes()).map_err(docx_error)
+}
+
+fn render_from_template(export: &DocumentExport, template: &[u8]) -> Result<Vec<u8>, RenderError> {
+    let mut archive = ZipArchive::new(Cursor::new(template)).map_err(docx_error)?;
+    let mut writer = ZipWriter::new(Cursor::new(Vec::new()));
+    let mut found = false;
+
+    for index in 0..archive.len() {
+        let mut file = archive.by_index(index).map_err(docx_error)?;
+        if file.name() != DOCUMENT_PART {
+            writer.raw_copy_file(file).map_err(docx_error)?;
+            continue;
+        }
+
+        let mut xml = String::new();
+        file.read_to_string(&mut xml).map_err(docx_error)?;
+        write_part(&mut writer, DOCUMENT_PART, &merge_template(&xml, export))?;
+        found = true;
+    }
+
+    if !found {
+        return Err(RenderError::Docx(format!(
+            "The template has no {DOCUMENT_PART} part"
+        )));
+    }
+
+    Ok(writer.finish().map_err(docx_error)?.into_inner())
+}
+
+fn render_document(export: &DocumentExport) -> Result<Vec<u8>, RenderError> {
+    let document = format!(
+        r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<w:document xmlns:w="http://schemas.openxmlformats.org/wordprocessingml/2006/main"><w:body>{}{SECTION}</w:body></w:document>"#,
+        body(export)
+    );
+
+    let mut writer = ZipWriter::new(Cursor::new(Vec::new()));
+    write_part(&mut writer, "[Content_Types].xml", CONTENT_TYPES)?;
+    write_part(&mut writer, "_rels/.rels", PACKAGE_RELATIONSHIPS)?;
+    write_part(&mut writer, DOCUMENT_PART, &document)?;
+    write_part(
+        &mut writer,
+        "word/_rels/document.xml.rels",
+        DOCUMENT_RELATIONSHIPS,
+    )?;
+    write_part(&mut writer, "word/styles.xml", STYLES)?;
+
+    Ok(writer.finish().map_err(docx_error)?.into_inner())
+}
+
+/// Renders the exported document as an editable DOCX. With a `template` (the content of a
+/// .docx file) its merge fields such as `{{developer.full_name}}` are filled in and a paragraph
+/// holding only `{{content}}` is replaced with the document body; the styles, headers and
+/// footers of the template are kept.
+pub fn render_docx(
+    export: &DocumentExport,
+    template: Option<&[u8]>,
+) -> Result<Vec<u8>, RenderError> {
+    match template {
+        Some(template) => render_from_template(export, template),
+        None => render_document(export),
+    }
+}
+
+/// Renders the exported document as a DOCX and writes it to `path`, optionally from the
+/// template at `template`.
+pub fn write_docx(
+    export: &DocumentExport,
+    template: Option<&Path>,
+    path: &Path,
+) -> Result<(), RenderError> {
+    let template = template
+        .map(std::fs::read)
+        .transpose()
+        .map_err(|error| RenderError::Io(error.to_string()))?;
+    let content = render_docx(export, template.as_deref())?;
+    std::fs::write(path, content).map_err(|error| RenderError::Io(error.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::content::tests::export;
+
+    fn part(docx: &[u8], name: &str) -> String {
+        let mut archive = ZipArchive::new(Cursor::new(docx)).expect("zip");
+        let mut file = archive.by_name(name).expect("part");
+        let mut content = String::new();
+        file.read_to_string(&mut content).expect("read");
+        content
+    }
+
+    #[test]
+    fn docx_contains_styled_headings_and_indicators_table() {
+        let docx = render_docx(&export(), None).expect("docx");
+
+        let document = part(&docx, DOCUMENT_PART);
+        assert!(document.contains(r#"<w:pStyle w:val="Heading1"/>"#));
+        assert!(document.contains("Технико-экономические показатели"));
+        assert!(document.contains("<w:tblHeader/>"));
+        assert!(document.contains("Общая площадь"));
+        assert!(document.contains("ООО &quot;Заказчик&quot;"));
+        assert!(part(&docx, "word/styles.xml").contains(r#"w:styleId="Heading1""#));
+        assert!(part(&docx, "[Content_Types].xml").contains("/word/document.xml"));
+    }
+
+    #[test]
+    fn template_fields_are_merged_across_runs() {
+        let template_xml = r#"<?xml version="1.0" encoding="UTF-8"?><w:document xmlns:w="http://schemas.openxmlformats.org/wordprocessingml/2006/main"><w:body><w:p w:rsidR="00A1"/><w:p><w:r><w:t>Застройщик: {{devel</w:t></w:r><w:r><w:rPr><w:b/></w:rPr><w:t>oper.full_name}}, </w:t></w:r><w:r><w:t xml:space="preserve">{{unknown}} {{ object.name }}</w:t></w:r></w:p><w:p><w:r><w:t>{{content}}</w:t></w:r></w:p><w:sectPr/></w:body></w:document>"#;
+        let mut writer = ZipWriter::new(Cursor::new(Vec::new()));
+        write_part(&mut writer, DOCUMENT_PART, template_xml).expect("document");
+        write_part(&mut writer, "word/footer1.xml", "<w:ftr/>").expect("footer");
+        let template = writer.finish().expect("zip").into_inner();
+
+        let docx = render_docx(&export(), Some(&template)).expect("docx");
+
+        let document = part(&docx, DOCUMENT_PART);
+        assert!(
+            document.contains(
+                r#"<w:t xml:space="preserve">Застройщик: ООО &quot;Заказчик&quot;</w:t>"#
+            )
+        );
+        assert!(document.contains(r#"<w:t xml:space="preserve">, </w:t>"#));
+        assert!(document.contains("{{unknown}} Школа на 550 мест"));
+        assert!(!document.contains("{{content}}"));
+        assert!(document.contains("ЗАДАНИЕ НА ПРОЕКТИРОВАНИЕ"));
+        assert!(document.ends_with("<w:sectPr/></w:body></w:document>"));
+        assert_eq!(part(&docx, "word/footer1.xml"), "<w:ftr/>");
+
+        assert!(matches!(
+            render_docx(&export(), Some(b"not a zip")),
+            Err(RenderError::Docx(_))
+        ));
+    }
+}
@@ -0,0 +1,87 @@
+//! Human-readable renderings of a ZNP: a PDF for printing and wet signatures and an editable
+//! DOCX. The layout is built from [`DocumentExport`], so an issued document is rendered from its
+//! snapshot.
+
+use std::path::Path;
+
+use zdnp_core::{
+    ConstructionObjectRepository, DocumentError, DocumentExport, DocumentRepository,
+    DocumentSourceRepository, ReferenceRepository, RequirementRepository, SignatoryRepository,
+    TechnicalIndicatorRepository,
+};
+
+mod content;
+mod docx;
+mod pdf;
+
+pub use content::MERGE_FIELDS;
+pub use docx::*;
+pub use pdf::*;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RenderError {
+    Document(DocumentError),
+    Pdf(String),
+    Docx(String),
+    Io(String),
+}
+
+impl std::fmt::Display for RenderError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Document(error) => write!(f, "{error}"),
+            Self::Pdf(message) => write!(f, "PDF error: {message}"),
+            Self::Docx(message) => write!(f, "DOCX error: {message}"),
+            Self::Io(message) => write!(f, "I/O error: {message}"),
+        }
+    }
+}
+
+impl std::error::Error for RenderError {}
+
+impl From<DocumentError> for RenderError {
+    fn from(error: DocumentError) -> Self {
+        Self::Document(error)
+    }
+}
+
+/// Exports the document and writes its PDF rendering to `path`.
+pub fn render_document_pdf<D, R>(
+    documents: &D,
+    references: &R,
+    id: i64,
+    path: &Path,
+) -> Result<(), RenderError>
+where
+    D: DocumentRepository
+        + ConstructionObjectRepository
+        + TechnicalIndicatorRepository
+        + RequirementRepository
+        + DocumentSourceRepository
+        + SignatoryRepository,
+    R: ReferenceRepository,
+{
+    let export: DocumentExport = zdnp_core::export_document(documents, references, id)?;
+    write_pdf(&export, path)
+}
+
+/// Exports the document and writes it to `path` as a DOCX, optionally from a template.
+pub fn render_document_docx<D, R>(
+    documents: &D,
+    references: &R,
+    id: i64,
+    template: Option<&Path>,
+    path: &Path,
+) -> Result<(), RenderError>
+where
+    D: DocumentRepository
+        + ConstructionObjectRepository
+        + TechnicalIndicatorRepository
+        + RequirementRepository
+        + DocumentSourceRepository
+        + SignatoryRepository,
+    R: ReferenceRepository,
+{
+    let export = zdnp_core::export_document(documents, references, id)?;
+    write_docx(&export, template, path)
+}
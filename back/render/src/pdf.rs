@@ -0,0 +1,441 @@
+use std::path::Path;
+
+use printpdf::{
+    IndirectFontRef, Line, Mm, PdfDocument, PdfDocumentReference, PdfLayerIndex, PdfLayerReference,
+    PdfPageIndex, Point,
+};
+use zdnp_core::DocumentExport;
+
+use crate::RenderError;
+use crate::content::{Block, Table, document_blocks};
+
+// ---------------- PDF Rendering ----------------
+
+/// Fonts embedded into every PDF, so the output does not depend on the fonts of the machine.
+const REGULAR_FONT: &[u8] = include_bytes!("../fonts/DejaVuSerif.ttf");
+const BOLD_FONT: &[u8] = include_bytes!("../fonts/DejaVuSerif-Bold.ttf");
+
+/// A4 portrait with the margins of GOST R 7.0.97: 30 mm left, 15 mm right, 20 mm top and bottom.
+const PAGE_WIDTH: f32 = 210.0;
+const PAGE_HEIGHT: f32 = 297.0;
+const MARGIN_LEFT: f32 = 30.0;
+const MARGIN_RIGHT: f32 = 15.0;
+const MARGIN_TOP: f32 = 20.0;
+const MARGIN_BOTTOM: f32 = 20.0;
+const TEXT_WIDTH: f32 = PAGE_WIDTH - MARGIN_LEFT - MARGIN_RIGHT;
+
+const TEXT_SIZE: f32 = 11.0;
+const SMALL_SIZE: f32 = 9.0;
+/// Width of the label column of a field.
+const LABEL_WIDTH: f32 = 55.0;
+const CELL_PADDING: f32 = 1.5;
+
+/// Millimetres in a typographic point.
+const POINT: f32 = 25.4 / 72.0;
+
+fn pdf_error<E: std::fmt::Display>(error: E) -> RenderError {
+    RenderError::Pdf(error.to_string())
+}
+
+fn line_height(size: f32) -> f32 {
+    size * POINT * 1.35
+}
+
+struct Font {
+    face: ttf_parser::Face<'static>,
+    reference: IndirectFontRef,
+}
+
+impl Font {
+    fn load(document: &PdfDocumentReference, data: &'static [u8]) -> Result<Self, RenderError> {
+        Ok(Self {
+            face: ttf_parser::Face::parse(data, 0).map_err(pdf_error)?,
+            reference: document.add_external_font(data).map_err(pdf_error)?,
+        })
+    }
+
+    /// Width of the text set in the font, in millimetres.
+    fn width(&self, text: &str, size: f32) -> f32 {
+        let units: u32 = text
+            .chars()
+            .filter_map(|ch| self.face.glyph_index(ch))
+            .filter_map(|glyph| self.face.glyph_hor_advance(glyph))
+            .map(u32::from)
+            .sum();
+        units as f32 / f32::from(self.face.units_per_em()) * size * POINT
+    }
+
+    /// Breaks the text into lines that fit into `width`. Words longer than a line are split.
+    fn wrap(&self, text: &str, size: f32, width: f32) -> Vec<String> {
+        let mut lines = Vec::new();
+        let mut line = String::new();
+
+        for word in text.split_whitespace() {
+            let candidate = if line.is_empty() {
+                word.to_string()
+            } else {
+                format!("{line} {word}")
+            };
+            if self.width(&candidate, size) <= width {
+                line = candidate;
+                continue;
+            }
+
+            if !line.is_empty() {
+                lines.push(std::mem::take(&mut line));
+            }
+            for ch in word.chars() {
+                line.push(ch);
+                if self.width(&line, size) > width && line.chars().count() > 1 {
+                    line.pop();
+                    lines.push(std::mem::replace(&mut line, ch.to_string()));
+                }
+            }
+        }
+
+        if !line.is_empty() || lines.is_empty() {
+            lines.push(line);
+        }
+        lines
+    }
+}
+
+/// Places the blocks on pages top to bottom, starting a new page when the next piece does not
+/// fit.
+struct Writer {
+    document: PdfDocumentReference,
+    pages: Vec<(PdfPageIndex, PdfLayerIndex)>,
+    layer: PdfLayerReference,
+    /// Distance of the next line from the bottom edge of the page, in millimetres.
+    y: f32,
+    regular: Font,
+    bold: Font,
+}
+
+impl Writer {
+    fn new(title: &str) -> Result<Self, RenderError> {
+        let (document, page, layer) =
+            PdfDocument::new(title, Mm(PAGE_WIDTH), Mm(PAGE_HEIGHT), "content");
+        let regular = Font::load(&document, REGULAR_FONT)?;
+        let bold = Font::load(&document, BOLD_FONT)?;
+        let layer_reference = document.get_page(page).get_layer(layer);
+        layer_reference.set_outline_thickness(0.5);
+
+        Ok(Self {
+            document,
+            pages: vec![(page, layer)],
+            layer: layer_reference,
+            y: PAGE_HEIGHT - MARGIN_TOP,
+            regular,
+            bold,
+        })
+    }
+
+    fn font(&self, bold: bool) -> &Font {
+        if bold { &self.bold } else { &self.regular }
+    }
+
+    fn new_page(&mut self) {
+        let (page, layer) = self
+            .document
+            .add_page(Mm(PAGE_WIDTH), Mm(PAGE_HEIGHT), "content");
+        self.layer = self.document.get_page(page).get_layer(layer);
+        self.layer.set_outline_thickness(0.5);
+        self.pages.push((page, layer));
+        self.y = PAGE_HEIGHT - MARGIN_TOP;
+    }
+
+    /// Starts a new page unless `height` millimetres still fit on the current one.
+    fn ensure(&mut self, height: f32) -> bool {
+        if self.y - height < MARGIN_BOTTOM && self.y < PAGE_HEIGHT - MARGIN_TOP {
+            self.new_page();
+            return true;
+        }
+        false
+    }
+
+    fn skip(&mut self, height: f32) {
+        self.y -= height;
+    }
+
+    fn put(&self, text: &str, size: f32, bold: bool, x: f32, top: f32) {
+        let font = self.font(bold);
+        self.layer
+            .use_text(text, size, Mm(x), Mm(top - size * POINT), &font.reference);
+    }
+
+    fn rule(&self, x1: f32, y1: f32, x2: f32, y2: f32) {
+        self.layer.add_line(Line {
+            points: vec![
+                (Point::new(Mm(x1), Mm(y1)), false),
+                (Point::new(Mm(x2), Mm(y2)), false),
+            ],
+            is_closed: false,
+        });
+    }
+
+    /// Writes wrapped text line by line; `centered` lines are centred on the text width.
+    fn text(&mut self, text: &str, size: f32, bold: bool, indent: f32, centered: bool) {
+        let lines = self.font(bold).wrap(text, size, TEXT_WIDTH - indent);
+        for line in lines {
+            self.ensure(line_height(size));
+            let x = if centered {
+                MARGIN_LEFT + (TEXT_WIDTH - self.font(bold).width(&line, size)) / 2.0
+            } else {
+                MARGIN_LEFT + indent
+            };
+            self.put(&line, size, bold, x, self.y);
+            self.skip(line_height(size));
+        }
+    }
+
+    fn field(&mut self, label: &str, value: &str) {
+        let labels = self.bold.wrap(label, TEXT_SIZE, LABEL_WIDTH - CELL_PADDING);
+        let values = self
+            .regular
+            .wrap(value, TEXT_SIZE, TEXT_WIDTH - LABEL_WIDTH);
+        let height = labels.len().max(values.len()) as f32 * line_height(TEXT_SIZE);
+        self.ensure(height);
+
+        for (index, line) in labels.iter().enumerate() {
+            let top = self.y - index as f32 * line_height(TEXT_SIZE);
+            self.put(line, TEXT_SIZE, true, MARGIN_LEFT, top);
+        }
+        for (index, line) in values.iter().enumerate() {
+            let top = self.y - index as f32 * line_height(TEXT_SIZE);
+            self.put(line, TEXT_SIZE, false, MARGIN_LEFT + LABEL_WIDTH, top);
+        }
+        self.skip(height);
+    }
+
+    fn table_row(&mut self, widths: &[f32], cells: &[String], bold: bool) -> f32 {
+        let wrapped: Vec<Vec<String>> = cells
+            .iter()
+            .zip(widths)
+            .map(|(cell, width)| {
+                self.font(bold)
+                    .wrap(cell, SMALL_SIZE, width - 2.0 * CELL_PADDING)
+            })
+            .collect();
+        let lines = wrapped.iter().map(Vec::len).max().unwrap_or(1);
+        let height = lines as f32 * line_height(SMALL_SIZE) + 2.0 * CELL_PADDING;
+
+        let mut x = MARGIN_LEFT;
+        for (cell, width) in wrapped.iter().zip(widths) {
+            for (index, line) in cell.iter().enumerate() {
+                let top = self.y - CELL_PADDING - index as f32 * line_height(SMALL_SIZE);
+                self.put(line, SMALL_SIZE, bold, x + CELL_PADDING, top);
+            }
+            self.rule(x, self.y, x, self.y - height);
+            x += width;
+        }
+        self.rule(x, self.y, x, self.y - height);
+        self.rule(MARGIN_LEFT, self.y, x, self.y);
+        self.rule(MARGIN_LEFT, self.y - height, x, self.y - height);
+
+        height
+    }
+
+    /// Draws a bordered table; the header row is repeated on every page the table spans.
+    fn table(&mut self, table: &Table) {
+        let widths: Vec<f32> = table
+            .columns
+            .iter()
+            .map(|(_, share)| share * TEXT_WIDTH)
+            .collect();
+        let header: Vec<String> = table
+            .columns
+            .iter()
+            .map(|(title, _)| title.to_string())
+            .collect();
+        let row_height = |writer: &Self, cells: &[String], bold: bool| {
+            let lines = cells
+                .iter()
+                .zip(&widths)
+                .map(|(cell, width)| {
+                    writer
+                        .font(bold)
+                        .wrap(cell, SMALL_SIZE, width - 2.0 * CELL_PADDING)
+                        .len()
+                })
+                .max()
+                .unwrap_or(1);
+            lines as f32 * line_height(SMALL_SIZE) + 2.0 * CELL_PADDING
+        };
+
+        let first = table
+            .rows
+            .first()
+            .map_or(0.0, |row| row_height(self, row, false));
+        self.ensure(row_height(self, &header, true) + first);
+        let height = self.table_row(&widths, &header, true);
+        self.skip(height);
+
+        for row in &table.rows {
+            if self.ensure(row_height(self, row, false)) {
+                let height = self.table_row(&widths, &header, true);
+                self.skip(height);
+            }
+            let height = self.table_row(&widths, row, false);
+            self.skip(height);
+        }
+    }
+
+    /// Writes the role caption and the position, then the signature line with the name and the
+    /// date, leaving a blank for a date that is not known yet.
+    fn signature(&mut self, role: &str, position: &str, name: &str, date: Option<&str>) {
+        let positions = match position {
+            "" => Vec::new(),
+            position => self.regular.wrap(position, TEXT_SIZE, TEXT_WIDTH),
+        };
+        let height = (positions.len() + 2) as f32 * line_height(TEXT_SIZE) + 6.0;
+        self.ensure(height);
+        self.skip(6.0);
+
+        self.put(role, TEXT_SIZE, true, MARGIN_LEFT, self.y);
+        self.skip(line_height(TEXT_SIZE));
+        for line in &positions {
+            self.put(line, TEXT_SIZE, false, MARGIN_LEFT, self.y);
+            self.skip(line_height(TEXT_SIZE));
+        }
+
+        let baseline = self.y - TEXT_SIZE * POINT;
+        self.rule(MARGIN_LEFT, baseline, MARGIN_LEFT + 40.0, baseline);
+        let date = date.unwrap_or("«___» __________ 20___ г.");
+        let date_width = self.regular.width(date, TEXT_SIZE);
+        let name = self
+            .regular
+            .wrap(name, TEXT_SIZE, TEXT_WIDTH - 50.0 - date_width)
+            .into_iter()
+            .next()
+            .unwrap_or_default();
+        self.put(&name, TEXT_SIZE, false, MARGIN_LEFT + 45.0, self.y);
+        self.put(
+            date,
+            TEXT_SIZE,
+            false,
+            MARGIN_LEFT + TEXT_WIDTH - date_width,
+            self.y,
+        );
+        self.skip(line_height(TEXT_SIZE));
+    }
+
+    fn block(&mut self, block: &Block) {
+        match block {
+            Block::Title(text) => self.text(text, 14.0, true, 0.0, true),
+            Block::Subtitle(text) => {
+                self.text(text, 12.0, true, 0.0, true);
+                self.skip(4.0);
+            }
+            Block::Heading(text) => {
+                self.skip(4.0);
+                // Keeps the heading together with the first line of its section.
+                self.ensure(line_height(12.0) + 2.0 * line_height(TEXT_SIZE));
+                self.text(text, 12.0, true, 0.0, false);
+                self.skip(1.0);
+            }
+            Block::Subheading(text) => {
+                self.skip(2.0);
+                self.ensure(2.0 * line_height(TEXT_SIZE));
+                self.text(text, TEXT_SIZE, true, 0.0, false);
+            }
+            Block::Field { label, value } => self.field(label, value),
+            Block::Paragraph(text) => self.text(text, TEXT_SIZE, false, 0.0, false),
+            Block::Table(table) => self.table(table),
+            Block::Signature {
+                role,
+                position,
+                name,
+                date,
+            } => self.signature(role, position, name, date.as_deref()),
+        }
+    }
+
+    /// Numbers the pages and serializes the document.
+    fn finish(self) -> Result<Vec<u8>, RenderError> {
+        let count = self.pages.len();
+        for (index, (page, layer)) in self.pages.iter().enumerate() {
+            let label = format!("{} из {count}", index + 1);
+            let x = PAGE_WIDTH - MARGIN_RIGHT - self.regular.width(&label, SMALL_SIZE);
+            self.document.get_page(*page).get_layer(*layer).use_text(
+                label,
+                SMALL_SIZE,
+                Mm(x),
+                Mm(10.0),
+                &self.regular.reference,
+            );
+        }
+
+        self.document.save_to_bytes().map_err(pdf_error)
+    }
+}
+
+/// Renders the exported document as a PDF with embedded Cyrillic fonts.
+pub fn render_pdf(export: &DocumentExport) -> Result<Vec<u8>, RenderError> {
+    let mut writer = Writer::new(&export.document.name)?;
+    for block in document_blocks(export) {
+        writer.block(&block);
+    }
+    writer.finish()
+}
+
+/// Renders the exported document as a PDF and writes it to `path`.
+pub fn write_pdf(export: &DocumentExport, path: &Path) -> Result<(), RenderError> {
+    let content = render_pdf(export)?;
+    std::fs::write(path, content).map_err(|error| RenderError::Io(error.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::content::tests::export;
+    use zdnp_core::TechnicalIndicator;
+
+    #[test]
+    fn long_words_and_lines_are_wrapped_to_the_width() {
+        let (document, _, _) = PdfDocument::new("test", Mm(PAGE_WIDTH), Mm(PAGE_HEIGHT), "test");
+        let font = Font::load(&document, REGULAR_FONT).expect("font");
+
+        let text = "Требования к архитектурно-художественным решениям фасадов";
+        let lines = font.wrap(text, TEXT_SIZE, 70.0);
+        assert!(lines.len() > 1);
+        assert!(lines.iter().all(|line| font.width(line, TEXT_SIZE) <= 70.0));
+        assert_eq!(lines.join(" "), text);
+
+        let word = "ОченьДлинноеСловоБезПробелов".repeat(4);
+        let lines = font.wrap(&word, TEXT_SIZE, 40.0);
+        assert!(lines.iter().all(|line| font.width(line, TEXT_SIZE) <= 40.0));
+        assert_eq!(lines.concat(), word);
+
+        assert_eq!(font.wrap("", TEXT_SIZE, 40.0), vec![String::new()]);
+    }
+
+    #[test]
+    fn pdf_embeds_the_fonts_and_spans_pages() {
+        let mut export = export();
+        export.indicators = (1..=120)
+            .map(|position| TechnicalIndicator {
+                id: position,
+                document_id: 1,
+                position,
+                name: format!("Показатель {position}"),
+                value: position.to_string(),
+                unit_okei: "796".into(),
+            })
+            .collect();
+
+        let mut writer = Writer::new(&export.document.name).expect("writer");
+        for block in document_blocks(&export) {
+            writer.block(&block);
+        }
+        assert!(
+            writer.pages.len() > 1,
+            "120 indicators do not fit on a page"
+        );
+
+        let content = writer.finish().expect("pdf");
+        assert!(content.starts_with(b"%PDF"));
+        let text = String::from_utf8_lossy(&content);
+        assert_eq!(text.matches("/FontFile2").count(), 2);
+    }
+}
@@ -0,0 +1,1264 @@
+use std::sync::Arc;
+
+use axum::extract::{Path, Query, State};
+use axum::http::{StatusCode, header};
+use axum::response::IntoResponse;
+use axum::routing::{get, put};
+use axum::{Json, Router};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, OpenApi, ToSchema};
+use zdnp_core::{
+    Address, AddressDto, ConstructionObject, ConstructionObjectDto, Document, DocumentDto,
+    DocumentExport, Entrepreneur, EntrepreneurDto, ListOptions, Organization, OrganizationDto,
+    Person, PersonDto, RequirementCategory, RequirementSection, RequirementSectionDto,
+    RequirementTemplate, RequirementTemplateDto, Signatory, SignatoryDto, SourceDocument,
+    SourceDocumentDto, TechnicalIndicator, TechnicalIndicatorDto,
+};
+use zdnp_data::{
+    SqliteAddressRepository, SqliteDocumentRepository, SqliteEntrepreneurRepository,
+    SqliteOrganizationRepository, SqlitePersonRepository, SqliteReferenceRepository,
+    SqliteRequirementTemplateRepository, SqliteSourceDocumentRepository,
+};
+
+use crate::error::{ApiError, ErrorBody};
+
+// ---------------- REST API ----------------
+
+/// Shared state of the handlers: the database every request opens its repositories on.
+#[derive(Clone)]
+pub struct Api {
+    database: Arc<str>,
+}
+
+impl Api {
+    pub fn new(database: &str) -> Self {
+        Self {
+            database: Arc::from(database),
+        }
+    }
+
+    fn addresses(&self) -> SqliteAddressRepository {
+        SqliteAddressRepository::with_file_name(&*self.database)
+    }
+
+    fn organizations(&self) -> SqliteOrganizationRepository {
+        SqliteOrganizationRepository::with_file_name(&*self.database)
+    }
+
+    fn entrepreneurs(&self) -> SqliteEntrepreneurRepository {
+        SqliteEntrepreneurRepository::with_file_name(&*self.database)
+    }
+
+    fn persons(&self) -> SqlitePersonRepository {
+        SqlitePersonRepository::with_file_name(&*self.database)
+    }
+
+    fn source_documents(&self) -> SqliteSourceDocumentRepository {
+        SqliteSourceDocumentRepository::with_file_name(&*self.database)
+    }
+
+    fn documents(&self) -> SqliteDocumentRepository {
+        SqliteDocumentRepository::with_file_name(&*self.database)
+    }
+
+    fn references(&self) -> SqliteReferenceRepository {
+        SqliteReferenceRepository::with_file_name(&*self.database)
+    }
+
+    fn requirement_templates(&self) -> SqliteRequirementTemplateRepository {
+        SqliteRequirementTemplateRepository::with_file_name(&*self.database)
+    }
+}
+
+/// The repositories block on their own runtime, so the services run on the blocking pool.
+async fn blocking<T, F>(work: F) -> Result<T, ApiError>
+where
+    T: Send + 'static,
+    F: FnOnce() -> Result<T, ApiError> + Send + 'static,
+{
+    tokio::task::spawn_blocking(work)
+        .await
+        .map_err(ApiError::internal)?
+}
+
+#[derive(OpenApi)]
+#[openapi(
+    info(
+        title = "ZDNP API",
+        description = "Reference data and design assignment documents of the local database."
+    ),
+    paths(
+        list_addresses,
+        create_address,
+        update_address,
+        list_organizations,
+        create_organization,
+        update_organization,
+        list_entrepreneurs,
+        create_entrepreneur,
+        update_entrepreneur,
+        list_persons,
+        create_person,
+        update_person,
+        list_source_documents,
+        create_source_document,
+        update_source_document,
+        list_documents,
+        create_document,
+        get_document,
+        update_document,
+        export_document,
+        render_document_pdf,
+        get_construction_object,
+        save_construction_object,
+        list_indicators,
+        add_indicator,
+        update_indicator,
+        list_requirements,
+        add_requirement,
+        update_requirement,
+        list_signatories,
+        add_signatory,
+        update_signatory,
+        list_requirement_templates,
+        create_requirement_template,
+        update_requirement_template,
+    ),
+    tags(
+        (name = "reference data", description = "Addresses, parties and source documents"),
+        (name = "documents", description = "Documents and their sections"),
+        (name = "requirement templates", description = "Library of standard requirement texts"),
+    )
+)]
+pub struct ApiDoc;
+
+pub fn router(api: Api) -> Router {
+    Router::new()
+        .route("/api/openapi.json", get(openapi))
+        .route("/api/addresses", get(list_addresses).post(create_address))
+        .route("/api/addresses/{id}", put(update_address))
+        .route(
+            "/api/organizations",
+            get(list_organizations).post(create_organization),
+        )
+        .route("/api/organizations/{id}", put(update_organization))
+        .route(
+            "/api/entrepreneurs",
+            get(list_entrepreneurs).post(create_entrepreneur),
+        )
+        .route("/api/entrepreneurs/{id}", put(update_entrepreneur))
+        .route("/api/persons", get(list_persons).post(create_person))
+        .route("/api/persons/{id}", put(update_person))
+        .route(
+            "/api/source-documents",
+            get(list_source_documents).post(create_source_document),
+        )
+        .route("/api/source-documents/{id}", put(update_source_document))
+        .route("/api/documents", get(list_documents).post(create_document))
+        .route(
+            "/api/documents/{id}",
+            get(get_document).put(update_document),
+        )
+        .route("/api/documents/{id}/export", get(export_document))
+        .route("/api/documents/{id}/pdf", get(render_document_pdf))
+        .route(
+            "/api/documents/{id}/construction-object",
+            get(get_construction_object).put(save_construction_object),
+        )
+        .route(
+            "/api/documents/{id}/indicators",
+            get(list_indicators).post(add_indicator),
+        )
+        .route("/api/indicators/{id}", put(update_indicator))
+        .route(
+            "/api/documents/{id}/requirements",
+            get(list_requirements).post(add_requirement),
+        )
+        .route("/api/requirements/{id}", put(update_requirement))
+        .route(
+            "/api/documents/{id}/signatories",
+            get(list_signatories).post(add_signatory),
+        )
+        .route("/api/signatories/{id}", put(update_signatory))
+        .route(
+            "/api/requirement-templates",
+            get(list_requirement_templates).post(create_requirement_template),
+        )
+        .route(
+            "/api/requirement-templates/{id}",
+            put(update_requirement_template),
+        )
+        .with_state(api)
+}
+
+async fn openapi() -> Json<utoipa::openapi::OpenApi> {
+    Json(ApiDoc::openapi())
+}
+
+/// Identifier of a created row.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct Created {
+    pub id: i64,
+}
+
+fn created(id: i64) -> (StatusCode, Json<Created>) {
+    (StatusCode::CREATED, Json(Created { id }))
+}
+
+#[derive(Debug, Clone, Copy, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ListQuery {
+    /// Include archived rows.
+    #[serde(default)]
+    pub include_archived: bool,
+}
+
+impl ListQuery {
+    fn options(self) -> ListOptions {
+        ListOptions {
+            include_archived: self.include_archived,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct TemplateQuery {
+    /// Only the templates of this category.
+    pub category: Option<RequirementCategory>,
+}
+
+// ---------------- Reference Data ----------------
+
+#[utoipa::path(
+    get,
+    path = "/api/addresses",
+    tag = "reference data",
+    params(ListQuery),
+    responses((status = 200, body = Vec<Address>), (status = 500, body = ErrorBody))
+)]
+async fn list_addresses(
+    State(api): State<Api>,
+    Query(query): Query<ListQuery>,
+) -> Result<Json<Vec<Address>>, ApiError> {
+    blocking(move || {
+        Ok(zdnp_core::list_addresses(
+            &api.addresses(),
+            &query.options(),
+        )?)
+    })
+    .await
+    .map(Json)
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/addresses",
+    tag = "reference data",
+    request_body = AddressDto,
+    responses((status = 201, body = Created), (status = 422, body = ErrorBody))
+)]
+async fn create_address(
+    State(api): State<Api>,
+    Json(dto): Json<AddressDto>,
+) -> Result<impl IntoResponse, ApiError> {
+    blocking(move || Ok(zdnp_core::create_address(&api.addresses(), &dto)?))
+        .await
+        .map(created)
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/addresses/{id}",
+    tag = "reference data",
+    params(("id" = i64, Path)),
+    request_body = AddressDto,
+    responses(
+        (status = 204),
+        (status = 404, body = ErrorBody),
+        (status = 422, body = ErrorBody)
+    )
+)]
+async fn update_address(
+    State(api): State<Api>,
+    Path(id): Path<i64>,
+    Json(dto): Json<AddressDto>,
+) -> Result<StatusCode, ApiError> {
+    blocking(move || Ok(zdnp_core::update_address(&api.addresses(), id, &dto)?))
+        .await
+        .map(|()| StatusCode::NO_CONTENT)
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/organizations",
+    tag = "reference data",
+    params(ListQuery),
+    responses((status = 200, body = Vec<Organization>), (status = 500, body = ErrorBody))
+)]
+async fn list_organizations(
+    State(api): State<Api>,
+    Query(query): Query<ListQuery>,
+) -> Result<Json<Vec<Organization>>, ApiError> {
+    blocking(move || {
+        Ok(zdnp_core::list_organizations(
+            &api.organizations(),
+            &query.options(),
+        )?)
+    })
+    .await
+    .map(Json)
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/organizations",
+    tag = "reference data",
+    request_body = OrganizationDto,
+    responses((status = 201, body = Created), (status = 422, body = ErrorBody))
+)]
+async fn create_organization(
+    State(api): State<Api>,
+    Json(dto): Json<OrganizationDto>,
+) -> Result<impl IntoResponse, ApiError> {
+    blocking(move || Ok(zdnp_core::create_organization(&api.organizations(), &dto)?))
+        .await
+        .map(created)
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/organizations/{id}",
+    tag = "reference data",
+    params(("id" = i64, Path)),
+    request_body = OrganizationDto,
+    responses(
+        (status = 204),
+        (status = 404, body = ErrorBody),
+        (status = 422, body = ErrorBody)
+    )
+)]
+async fn update_organization(
+    State(api): State<Api>,
+    Path(id): Path<i64>,
+    Json(dto): Json<OrganizationDto>,
+) -> Result<StatusCode, ApiError> {
+    blocking(move || {
+        Ok(zdnp_core::update_organization(
+            &api.organizations(),
+            id,
+            &dto,
+        )?)
+    })
+    .await
+    .map(|()| StatusCode::NO_CONTENT)
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/entrepreneurs",
+    tag = "reference data",
+    params(ListQuery),
+    responses((status = 200, body = Vec<Entrepreneur>), (status = 500, body = ErrorBody))
+)]
+async fn list_entrepreneurs(
+    State(api): State<Api>,
+    Query(query): Query<ListQuery>,
+) -> Result<Json<Vec<Entrepreneur>>, ApiError> {
+    blocking(move || {
+        Ok(zdnp_core::list_entrepreneurs(
+            &api.entrepreneurs(),
+            &query.options(),
+        )?)
+    })
+    .await
+    .map(Json)
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/entrepreneurs",
+    tag = "reference data",
+    request_body = EntrepreneurDto,
+    responses((status = 201, body = Created), (status = 422, body = ErrorBody))
+)]
+async fn create_entrepreneur(
+    State(api): State<Api>,
+    Json(dto): Json<EntrepreneurDto>,
+) -> Result<impl IntoResponse, ApiError> {
+    blocking(move || Ok(zdnp_core::create_entrepreneur(&api.entrepreneurs(), &dto)?))
+        .await
+        .map(created)
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/entrepreneurs/{id}",
+    tag = "reference data",
+    params(("id" = i64, Path)),
+    request_body = EntrepreneurDto,
+    responses(
+        (status = 204),
+        (status = 404, body = ErrorBody),
+        (status = 422, body = ErrorBody)
+    )
+)]
+async fn update_entrepreneur(
+    State(api): State<Api>,
+    Path(id): Path<i64>,
+    Json(dto): Json<EntrepreneurDto>,
+) -> Result<StatusCode, ApiError> {
+    blocking(move || {
+        Ok(zdnp_core::update_entrepreneur(
+            &api.entrepreneurs(),
+            id,
+            &dto,
+        )?)
+    })
+    .await
+    .map(|()| StatusCode::NO_CONTENT)
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/persons",
+    tag = "reference data",
+    params(ListQuery),
+    responses((status = 200, body = Vec<Person>), (status = 500, body = ErrorBody))
+)]
+async fn list_persons(
+    State(api): State<Api>,
+    Query(query): Query<ListQuery>,
+) -> Result<Json<Vec<Person>>, ApiError> {
+    blocking(move || Ok(zdnp_core::list_persons(&api.persons(), &query.options())?))
+        .await
+        .map(Json)
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/persons",
+    tag = "reference data",
+    request_body = PersonDto,
+    responses((status = 201, body = Created), (status = 422, body = ErrorBody))
+)]
+async fn create_person(
+    State(api): State<Api>,
+    Json(dto): Json<PersonDto>,
+) -> Result<impl IntoResponse, ApiError> {
+    blocking(move || Ok(zdnp_core::create_person(&api.persons(), &dto)?))
+        .await
+        .map(created)
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/persons/{id}",
+    tag = "reference data",
+    params(("id" = i64, Path)),
+    request_body = PersonDto,
+    responses(
+        (status = 204),
+        (status = 404, body = ErrorBody),
+        (status = 422, body = ErrorBody)
+    )
+)]
+async fn update_person(
+    State(api): State<Api>,
+    Path(id): Path<i64>,
+    Json(dto): Json<PersonDto>,
+) -> Result<StatusCode, ApiError> {
+    blocking(move || Ok(zdnp_core::update_person(&api.persons(), id, &dto)?))
+        .await
+        .map(|()| StatusCode::NO_CONTENT)
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/source-documents",
+    tag = "reference data",
+    params(ListQuery),
+    responses((status = 200, body = Vec<SourceDocument>), (status = 500, body = ErrorBody))
+)]
+async fn list_source_documents(
+    State(api): State<Api>,
+    Query(query): Query<ListQuery>,
+) -> Result<Json<Vec<SourceDocument>>, ApiError> {
+    blocking(move || {
+        Ok(zdnp_core::list_source_documents(
+            &api.source_documents(),
+            &query.options(),
+        )?)
+    })
+    .await
+    .map(Json)
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/source-documents",
+    tag = "reference data",
+    request_body = SourceDocumentDto,
+    responses((status = 201, body = Created), (status = 422, body = ErrorBody))
+)]
+async fn create_source_document(
+    State(api): State<Api>,
+    Json(dto): Json<SourceDocumentDto>,
+) -> Result<impl IntoResponse, ApiError> {
+    blocking(move || {
+        Ok(zdnp_core::create_source_document(
+            &api.source_documents(),
+            &api.references(),
+            &dto,
+        )?)
+    })
+    .await
+    .map(created)
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/source-documents/{id}",
+    tag = "reference data",
+    params(("id" = i64, Path)),
+    request_body = SourceDocumentDto,
+    responses(
+        (status = 204),
+        (status = 404, body = ErrorBody),
+        (status = 422, body = ErrorBody)
+    )
+)]
+async fn update_source_document(
+    State(api): State<Api>,
+    Path(id): Path<i64>,
+    Json(dto): Json<SourceDocumentDto>,
+) -> Result<StatusCode, ApiError> {
+    blocking(move || {
+        Ok(zdnp_core::update_source_document(
+            &api.source_documents(),
+            &api.references(),
+            id,
+            &dto,
+        )?)
+    })
+    .await
+    .map(|()| StatusCode::NO_CONTENT)
+}
+
+// ---------------- Documents ----------------
+
+#[utoipa::path(
+    get,
+    path = "/api/documents",
+    tag = "documents",
+    responses((status = 200, body = Vec<Document>), (status = 500, body = ErrorBody))
+)]
+async fn list_documents(State(api): State<Api>) -> Result<Json<Vec<Document>>, ApiError> {
+    blocking(move || Ok(zdnp_core::list_documents(&api.documents())?))
+        .await
+        .map(Json)
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/documents",
+    tag = "documents",
+    request_body = DocumentDto,
+    responses((status = 201, body = Created), (status = 422, body = ErrorBody))
+)]
+async fn create_document(
+    State(api): State<Api>,
+    Json(dto): Json<DocumentDto>,
+) -> Result<impl IntoResponse, ApiError> {
+    blocking(move || {
+        Ok(zdnp_core::create_document(
+            &api.documents(),
+            &api.references(),
+            &dto,
+        )?)
+    })
+    .await
+    .map(created)
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/documents/{id}",
+    tag = "documents",
+    params(("id" = i64, Path)),
+    responses((status = 200, body = Document), (status = 404, body = ErrorBody))
+)]
+async fn get_document(
+    State(api): State<Api>,
+    Path(id): Path<i64>,
+) -> Result<Json<Document>, ApiError> {
+    blocking(move || Ok(zdnp_core::find_document(&api.documents(), id)?))
+        .await
+        .map(Json)
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/documents/{id}",
+    tag = "documents",
+    params(("id" = i64, Path)),
+    request_body = DocumentDto,
+    responses(
+        (status = 204),
+        (status = 404, body = ErrorBody),
+        (status = 409, description = "The document is no longer a draft", body = ErrorBody),
+        (status = 422, body = ErrorBody)
+    )
+)]
+async fn update_document(
+    State(api): State<Api>,
+    Path(id): Path<i64>,
+    Json(dto): Json<DocumentDto>,
+) -> Result<StatusCode, ApiError> {
+    blocking(move || {
+        Ok(zdnp_core::update_document(
+            &api.documents(),
+            &api.references(),
+            id,
+            &dto,
+        )?)
+    })
+    .await
+    .map(|()| StatusCode::NO_CONTENT)
+}
+
+/// The document with its sections and referenced entities; issued documents are exported from
+/// their snapshot.
+#[utoipa::path(
+    get,
+    path = "/api/documents/{id}/export",
+    tag = "documents",
+    params(("id" = i64, Path)),
+    responses((status = 200, body = DocumentExport), (status = 404, body = ErrorBody))
+)]
+async fn export_document(
+    State(api): State<Api>,
+    Path(id): Path<i64>,
+) -> Result<Json<DocumentExport>, ApiError> {
+    blocking(move || {
+        Ok(zdnp_core::export_document(
+            &api.documents(),
+            &api.references(),
+            id,
+        )?)
+    })
+    .await
+    .map(Json)
+}
+
+/// The document rendered as a PDF for printing.
+#[utoipa::path(
+    get,
+    path = "/api/documents/{id}/pdf",
+    tag = "documents",
+    params(("id" = i64, Path)),
+    responses(
+        (status = 200, content_type = "application/pdf", description = "PDF file"),
+        (status = 404, body = ErrorBody)
+    )
+)]
+async fn render_document_pdf(
+    State(api): State<Api>,
+    Path(id): Path<i64>,
+) -> Result<impl IntoResponse, ApiError> {
+    let pdf = blocking(move || {
+        let path = std::env::temp_dir().join(format!("zdnp-{}.pdf", zdnp_core::generate_uid()));
+        let rendered =
+            zdnp_render::render_document_pdf(&api.documents(), &api.references(), id, &path);
+        let pdf = rendered
+            .map_err(ApiError::from)
+            .and_then(|()| std::fs::read(&path).map_err(ApiError::internal));
+        let _ = std::fs::remove_file(&path);
+        pdf
+    })
+    .await?;
+
+    Ok(([(header::CONTENT_TYPE, "application/pdf")], pdf))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/documents/{id}/construction-object",
+    tag = "documents",
+    params(("id" = i64, Path)),
+    responses((status = 200, body = ConstructionObject), (status = 404, body = ErrorBody))
+)]
+async fn get_construction_object(
+    State(api): State<Api>,
+    Path(id): Path<i64>,
+) -> Result<Json<ConstructionObject>, ApiError> {
+    blocking(move || {
+        zdnp_core::find_construction_object(&api.documents(), id)?
+            .ok_or_else(|| ApiError::not_found(format!("Document {id} has no construction object")))
+    })
+    .await
+    .map(Json)
+}
+
+/// Creates or replaces the construction object of the document.
+#[utoipa::path(
+    put,
+    path = "/api/documents/{id}/construction-object",
+    tag = "documents",
+    params(("id" = i64, Path)),
+    request_body = ConstructionObjectDto,
+    responses(
+        (status = 200, body = Created),
+        (status = 409, body = ErrorBody),
+        (status = 422, body = ErrorBody)
+    )
+)]
+async fn save_construction_object(
+    State(api): State<Api>,
+    Path(id): Path<i64>,
+    Json(dto): Json<ConstructionObjectDto>,
+) -> Result<Json<Created>, ApiError> {
+    blocking(move || {
+        Ok(zdnp_core::save_construction_object(
+            &api.documents(),
+            &api.references(),
+            id,
+            &dto,
+        )?)
+    })
+    .await
+    .map(|id| Json(Created { id }))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/documents/{id}/indicators",
+    tag = "documents",
+    params(("id" = i64, Path)),
+    responses((status = 200, body = Vec<TechnicalIndicator>), (status = 500, body = ErrorBody))
+)]
+async fn list_indicators(
+    State(api): State<Api>,
+    Path(id): Path<i64>,
+) -> Result<Json<Vec<TechnicalIndicator>>, ApiError> {
+    blocking(move || Ok(zdnp_core::list_indicators(&api.documents(), id)?))
+        .await
+        .map(Json)
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/documents/{id}/indicators",
+    tag = "documents",
+    params(("id" = i64, Path)),
+    request_body = TechnicalIndicatorDto,
+    responses(
+        (status = 201, body = Created),
+        (status = 409, body = ErrorBody),
+        (status = 422, body = ErrorBody)
+    )
+)]
+async fn add_indicator(
+    State(api): State<Api>,
+    Path(id): Path<i64>,
+    Json(dto): Json<TechnicalIndicatorDto>,
+) -> Result<impl IntoResponse, ApiError> {
+    blocking(move || Ok(zdnp_core::add_indicator(&api.documents(), id, &dto)?))
+        .await
+        .map(created)
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/indicators/{id}",
+    tag = "documents",
+    params(("id" = i64, Path)),
+    request_body = TechnicalIndicatorDto,
+    responses(
+        (status = 204),
+        (status = 404, body = ErrorBody),
+        (status = 409, body = ErrorBody),
+        (status = 422, body = ErrorBody)
+    )
+)]
+async fn update_indicator(
+    State(api): State<Api>,
+    Path(id): Path<i64>,
+    Json(dto): Json<TechnicalIndicatorDto>,
+) -> Result<StatusCode, ApiError> {
+    blocking(move || Ok(zdnp_core::update_indicator(&api.documents(), id, &dto)?))
+        .await
+        .map(|()| StatusCode::NO_CONTENT)
+}
+
+/// Requirement sections of the document; the texts keep their placeholders.
+#[utoipa::path(
+    get,
+    path = "/api/documents/{id}/requirements",
+    tag = "documents",
+    params(("id" = i64, Path)),
+    responses((status = 200, body = Vec<RequirementSection>), (status = 500, body = ErrorBody))
+)]
+async fn list_requirements(
+    State(api): State<Api>,
+    Path(id): Path<i64>,
+) -> Result<Json<Vec<RequirementSection>>, ApiError> {
+    blocking(move || Ok(zdnp_core::list_requirements(&api.documents(), id)?))
+        .await
+        .map(Json)
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/documents/{id}/requirements",
+    tag = "documents",
+    params(("id" = i64, Path)),
+    request_body = RequirementSectionDto,
+    responses(
+        (status = 201, body = Created),
+        (status = 409, body = ErrorBody),
+        (status = 422, body = ErrorBody)
+    )
+)]
+async fn add_requirement(
+    State(api): State<Api>,
+    Path(id): Path<i64>,
+    Json(dto): Json<RequirementSectionDto>,
+) -> Result<impl IntoResponse, ApiError> {
+    blocking(move || Ok(zdnp_core::add_requirement(&api.documents(), id, &dto)?))
+        .await
+        .map(created)
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/requirements/{id}",
+    tag = "documents",
+    params(("id" = i64, Path)),
+    request_body = RequirementSectionDto,
+    responses(
+        (status = 204),
+        (status = 404, body = ErrorBody),
+        (status = 409, body = ErrorBody),
+        (status = 422, body = ErrorBody)
+    )
+)]
+async fn update_requirement(
+    State(api): State<Api>,
+    Path(id): Path<i64>,
+    Json(dto): Json<RequirementSectionDto>,
+) -> Result<StatusCode, ApiError> {
+    blocking(move || Ok(zdnp_core::update_requirement(&api.documents(), id, &dto)?))
+        .await
+        .map(|()| StatusCode::NO_CONTENT)
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/documents/{id}/signatories",
+    tag = "documents",
+    params(("id" = i64, Path)),
+    responses((status = 200, body = Vec<Signatory>), (status = 500, body = ErrorBody))
+)]
+async fn list_signatories(
+    State(api): State<Api>,
+    Path(id): Path<i64>,
+) -> Result<Json<Vec<Signatory>>, ApiError> {
+    blocking(move || Ok(zdnp_core::list_signatories(&api.documents(), id)?))
+        .await
+        .map(Json)
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/documents/{id}/signatories",
+    tag = "documents",
+    params(("id" = i64, Path)),
+    request_body = SignatoryDto,
+    responses(
+        (status = 201, body = Created),
+        (status = 409, body = ErrorBody),
+        (status = 422, body = ErrorBody)
+    )
+)]
+async fn add_signatory(
+    State(api): State<Api>,
+    Path(id): Path<i64>,
+    Json(dto): Json<SignatoryDto>,
+) -> Result<impl IntoResponse, ApiError> {
+    blocking(move || {
+        Ok(zdnp_core::add_signatory(
+            &api.documents(),
+            &api.references(),
+            id,
+            &dto,
+        )?)
+    })
+    .await
+    .map(created)
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/signatories/{id}",
+    tag = "documents",
+    params(("id" = i64, Path)),
+    request_body = SignatoryDto,
+    responses(
+        (status = 204),
+        (status = 404, body = ErrorBody),
+        (status = 409, body = ErrorBody),
+        (status = 422, body = ErrorBody)
+    )
+)]
+async fn update_signatory(
+    State(api): State<Api>,
+    Path(id): Path<i64>,
+    Json(dto): Json<SignatoryDto>,
+) -> Result<StatusCode, ApiError> {
+    blocking(move || {
+        Ok(zdnp_core::update_signatory(
+            &api.documents(),
+            &api.references(),
+            id,
+            &dto,
+        )?)
+    })
+    .await
+    .map(|()| StatusCode::NO_CONTENT)
+}
+
+// ---------------- Requirement Templates ----------------
+
+#[utoipa::path(
+    get,
+    path = "/api/requirement-templates",
+    tag = "requirement templates",
+    params(TemplateQuery),
+    responses((status = 200, body = Vec<RequirementTemplate>), (status = 500, body = ErrorBody))
+)]
+async fn list_requirement_templates(
+    State(api): State<Api>,
+    Query(query): Query<TemplateQuery>,
+) -> Result<Json<Vec<RequirementTemplate>>, ApiError> {
+    blocking(move || {
+        Ok(zdnp_core::list_requirement_templates(
+            &api.requirement_templates(),
+            query.category,
+        )?)
+    })
+    .await
+    .map(Json)
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/requirement-templates",
+    tag = "requirement templates",
+    request_body = RequirementTemplateDto,
+    responses((status = 201, body = Created), (status = 422, body = ErrorBody))
+)]
+async fn create_requirement_template(
+    State(api): State<Api>,
+    Json(dto): Json<RequirementTemplateDto>,
+) -> Result<impl IntoResponse, ApiError> {
+    blocking(move || {
+        Ok(zdnp_core::create_requirement_template(
+            &api.requirement_templates(),
+            &dto,
+        )?)
+    })
+    .await
+    .map(created)
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/requirement-templates/{id}",
+    tag = "requirement templates",
+    params(("id" = i64, Path)),
+    request_body = RequirementTemplateDto,
+    responses(
+        (status = 204),
+        (status = 404, body = ErrorBody),
+        (status = 422, body = ErrorBody)
+    )
+)]
+async fn update_requirement_template(
+    State(api): State<Api>,
+    Path(id): Path<i64>,
+    Json(dto): Json<RequirementTemplateDto>,
+) -> Result<StatusCode, ApiError> {
+    blocking(move || {
+        Ok(zdnp_core::update_requirement_template(
+            &api.requirement_templates(),
+            id,
+            &dto,
+        )?)
+    })
+    .await
+    .map(|()| StatusCode::NO_CONTENT)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::Body;
+    use axum::http::{Method, Request};
+    use serde_json::{Value, json};
+    use tower::ServiceExt;
+    use zdnp_core::Migrations;
+    use zdnp_data::{DEFAULT_DATABASE_FILE_NAME, SqliteMigrations};
+
+    struct TestServer {
+        _directory: tempfile::TempDir,
+        router: Router,
+        runtime: tokio::runtime::Runtime,
+    }
+
+    impl TestServer {
+        fn new() -> Self {
+            let directory = tempfile::tempdir().expect("temp dir");
+            let path = directory
+                .path()
+                .join(DEFAULT_DATABASE_FILE_NAME)
+                .to_string_lossy()
+                .into_owned();
+
+            SqliteMigrations::with_file_name(path.as_str())
+                .run()
+                .expect("migrations should run");
+
+            Self {
+                _directory: directory,
+                router: router(Api::new(&path)),
+                runtime: tokio::runtime::Runtime::new().expect("runtime"),
+            }
+        }
+
+        fn request(&self, method: Method, uri: &str, body: Option<Value>) -> (StatusCode, Value) {
+            let request = Request::builder().method(method).uri(uri);
+            let request = match body {
+                Some(body) => request
+                    .header(header::CONTENT_TYPE, "application/json")
+                    .body(Body::from(body.to_string())),
+                None => request.body(Body::empty()),
+            }
+            .expect("request");
+
+            self.runtime.block_on(async {
+                let response = self
+                    .router
+                    .clone()
+                    .oneshot(request)
+                    .await
+                    .expect("response");
+                let status = response.status();
+                let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+                    .await
+                    .expect("body");
+                let body = if bytes.is_empty() {
+                    Value::Null
+                } else {
+                    serde_json::from_slice(&bytes).expect("JSON body")
+                };
+                (status, body)
+            })
+        }
+    }
+
+    fn create_organization(server: &TestServer) -> i64 {
+        let (status, address) = server.request(
+            Method::POST,
+            "/api/addresses",
+            Some(json!({ "region_code": "77", "city": "Москва" })),
+        );
+        assert_eq!(status, StatusCode::CREATED);
+
+        let (status, organization) = server.request(
+            Method::POST,
+            "/api/organizations",
+            Some(json!({
+                "full_name": "ООО \"Проект\"",
+                "abbreviated_name": "Проект",
+                "inn": "7700000000",
+                "kpp": "770001001",
+                "address_id": address["id"],
+                "email": "info@example.com",
+            })),
+        );
+        assert_eq!(status, StatusCode::CREATED);
+        organization["id"].as_i64().expect("id")
+    }
+
+    #[test]
+    fn reference_data_is_created_listed_and_updated() {
+        let server = TestServer::new();
+        let organization_id = create_organization(&server);
+
+        let (status, organizations) = server.request(Method::GET, "/api/organizations", None);
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(organizations.as_array().map(Vec::len), Some(1));
+        assert_eq!(organizations[0]["abbreviated_name"], "Проект");
+
+        let (status, _) = server.request(
+            Method::PUT,
+            &format!("/api/organizations/{organization_id}"),
+            Some(json!({
+                "full_name": "ООО \"Новый проект\"",
+                "abbreviated_name": "Новый проект",
+                "inn": "7700000000",
+                "kpp": "770001001",
+                "address_id": organizations[0]["address_id"],
+                "email": "info@example.com",
+            })),
+        );
+        assert_eq!(status, StatusCode::NO_CONTENT);
+
+        let (_, organizations) = server.request(Method::GET, "/api/organizations", None);
+        assert_eq!(organizations[0]["abbreviated_name"], "Новый проект");
+
+        let (status, error) = server.request(
+            Method::POST,
+            "/api/organizations",
+            Some(json!({ "abbreviated_name": "Без имени" })),
+        );
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert!(error["error"].is_string());
+
+        let (status, _) = server.request(
+            Method::PUT,
+            "/api/organizations/999",
+            Some(json!({
+                "full_name": "ООО \"Нет\"",
+                "abbreviated_name": "Нет",
+                "inn": "7700000000",
+                "kpp": "770001001",
+                "address_id": organizations[0]["address_id"],
+                "email": "info@example.com",
+            })),
+        );
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+
+    #[test]
+    fn addresses_persons_and_source_documents_are_updated() {
+        let server = TestServer::new();
+        let organization_id = create_organization(&server);
+
+        let (_, addresses) = server.request(Method::GET, "/api/addresses", None);
+        let address_id = addresses[0]["id"].as_i64().expect("id");
+        let (status, _) = server.request(
+            Method::PUT,
+            &format!("/api/addresses/{address_id}"),
+            Some(json!({ "region_code": "50", "city": " Химки " })),
+        );
+        assert_eq!(status, StatusCode::NO_CONTENT);
+        let (_, addresses) = server.request(Method::GET, "/api/addresses", None);
+        assert_eq!(addresses[0]["city"], "Химки");
+
+        let person = |surname: &str| {
+            json!({
+                "name": "Иван",
+                "surname": surname,
+                "snils": "11223344595",
+                "email": "ivanov@example.com",
+                "address_id": address_id,
+            })
+        };
+        let (status, created) =
+            server.request(Method::POST, "/api/persons", Some(person("Иванов")));
+        assert_eq!(status, StatusCode::CREATED);
+        let (status, _) = server.request(
+            Method::PUT,
+            &format!("/api/persons/{}", created["id"]),
+            Some(person("Петров")),
+        );
+        assert_eq!(status, StatusCode::NO_CONTENT);
+        let (_, persons) = server.request(Method::GET, "/api/persons", None);
+        assert_eq!(persons[0]["surname"], "Петров");
+
+        let source = |number: &str| {
+            json!({
+                "kind": "technical_conditions",
+                "number": number,
+                "date": "2026-10-01",
+                "issuer_id": organization_id,
+            })
+        };
+        let (status, created) =
+            server.request(Method::POST, "/api/source-documents", Some(source("ТУ-1")));
+        assert_eq!(status, StatusCode::CREATED);
+        let (status, _) = server.request(
+            Method::PUT,
+            &format!("/api/source-documents/{}", created["id"]),
+            Some(source("ТУ-2")),
+        );
+        assert_eq!(status, StatusCode::NO_CONTENT);
+        let (_, sources) = server.request(Method::GET, "/api/source-documents", None);
+        assert_eq!(sources[0]["number"], "ТУ-2");
+
+        let (status, _) = server.request(
+            Method::PUT,
+            &format!("/api/source-documents/{}", created["id"]),
+            Some(json!({ "kind": "technical_conditions", "number": "ТУ-3" })),
+        );
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        let (status, _) = server.request(
+            Method::PUT,
+            "/api/source-documents/999",
+            Some(source("ТУ-4")),
+        );
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        let (status, _) = server.request(Method::PUT, "/api/persons/999", Some(person("Нет")));
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        let (status, _) = server.request(
+            Method::PUT,
+            "/api/addresses/999",
+            Some(json!({ "region_code": "77" })),
+        );
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+
+    #[test]
+    fn documents_are_created_and_exported() {
+        let server = TestServer::new();
+        let organization_id = create_organization(&server);
+
+        let (status, document) = server.request(
+            Method::POST,
+            "/api/documents",
+            Some(json!({
+                "name": "Задание на проектирование",
+                "document_type": "new_construction",
+                "author": { "kind": "organization", "id": organization_id },
+            })),
+        );
+        assert_eq!(status, StatusCode::CREATED);
+        let document_id = document["id"].as_i64().expect("id");
+
+        let (status, export) = server.request(
+            Method::GET,
+            &format!("/api/documents/{document_id}/export"),
+            None,
+        );
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(export["document"]["name"], "Задание на проектирование");
+        assert_eq!(export["from_snapshot"], false);
+
+        let (status, _) = server.request(Method::GET, "/api/documents/999/export", None);
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+
+    #[test]
+    fn openapi_description_lists_the_routes() {
+        let server = TestServer::new();
+
+        let (status, description) = server.request(Method::GET, "/api/openapi.json", None);
+        assert_eq!(status, StatusCode::OK);
+        assert!(description["paths"]["/api/documents/{id}/export"]["get"].is_object());
+        assert!(description["paths"]["/api/organizations/{id}"]["put"].is_object());
+        assert!(description["paths"]["/api/source-documents/{id}"]["put"].is_object());
+        assert!(description["components"]["schemas"]["DocumentDto"].is_object());
+    }
+}
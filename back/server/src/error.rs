@@ -0,0 +1,276 @@
+use axum::Json;
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use serde::Serialize;
+use utoipa::ToSchema;
+use zdnp_core::{
+    AddressError, AddressRepositoryError, ConstructionObjectError,
+    ConstructionObjectRepositoryError, DocumentError, DocumentRepositoryError, EntrepreneurError,
+    EntrepreneurRepositoryError, OrganizationError, OrganizationRepositoryError, PersonError,
+    PersonRepositoryError, ReferenceError, RequirementError, RequirementRepositoryError,
+    RequirementTemplateRepositoryError, SignatoryError, SignatoryRepositoryError,
+    SourceDocumentError, SourceDocumentRepositoryError, TechnicalIndicatorError,
+    TechnicalIndicatorRepositoryError,
+};
+use zdnp_render::RenderError;
+
+// ---------------- API Errors ----------------
+
+/// Body of every error response.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, ToSchema)]
+pub struct ErrorBody {
+    pub error: String,
+}
+
+/// A failed request: `404` for unknown ids, `409` for operations the state of a document does
+/// not allow, `422` for invalid input and `500` for storage failures.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ApiError {
+    pub status: StatusCode,
+    pub message: String,
+}
+
+impl ApiError {
+    pub fn new<M: std::fmt::Display>(status: StatusCode, message: M) -> Self {
+        Self {
+            status,
+            message: message.to_string(),
+        }
+    }
+
+    pub fn not_found<M: std::fmt::Display>(message: M) -> Self {
+        Self::new(StatusCode::NOT_FOUND, message)
+    }
+
+    pub fn internal<M: std::fmt::Display>(message: M) -> Self {
+        Self::new(StatusCode::INTERNAL_SERVER_ERROR, message)
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let body = ErrorBody {
+            error: self.message,
+        };
+        (self.status, Json(body)).into_response()
+    }
+}
+
+// Storage failures of the repositories.
+impl From<AddressRepositoryError> for ApiError {
+    fn from(error: AddressRepositoryError) -> Self {
+        Self::internal(error)
+    }
+}
+
+impl From<OrganizationRepositoryError> for ApiError {
+    fn from(error: OrganizationRepositoryError) -> Self {
+        Self::internal(error)
+    }
+}
+
+impl From<EntrepreneurRepositoryError> for ApiError {
+    fn from(error: EntrepreneurRepositoryError) -> Self {
+        Self::internal(error)
+    }
+}
+
+impl From<PersonRepositoryError> for ApiError {
+    fn from(error: PersonRepositoryError) -> Self {
+        Self::internal(error)
+    }
+}
+
+impl From<SourceDocumentRepositoryError> for ApiError {
+    fn from(error: SourceDocumentRepositoryError) -> Self {
+        Self::internal(error)
+    }
+}
+
+impl From<DocumentRepositoryError> for ApiError {
+    fn from(error: DocumentRepositoryError) -> Self {
+        Self::internal(error)
+    }
+}
+
+impl From<ConstructionObjectRepositoryError> for ApiError {
+    fn from(error: ConstructionObjectRepositoryError) -> Self {
+        Self::internal(error)
+    }
+}
+
+impl From<TechnicalIndicatorRepositoryError> for ApiError {
+    fn from(error: TechnicalIndicatorRepositoryError) -> Self {
+        Self::internal(error)
+    }
+}
+
+impl From<RequirementRepositoryError> for ApiError {
+    fn from(error: RequirementRepositoryError) -> Self {
+        Self::internal(error)
+    }
+}
+
+impl From<RequirementTemplateRepositoryError> for ApiError {
+    fn from(error: RequirementTemplateRepositoryError) -> Self {
+        Self::internal(error)
+    }
+}
+
+impl From<SignatoryRepositoryError> for ApiError {
+    fn from(error: SignatoryRepositoryError) -> Self {
+        Self::internal(error)
+    }
+}
+
+// Service errors: the variants other than the listed ones are validation failures.
+impl From<AddressError> for ApiError {
+    fn from(error: AddressError) -> Self {
+        let status = match &error {
+            AddressError::NotFound(_) => StatusCode::NOT_FOUND,
+            AddressError::Repository(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            _ => StatusCode::UNPROCESSABLE_ENTITY,
+        };
+        Self::new(status, error)
+    }
+}
+
+impl From<OrganizationError> for ApiError {
+    fn from(error: OrganizationError) -> Self {
+        let status = match &error {
+            OrganizationError::NotFound(_) => StatusCode::NOT_FOUND,
+            OrganizationError::Repository(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            _ => StatusCode::UNPROCESSABLE_ENTITY,
+        };
+        Self::new(status, error)
+    }
+}
+
+impl From<EntrepreneurError> for ApiError {
+    fn from(error: EntrepreneurError) -> Self {
+        let status = match &error {
+            EntrepreneurError::NotFound(_) => StatusCode::NOT_FOUND,
+            EntrepreneurError::Repository(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            _ => StatusCode::UNPROCESSABLE_ENTITY,
+        };
+        Self::new(status, error)
+    }
+}
+
+impl From<PersonError> for ApiError {
+    fn from(error: PersonError) -> Self {
+        let status = match &error {
+            PersonError::NotFound(_) => StatusCode::NOT_FOUND,
+            PersonError::Repository(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            _ => StatusCode::UNPROCESSABLE_ENTITY,
+        };
+        Self::new(status, error)
+    }
+}
+
+// A request referring to a missing or archived entity cannot be processed.
+impl From<ReferenceError> for ApiError {
+    fn from(error: ReferenceError) -> Self {
+        let status = match &error {
+            ReferenceError::Repository(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            _ => StatusCode::UNPROCESSABLE_ENTITY,
+        };
+        Self::new(status, error)
+    }
+}
+
+impl From<DocumentError> for ApiError {
+    fn from(error: DocumentError) -> Self {
+        let status = match &error {
+            DocumentError::NotFound(_) => StatusCode::NOT_FOUND,
+            DocumentError::AlreadyRevised { .. }
+            | DocumentError::AlreadyIssued(_)
+            | DocumentError::NotIssued(_)
+            | DocumentError::ReadOnly(_)
+            | DocumentError::InvalidTransition { .. }
+            | DocumentError::Incomplete(_) => StatusCode::CONFLICT,
+            DocumentError::Reference(error) => return error.clone().into(),
+            DocumentError::Repository(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            _ => StatusCode::UNPROCESSABLE_ENTITY,
+        };
+        Self::new(status, error)
+    }
+}
+
+impl From<SourceDocumentError> for ApiError {
+    fn from(error: SourceDocumentError) -> Self {
+        let status = match error {
+            SourceDocumentError::NotFound(_) | SourceDocumentError::NotAttached { .. } => {
+                StatusCode::NOT_FOUND
+            }
+            SourceDocumentError::Document(error) => return error.into(),
+            SourceDocumentError::Reference(error) => return error.into(),
+            SourceDocumentError::Repository(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            _ => StatusCode::UNPROCESSABLE_ENTITY,
+        };
+        Self::new(status, error)
+    }
+}
+
+impl From<ConstructionObjectError> for ApiError {
+    fn from(error: ConstructionObjectError) -> Self {
+        let status = match error {
+            ConstructionObjectError::Document(error) => return error.into(),
+            ConstructionObjectError::Reference(error) => return error.into(),
+            ConstructionObjectError::Repository(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            _ => StatusCode::UNPROCESSABLE_ENTITY,
+        };
+        Self::new(status, error)
+    }
+}
+
+impl From<TechnicalIndicatorError> for ApiError {
+    fn from(error: TechnicalIndicatorError) -> Self {
+        let status = match error {
+            TechnicalIndicatorError::NotFound(_) => StatusCode::NOT_FOUND,
+            TechnicalIndicatorError::Document(error) => return error.into(),
+            TechnicalIndicatorError::Repository(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            _ => StatusCode::UNPROCESSABLE_ENTITY,
+        };
+        Self::new(status, error)
+    }
+}
+
+impl From<RequirementError> for ApiError {
+    fn from(error: RequirementError) -> Self {
+        let status = match error {
+            RequirementError::NotFound(_) | RequirementError::TemplateNotFound(_) => {
+                StatusCode::NOT_FOUND
+            }
+            RequirementError::Document(error) => return error.into(),
+            RequirementError::Repository(_) | RequirementError::TemplateRepository(_) => {
+                StatusCode::INTERNAL_SERVER_ERROR
+            }
+            _ => StatusCode::UNPROCESSABLE_ENTITY,
+        };
+        Self::new(status, error)
+    }
+}
+
+impl From<SignatoryError> for ApiError {
+    fn from(error: SignatoryError) -> Self {
+        let status = match error {
+            SignatoryError::NotFound(_) => StatusCode::NOT_FOUND,
+            SignatoryError::DuplicateRole(_) => StatusCode::CONFLICT,
+            SignatoryError::Document(error) => return error.into(),
+            SignatoryError::Reference(error) => return error.into(),
+            SignatoryError::Repository(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            _ => StatusCode::UNPROCESSABLE_ENTITY,
+        };
+        Self::new(status, error)
+    }
+}
+
+impl From<RenderError> for ApiError {
+    fn from(error: RenderError) -> Self {
+        match error {
+            RenderError::Document(error) => error.into(),
+            error => Self::internal(error),
+        }
+    }
+}
@@ -0,0 +1,79 @@
+//! Local REST API over the core services, for tools that cannot link the native library.
+//!
+//! ```text
+//! zdnp_server [--port PORT] [--database FILE]
+//! ```
+//!
+//! The server listens on `127.0.0.1` only. `FILE` is resolved next to the executable, like the
+//! database of the desktop application; the OpenAPI description is served at
+//! `/api/openapi.json`.
+
+mod api;
+mod error;
+
+use std::net::{Ipv4Addr, SocketAddr};
+
+use zdnp_core::Migrations;
+
+const DEFAULT_PORT: u16 = 8080;
+
+struct Options {
+    port: u16,
+    database: String,
+}
+
+fn parse_options(mut arguments: impl Iterator<Item = String>) -> Result<Options, String> {
+    let mut options = Options {
+        port: DEFAULT_PORT,
+        database: zdnp_data::DEFAULT_DATABASE_FILE_NAME.to_string(),
+    };
+
+    while let Some(argument) = arguments.next() {
+        let mut value = || {
+            arguments
+                .next()
+                .ok_or_else(|| format!("{argument} requires a value"))
+        };
+        match argument.as_str() {
+            "--port" => {
+                let port = value()?;
+                options.port = port.parse().map_err(|_| format!("Invalid port: {port}"))?;
+            }
+            "--database" => options.database = value()?,
+            _ => return Err(format!("Unknown argument: {argument}")),
+        }
+    }
+
+    Ok(options)
+}
+
+fn main() {
+    let options = match parse_options(std::env::args().skip(1)) {
+        Ok(options) => options,
+        Err(message) => {
+            eprintln!("{message}");
+            eprintln!("Usage: zdnp_server [--port PORT] [--database FILE]");
+            std::process::exit(2);
+        }
+    };
+
+    if let Err(error) = serve(options) {
+        eprintln!("{error}");
+        std::process::exit(1);
+    }
+}
+
+fn serve(options: Options) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    zdnp_data::SqliteMigrations::with_file_name(options.database.as_str()).run()?;
+
+    let runtime = tokio::runtime::Runtime::new()?;
+    runtime.block_on(async {
+        let address = SocketAddr::from((Ipv4Addr::LOCALHOST, options.port));
+        let listener = tokio::net::TcpListener::bind(address).await?;
+        println!("Listening on http://{address}");
+
+        let router = api::router(api::Api::new(&options.database));
+        axum::serve(listener, router).await?;
+        Ok(())
+    })
+}
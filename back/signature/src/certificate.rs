@@ -0,0 +1,196 @@
+use const_oid::ObjectIdentifier;
+use const_oid::db::{rfc3280, rfc4519};
+use der::{Any, Encode, Tag, Tagged};
+use serde::Serialize;
+use x509_cert::Certificate;
+use x509_cert::name::Name;
+use zdnp_core::{EntityRef, ReferenceEntity};
+
+// ---------------- Signer Certificates ----------------
+
+/// Subject attributes of qualified certificates defined by the order of the FSB of Russia
+/// No. 795.
+const OGRN: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.2.643.100.1");
+const SNILS: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.2.643.100.3");
+const INN_LE: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.2.643.100.4");
+const OGRNIP: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.2.643.100.5");
+const INN: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.2.643.3.131.1.1");
+
+/// Signer certificate as shown to the user.
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize)]
+pub struct CertificateInfo {
+    /// Subject in the RFC 4514 form.
+    pub subject: String,
+    pub issuer: String,
+    /// Serial number in hexadecimal.
+    pub serial_number: String,
+    pub not_before: String,
+    pub not_after: String,
+    pub common_name: Option<String>,
+    pub organization: Option<String>,
+    pub title: Option<String>,
+    pub email: Option<String>,
+    /// ИНН of the person, or of the organization padded with two zeros in older certificates.
+    pub inn: Option<String>,
+    /// ИНН of the organization.
+    pub inn_le: Option<String>,
+    pub ogrn: Option<String>,
+    pub ogrnip: Option<String>,
+    pub snils: Option<String>,
+}
+
+/// Reads a directory string of any of the types found in certificates.
+fn text(value: &Any) -> Option<String> {
+    match value.tag() {
+        Tag::Utf8String
+        | Tag::PrintableString
+        | Tag::NumericString
+        | Tag::Ia5String
+        | Tag::VisibleString
+        | Tag::TeletexString => String::from_utf8(value.value().to_vec()).ok(),
+        Tag::BmpString => {
+            let units: Vec<u16> = value
+                .value()
+                .chunks_exact(2)
+                .map(|pair| u16::from_be_bytes([pair[0], pair[1]]))
+                .collect();
+            String::from_utf16(&units).ok()
+        }
+        _ => None,
+    }
+}
+
+fn attribute(name: &Name, oid: ObjectIdentifier) -> Option<String> {
+    name.0
+        .iter()
+        .flat_map(|rdn| rdn.0.iter())
+        .find(|attribute| attribute.oid == oid)
+        .and_then(|attribute| text(&attribute.value))
+        .map(|value| value.trim().to_string())
+}
+
+/// Renders a name in the RFC 4514 form, most specific attribute first, with readable labels
+/// for the Russian identifiers.
+fn name_text(name: &Name) -> String {
+    name.0
+        .iter()
+        .rev()
+        .flat_map(|rdn| rdn.0.iter())
+        .map(|attribute| {
+            let label = match attribute.oid {
+                oid if oid == rfc4519::CN => "CN".to_string(),
+                oid if oid == rfc4519::O => "O".to_string(),
+                oid if oid == rfc4519::OU => "OU".to_string(),
+                oid if oid == rfc4519::C => "C".to_string(),
+                oid if oid == rfc4519::L => "L".to_string(),
+                oid if oid == rfc4519::ST => "ST".to_string(),
+                oid if oid == rfc4519::STREET => "STREET".to_string(),
+                oid if oid == rfc4519::TITLE => "T".to_string(),
+                oid if oid == rfc4519::SN => "SN".to_string(),
+                oid if oid == rfc4519::GIVEN_NAME => "G".to_string(),
+                oid if oid == rfc3280::EMAIL_ADDRESS => "E".to_string(),
+                oid if oid == OGRN => "OGRN".to_string(),
+                oid if oid == SNILS => "SNILS".to_string(),
+                oid if oid == INN_LE => "INNLE".to_string(),
+                oid if oid == OGRNIP => "OGRNIP".to_string(),
+                oid if oid == INN => "INN".to_string(),
+                oid => oid.to_string(),
+            };
+            let value = match text(&attribute.value) {
+                Some(value) => value
+                    .chars()
+                    .flat_map(|char| {
+                        let special = matches!(char, '\\' | ',' | '+' | '"' | ';' | '<' | '>');
+                        special.then_some('\\').into_iter().chain([char])
+                    })
+                    .collect(),
+                None => attribute
+                    .value
+                    .to_der()
+                    .map(|der| der.iter().map(|byte| format!("{byte:02X}")).collect())
+                    .map(|hex: String| format!("#{hex}"))
+                    .unwrap_or_default(),
+            };
+            format!("{label}={value}")
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+impl CertificateInfo {
+    pub fn from_certificate(certificate: &Certificate) -> Self {
+        let tbs = &certificate.tbs_certificate;
+        let subject = &tbs.subject;
+
+        Self {
+            subject: name_text(subject),
+            issuer: name_text(&tbs.issuer),
+            serial_number: tbs
+                .serial_number
+                .as_bytes()
+                .iter()
+                .map(|byte| format!("{byte:02X}"))
+                .collect(),
+            not_before: tbs.validity.not_before.to_date_time().to_string(),
+            not_after: tbs.validity.not_after.to_date_time().to_string(),
+            common_name: attribute(subject, rfc4519::CN),
+            organization: attribute(subject, rfc4519::O),
+            title: attribute(subject, rfc4519::TITLE),
+            email: attribute(subject, rfc3280::EMAIL_ADDRESS),
+            inn: attribute(subject, INN),
+            inn_le: attribute(subject, INN_LE),
+            ogrn: attribute(subject, OGRN),
+            ogrnip: attribute(subject, OGRNIP),
+            snils: attribute(subject, SNILS),
+        }
+    }
+
+    /// Whether the certificate was issued to the entity or to an employee of the organization.
+    /// Organizations are matched by ИНН or ОГРН, entrepreneurs by ИНН or ОГРНИП and people by
+    /// СНИЛС.
+    pub fn matches(&self, entity: &ReferenceEntity) -> bool {
+        fn digits(value: &str) -> String {
+            value.chars().filter(char::is_ascii_digit).collect()
+        }
+        fn same(certificate: &Option<String>, record: &str) -> bool {
+            let record = digits(record);
+            !record.is_empty()
+                && certificate
+                    .as_deref()
+                    .is_some_and(|value| digits(value) == record)
+        }
+
+        match entity {
+            ReferenceEntity::Organization(organization) => {
+                let padded_inn = self
+                    .inn
+                    .as_deref()
+                    .and_then(|inn| inn.strip_prefix("00"))
+                    .map(str::to_string);
+                same(&self.inn_le, &organization.inn)
+                    || same(&padded_inn, &organization.inn)
+                    || organization
+                        .ogrn
+                        .as_deref()
+                        .is_some_and(|ogrn| same(&self.ogrn, ogrn))
+            }
+            ReferenceEntity::Entrepreneur(entrepreneur) => {
+                same(&self.inn, &entrepreneur.inn) || same(&self.ogrnip, &entrepreneur.ogrnip)
+            }
+            ReferenceEntity::Person(person) => same(&self.snils, &person.snils),
+            ReferenceEntity::Address(_) | ReferenceEntity::SourceDocument(_) => false,
+        }
+    }
+}
+
+/// Organizations, entrepreneurs and people among `entities` the certificate was issued to.
+pub fn matching_entities(
+    certificate: &CertificateInfo,
+    entities: &[ReferenceEntity],
+) -> Vec<EntityRef> {
+    entities
+        .iter()
+        .filter(|entity| certificate.matches(entity))
+        .map(ReferenceEntity::reference)
+        .collect()
+}
@@ -0,0 +1,87 @@
+//! Detached electronic signatures (CMS/PKCS#7) of exported ZNP files. Signatures are checked
+//! offline: the CMS structure, the digest of the signed file and the signature value are
+//! verified, the signer certificate is reported with the Russian identifiers of its subject so
+//! the signer can be matched against the reference data. Signatures are created from a
+//! PKCS#12 key file once its certificate is checked against the signatories of the document.
+//!
+//! Trust in the certificate itself (the chain up to an accredited CA, revocation) is not
+//! checked.
+//!
+//! Only RSA (PKCS#1 v1.5) and ECDSA P-256 with SHA digests are implemented. GOST R 34.10-2012
+//! signatures with GOST R 34.11-2012 (Streebog) digests, which the qualified certificates of
+//! accredited Russian CAs use, are recognised but neither verified nor created: they fail with
+//! [`SignatureError::GostNotSupported`] and have to be handled by a certified cryptographic
+//! provider.
+
+use const_oid::ObjectIdentifier;
+
+mod certificate;
+mod sign;
+mod verify;
+
+pub use certificate::*;
+pub use sign::*;
+pub use verify::*;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SignatureError {
+    Io(String),
+    /// The signature file is not a valid CMS structure.
+    Malformed(String),
+    /// The CMS structure is not `SignedData`.
+    NotSignedData,
+    /// The signature embeds the signed content instead of referring to a separate file.
+    NotDetached,
+    NoSigners,
+    /// The certificate of a signer is not included in the signature.
+    CertificateNotFound,
+    UnsupportedAlgorithm(String),
+    /// The signature uses a GOST algorithm, named in the error, which is not implemented.
+    GostNotSupported(&'static str),
+    /// The file was changed after it was signed.
+    DigestMismatch,
+    InvalidSignature,
+}
+
+impl std::fmt::Display for SignatureError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(message) => write!(f, "I/O error: {message}"),
+            Self::Malformed(message) => write!(f, "Malformed signature: {message}"),
+            Self::NotSignedData => f.write_str("Signature is not a CMS SignedData structure"),
+            Self::NotDetached => f.write_str("Signature is not detached"),
+            Self::NoSigners => f.write_str("Signature has no signers"),
+            Self::CertificateNotFound => f.write_str("Signer certificate is not included"),
+            Self::UnsupportedAlgorithm(oid) => write!(f, "Unsupported algorithm: {oid}"),
+            Self::GostNotSupported(algorithm) => write!(
+                f,
+                "{algorithm} is not supported: GOST signatures have to be verified with a \
+                 certified cryptographic provider"
+            ),
+            Self::DigestMismatch => f.write_str("File does not match the signed digest"),
+            Self::InvalidSignature => f.write_str("Signature value is invalid"),
+        }
+    }
+}
+
+impl std::error::Error for SignatureError {}
+
+/// Names the GOST R 34.10 key and signature algorithms and the GOST R 34.11 digests, so they
+/// can be reported as such instead of as unknown object identifiers.
+pub(crate) fn gost_algorithm(oid: ObjectIdentifier) -> Option<&'static str> {
+    match oid.to_string().as_str() {
+        "1.2.643.7.1.1.1.1" | "1.2.643.7.1.1.3.2" => Some("GOST R 34.10-2012 (256 bit)"),
+        "1.2.643.7.1.1.1.2" | "1.2.643.7.1.1.3.3" => Some("GOST R 34.10-2012 (512 bit)"),
+        "1.2.643.7.1.1.2.2" => Some("GOST R 34.11-2012 (Streebog-256)"),
+        "1.2.643.7.1.1.2.3" => Some("GOST R 34.11-2012 (Streebog-512)"),
+        "1.2.643.2.2.19" | "1.2.643.2.2.3" => Some("GOST R 34.10-2001"),
+        "1.2.643.2.2.9" => Some("GOST R 34.11-94"),
+        _ => None,
+    }
+}
+
+impl From<der::Error> for SignatureError {
+    fn from(error: der::Error) -> Self {
+        Self::Malformed(error.to_string())
+    }
+}
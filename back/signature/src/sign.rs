@@ -0,0 +1,650 @@
+use std::path::{Path, PathBuf};
+
+use cms::builder::{SignedDataBuilder, SignerInfoBuilder, create_signing_time_attribute};
+use cms::cert::{CertificateChoices, IssuerAndSerialNumber};
+use cms::content_info::ContentInfo;
+use cms::encrypted_data::EncryptedData;
+use cms::signed_data::{EncapsulatedContentInfo, SignerIdentifier};
+use const_oid::db::{rfc5911, rfc5912};
+use der::asn1::{ContextSpecific, OctetString};
+use der::{Decode, Encode};
+use hmac::{Mac, SimpleHmac};
+use pkcs8::{DecodePrivateKey, EncodePublicKey, EncryptedPrivateKeyInfo, PrivateKeyInfo};
+use pkcs12::cert_type::CertBag;
+use pkcs12::kdf::{Pkcs12KeyType, derive_key_utf8};
+use pkcs12::mac_data::MacData;
+use pkcs12::pfx::Pfx;
+use pkcs12::safe_bag::SafeContents;
+use sha2::Digest;
+use sha2::digest::FixedOutputReset;
+use sha2::digest::core_api::BlockSizeUser;
+use spki::AlgorithmIdentifierOwned;
+use x509_cert::Certificate;
+use zdnp_core::{
+    ConstructionObjectRepository, DocumentError, DocumentExport, DocumentRepository,
+    DocumentSourceRepository, EntityRef, ReferenceRepository, RequirementRepository, Signatory,
+    SignatoryRepository, SigningRole, TechnicalIndicatorRepository,
+};
+
+use crate::CertificateInfo;
+
+// ---------------- Detached Signature Creation ----------------
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SigningError {
+    Io(String),
+    /// The key file is not a PKCS#12 container or lacks the key or its certificate.
+    KeyFile(String),
+    WrongPassword,
+    UnsupportedAlgorithm(String),
+    /// The key file holds a GOST key, named in the error, which cannot be used for signing.
+    GostNotSupported(&'static str),
+    Document(DocumentError),
+    /// The document has no signatories to check the certificate against.
+    NoSignatories,
+    /// The certificate was not issued to any of the signatories of the document.
+    NotSignatory,
+    /// The certificate names an organization or an entrepreneur other than the party the
+    /// signatory signs for: the developer when approving, the author (the designer) when
+    /// agreeing.
+    OrganizationMismatch,
+    Signing(String),
+}
+
+impl std::fmt::Display for SigningError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(message) => write!(f, "I/O error: {message}"),
+            Self::KeyFile(message) => write!(f, "Invalid key file: {message}"),
+            Self::WrongPassword => f.write_str("Wrong key file password"),
+            Self::UnsupportedAlgorithm(oid) => write!(f, "Unsupported algorithm: {oid}"),
+            Self::GostNotSupported(algorithm) => write!(
+                f,
+                "{algorithm} keys are not supported: GOST signatures have to be created with a \
+                 certified cryptographic provider"
+            ),
+            Self::Document(error) => write!(f, "Document error: {error}"),
+            Self::NoSignatories => f.write_str("Document has no signatories"),
+            Self::NotSignatory => {
+                f.write_str("Certificate was not issued to a signatory of the document")
+            }
+            Self::OrganizationMismatch => f.write_str(
+                "Certificate was issued to an organization other than the one the signatory signs \
+                 for",
+            ),
+            Self::Signing(message) => write!(f, "Signing failed: {message}"),
+        }
+    }
+}
+
+impl std::error::Error for SigningError {}
+
+impl From<DocumentError> for SigningError {
+    fn from(error: DocumentError) -> Self {
+        Self::Document(error)
+    }
+}
+
+fn key_file_error(error: impl std::fmt::Display) -> SigningError {
+    SigningError::KeyFile(error.to_string())
+}
+
+enum PrivateKey {
+    Rsa(Box<rsa::RsaPrivateKey>),
+    P256(p256::SecretKey),
+}
+
+impl PrivateKey {
+    fn from_pkcs8(der: &[u8]) -> Result<Self, SigningError> {
+        let info = PrivateKeyInfo::from_der(der).map_err(key_file_error)?;
+        match info.algorithm.oid {
+            rfc5912::RSA_ENCRYPTION => rsa::RsaPrivateKey::from_pkcs8_der(der)
+                .map(|key| Self::Rsa(Box::new(key)))
+                .map_err(key_file_error),
+            rfc5912::ID_EC_PUBLIC_KEY => p256::SecretKey::from_pkcs8_der(der)
+                .map(Self::P256)
+                .map_err(|_| {
+                    SigningError::UnsupportedAlgorithm(
+                        info.algorithm
+                            .parameters_oid()
+                            .map_or_else(|_| "ECDSA".to_string(), |curve| curve.to_string()),
+                    )
+                }),
+            oid => Err(match crate::gost_algorithm(oid) {
+                Some(algorithm) => SigningError::GostNotSupported(algorithm),
+                None => SigningError::UnsupportedAlgorithm(oid.to_string()),
+            }),
+        }
+    }
+
+    fn public_key_der(&self) -> Result<Vec<u8>, SigningError> {
+        let document = match self {
+            Self::Rsa(key) => key.to_public_key().to_public_key_der(),
+            Self::P256(key) => key.public_key().to_public_key_der(),
+        };
+        Ok(document.map_err(key_file_error)?.into_vec())
+    }
+}
+
+/// Private key and certificate of a signer, read from a PKCS#12 (.p12, .pfx) key file. RSA and
+/// ECDSA P-256 keys are supported; GOST R 34.10-2012 keys of qualified certificates are
+/// rejected with [`SigningError::GostNotSupported`].
+pub struct SigningKey {
+    certificate: Certificate,
+    key: PrivateKey,
+}
+
+/// Checks the integrity of the container, which also tells a wrong password apart.
+fn verify_mac<D>(mac_data: &MacData, password: &str, content: &[u8]) -> Result<(), SigningError>
+where
+    D: Digest + FixedOutputReset + BlockSizeUser + Clone,
+{
+    let key = derive_key_utf8::<D>(
+        password,
+        mac_data.mac_salt.as_bytes(),
+        Pkcs12KeyType::Mac,
+        mac_data.iterations,
+        <D as Digest>::output_size(),
+    )
+    .map_err(key_file_error)?;
+    let mut mac = SimpleHmac::<D>::new_from_slice(&key).map_err(key_file_error)?;
+    mac.update(content);
+    mac.verify_slice(mac_data.mac.digest.as_bytes())
+        .map_err(|_| SigningError::WrongPassword)
+}
+
+/// Decrypts a password-based encryption (PBES2) content, as used by modern key files.
+fn decrypt(
+    algorithm: &AlgorithmIdentifierOwned,
+    password: &str,
+    ciphertext: &[u8],
+) -> Result<Vec<u8>, SigningError> {
+    if algorithm.oid != pkcs8::pkcs5::pbes2::PBES2_OID {
+        return Err(SigningError::UnsupportedAlgorithm(
+            algorithm.oid.to_string(),
+        ));
+    }
+    let parameters = algorithm.to_der().map_err(key_file_error)?;
+    let scheme = pkcs8::pkcs5::EncryptionScheme::from_der(&parameters).map_err(key_file_error)?;
+    scheme
+        .decrypt(password, ciphertext)
+        .map_err(|_| SigningError::WrongPassword)
+}
+
+fn data_content(content_info: &ContentInfo) -> Result<Vec<u8>, SigningError> {
+    Ok(content_info
+        .content
+        .decode_as::<OctetString>()
+        .map_err(key_file_error)?
+        .into_bytes())
+}
+
+impl SigningKey {
+    /// Reads a PKCS#12 container protected with `password`. Containers encrypted with PBES2
+    /// (the default of current tools) are supported; the first private key is used together
+    /// with the certificate of its public key.
+    pub fn from_pkcs12(der: &[u8], password: &str) -> Result<Self, SigningError> {
+        let pfx = Pfx::from_der(der).map_err(key_file_error)?;
+        if pfx.auth_safe.content_type != rfc5911::ID_DATA {
+            return Err(SigningError::UnsupportedAlgorithm(
+                pfx.auth_safe.content_type.to_string(),
+            ));
+        }
+        let auth_safe = data_content(&pfx.auth_safe)?;
+
+        if let Some(mac_data) = &pfx.mac_data {
+            match mac_data.mac.algorithm.oid {
+                rfc5912::ID_SHA_1 => verify_mac::<sha1::Sha1>(mac_data, password, &auth_safe)?,
+                rfc5912::ID_SHA_256 => verify_mac::<sha2::Sha256>(mac_data, password, &auth_safe)?,
+                rfc5912::ID_SHA_384 => verify_mac::<sha2::Sha384>(mac_data, password, &auth_safe)?,
+                rfc5912::ID_SHA_512 => verify_mac::<sha2::Sha512>(mac_data, password, &auth_safe)?,
+                oid => return Err(SigningError::UnsupportedAlgorithm(oid.to_string())),
+            }
+        }
+
+        let mut keys = Vec::new();
+        let mut certificates = Vec::new();
+        for content_info in Vec::<ContentInfo>::from_der(&auth_safe).map_err(key_file_error)? {
+            let safe_contents = match content_info.content_type {
+                rfc5911::ID_DATA => data_content(&content_info)?,
+                rfc5911::ID_ENCRYPTED_DATA => {
+                    let encrypted: EncryptedData =
+                        content_info.content.decode_as().map_err(key_file_error)?;
+                    let info = encrypted.enc_content_info;
+                    let ciphertext = info.encrypted_content.ok_or_else(|| {
+                        SigningError::KeyFile("encrypted content is missing".into())
+                    })?;
+                    decrypt(&info.content_enc_alg, password, ciphertext.as_bytes())?
+                }
+                oid => return Err(SigningError::UnsupportedAlgorithm(oid.to_string())),
+            };
+
+            for bag in SafeContents::from_der(&safe_contents).map_err(key_file_error)? {
+                match bag.bag_id {
+                    pkcs12::PKCS_12_PKCS8_KEY_BAG_OID => {
+                        let shrouded: ContextSpecific<EncryptedPrivateKeyInfo> =
+                            ContextSpecific::from_der(&bag.bag_value).map_err(key_file_error)?;
+                        let algorithm = AlgorithmIdentifierOwned::from_der(
+                            &shrouded
+                                .value
+                                .encryption_algorithm
+                                .to_der()
+                                .map_err(key_file_error)?,
+                        )
+                        .map_err(key_file_error)?;
+                        let der = decrypt(&algorithm, password, shrouded.value.encrypted_data)?;
+                        keys.push(PrivateKey::from_pkcs8(&der)?);
+                    }
+                    pkcs12::PKCS_12_KEY_BAG_OID => {
+                        let plain: ContextSpecific<PrivateKeyInfo> =
+                            ContextSpecific::from_der(&bag.bag_value).map_err(key_file_error)?;
+                        let der = plain.value.to_der().map_err(key_file_error)?;
+                        keys.push(PrivateKey::from_pkcs8(&der)?);
+                    }
+                    pkcs12::PKCS_12_CERT_BAG_OID => {
+                        let cert_bag: ContextSpecific<CertBag> =
+                            ContextSpecific::from_der(&bag.bag_value).map_err(key_file_error)?;
+                        if cert_bag.value.cert_id == pkcs12::PKCS_12_X509_CERT_OID {
+                            certificates.push(
+                                Certificate::from_der(cert_bag.value.cert_value.as_bytes())
+                                    .map_err(key_file_error)?,
+                            );
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        let key = keys
+            .into_iter()
+            .next()
+            .ok_or_else(|| SigningError::KeyFile("private key is missing".into()))?;
+        let public_key = key.public_key_der()?;
+        let certificate = certificates
+            .into_iter()
+            .find(|certificate| {
+                certificate
+                    .tbs_certificate
+                    .subject_public_key_info
+                    .to_der()
+                    .is_ok_and(|der| der == public_key)
+            })
+            .ok_or_else(|| SigningError::KeyFile("certificate of the key is missing".into()))?;
+
+        Ok(Self { certificate, key })
+    }
+
+    pub fn from_pkcs12_file(path: &Path, password: &str) -> Result<Self, SigningError> {
+        let der = std::fs::read(path).map_err(|error| SigningError::Io(error.to_string()))?;
+        Self::from_pkcs12(&der, password)
+    }
+
+    pub fn certificate(&self) -> CertificateInfo {
+        CertificateInfo::from_certificate(&self.certificate)
+    }
+}
+
+/// Creates a detached CMS signature (DER) of `content` with SHA-256, the signing time and the
+/// signer certificate.
+pub fn sign_detached(content: &[u8], key: &SigningKey) -> Result<Vec<u8>, SigningError> {
+    let signing_error = |error: cms::builder::Error| SigningError::Signing(error.to_string());
+
+    let digest = sha2::Sha256::digest(content);
+    let digest_algorithm = AlgorithmIdentifierOwned {
+        oid: rfc5912::ID_SHA_256,
+        parameters: None,
+    };
+    let content_info = EncapsulatedContentInfo {
+        econtent_type: rfc5911::ID_DATA,
+        econtent: None,
+    };
+    let tbs = &key.certificate.tbs_certificate;
+    let signer_id = SignerIdentifier::IssuerAndSerialNumber(IssuerAndSerialNumber {
+        issuer: tbs.issuer.clone(),
+        serial_number: tbs.serial_number.clone(),
+    });
+
+    let mut builder = SignedDataBuilder::new(&content_info);
+    builder
+        .add_digest_algorithm(digest_algorithm.clone())
+        .map_err(signing_error)?
+        .add_certificate(CertificateChoices::Certificate(key.certificate.clone()))
+        .map_err(signing_error)?;
+
+    let signing_time = create_signing_time_attribute().map_err(signing_error)?;
+    match &key.key {
+        PrivateKey::Rsa(private_key) => {
+            let signer =
+                rsa::pkcs1v15::SigningKey::<sha2::Sha256>::new(private_key.as_ref().clone());
+            let mut signer_info = SignerInfoBuilder::new(
+                &signer,
+                signer_id,
+                digest_algorithm,
+                &content_info,
+                Some(&digest),
+            )
+            .map_err(signing_error)?;
+            signer_info
+                .add_signed_attribute(signing_time)
+                .map_err(signing_error)?;
+            builder
+                .add_signer_info::<_, rsa::pkcs1v15::Signature>(signer_info)
+                .map_err(signing_error)?;
+        }
+        PrivateKey::P256(private_key) => {
+            let signer = p256::ecdsa::SigningKey::from(private_key);
+            let mut signer_info = SignerInfoBuilder::new(
+                &signer,
+                signer_id,
+                digest_algorithm,
+                &content_info,
+                Some(&digest),
+            )
+            .map_err(signing_error)?;
+            signer_info
+                .add_signed_attribute(signing_time)
+                .map_err(signing_error)?;
+            builder
+                .add_signer_info::<_, p256::ecdsa::DerSignature>(signer_info)
+                .map_err(signing_error)?;
+        }
+    }
+
+    builder
+        .build()
+        .map_err(signing_error)?
+        .to_der()
+        .map_err(|error| SigningError::Signing(error.to_string()))
+}
+
+/// Checks that the certificate was issued to one of the signatories of the document and,
+/// when it names an organization or an entrepreneur, to the party that signatory signs for:
+/// the developer approves the ZNP and the designer, recorded as the author of the document,
+/// agrees it. Returns the matching signatory.
+pub fn check_signer(
+    certificate: &CertificateInfo,
+    export: &DocumentExport,
+) -> Result<Signatory, SigningError> {
+    if export.signatories.is_empty() {
+        return Err(SigningError::NoSignatories);
+    }
+
+    let entity = |reference: EntityRef| {
+        export
+            .entities
+            .iter()
+            .find(|entity| entity.reference() == reference)
+    };
+
+    let signatories: Vec<&Signatory> = export
+        .signatories
+        .iter()
+        .filter(|signatory| {
+            entity(EntityRef::Person(signatory.person_id))
+                .is_some_and(|person| certificate.matches(person))
+        })
+        .collect();
+    let first = *signatories.first().ok_or(SigningError::NotSignatory)?;
+
+    let names_party =
+        certificate.inn_le.is_some() || certificate.ogrn.is_some() || certificate.ogrnip.is_some();
+    if !names_party {
+        return Ok(first.clone());
+    }
+
+    let document = &export.document;
+    let party = |role: SigningRole| match role {
+        SigningRole::Approval => document.developer.map(|developer| developer.reference()),
+        SigningRole::Agreement => document.author.reference(),
+    };
+    signatories
+        .into_iter()
+        .find(|signatory| {
+            party(signatory.role)
+                .filter(|reference| !matches!(reference, EntityRef::Person(_)))
+                .and_then(entity)
+                .is_some_and(|party| certificate.matches(party))
+        })
+        .cloned()
+        .ok_or(SigningError::OrganizationMismatch)
+}
+
+/// Path of the detached signature of a file: the file name with `.sig` appended.
+pub fn signature_path(content_path: &Path) -> PathBuf {
+    let mut path = content_path.as_os_str().to_owned();
+    path.push(".sig");
+    PathBuf::from(path)
+}
+
+/// Signs a file exported from the document with a PKCS#12 key file after checking the
+/// certificate against the signatories of the document, see [`check_signer`]. The signature
+/// is written next to the file and its path is returned.
+///
+/// Signatures are made with RSA or ECDSA P-256 and SHA-256 only. Files that have to carry a
+/// GOST R 34.10-2012 qualified signature must be signed with a certified cryptographic
+/// provider instead.
+pub fn sign_document_file<D, R>(
+    documents: &D,
+    references: &R,
+    id: i64,
+    content_path: &Path,
+    key: &SigningKey,
+) -> Result<PathBuf, SigningError>
+where
+    D: DocumentRepository
+        + ConstructionObjectRepository
+        + TechnicalIndicatorRepository
+        + RequirementRepository
+        + DocumentSourceRepository
+        + SignatoryRepository,
+    R: ReferenceRepository,
+{
+    let export = zdnp_core::export_document(documents, references, id)?;
+    check_signer(&key.certificate(), &export)?;
+
+    let io_error = |error: std::io::Error| SigningError::Io(error.to_string());
+    let content = std::fs::read(content_path).map_err(io_error)?;
+    let signature = sign_detached(&content, key)?;
+    let path = signature_path(content_path);
+    std::fs::write(&path, signature).map_err(io_error)?;
+
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use zdnp_core::{
+        Document, DocumentAuthor, DocumentDeveloper, DocumentStatus, Organization, Person,
+        ReferenceEntity,
+    };
+
+    const DOCUMENT: &[u8] = include_bytes!("../testdata/document.xml");
+    const RSA_KEY: &[u8] = include_bytes!("../testdata/rsa.p12");
+    const EC_KEY: &[u8] = include_bytes!("../testdata/ec.p12");
+
+    fn export(snils: &str, inn: &str) -> DocumentExport {
+        DocumentExport {
+            document: Document {
+                id: 1,
+                name: "ЗНП на строительство школы".into(),
+                number: None,
+                date: None,
+                change_mark: None,
+                uid: "2f1c9a8e-3b7d-4c5e-9a10-1b2c3d4e5f60".into(),
+                document_type: None,
+                type_note: None,
+                author: DocumentAuthor::Person { id: 5 },
+                developer: Some(DocumentDeveloper::Organization { id: 3 }),
+                previous_revision_id: None,
+                is_template: false,
+                status: DocumentStatus::Approved,
+                submitted_at: None,
+                approved_at: None,
+                issued_at: None,
+                superseded_at: None,
+                cancelled_at: None,
+            },
+            construction_object: None,
+            indicators: Vec::new(),
+            requirements: Vec::new(),
+            signatories: vec![Signatory {
+                id: 1,
+                document_id: 1,
+                person_id: 5,
+                position: "Генеральный директор".into(),
+                role: SigningRole::Approval,
+                signed_on: None,
+            }],
+            entities: vec![
+                ReferenceEntity::Organization(Organization {
+                    id: 3,
+                    uid: "organization-3".into(),
+                    full_name: "ООО \"Проект\"".into(),
+                    abbreviated_name: "Проект".into(),
+                    ogrn: None,
+                    rafp: None,
+                    inn: inn.into(),
+                    kpp: "770001001".into(),
+                    address_id: 9,
+                    email: "info@example.com".into(),
+                    archived_at: None,
+                }),
+                ReferenceEntity::Person(Person {
+                    id: 5,
+                    uid: "person-5".into(),
+                    name: "Иван".into(),
+                    patronymic: Some("Иванович".into()),
+                    surname: "Иванов".into(),
+                    snils: snils.into(),
+                    email: "ivanov@example.com".into(),
+                    address_id: 9,
+                    archived_at: None,
+                }),
+            ],
+            from_snapshot: false,
+        }
+    }
+
+    #[test]
+    fn signatures_made_with_key_files_are_verified() {
+        for (key_file, common_name) in [
+            (RSA_KEY, "Иванов Иван Иванович"),
+            (EC_KEY, "Петров Пётр Петрович"),
+        ] {
+            let key = SigningKey::from_pkcs12(key_file, "secret").expect("key file");
+            assert_eq!(key.certificate().common_name.as_deref(), Some(common_name));
+
+            let signature = sign_detached(DOCUMENT, &key).expect("signature");
+            let signers = crate::verify_detached(DOCUMENT, &signature).expect("valid signature");
+            assert_eq!(signers.len(), 1);
+            assert_eq!(signers[0].certificate, key.certificate());
+            assert!(signers[0].signing_time.is_some());
+        }
+
+        assert!(matches!(
+            SigningKey::from_pkcs12(RSA_KEY, "wrong"),
+            Err(SigningError::WrongPassword)
+        ));
+        assert!(matches!(
+            SigningKey::from_pkcs12(DOCUMENT, "secret"),
+            Err(SigningError::KeyFile(_))
+        ));
+        assert_eq!(
+            signature_path(Path::new("/tmp/document.xml")),
+            Path::new("/tmp/document.xml.sig")
+        );
+    }
+
+    #[test]
+    fn certificate_is_checked_against_the_signatories() {
+        let certificate = SigningKey::from_pkcs12(RSA_KEY, "secret")
+            .expect("key file")
+            .certificate();
+
+        let signatory =
+            check_signer(&certificate, &export("112-233-445 95", "7700000000")).expect("signatory");
+        assert_eq!(signatory.person_id, 5);
+
+        assert_eq!(
+            check_signer(&certificate, &export("98765432100", "7700000000")),
+            Err(SigningError::NotSignatory)
+        );
+        assert_eq!(
+            check_signer(&certificate, &export("112-233-445 95", "7711111111")),
+            Err(SigningError::OrganizationMismatch)
+        );
+
+        let mut unsigned = export("112-233-445 95", "7700000000");
+        unsigned.signatories.clear();
+        assert_eq!(
+            check_signer(&certificate, &unsigned),
+            Err(SigningError::NoSignatories)
+        );
+    }
+
+    #[test]
+    fn gost_keys_are_rejected_explicitly() {
+        let key = PrivateKeyInfo {
+            algorithm: pkcs8::AlgorithmIdentifierRef {
+                oid: const_oid::ObjectIdentifier::new_unwrap("1.2.643.7.1.1.1.1"),
+                parameters: None,
+            },
+            private_key: &[7; 32],
+            public_key: None,
+        }
+        .to_der()
+        .expect("key");
+
+        let error = PrivateKey::from_pkcs8(&key)
+            .err()
+            .expect("GOST key is rejected");
+        assert_eq!(
+            error,
+            SigningError::GostNotSupported("GOST R 34.10-2012 (256 bit)")
+        );
+        assert!(
+            error
+                .to_string()
+                .contains("certified cryptographic provider")
+        );
+    }
+
+    #[test]
+    fn organization_is_checked_against_the_role_of_the_signatory() {
+        let certificate = SigningKey::from_pkcs12(RSA_KEY, "secret")
+            .expect("key file")
+            .certificate();
+
+        // The designer agrees the ZNP: the organization must be the author, not the developer.
+        let mut agreement = export("112-233-445 95", "7700000000");
+        agreement.signatories[0].role = SigningRole::Agreement;
+        assert_eq!(
+            check_signer(&certificate, &agreement),
+            Err(SigningError::OrganizationMismatch)
+        );
+
+        agreement.document.author = DocumentAuthor::Organization { id: 3 };
+        agreement.document.developer = None;
+        let signatory = check_signer(&certificate, &agreement).expect("designer signs");
+        assert_eq!(signatory.role, SigningRole::Agreement);
+
+        // The author cannot approve in place of the developer.
+        let mut approval = agreement.clone();
+        approval.signatories[0].role = SigningRole::Approval;
+        assert_eq!(
+            check_signer(&certificate, &approval),
+            Err(SigningError::OrganizationMismatch)
+        );
+
+        // A person signing in both roles is matched to the role their organization fits.
+        approval.signatories.push(Signatory {
+            id: 2,
+            role: SigningRole::Agreement,
+            ..approval.signatories[0].clone()
+        });
+        let signatory = check_signer(&certificate, &approval).expect("designer signs");
+        assert_eq!(signatory.id, 2);
+    }
+}
@@ -0,0 +1,436 @@
+use std::path::Path;
+
+use cms::cert::CertificateChoices;
+use cms::content_info::ContentInfo;
+use cms::signed_data::{SignedData, SignerIdentifier, SignerInfo};
+use const_oid::ObjectIdentifier;
+use const_oid::db::{rfc5280, rfc5911, rfc5912};
+use der::asn1::OctetString;
+use der::{Decode, Encode};
+use p256::ecdsa::signature::hazmat::PrehashVerifier;
+use rsa::pkcs8::DecodePublicKey;
+use rsa::{Pkcs1v15Sign, RsaPublicKey};
+use serde::Serialize;
+use sha2::Digest;
+use x509_cert::Certificate;
+use x509_cert::time::Time;
+
+use crate::{CertificateInfo, SignatureError};
+
+// ---------------- Detached Signature Verification ----------------
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum DigestAlgorithm {
+    Sha1,
+    Sha256,
+    Sha384,
+    Sha512,
+}
+
+impl DigestAlgorithm {
+    pub(crate) fn from_oid(oid: ObjectIdentifier) -> Result<Self, SignatureError> {
+        match oid {
+            rfc5912::ID_SHA_1 => Ok(Self::Sha1),
+            rfc5912::ID_SHA_256 => Ok(Self::Sha256),
+            rfc5912::ID_SHA_384 => Ok(Self::Sha384),
+            rfc5912::ID_SHA_512 => Ok(Self::Sha512),
+            oid => Err(match crate::gost_algorithm(oid) {
+                Some(algorithm) => SignatureError::GostNotSupported(algorithm),
+                None => SignatureError::UnsupportedAlgorithm(oid.to_string()),
+            }),
+        }
+    }
+
+    pub(crate) fn name(self) -> &'static str {
+        match self {
+            Self::Sha1 => "SHA-1",
+            Self::Sha256 => "SHA-256",
+            Self::Sha384 => "SHA-384",
+            Self::Sha512 => "SHA-512",
+        }
+    }
+
+    pub(crate) fn digest(self, data: &[u8]) -> Vec<u8> {
+        match self {
+            Self::Sha1 => sha1::Sha1::digest(data).to_vec(),
+            Self::Sha256 => sha2::Sha256::digest(data).to_vec(),
+            Self::Sha384 => sha2::Sha384::digest(data).to_vec(),
+            Self::Sha512 => sha2::Sha512::digest(data).to_vec(),
+        }
+    }
+
+    pub(crate) fn pkcs1v15(self) -> Pkcs1v15Sign {
+        match self {
+            Self::Sha1 => Pkcs1v15Sign::new::<sha1::Sha1>(),
+            Self::Sha256 => Pkcs1v15Sign::new::<sha2::Sha256>(),
+            Self::Sha384 => Pkcs1v15Sign::new::<sha2::Sha384>(),
+            Self::Sha512 => Pkcs1v15Sign::new::<sha2::Sha512>(),
+        }
+    }
+}
+
+/// Signer of a verified signature.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SignerReport {
+    pub digest_algorithm: &'static str,
+    pub signature_algorithm: &'static str,
+    /// Signing time claimed by the signer, when the signature carries it.
+    pub signing_time: Option<String>,
+    pub certificate: CertificateInfo,
+}
+
+/// Reads a signature saved in DER or PEM (`-----BEGIN CMS-----` or `PKCS7`).
+fn content_info(signature: &[u8]) -> Result<ContentInfo, SignatureError> {
+    if signature.starts_with(b"-----BEGIN") {
+        let (_, der) = der::pem::decode_vec(signature)
+            .map_err(|error| SignatureError::Malformed(error.to_string()))?;
+        Ok(ContentInfo::from_der(&der)?)
+    } else {
+        Ok(ContentInfo::from_der(signature)?)
+    }
+}
+
+fn subject_key_identifier(certificate: &Certificate) -> Option<Vec<u8>> {
+    certificate
+        .tbs_certificate
+        .extensions
+        .as_ref()?
+        .iter()
+        .find(|extension| extension.extn_id == rfc5280::ID_CE_SUBJECT_KEY_IDENTIFIER)
+        .and_then(|extension| OctetString::from_der(extension.extn_value.as_bytes()).ok())
+        .map(|identifier| identifier.as_bytes().to_vec())
+}
+
+fn signer_certificate<'a>(
+    signed_data: &'a SignedData,
+    signer: &SignerInfo,
+) -> Result<&'a Certificate, SignatureError> {
+    signed_data
+        .certificates
+        .iter()
+        .flat_map(|certificates| certificates.0.iter())
+        .filter_map(|choice| match choice {
+            CertificateChoices::Certificate(certificate) => Some(certificate),
+            CertificateChoices::Other(_) => None,
+        })
+        .find(|certificate| match &signer.sid {
+            SignerIdentifier::IssuerAndSerialNumber(id) => {
+                certificate.tbs_certificate.issuer == id.issuer
+                    && certificate.tbs_certificate.serial_number == id.serial_number
+            }
+            SignerIdentifier::SubjectKeyIdentifier(id) => {
+                subject_key_identifier(certificate).as_deref() == Some(id.0.as_bytes())
+            }
+        })
+        .ok_or(SignatureError::CertificateNotFound)
+}
+
+/// Checks the signed attributes against the content and returns the bytes the signature was
+/// computed over together with the claimed signing time.
+fn signed_message(
+    signer: &SignerInfo,
+    digest: &[u8],
+) -> Result<(Option<Vec<u8>>, Option<String>), SignatureError> {
+    let Some(attributes) = &signer.signed_attrs else {
+        return Ok((None, None));
+    };
+
+    let value = |oid: ObjectIdentifier| {
+        attributes
+            .iter()
+            .find(|attribute| attribute.oid == oid)
+            .and_then(|attribute| attribute.values.iter().next())
+    };
+
+    let message_digest = value(rfc5911::ID_MESSAGE_DIGEST)
+        .ok_or_else(|| SignatureError::Malformed("message digest attribute is missing".into()))?
+        .decode_as::<OctetString>()?;
+    if message_digest.as_bytes() != digest {
+        return Err(SignatureError::DigestMismatch);
+    }
+
+    let signing_time = value(rfc5911::ID_SIGNING_TIME)
+        .map(|time| Time::from_der(&time.to_der()?))
+        .transpose()?
+        .map(|time| time.to_date_time().to_string());
+
+    // The signature covers the DER encoding of the attributes as a SET OF, not the implicitly
+    // tagged form they have inside the signer info.
+    Ok((Some(attributes.to_der()?), signing_time))
+}
+
+fn verify_value(
+    certificate: &Certificate,
+    signer: &SignerInfo,
+    digest_algorithm: DigestAlgorithm,
+    hash: &[u8],
+) -> Result<&'static str, SignatureError> {
+    let key_info = &certificate.tbs_certificate.subject_public_key_info;
+    if let Some(algorithm) = crate::gost_algorithm(key_info.algorithm.oid)
+        .or_else(|| crate::gost_algorithm(signer.signature_algorithm.oid))
+    {
+        return Err(SignatureError::GostNotSupported(algorithm));
+    }
+
+    let key = key_info.to_der()?;
+    let signature = signer.signature.as_bytes();
+
+    match signer.signature_algorithm.oid {
+        rfc5912::RSA_ENCRYPTION
+        | rfc5912::SHA_1_WITH_RSA_ENCRYPTION
+        | rfc5912::SHA_256_WITH_RSA_ENCRYPTION
+        | rfc5912::SHA_384_WITH_RSA_ENCRYPTION
+        | rfc5912::SHA_512_WITH_RSA_ENCRYPTION => {
+            let key = RsaPublicKey::from_public_key_der(&key)
+                .map_err(|error| SignatureError::Malformed(error.to_string()))?;
+            key.verify(digest_algorithm.pkcs1v15(), hash, signature)
+                .map_err(|_| SignatureError::InvalidSignature)?;
+            Ok("RSA")
+        }
+        rfc5912::ID_EC_PUBLIC_KEY
+        | rfc5912::ECDSA_WITH_SHA_256
+        | rfc5912::ECDSA_WITH_SHA_384
+        | rfc5912::ECDSA_WITH_SHA_512 => {
+            let key = p256::ecdsa::VerifyingKey::from_public_key_der(&key).map_err(|_| {
+                SignatureError::UnsupportedAlgorithm(
+                    certificate
+                        .tbs_certificate
+                        .subject_public_key_info
+                        .algorithm
+                        .parameters
+                        .as_ref()
+                        .and_then(|parameters| parameters.decode_as::<ObjectIdentifier>().ok())
+                        .map_or_else(|| "ECDSA".to_string(), |curve| curve.to_string()),
+                )
+            })?;
+            let signature = p256::ecdsa::Signature::from_der(signature)
+                .map_err(|_| SignatureError::InvalidSignature)?;
+            key.verify_prehash(hash, &signature)
+                .map_err(|_| SignatureError::InvalidSignature)?;
+            Ok("ECDSA P-256")
+        }
+        oid => Err(SignatureError::UnsupportedAlgorithm(oid.to_string())),
+    }
+}
+
+/// Verifies a detached CMS signature of `content` and reports its signers. Every signer must
+/// be valid for the signature to be accepted.
+pub fn verify_detached(
+    content: &[u8],
+    signature: &[u8],
+) -> Result<Vec<SignerReport>, SignatureError> {
+    let content_info = content_info(signature)?;
+    if content_info.content_type != rfc5911::ID_SIGNED_DATA {
+        return Err(SignatureError::NotSignedData);
+    }
+    let signed_data: SignedData = content_info.content.decode_as()?;
+    if signed_data.encap_content_info.econtent.is_some() {
+        return Err(SignatureError::NotDetached);
+    }
+
+    let mut reports = Vec::new();
+    for signer in signed_data.signer_infos.0.iter() {
+        let digest_algorithm = DigestAlgorithm::from_oid(signer.digest_alg.oid)?;
+        let certificate = signer_certificate(&signed_data, signer)?;
+
+        let digest = digest_algorithm.digest(content);
+        let (message, signing_time) = signed_message(signer, &digest)?;
+        let hash = match message {
+            Some(message) => digest_algorithm.digest(&message),
+            None => digest,
+        };
+        let signature_algorithm = verify_value(certificate, signer, digest_algorithm, &hash)?;
+
+        reports.push(SignerReport {
+            digest_algorithm: digest_algorithm.name(),
+            signature_algorithm,
+            signing_time,
+            certificate: CertificateInfo::from_certificate(certificate),
+        });
+    }
+
+    if reports.is_empty() {
+        return Err(SignatureError::NoSigners);
+    }
+
+    Ok(reports)
+}
+
+/// Verifies the detached signature file (usually `<file>.sig`) of a file.
+pub fn verify_detached_file(
+    content_path: &Path,
+    signature_path: &Path,
+) -> Result<Vec<SignerReport>, SignatureError> {
+    let read =
+        |path: &Path| std::fs::read(path).map_err(|error| SignatureError::Io(error.to_string()));
+
+    verify_detached(&read(content_path)?, &read(signature_path)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use zdnp_core::{EntityRef, Organization, Person, ReferenceEntity};
+
+    const DOCUMENT: &[u8] = include_bytes!("../testdata/document.xml");
+    const RSA_SIGNATURE: &[u8] = include_bytes!("../testdata/document.xml.sig");
+    const EC_SIGNATURE: &[u8] = include_bytes!("../testdata/document.xml.ec.sig");
+
+    #[test]
+    fn signature_with_attributes_reports_the_signer() {
+        let signers = verify_detached(DOCUMENT, RSA_SIGNATURE).expect("valid signature");
+        assert_eq!(signers.len(), 1);
+
+        let signer = &signers[0];
+        assert_eq!(signer.digest_algorithm, "SHA-256");
+        assert_eq!(signer.signature_algorithm, "RSA");
+        assert!(signer.signing_time.is_some());
+
+        let certificate = &signer.certificate;
+        assert_eq!(
+            certificate.common_name.as_deref(),
+            Some("Иванов Иван Иванович")
+        );
+        assert_eq!(certificate.title.as_deref(), Some("Генеральный директор"));
+        assert_eq!(certificate.inn_le.as_deref(), Some("7700000000"));
+        assert_eq!(certificate.ogrn.as_deref(), Some("1027700000000"));
+        assert_eq!(certificate.snils.as_deref(), Some("11223344595"));
+        assert!(
+            certificate
+                .subject
+                .ends_with("O=ООО \\\"Проект\\\", CN=Иванов Иван Иванович"),
+            "{}",
+            certificate.subject
+        );
+        assert!(certificate.subject.contains("INNLE=7700000000"));
+
+        let organization = Organization {
+            id: 3,
+            uid: "organization-3".into(),
+            full_name: "ООО \"Проект\"".into(),
+            abbreviated_name: "Проект".into(),
+            ogrn: Some("1027700000000".into()),
+            rafp: None,
+            inn: "7700000000".into(),
+            kpp: "770001001".into(),
+            address_id: 9,
+            email: "info@example.com".into(),
+            archived_at: None,
+        };
+        let person = Person {
+            id: 5,
+            uid: "person-5".into(),
+            name: "Иван".into(),
+            patronymic: Some("Иванович".into()),
+            surname: "Иванов".into(),
+            snils: "112-233-445 95".into(),
+            email: "ivanov@example.com".into(),
+            address_id: 9,
+            archived_at: None,
+        };
+        let stranger = Person {
+            id: 6,
+            snils: "98765432100".into(),
+            ..person.clone()
+        };
+        assert_eq!(
+            crate::matching_entities(
+                certificate,
+                &[
+                    ReferenceEntity::Organization(organization),
+                    ReferenceEntity::Person(person),
+                    ReferenceEntity::Person(stranger),
+                ]
+            ),
+            vec![EntityRef::Organization(3), EntityRef::Person(5)]
+        );
+    }
+
+    #[test]
+    fn pem_signature_without_attributes_is_verified() {
+        let signers = verify_detached(DOCUMENT, EC_SIGNATURE).expect("valid signature");
+
+        assert_eq!(signers[0].signature_algorithm, "ECDSA P-256");
+        assert_eq!(signers[0].signing_time, None);
+        assert_eq!(
+            signers[0].certificate.ogrnip.as_deref(),
+            Some("304770000000012")
+        );
+    }
+
+    #[test]
+    fn changed_files_and_broken_signatures_are_rejected() {
+        let mut changed = DOCUMENT.to_vec();
+        changed.extend_from_slice(b"\n");
+        assert_eq!(
+            verify_detached(&changed, RSA_SIGNATURE),
+            Err(SignatureError::DigestMismatch)
+        );
+        assert_eq!(
+            verify_detached(&changed, EC_SIGNATURE),
+            Err(SignatureError::InvalidSignature)
+        );
+
+        let mut tampered = RSA_SIGNATURE.to_vec();
+        let last = tampered.len() - 1;
+        tampered[last] ^= 0x01;
+        assert_eq!(
+            verify_detached(DOCUMENT, &tampered),
+            Err(SignatureError::InvalidSignature)
+        );
+
+        assert!(matches!(
+            verify_detached(DOCUMENT, DOCUMENT),
+            Err(SignatureError::Malformed(_))
+        ));
+    }
+
+    /// Rewrites the algorithms of the signer, as a GOST signature from a Russian CA has them.
+    fn with_algorithms(signature: &[u8], digest: &str, signature_algorithm: &str) -> Vec<u8> {
+        let content_info = ContentInfo::from_der(signature).expect("content info");
+        let mut signed_data: SignedData = content_info.content.decode_as().expect("signed data");
+        let signers = signed_data
+            .signer_infos
+            .0
+            .iter()
+            .cloned()
+            .map(|mut signer| {
+                signer.digest_alg.oid = ObjectIdentifier::new_unwrap(digest);
+                signer.signature_algorithm.oid = ObjectIdentifier::new_unwrap(signature_algorithm);
+                signer
+            })
+            .collect::<Vec<_>>();
+        signed_data.signer_infos.0 = signers.try_into().expect("signer infos");
+
+        ContentInfo {
+            content_type: content_info.content_type,
+            content: der::Any::encode_from(&signed_data).expect("content"),
+        }
+        .to_der()
+        .expect("signature")
+    }
+
+    #[test]
+    fn gost_signatures_are_rejected_explicitly() {
+        let streebog = with_algorithms(RSA_SIGNATURE, "1.2.643.7.1.1.2.2", "1.2.643.7.1.1.3.2");
+        assert_eq!(
+            verify_detached(DOCUMENT, &streebog),
+            Err(SignatureError::GostNotSupported(
+                "GOST R 34.11-2012 (Streebog-256)"
+            ))
+        );
+
+        let gost_512 =
+            with_algorithms(RSA_SIGNATURE, "2.16.840.1.101.3.4.2.1", "1.2.643.7.1.1.3.3");
+        let error = verify_detached(DOCUMENT, &gost_512).expect_err("GOST is not verified");
+        assert_eq!(
+            error,
+            SignatureError::GostNotSupported("GOST R 34.10-2012 (512 bit)")
+        );
+        assert!(
+            error
+                .to_string()
+                .contains("certified cryptographic provider")
+        );
+    }
+}
@@ -0,0 +1,37 @@
+#!/bin/sh
+# Regenerates the test certificates and detached signatures.
+set -e
+cd "$(dirname "$0")"
+
+cat > document.xml <<'XML'
+<?xml version="1.0" encoding="UTF-8"?>
+<Document><Name>Задание на проектирование</Name></Document>
+XML
+
+# ИНН ЮЛ (1.2.643.100.4) is not known to OpenSSL.
+cat > oids.cnf <<'CNF'
+oid_section = new_oids
+[new_oids]
+INNLE = 1.2.643.100.4
+[req]
+distinguished_name = dn
+[dn]
+CNF
+
+openssl req -x509 -newkey rsa:2048 -nodes -utf8 -days 3650 -sha256 -config oids.cnf \
+    -keyout rsa.key -out rsa.pem \
+    -subj "/CN=Иванов Иван Иванович/O=ООО \"Проект\"/title=Генеральный директор/INNLE=7700000000/OGRN=1027700000000/SNILS=11223344595/INN=007700000000/emailAddress=ivanov@example.com/C=RU"
+openssl cms -sign -binary -md sha256 -in document.xml -signer rsa.pem -inkey rsa.key \
+    -outform DER -out document.xml.sig
+
+openssl req -x509 -newkey ec -pkeyopt ec_paramgen_curve:P-256 -nodes -utf8 -days 3650 \
+    -config oids.cnf -keyout ec.key -out ec.pem \
+    -subj "/CN=Петров Пётр Петрович/OGRNIP=304770000000012/INN=770000000034/C=RU"
+openssl cms -sign -binary -md sha256 -noattr -in document.xml -signer ec.pem -inkey ec.key \
+    -outform PEM -out document.xml.ec.sig
+
+# Key files for signing, protected with the password "secret".
+openssl pkcs12 -export -in rsa.pem -inkey rsa.key -passout pass:secret -out rsa.p12
+openssl pkcs12 -export -in ec.pem -inkey ec.key -passout pass:secret -out ec.p12
+
+rm oids.cnf rsa.key ec.key
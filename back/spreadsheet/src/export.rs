@@ -0,0 +1,583 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use rust_xlsxwriter::{Format, Workbook};
+use serde::Serialize;
+use zdnp_core::{
+    Address, AddressRepository, EntrepreneurRepository, ListOptions, OrganizationRepository,
+    PersonRepository,
+};
+
+use crate::{SpreadsheetError, Table, TableRow};
+
+/// Reference data list that can be exported.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExportList {
+    Addresses,
+    Organizations,
+    Entrepreneurs,
+    Persons,
+}
+
+impl ExportList {
+    pub const ALL: [ExportList; 4] = [
+        Self::Addresses,
+        Self::Organizations,
+        Self::Entrepreneurs,
+        Self::Persons,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Addresses => "addresses",
+            Self::Organizations => "organizations",
+            Self::Entrepreneurs => "entrepreneurs",
+            Self::Persons => "persons",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|list| list.as_str() == value)
+    }
+
+    /// Worksheet name of the exported workbook.
+    pub fn title(self) -> &'static str {
+        match self {
+            Self::Addresses => "Адреса",
+            Self::Organizations => "Организации",
+            Self::Entrepreneurs => "Индивидуальные предприниматели",
+            Self::Persons => "Физические лица",
+        }
+    }
+
+    /// Columns offered for the list, in their default order.
+    pub fn columns(self) -> &'static [ExportColumn] {
+        match self {
+            Self::Addresses => ADDRESS_COLUMNS,
+            Self::Organizations => ORGANIZATION_COLUMNS,
+            Self::Entrepreneurs => ENTREPRENEUR_COLUMNS,
+            Self::Persons => PERSON_COLUMNS,
+        }
+    }
+}
+
+/// Exported column. The keys are the DTO field names also used by the import mappings;
+/// `address` is the formatted address of the entity.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct ExportColumn {
+    pub key: &'static str,
+    pub header: &'static str,
+}
+
+const fn column(key: &'static str, header: &'static str) -> ExportColumn {
+    ExportColumn { key, header }
+}
+
+const ADDRESS_COLUMNS: &[ExportColumn] = &[
+    column("id", "№"),
+    column("uid", "UID"),
+    column("region_code", "Код региона"),
+    column("note", "Примечание"),
+    column("country", "Страна"),
+    column("district", "Район"),
+    column("city", "Город"),
+    column("settlement", "Населённый пункт"),
+    column("street", "Улица"),
+    column("building", "Здание"),
+    column("room", "Помещение"),
+    column("address", "Адрес"),
+    column("archived_at", "В архиве с"),
+];
+
+const ORGANIZATION_COLUMNS: &[ExportColumn] = &[
+    column("id", "№"),
+    column("uid", "UID"),
+    column("full_name", "Полное наименование"),
+    column("abbreviated_name", "Сокращённое наименование"),
+    column("ogrn", "ОГРН"),
+    column("rafp", "РАФП"),
+    column("inn", "ИНН"),
+    column("kpp", "КПП"),
+    column("email", "Эл. почта"),
+    column("address", "Адрес"),
+    column("archived_at", "В архиве с"),
+];
+
+const ENTREPRENEUR_COLUMNS: &[ExportColumn] = &[
+    column("id", "№"),
+    column("uid", "UID"),
+    column("surname", "Фамилия"),
+    column("name", "Имя"),
+    column("patronymic", "Отчество"),
+    column("ogrnip", "ОГРНИП"),
+    column("inn", "ИНН"),
+    column("email", "Эл. почта"),
+    column("address", "Адрес"),
+    column("archived_at", "В архиве с"),
+];
+
+const PERSON_COLUMNS: &[ExportColumn] = &[
+    column("id", "№"),
+    column("uid", "UID"),
+    column("surname", "Фамилия"),
+    column("name", "Имя"),
+    column("patronymic", "Отчество"),
+    column("snils", "СНИЛС"),
+    column("email", "Эл. почта"),
+    column("address", "Адрес"),
+    column("archived_at", "В архиве с"),
+];
+
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct ExportOptions {
+    /// Column keys in output order; all columns of the list when empty.
+    pub columns: Vec<String>,
+    /// Same filter as the list queries.
+    pub list: ListOptions,
+}
+
+/// Builds the table of the list with the selected columns.
+pub fn export_table<A, O, E, P>(
+    addresses: &A,
+    organizations: &O,
+    entrepreneurs: &E,
+    persons: &P,
+    list: ExportList,
+    options: &ExportOptions,
+) -> Result<Table, SpreadsheetError>
+where
+    A: AddressRepository,
+    O: OrganizationRepository,
+    E: EntrepreneurRepository,
+    P: PersonRepository,
+{
+    let columns = selected_columns(list, &options.columns)?;
+    let repository =
+        |error: &dyn std::fmt::Display| SpreadsheetError::Repository(error.to_string());
+
+    // Archived addresses stay referenced by active entities, so all of them are looked up.
+    let all_addresses = addresses
+        .list(&ListOptions {
+            include_archived: true,
+        })
+        .map_err(|error| repository(&error))?;
+    let formatted: HashMap<i64, String> = all_addresses
+        .iter()
+        .map(|address| (address.id, address.formatted()))
+        .collect();
+    let address = |id: i64| formatted.get(&id).cloned().unwrap_or_default();
+
+    let rows: Vec<Vec<String>> = match list {
+        ExportList::Addresses => all_addresses
+            .iter()
+            .filter(|row| options.list.include_archived || row.archived_at.is_none())
+            .map(|row| cells(&columns, |key| address_value(row, key)))
+            .collect(),
+        ExportList::Organizations => organizations
+            .list(&options.list)
+            .map_err(|error| repository(&error))?
+            .into_iter()
+            .map(|row| {
+                cells(&columns, |key| match key {
+                    "id" => Some(row.id.to_string()),
+                    "uid" => Some(row.uid.clone()),
+                    "full_name" => Some(row.full_name.clone()),
+                    "abbreviated_name" => Some(row.abbreviated_name.clone()),
+                    "ogrn" => row.ogrn.clone(),
+                    "rafp" => row.rafp.clone(),
+                    "inn" => Some(row.inn.clone()),
+                    "kpp" => Some(row.kpp.clone()),
+                    "email" => Some(row.email.clone()),
+                    "address" => Some(address(row.address_id)),
+                    "archived_at" => row.archived_at.clone(),
+                    _ => None,
+                })
+            })
+            .collect(),
+        ExportList::Entrepreneurs => entrepreneurs
+            .list(&options.list)
+            .map_err(|error| repository(&error))?
+            .into_iter()
+            .map(|row| {
+                cells(&columns, |key| match key {
+                    "id" => Some(row.id.to_string()),
+                    "uid" => Some(row.uid.clone()),
+                    "surname" => Some(row.surname.clone()),
+                    "name" => Some(row.name.clone()),
+                    "patronymic" => row.patronymic.clone(),
+                    "ogrnip" => Some(row.ogrnip.clone()),
+                    "inn" => Some(row.inn.clone()),
+                    "email" => row.email.clone(),
+                    "address" => Some(address(row.address_id)),
+                    "archived_at" => row.archived_at.clone(),
+                    _ => None,
+                })
+            })
+            .collect(),
+        ExportList::Persons => persons
+            .list(&options.list)
+            .map_err(|error| repository(&error))?
+            .into_iter()
+            .map(|row| {
+                cells(&columns, |key| match key {
+                    "id" => Some(row.id.to_string()),
+                    "uid" => Some(row.uid.clone()),
+                    "surname" => Some(row.surname.clone()),
+                    "name" => Some(row.name.clone()),
+                    "patronymic" => row.patronymic.clone(),
+                    "snils" => Some(row.snils.clone()),
+                    "email" => Some(row.email.clone()),
+                    "address" => Some(address(row.address_id)),
+                    "archived_at" => row.archived_at.clone(),
+                    _ => None,
+                })
+            })
+            .collect(),
+    };
+
+    Ok(Table {
+        headers: columns
+            .iter()
+            .map(|column| column.header.to_string())
+            .collect(),
+        rows: rows
+            .into_iter()
+            .enumerate()
+            .map(|(index, cells)| TableRow {
+                number: index + 2,
+                cells,
+            })
+            .collect(),
+    })
+}
+
+fn selected_columns(
+    list: ExportList,
+    keys: &[String],
+) -> Result<Vec<ExportColumn>, SpreadsheetError> {
+    if keys.is_empty() {
+        return Ok(list.columns().to_vec());
+    }
+
+    keys.iter()
+        .map(|key| {
+            list.columns()
+                .iter()
+                .find(|column| column.key == key)
+                .copied()
+                .ok_or_else(|| SpreadsheetError::UnknownField(key.clone()))
+        })
+        .collect()
+}
+
+fn cells(columns: &[ExportColumn], value: impl Fn(&str) -> Option<String>) -> Vec<String> {
+    columns
+        .iter()
+        .map(|column| value(column.key).unwrap_or_default())
+        .collect()
+}
+
+fn address_value(address: &Address, key: &str) -> Option<String> {
+    match key {
+        "id" => Some(address.id.to_string()),
+        "uid" => Some(address.uid.clone()),
+        "region_code" => Some(address.region_code.clone()),
+        "note" => address.note.clone(),
+        "country" => address.country.clone(),
+        "district" => address.district.clone(),
+        "city" => address.city.clone(),
+        "settlement" => address.settlement.clone(),
+        "street" => address.street.clone(),
+        "building" => address.building.clone(),
+        "room" => address.room.clone(),
+        "address" => Some(address.formatted()),
+        "archived_at" => address.archived_at.clone(),
+        _ => None,
+    }
+}
+
+/// CSV for Russian Excel: UTF-8 with BOM, `;` separated, CRLF line ends.
+pub fn write_csv(table: &Table) -> Result<Vec<u8>, SpreadsheetError> {
+    let mut writer = csv::WriterBuilder::new()
+        .delimiter(b';')
+        .terminator(csv::Terminator::CRLF)
+        .from_writer(b"\xEF\xBB\xBF".to_vec());
+
+    writer
+        .write_record(&table.headers)
+        .map_err(|error| SpreadsheetError::Csv(error.to_string()))?;
+    for row in &table.rows {
+        writer
+            .write_record(&row.cells)
+            .map_err(|error| SpreadsheetError::Csv(error.to_string()))?;
+    }
+
+    writer
+        .into_inner()
+        .map_err(|error| SpreadsheetError::Csv(error.to_string()))
+}
+
+/// Single-sheet workbook with a bold, frozen header row. Cells are written as text so that
+/// identifiers keep their leading zeros.
+pub fn write_xlsx(table: &Table, sheet: &str) -> Result<Vec<u8>, SpreadsheetError> {
+    let xlsx = |error: rust_xlsxwriter::XlsxError| SpreadsheetError::Xlsx(error.to_string());
+    let mut workbook = Workbook::new();
+    let worksheet = workbook.add_worksheet();
+    worksheet.set_name(sheet).map_err(xlsx)?;
+
+    let bold = Format::new().set_bold();
+    for (column, header) in table.headers.iter().enumerate() {
+        worksheet
+            .write_string_with_format(0, column as u16, header, &bold)
+            .map_err(xlsx)?;
+    }
+    for (index, row) in table.rows.iter().enumerate() {
+        for (column, cell) in row.cells.iter().enumerate() {
+            worksheet
+                .write_string(index as u32 + 1, column as u16, cell)
+                .map_err(xlsx)?;
+        }
+    }
+    worksheet.set_freeze_panes(1, 0).map_err(xlsx)?;
+    worksheet.autofit();
+
+    workbook.save_to_buffer().map_err(xlsx)
+}
+
+/// Writes the table to a `.csv` or `.xlsx` file; `sheet` names the worksheet of a workbook.
+pub fn write_table_file(path: &Path, table: &Table, sheet: &str) -> Result<(), SpreadsheetError> {
+    let extension = path
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .unwrap_or_default()
+        .to_lowercase();
+    let bytes = match extension.as_str() {
+        "csv" => write_csv(table)?,
+        "xlsx" => write_xlsx(table, sheet)?,
+        _ => return Err(SpreadsheetError::UnsupportedFormat(extension)),
+    };
+
+    std::fs::write(path, bytes).map_err(|error| SpreadsheetError::Io(error.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::read_xlsx;
+    use zdnp_core::{
+        AddressDto, AddressRepositoryError, Entrepreneur, EntrepreneurDto,
+        EntrepreneurRepositoryError, Organization, OrganizationDto, OrganizationRepositoryError,
+        Person, PersonDto, PersonRepositoryError,
+    };
+
+    /// Read-only reference data; the export only lists it.
+    struct Directory {
+        addresses: Vec<Address>,
+        organizations: Vec<Organization>,
+    }
+
+    fn listed<T: Clone>(
+        rows: &[T],
+        archived: impl Fn(&T) -> bool,
+        options: &ListOptions,
+    ) -> Vec<T> {
+        rows.iter()
+            .filter(|row| options.include_archived || !archived(row))
+            .cloned()
+            .collect()
+    }
+
+    impl AddressRepository for Directory {
+        fn create(&self, _: &AddressDto) -> Result<i64, AddressRepositoryError> {
+            Err(AddressRepositoryError::storage("not supported"))
+        }
+
+        fn update(&self, _: i64, _: &AddressDto) -> Result<bool, AddressRepositoryError> {
+            Err(AddressRepositoryError::storage("not supported"))
+        }
+
+        fn list(&self, options: &ListOptions) -> Result<Vec<Address>, AddressRepositoryError> {
+            Ok(listed(
+                &self.addresses,
+                |row| row.archived_at.is_some(),
+                options,
+            ))
+        }
+
+        fn archive(&self, _: i64) -> Result<bool, AddressRepositoryError> {
+            Err(AddressRepositoryError::storage("not supported"))
+        }
+
+        fn restore(&self, _: i64) -> Result<bool, AddressRepositoryError> {
+            Err(AddressRepositoryError::storage("not supported"))
+        }
+    }
+
+    impl OrganizationRepository for Directory {
+        fn create(&self, _: &OrganizationDto) -> Result<i64, OrganizationRepositoryError> {
+            Err(OrganizationRepositoryError::storage("not supported"))
+        }
+
+        fn update(&self, _: i64, _: &OrganizationDto) -> Result<bool, OrganizationRepositoryError> {
+            Err(OrganizationRepositoryError::storage("not supported"))
+        }
+
+        fn list(
+            &self,
+            options: &ListOptions,
+        ) -> Result<Vec<Organization>, OrganizationRepositoryError> {
+            Ok(listed(
+                &self.organizations,
+                |row| row.archived_at.is_some(),
+                options,
+            ))
+        }
+
+        fn archive(&self, _: i64) -> Result<bool, OrganizationRepositoryError> {
+            Err(OrganizationRepositoryError::storage("not supported"))
+        }
+
+        fn restore(&self, _: i64) -> Result<bool, OrganizationRepositoryError> {
+            Err(OrganizationRepositoryError::storage("not supported"))
+        }
+    }
+
+    impl EntrepreneurRepository for Directory {
+        fn create(&self, _: &EntrepreneurDto) -> Result<i64, EntrepreneurRepositoryError> {
+            Err(EntrepreneurRepositoryError::storage("not supported"))
+        }
+
+        fn update(&self, _: i64, _: &EntrepreneurDto) -> Result<bool, EntrepreneurRepositoryError> {
+            Err(EntrepreneurRepositoryError::storage("not supported"))
+        }
+
+        fn list(&self, _: &ListOptions) -> Result<Vec<Entrepreneur>, EntrepreneurRepositoryError> {
+            Ok(Vec::new())
+        }
+
+        fn archive(&self, _: i64) -> Result<bool, EntrepreneurRepositoryError> {
+            Err(EntrepreneurRepositoryError::storage("not supported"))
+        }
+
+        fn restore(&self, _: i64) -> Result<bool, EntrepreneurRepositoryError> {
+            Err(EntrepreneurRepositoryError::storage("not supported"))
+        }
+    }
+
+    impl PersonRepository for Directory {
+        fn create(&self, _: &PersonDto) -> Result<i64, PersonRepositoryError> {
+            Err(PersonRepositoryError::storage("not supported"))
+        }
+
+        fn update(&self, _: i64, _: &PersonDto) -> Result<bool, PersonRepositoryError> {
+            Err(PersonRepositoryError::storage("not supported"))
+        }
+
+        fn list(&self, _: &ListOptions) -> Result<Vec<Person>, PersonRepositoryError> {
+            Ok(Vec::new())
+        }
+
+        fn archive(&self, _: i64) -> Result<bool, PersonRepositoryError> {
+            Err(PersonRepositoryError::storage("not supported"))
+        }
+
+        fn restore(&self, _: i64) -> Result<bool, PersonRepositoryError> {
+            Err(PersonRepositoryError::storage("not supported"))
+        }
+    }
+
+    fn directory() -> Directory {
+        let organization = |id: i64, inn: &str, archived_at: Option<&str>| Organization {
+            id,
+            uid: format!("organization-{id}"),
+            full_name: format!("Организация {id}"),
+            abbreviated_name: format!("Орг. {id}"),
+            ogrn: None,
+            rafp: None,
+            inn: inn.into(),
+            kpp: "770001001".into(),
+            address_id: 1,
+            email: "info@example.com".into(),
+            archived_at: archived_at.map(str::to_string),
+        };
+
+        Directory {
+            addresses: vec![Address {
+                id: 1,
+                uid: "address-1".into(),
+                region_code: "77".into(),
+                note: None,
+                country: None,
+                district: None,
+                city: Some("Москва".into()),
+                settlement: None,
+                street: Some("Тверская".into()),
+                building: Some("1".into()),
+                room: None,
+                archived_at: Some("2026-10-01 09:00:00".into()),
+            }],
+            organizations: vec![
+                organization(1, "0100000001", None),
+                organization(2, "7700000000", Some("2026-10-01 09:00:00")),
+            ],
+        }
+    }
+
+    fn export(list: ExportList, options: &ExportOptions) -> Result<Table, SpreadsheetError> {
+        let directory = directory();
+        export_table(
+            &directory, &directory, &directory, &directory, list, options,
+        )
+    }
+
+    #[test]
+    fn selected_columns_are_exported_with_the_formatted_address() {
+        let options = ExportOptions {
+            columns: vec!["inn".into(), "abbreviated_name".into(), "address".into()],
+            list: ListOptions::default(),
+        };
+
+        let table = export(ExportList::Organizations, &options).expect("export");
+
+        assert_eq!(table.headers, ["ИНН", "Сокращённое наименование", "Адрес"]);
+        assert_eq!(table.rows.len(), 1);
+        assert_eq!(
+            table.rows[0].cells,
+            ["0100000001", "Орг. 1", "77, Москва, Тверская, 1"]
+        );
+
+        let csv = String::from_utf8(write_csv(&table).unwrap()).unwrap();
+        assert_eq!(
+            csv,
+            "\u{feff}ИНН;Сокращённое наименование;Адрес\r\n0100000001;Орг. 1;77, Москва, Тверская, 1\r\n"
+        );
+
+        let options = ExportOptions {
+            columns: vec!["snils".into()],
+            list: ListOptions::default(),
+        };
+        assert_eq!(
+            export(ExportList::Organizations, &options),
+            Err(SpreadsheetError::UnknownField("snils".into()))
+        );
+    }
+
+    #[test]
+    fn xlsx_export_reads_back_as_text() {
+        let options = ExportOptions {
+            list: ListOptions {
+                include_archived: true,
+            },
+            ..ExportOptions::default()
+        };
+        let table = export(ExportList::Organizations, &options).expect("export");
+        assert_eq!(table.rows.len(), 2);
+
+        let bytes = write_xlsx(&table, "Организации").expect("xlsx");
+
+        assert_eq!(read_xlsx(&bytes, None), Ok(table));
+        let addresses = export(ExportList::Addresses, &ExportOptions::default()).expect("export");
+        assert!(addresses.rows.is_empty());
+    }
+}
@@ -0,0 +1,342 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use zdnp_core::{
+    AddressDto, EntityRef, EntrepreneurDto, OrganizationDto, PersonDto, ReferenceImportRepository,
+    ReferenceRecord, import_reference_records, sanitize_reference_record,
+};
+
+use crate::{SpreadsheetError, Table};
+
+/// Kind of reference data the rows of a spreadsheet hold.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportTarget {
+    Address,
+    Organization,
+    Entrepreneur,
+    Person,
+}
+
+impl ImportTarget {
+    fn blank_record(self) -> ReferenceRecord {
+        let address = AddressDto::default();
+        match self {
+            Self::Address => ReferenceRecord::Address { address },
+            Self::Organization => ReferenceRecord::Organization {
+                organization: OrganizationDto::default(),
+                address,
+            },
+            Self::Entrepreneur => ReferenceRecord::Entrepreneur {
+                entrepreneur: EntrepreneurDto::default(),
+                address,
+            },
+            Self::Person => ReferenceRecord::Person {
+                person: PersonDto::default(),
+                address,
+            },
+        }
+    }
+}
+
+/// Assignment of spreadsheet columns to DTO fields, e.g.
+/// `{"target": "organization", "fields": {"inn": "ИНН", "address.city": "Город"}}`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ColumnMapping {
+    pub target: ImportTarget,
+    /// Field name to column header. Fields are named as in the DTOs; the address fields of an
+    /// organization, entrepreneur or person take the `address.` prefix.
+    pub fields: BTreeMap<String, String>,
+}
+
+impl ColumnMapping {
+    /// Field names with the positions of their columns in the table.
+    fn resolve<'a>(&'a self, table: &Table) -> Result<Vec<(&'a str, usize)>, SpreadsheetError> {
+        let mut blank = self.target.blank_record();
+        self.fields
+            .iter()
+            .map(|(field, header)| {
+                if record_field(&mut blank, field).is_none() {
+                    return Err(SpreadsheetError::UnknownField(field.clone()));
+                }
+                let column = table
+                    .column(header)
+                    .ok_or_else(|| SpreadsheetError::UnknownColumn(header.clone()))?;
+                Ok((field.as_str(), column))
+            })
+            .collect()
+    }
+}
+
+fn address_field<'a>(address: &'a mut AddressDto, field: &str) -> Option<&'a mut Option<String>> {
+    Some(match field {
+        "region_code" => &mut address.region_code,
+        "note" => &mut address.note,
+        "country" => &mut address.country,
+        "district" => &mut address.district,
+        "city" => &mut address.city,
+        "settlement" => &mut address.settlement,
+        "street" => &mut address.street,
+        "building" => &mut address.building,
+        "room" => &mut address.room,
+        _ => return None,
+    })
+}
+
+fn record_field<'a>(
+    record: &'a mut ReferenceRecord,
+    field: &str,
+) -> Option<&'a mut Option<String>> {
+    match record {
+        ReferenceRecord::Address { address } => address_field(address, field),
+        ReferenceRecord::Organization {
+            organization,
+            address,
+        } => match field.strip_prefix("address.") {
+            Some(field) => address_field(address, field),
+            None => Some(match field {
+                "full_name" => &mut organization.full_name,
+                "abbreviated_name" => &mut organization.abbreviated_name,
+                "ogrn" => &mut organization.ogrn,
+                "rafp" => &mut organization.rafp,
+                "inn" => &mut organization.inn,
+                "kpp" => &mut organization.kpp,
+                "email" => &mut organization.email,
+                _ => return None,
+            }),
+        },
+        ReferenceRecord::Entrepreneur {
+            entrepreneur,
+            address,
+        } => match field.strip_prefix("address.") {
+            Some(field) => address_field(address, field),
+            None => Some(match field {
+                "surname" => &mut entrepreneur.surname,
+                "name" => &mut entrepreneur.name,
+                "patronymic" => &mut entrepreneur.patronymic,
+                "ogrnip" => &mut entrepreneur.ogrnip,
+                "inn" => &mut entrepreneur.inn,
+                "email" => &mut entrepreneur.email,
+                _ => return None,
+            }),
+        },
+        ReferenceRecord::Person { person, address } => match field.strip_prefix("address.") {
+            Some(field) => address_field(address, field),
+            None => Some(match field {
+                "surname" => &mut person.surname,
+                "name" => &mut person.name,
+                "patronymic" => &mut person.patronymic,
+                "snils" => &mut person.snils,
+                "email" => &mut person.email,
+                _ => return None,
+            }),
+        },
+    }
+}
+
+/// Outcome of the dry run for one spreadsheet row.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RowValidation {
+    pub row: usize,
+    /// The record as it would be stored, or as read when it is invalid.
+    pub record: ReferenceRecord,
+    pub error: Option<String>,
+}
+
+/// Maps and validates every row without storing anything. Rows whose mapped cells are all
+/// empty are left out.
+pub fn validate_rows(
+    table: &Table,
+    mapping: &ColumnMapping,
+) -> Result<Vec<RowValidation>, SpreadsheetError> {
+    let columns = mapping.resolve(table)?;
+
+    Ok(table
+        .rows
+        .iter()
+        .filter(|row| {
+            columns
+                .iter()
+                .any(|&(_, column)| !row.cell(column).is_empty())
+        })
+        .map(|row| {
+            let mut record = mapping.target.blank_record();
+            for &(field, column) in &columns {
+                if let Some(value) = record_field(&mut record, field) {
+                    *value = Some(row.cell(column).to_string());
+                }
+            }
+
+            match sanitize_reference_record(&record) {
+                Ok(record) => RowValidation {
+                    row: row.number,
+                    record,
+                    error: None,
+                },
+                Err(error) => RowValidation {
+                    row: row.number,
+                    record,
+                    error: Some(error.to_string()),
+                },
+            }
+        })
+        .collect())
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ImportedRow {
+    pub row: usize,
+    pub entity: EntityRef,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ImportSummary {
+    pub imported: Vec<ImportedRow>,
+    /// Rows left out because they failed validation.
+    pub skipped: Vec<usize>,
+}
+
+/// Stores all valid rows in one transaction and skips the invalid ones reported by
+/// [`validate_rows`]. When storing fails nothing is imported.
+pub fn import_rows<R: ReferenceImportRepository>(
+    repository: &R,
+    table: &Table,
+    mapping: &ColumnMapping,
+) -> Result<ImportSummary, SpreadsheetError> {
+    let (valid, invalid): (Vec<_>, Vec<_>) = validate_rows(table, mapping)?
+        .into_iter()
+        .partition(|validation| validation.error.is_none());
+
+    let records: Vec<ReferenceRecord> = valid
+        .iter()
+        .map(|validation| validation.record.clone())
+        .collect();
+    let entities = import_reference_records(repository, &records)?;
+
+    Ok(ImportSummary {
+        imported: valid
+            .iter()
+            .zip(entities)
+            .map(|(validation, entity)| ImportedRow {
+                row: validation.row,
+                entity,
+            })
+            .collect(),
+        skipped: invalid.iter().map(|validation| validation.row).collect(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+
+    use super::*;
+    use crate::read_csv;
+    use zdnp_core::ReferenceImportRepositoryError;
+
+    #[derive(Default)]
+    struct RecordingImport {
+        imported: RefCell<Vec<ReferenceRecord>>,
+    }
+
+    impl ReferenceImportRepository for RecordingImport {
+        fn import(
+            &self,
+            records: &[ReferenceRecord],
+        ) -> Result<Vec<EntityRef>, ReferenceImportRepositoryError> {
+            self.imported.borrow_mut().extend_from_slice(records);
+            Ok((1..=records.len() as i64).map(EntityRef::Person).collect())
+        }
+    }
+
+    fn people() -> Table {
+        read_csv(
+            "Фамилия;Имя;СНИЛС;Почта;Регион;Комментарий\n\
+             Иванов;Иван;11223344595;ivanov@example.com;77;\n\
+             ;;;;;пустая строка\n\
+             Петров;Пётр;;petrov@example.com;77;\n\
+             Сидоров;Сидор;11223344596;sidorov@example.com;78;\n"
+                .as_bytes(),
+        )
+        .unwrap()
+    }
+
+    fn mapping() -> ColumnMapping {
+        ColumnMapping {
+            target: ImportTarget::Person,
+            fields: [
+                ("surname", "Фамилия"),
+                ("name", "Имя"),
+                ("snils", "СНИЛС"),
+                ("email", "Почта"),
+                ("address.region_code", "Регион"),
+            ]
+            .into_iter()
+            .map(|(field, column)| (field.to_string(), column.to_string()))
+            .collect(),
+        }
+    }
+
+    #[test]
+    fn dry_run_reports_every_row() {
+        let validations = validate_rows(&people(), &mapping()).expect("validate");
+
+        let rows: Vec<_> = validations
+            .iter()
+            .map(|validation| (validation.row, validation.error.as_deref()))
+            .collect();
+        assert_eq!(
+            rows,
+            [
+                (2, None),
+                (4, Some("Person error: SNILS is required")),
+                (5, None),
+            ]
+        );
+        assert_eq!(
+            validations[0].record.address().region_code.as_deref(),
+            Some("77")
+        );
+    }
+
+    #[test]
+    fn valid_rows_are_imported_together() {
+        let repository = RecordingImport::default();
+
+        let summary = import_rows(&repository, &people(), &mapping()).expect("import");
+
+        assert_eq!(
+            summary,
+            ImportSummary {
+                imported: vec![
+                    ImportedRow {
+                        row: 2,
+                        entity: EntityRef::Person(1),
+                    },
+                    ImportedRow {
+                        row: 5,
+                        entity: EntityRef::Person(2),
+                    },
+                ],
+                skipped: vec![4],
+            }
+        );
+        assert_eq!(repository.imported.borrow().len(), 2);
+    }
+
+    #[test]
+    fn mapping_must_match_the_target_and_the_headers() {
+        let mut mapping = mapping();
+        mapping.fields.insert("kpp".into(), "КПП".into());
+        assert_eq!(
+            validate_rows(&people(), &mapping),
+            Err(SpreadsheetError::UnknownField("kpp".into()))
+        );
+
+        mapping.target = ImportTarget::Organization;
+        assert_eq!(
+            validate_rows(&people(), &mapping),
+            Err(SpreadsheetError::UnknownColumn("КПП".into()))
+        );
+    }
+}
@@ -0,0 +1,58 @@
+//! Exchange of reference data with CSV and XLSX spreadsheets.
+//!
+//! For import, a column mapping assigns the spreadsheet columns to the fields of the address,
+//! organization, entrepreneur and person DTOs; the rows can be validated in a dry run before
+//! all valid ones are stored together. For export, the reference lists are written with
+//! selected columns and the entity addresses formatted in one cell.
+
+mod export;
+mod import;
+mod table;
+
+pub use export::*;
+pub use import::*;
+pub use table::*;
+
+use zdnp_core::ReferenceImportError;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SpreadsheetError {
+    Io(String),
+    /// The file extension is neither `.csv` nor `.xlsx`.
+    UnsupportedFormat(String),
+    Csv(String),
+    Xlsx(String),
+    SheetNotFound(String),
+    /// The mapping names a field the import target does not have.
+    UnknownField(String),
+    /// The mapping names a column missing from the header row.
+    UnknownColumn(String),
+    Import(ReferenceImportError),
+    Repository(String),
+}
+
+impl std::fmt::Display for SpreadsheetError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(message) => write!(f, "I/O error: {message}"),
+            Self::UnsupportedFormat(extension) => {
+                write!(f, "Unsupported spreadsheet format: {extension}")
+            }
+            Self::Csv(message) => write!(f, "Malformed CSV: {message}"),
+            Self::Xlsx(message) => write!(f, "Malformed XLSX: {message}"),
+            Self::SheetNotFound(name) => write!(f, "Sheet {name} not found"),
+            Self::UnknownField(field) => write!(f, "Unknown field: {field}"),
+            Self::UnknownColumn(column) => write!(f, "Column {column} not found"),
+            Self::Import(error) => write!(f, "Import error: {error}"),
+            Self::Repository(message) => write!(f, "Repository error: {message}"),
+        }
+    }
+}
+
+impl std::error::Error for SpreadsheetError {}
+
+impl From<ReferenceImportError> for SpreadsheetError {
+    fn from(error: ReferenceImportError) -> Self {
+        Self::Import(error)
+    }
+}
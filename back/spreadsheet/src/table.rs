@@ -0,0 +1,200 @@
+use std::io::Cursor;
+use std::path::Path;
+
+use calamine::{Data, Reader, Xlsx};
+use encoding_rs::WINDOWS_1251;
+
+use crate::SpreadsheetError;
+
+/// Spreadsheet contents as text; the first row holds the column headers.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Table {
+    pub headers: Vec<String>,
+    pub rows: Vec<TableRow>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TableRow {
+    /// Row number as shown by spreadsheet applications, the header row being 1.
+    pub number: usize,
+    pub cells: Vec<String>,
+}
+
+impl TableRow {
+    /// Trimmed cell value; missing cells read as empty.
+    pub fn cell(&self, column: usize) -> &str {
+        self.cells.get(column).map_or("", |cell| cell.trim())
+    }
+}
+
+impl Table {
+    fn from_rows(rows: impl IntoIterator<Item = Vec<String>>) -> Self {
+        let mut rows = rows.into_iter();
+        let headers = rows
+            .next()
+            .unwrap_or_default()
+            .into_iter()
+            .map(|header| header.trim().to_string())
+            .collect();
+        let rows = rows
+            .enumerate()
+            .map(|(index, cells)| TableRow {
+                number: index + 2,
+                cells,
+            })
+            .collect();
+
+        Self { headers, rows }
+    }
+
+    /// Position of the column with the header, compared case-insensitively.
+    pub fn column(&self, header: &str) -> Option<usize> {
+        let header = header.trim().to_lowercase();
+        self.headers
+            .iter()
+            .position(|candidate| candidate.to_lowercase() == header)
+    }
+}
+
+/// Reads CSV as saved by spreadsheet applications: UTF-8 (with or without BOM) or
+/// windows-1251, separated by `;`, `,` or tabs, whichever the header row uses most.
+pub fn read_csv(bytes: &[u8]) -> Result<Table, SpreadsheetError> {
+    let bytes = bytes.strip_prefix(b"\xEF\xBB\xBF").unwrap_or(bytes);
+    let text = match std::str::from_utf8(bytes) {
+        Ok(text) => text.to_string(),
+        Err(_) => WINDOWS_1251
+            .decode_without_bom_handling(bytes)
+            .0
+            .into_owned(),
+    };
+
+    let header = text.lines().next().unwrap_or_default();
+    let delimiter = [b';', b',', b'\t']
+        .into_iter()
+        .max_by_key(|delimiter| header.bytes().filter(|byte| byte == delimiter).count())
+        .unwrap_or(b';');
+
+    let mut reader = csv::ReaderBuilder::new()
+        .delimiter(delimiter)
+        .has_headers(false)
+        .flexible(true)
+        .from_reader(text.as_bytes());
+    let rows = reader
+        .records()
+        .map(|record| {
+            record
+                .map(|record| record.iter().map(str::to_string).collect())
+                .map_err(|error| SpreadsheetError::Csv(error.to_string()))
+        })
+        .collect::<Result<Vec<Vec<String>>, _>>()?;
+
+    Ok(Table::from_rows(rows))
+}
+
+/// Reads a worksheet of an XLSX workbook, the first one unless `sheet` names another.
+pub fn read_xlsx(bytes: &[u8], sheet: Option<&str>) -> Result<Table, SpreadsheetError> {
+    let mut workbook =
+        Xlsx::new(Cursor::new(bytes)).map_err(|error| SpreadsheetError::Xlsx(error.to_string()))?;
+
+    let name = match sheet {
+        Some(sheet) => workbook
+            .sheet_names()
+            .into_iter()
+            .find(|name| name == sheet)
+            .ok_or_else(|| SpreadsheetError::SheetNotFound(sheet.to_string()))?,
+        None => workbook
+            .sheet_names()
+            .into_iter()
+            .next()
+            .ok_or_else(|| SpreadsheetError::Xlsx("Workbook has no sheets".to_string()))?,
+    };
+    let range = workbook
+        .worksheet_range(&name)
+        .map_err(|error| SpreadsheetError::Xlsx(error.to_string()))?;
+
+    // Rows above the used range are empty, keep the row numbers as the application shows them.
+    let offset = range.start().map_or(0, |(row, _)| row as usize);
+    let mut table = Table::from_rows(
+        range
+            .rows()
+            .map(|row| row.iter().map(cell_text).collect::<Vec<_>>()),
+    );
+    for row in &mut table.rows {
+        row.number += offset;
+    }
+
+    Ok(table)
+}
+
+/// Text of a cell. Identifiers such as ИНН are often typed as numbers, so whole numbers are
+/// written without a fractional part.
+fn cell_text(cell: &Data) -> String {
+    match cell {
+        Data::Float(value) if value.fract() == 0.0 && value.abs() < 1e15 => {
+            format!("{value:.0}")
+        }
+        Data::Empty => String::new(),
+        other => other.to_string(),
+    }
+}
+
+/// Reads a `.csv` or `.xlsx` file; `sheet` only applies to workbooks.
+pub fn read_table_file(path: &Path, sheet: Option<&str>) -> Result<Table, SpreadsheetError> {
+    let extension = path
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .unwrap_or_default()
+        .to_lowercase();
+    if extension != "csv" && extension != "xlsx" {
+        return Err(SpreadsheetError::UnsupportedFormat(extension));
+    }
+
+    let bytes = std::fs::read(path).map_err(|error| SpreadsheetError::Io(error.to_string()))?;
+    if extension == "csv" {
+        read_csv(&bytes)
+    } else {
+        read_xlsx(&bytes, sheet)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn csv_in_windows_1251_with_semicolons_is_read() {
+        let text = "Фамилия;Имя;Город\nИванов;Иван;\"Москва; ЦАО\"\n";
+        let bytes = WINDOWS_1251.encode(text).0;
+
+        let table = read_csv(&bytes).expect("csv");
+
+        assert_eq!(table.headers, ["Фамилия", "Имя", "Город"]);
+        assert_eq!(table.rows.len(), 1);
+        assert_eq!(table.rows[0].number, 2);
+        assert_eq!(table.rows[0].cell(2), "Москва; ЦАО");
+        assert_eq!(table.rows[0].cell(5), "");
+        assert_eq!(table.column(" город "), Some(2));
+    }
+
+    #[test]
+    fn xlsx_numbers_are_read_as_identifiers() {
+        let mut workbook = rust_xlsxwriter::Workbook::new();
+        workbook.add_worksheet().set_name("Прочее").unwrap();
+        let sheet = workbook.add_worksheet().set_name("Контрагенты").unwrap();
+        sheet.write(1, 0, "ИНН").unwrap();
+        sheet.write(1, 1, "Наименование").unwrap();
+        sheet.write(2, 0, 7700000000.0).unwrap();
+        sheet.write(2, 1, "ООО \"Проект\"").unwrap();
+        let bytes = workbook.save_to_buffer().unwrap();
+
+        let table = read_xlsx(&bytes, Some("Контрагенты")).expect("xlsx");
+
+        assert_eq!(table.headers, ["ИНН", "Наименование"]);
+        assert_eq!(table.rows[0].number, 3);
+        assert_eq!(table.rows[0].cells, ["7700000000", "ООО \"Проект\""]);
+        assert_eq!(
+            read_xlsx(&bytes, Some("Лист1")),
+            Err(SpreadsheetError::SheetNotFound("Лист1".into()))
+        );
+    }
+}